@@ -0,0 +1,93 @@
+//! Local cache of the last handoff published from this machine.
+//!
+//! Kept in the `active` table of the [state database](crate::state), written after
+//! every publish and cleared on revoke, so cheap callers such as a shell prompt
+//! segment (`cclink env --prompt`) can report active handoffs without loading the key
+//! or querying the DHT. It is a hint, not a source of truth: a
+//! burn-after-read pickup elsewhere will not clear it until the TTL runs out.
+//!
+//! The entry also keeps a hash of the exact record published, which `cclink monitor`
+//! compares against what the DHT serves.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// What the cache remembers about a published handoff.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CachedHandoff {
+    pub created_at: u64,
+    pub pubkey: String,
+    /// [`record_hash`] of the published record; absent in caches written before `monitor`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_hash: Option<String>,
+    pub ttl: u64,
+}
+
+impl CachedHandoff {
+    pub fn from_record(record: &crate::record::HandoffRecord) -> Self {
+        CachedHandoff {
+            created_at: record.created_at,
+            pubkey: record.pubkey.clone(),
+            record_hash: Some(record_hash(record)),
+            ttl: record.ttl,
+        }
+    }
+
+    pub fn is_active(&self, now: u64) -> bool {
+        now < self.created_at.saturating_add(self.ttl)
+    }
+}
+
+/// Hex SHA-256 of a record's JSON encoding — identical only for byte-identical records.
+pub fn record_hash(record: &crate::record::HandoffRecord) -> String {
+    let json = serde_json::to_vec(record).expect("HandoffRecord always serializes");
+    crate::util::to_hex(&Sha256::digest(json))
+}
+
+/// The cached handoff, if any.
+pub fn load() -> anyhow::Result<Option<CachedHandoff>> {
+    crate::state::State::open()?.active()
+}
+
+/// Remember a freshly published record. Failures are logged, not returned —
+/// the cache must never make a publish fail.
+pub fn remember(record: &crate::record::HandoffRecord) {
+    let result = crate::state::State::open()
+        .and_then(|state| state.save_active(&CachedHandoff::from_record(record)));
+    if let Err(e) = result {
+        tracing::debug!(error = %e, "failed to update handoff cache");
+    }
+}
+
+/// Forget the cached handoff (after a revoke). Failures are logged, not returned.
+pub fn forget() {
+    if let Err(e) = crate::state::State::open().and_then(|state| state.clear_active()) {
+        tracing::debug!(error = %e, "failed to clear handoff cache");
+    }
+}
+
+/// Number of handoffs from this machine that are still within their TTL.
+pub fn active_count(now: u64) -> usize {
+    load()
+        .ok()
+        .flatten()
+        .filter(|h| h.is_active(now))
+        .map_or(0, |_| 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_handoff_expiry() {
+        let handoff = CachedHandoff {
+            created_at: 1_000,
+            pubkey: "abc".to_string(),
+            record_hash: None,
+            ttl: 60,
+        };
+        assert!(handoff.is_active(1_059));
+        assert!(!handoff.is_active(1_060));
+    }
+}
@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(
@@ -7,30 +7,148 @@ use clap::{Parser, Subcommand};
     about = "Hand off a Claude Code session to another machine via PKARR DHT"
 )]
 pub struct Cli {
-    /// Claude Code session ID to publish (auto-discovers most recent if omitted)
+    /// Session ID to publish (auto-discovers most recent if omitted)
     #[arg(value_name = "SESSION_ID")]
     pub session_id: Option<String>,
 
-    /// Time-to-live in seconds (default: 86400 = 24 hours)
-    #[arg(long, default_value = "86400")]
+    /// Time-to-live: seconds, or a duration like 30m, 2h, 7d (at most 30d)
+    #[arg(long, default_value = "24h", value_parser = crate::util::parse_ttl)]
     pub ttl: u64,
 
     /// Render a QR code in the terminal after publish
     #[arg(long)]
     pub qr: bool,
 
-    /// Encrypt for a specific recipient's z32-encoded public key
-    #[arg(long, value_name = "PUBKEY")]
+    /// Render a QR code holding the whole encrypted handoff, for `cclink pickup --from-qr`
+    /// on a machine without network access
+    #[arg(long, conflicts_with = "qr")]
+    pub qr_payload: bool,
+
+    /// Publish into DIR, a folder shared with the other machine, instead of the DHT
+    /// (default: transport.dir in ~/.pubky/cclink.toml)
+    #[arg(long, value_name = "DIR")]
+    pub dir: Option<std::path::PathBuf>,
+
+    /// Copy the pickup command to the clipboard (falls back to OSC 52 over SSH)
+    #[arg(long)]
+    pub copy: bool,
+
+    /// Encrypt for a specific recipient (z32-encoded public key, contact name, or an
+    /// age recipient such as age1… or age1yubikey1…)
+    #[arg(long, value_name = "PUBKEY|CONTACT|AGE")]
     pub share: Option<String>,
 
-    /// Mark as burn-after-read (deleted after first successful pickup)
-    #[arg(long, conflicts_with = "share")]
-    pub burn: bool,
+    /// Encrypt for one of your registered devices only (see `cclink device register`)
+    #[arg(long, value_name = "DEVICE", conflicts_with_all = ["share", "pin"])]
+    pub to: Option<String>,
+
+    /// Print a one-time code (e.g. 7-guitarist-revenge) and encrypt for whoever types it
+    /// into `cclink pickup --code`, instead of exchanging keys first
+    #[arg(long, conflicts_with_all = ["share", "to", "pin"])]
+    pub code: bool,
+
+    /// Mark as burn-after-read: deleted after the first successful pickup, or after
+    /// N pickups with `--burn=N`
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "1",
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with = "share"
+    )]
+    pub burn: Option<u32>,
+
+    /// Keep a burn handoff readable for this long after its last pickup (e.g. 10m)
+    #[arg(long, value_name = "DURATION", requires = "burn", value_parser = crate::util::parse_duration)]
+    pub burn_grace: Option<u64>,
 
     /// Protect handoff with a PIN (prompts for PIN at publish time)
     #[arg(long, conflicts_with = "share")]
     pub pin: bool,
 
+    /// After publishing, read the record back through a fresh DHT client and check it matches
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Ask the picker to publish a pickup receipt, listed by `cclink receipts`
+    #[arg(long)]
+    pub receipt: bool,
+
+    /// Revoke the handoff once the picker's receipt arrives: with --wait-receipt, or
+    /// later with `cclink receipts --revoke`
+    #[arg(long, conflicts_with = "burn")]
+    pub revoke_after_pickup: bool,
+
+    /// Stay running after publish until the receipt arrives, for at most DURATION
+    /// (`--wait-receipt=1h`, default 10m)
+    #[arg(
+        long,
+        value_name = "DURATION",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "10m",
+        requires = "revoke_after_pickup",
+        value_parser = crate::util::parse_duration
+    )]
+    pub wait_receipt: Option<u64>,
+
+    /// Include the full session transcript, so pickup works on machines that never had the session (it must compress to at most 37.5 KiB over the DHT)
+    #[arg(long)]
+    pub with_transcript: bool,
+
+    /// Show the session's last N messages (`--preview=N`, default 6) and confirm before publishing
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "6"
+    )]
+    pub preview: Option<usize>,
+
+    /// Coding agent whose session to publish (default: the most recent session of any agent)
+    #[arg(long, value_enum)]
+    pub agent: Option<crate::session::Agent>,
+
+    /// Use a named profile: a separate key and settings in ~/.pubky/profiles/<NAME>/
+    /// (default: $CCLINK_PROFILE, else ~/.pubky/)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Read the key passphrase from FILE instead of prompting (default: $CCLINK_PASSPHRASE)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub passphrase_file: Option<std::path::PathBuf>,
+
+    /// Read handoff PINs from FILE instead of prompting (default: $CCLINK_PIN)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub pin_file: Option<std::path::PathBuf>,
+
+    /// Give each DHT request at most this long to be answered, e.g. 10s (default: [network] timeout)
+    #[arg(long, global = true, value_name = "DURATION", value_parser = crate::util::parse_duration)]
+    pub timeout: Option<u64>,
+
+    /// Retry a failed DHT lookup at most N times (default: [network] max_retries, else 3)
+    #[arg(long, global = true, value_name = "N")]
+    pub max_retries: Option<usize>,
+
+    /// Increase diagnostic logging on stderr (-v debug, -vv trace; RUST_LOG overrides)
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Print only requested output, warnings and errors: no status lines, progress bars, or `cclink explain` hint
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Print how long DHT requests, key derivations and LAN fetches took when the command ends
+    #[arg(long, global = true)]
+    pub timing: bool,
+
+    /// Print without colors (also when NO_COLOR is set)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -43,18 +161,91 @@ pub enum Commands {
     Whoami,
     /// Pick up a Claude Code session handoff from the DHT
     Pickup(PickupArgs),
-    /// Show the active handoff record on the DHT
-    List,
+    /// Like the default publish, but put the handoff on the clipboard for `cclink paste`
+    Copy,
+    /// Pick up a handoff from the clipboard, put there by `cclink copy`
+    Paste(PickupArgs),
+    /// Like the default publish, but write the handoff as armored text for `cclink import`
+    Export(ExportArgs),
+    /// Pick up an armored handoff written by `cclink export`
+    Import(ImportArgs),
+    /// Like the default publish, but serve the handoff on the local network for `pickup --lan`
+    Serve(ServeArgs),
+    /// Show the active handoff record on the DHT (yours, or another publisher's)
+    List(ListArgs),
+    /// Overview of your handoff, your contacts' latest handoffs, and local sessions
+    Status,
+    /// Fetch a handoff record and show its envelope metadata without decrypting
+    Inspect(InspectArgs),
     /// Revoke the active handoff record from the DHT
     Revoke(RevokeArgs),
+    /// Clear your expired handoff and sent files that the DHT still serves
+    Prune(PruneArgs),
+    /// Clear every record of your key from the DHT: handoff, display name, devices, sent files
+    Wipe(WipeArgs),
+    /// Save every record of this key on the DHT to an encrypted file
+    Backup(BackupArgs),
+    /// Republish the records saved by `cclink backup`
+    Restore(RestoreArgs),
+    /// Full-screen dashboard of sessions, handoffs, contacts and history
+    Ui,
+    /// Diagnose common setup problems (key file, DHT connectivity, Claude Code install)
+    Doctor,
+    /// Manage named contacts (aliases for public keys)
+    Contacts(ContactsArgs),
+    /// Register this machine as a named device, for handoffs meant for it alone
+    Device(DeviceArgs),
+    /// Publish a display name for your key, shown next to it by list, pickup and inspect
+    Profile(ProfileArgs),
+    /// Print shell integration (functions and a prompt segment) for eval
+    Env(EnvArgs),
+    /// Manage publisher keys pinned on first pickup
+    Trust(TrustArgs),
+    /// Compare a short code with another user to verify each other's keys
+    Verify(VerifyArgs),
+    /// Explain an error code: what it means, likely causes, and how to fix it
+    Explain(ExplainArgs),
+    /// Encrypt a file (up to 1 MiB, 37.5 KiB compressed) and publish it for `cclink recv`
+    Send(SendArgs),
+    /// Receive a file published with `cclink send`
+    Recv(RecvArgs),
+    /// Manage the secret key: backups and escrow shares, keyring passphrase, rotation, revocation
+    Key(KeyArgs),
+    /// Periodically re-fetch your handoff from the DHT and alert if it was changed or removed
+    Monitor(MonitorArgs),
+    /// Show where and when your recent handoffs were picked up
+    Receipts(ReceiptsArgs),
+    /// Work with the local log of handoffs and files sent and received
+    History(HistoryArgs),
+    /// List local agent sessions, or pick one to publish
+    Sessions(SessionsArgs),
+    /// Wait for new handoffs from trusted publishers and raise a desktop notification
+    Listen(ListenArgs),
+    /// Keep the unlocked key in memory for a while so commands stop prompting
+    Agent(AgentArgs),
 }
 
 #[derive(Parser)]
 pub struct InitArgs {
-    /// Import an existing keypair from file path or stdin (use - for stdin)
+    /// Import an existing keypair from file path or stdin (use - for stdin): a cclink
+    /// hex key or an OpenSSH ed25519 private key such as ~/.ssh/id_ed25519
     #[arg(long, value_name = "PATH")]
     pub import: Option<String>,
 
+    /// Restore the keypair from a 24-word recovery phrase (`cclink key backup --mnemonic`)
+    #[arg(long, conflicts_with = "import")]
+    pub from_mnemonic: bool,
+
+    /// Restore the keypair from a QR backup (`cclink key backup --qr`): a scanned
+    /// image file, or - to paste the code's text
+    #[arg(long, value_name = "SOURCE", conflicts_with_all = ["import", "from_mnemonic"])]
+    pub import_qr: Option<String>,
+
+    /// Add the identities in an age identity file (age-keygen or age plugin output), so
+    /// handoffs shared to their age recipients can be picked up. Keeps the current key.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["import", "from_mnemonic", "import_qr"])]
+    pub import_age: Option<std::path::PathBuf>,
+
     /// Skip overwrite confirmation prompt
     #[arg(long, short = 'y')]
     pub yes: bool,
@@ -66,10 +257,37 @@ pub struct InitArgs {
 
 #[derive(Parser)]
 pub struct PickupArgs {
-    /// z32-encoded public key of the Claude Code session publisher (defaults to own key)
-    #[arg(value_name = "PUBKEY")]
+    /// Publisher's z32-encoded public key or contact name (defaults to own key)
+    #[arg(value_name = "PUBKEY|CONTACT")]
     pub pubkey: Option<String>,
 
+    /// Pick up a handoff published with `cclink --code`, using the code it printed
+    #[arg(long, value_name = "CODE", conflicts_with_all = ["pubkey", "watch"])]
+    pub code: Option<String>,
+
+    /// Pick up the handoff carried by a `--qr-payload` QR code instead of looking it up:
+    /// a scanned image, the code's text, or - to paste the text
+    #[arg(
+        long,
+        value_name = "SOURCE",
+        conflicts_with_all = ["pubkey", "code", "watch"]
+    )]
+    pub from_qr: Option<String>,
+
+    /// Read the handoff from DIR, a folder shared with the publisher, instead of the DHT
+    /// (default: transport.dir in ~/.pubky/cclink.toml)
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["code", "from_qr", "watch"])]
+    pub dir: Option<std::path::PathBuf>,
+
+    /// Find the handoff on the local network (`cclink serve --lan`) instead of the DHT
+    #[arg(long, conflicts_with_all = ["code", "from_qr", "watch", "dir"])]
+    pub lan: bool,
+
+    /// Resume in a new tmux window instead of this terminal: in the current tmux session,
+    /// in SESSION with --tmux=SESSION, or in a detached "cclink" session outside tmux
+    #[arg(long, value_name = "SESSION", num_args = 0..=1, require_equals = true)]
+    pub tmux: Option<Option<String>>,
+
     /// Skip confirmation prompt and launch immediately
     #[arg(long, short = 'y')]
     pub yes: bool,
@@ -77,6 +295,338 @@ pub struct PickupArgs {
     /// Render a QR code showing the session ID
     #[arg(long)]
     pub qr: bool,
+
+    /// Refuse to pick up if the publisher's key differs from the one pinned earlier
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Pick up even if the handoff is older than one already seen from the publisher
+    #[arg(long)]
+    pub allow_rollback: bool,
+
+    /// Wait for the publisher's next handoff instead of picking up the current one
+    #[arg(long)]
+    pub watch: bool,
+
+    /// If the project does not exist here, clone the publisher's git remote into DIR and resume there
+    #[arg(long, value_name = "DIR")]
+    pub clone_to: Option<std::path::PathBuf>,
+
+    /// Resume in DIR instead of the published project path (see [paths] in ~/.pubky/cclink.toml)
+    #[arg(long, value_name = "DIR", conflicts_with = "clone_to")]
+    pub cd: Option<std::path::PathBuf>,
+
+    /// Extra arguments for the agent's resume command, after `--`
+    /// (e.g. `cclink pickup -- --model opus`)
+    #[arg(last = true, value_name = "AGENT_ARGS")]
+    pub agent_args: Vec<String>,
+}
+
+#[derive(Parser)]
+pub struct ExportArgs {
+    /// Write the armored handoff to FILE instead of stdout
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<std::path::PathBuf>,
+}
+
+#[derive(Parser)]
+pub struct ServeArgs {
+    /// Advertise the handoff over mDNS and serve it over HTTP on the local network
+    #[arg(long, required = true)]
+    pub lan: bool,
+
+    /// Stop after the first machine has fetched the handoff, instead of when it expires
+    #[arg(long)]
+    pub once: bool,
+}
+
+#[derive(Parser)]
+pub struct ImportArgs {
+    /// File holding the armored handoff, or - for stdin (the default)
+    #[arg(value_name = "FILE")]
+    pub file: Option<std::path::PathBuf>,
+
+    /// Skip confirmation prompt and launch immediately
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+
+    /// Refuse to pick up if the publisher's key differs from the one pinned earlier
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Pick up even if the handoff is older than one already seen from the publisher
+    #[arg(long)]
+    pub allow_rollback: bool,
+
+    /// If the project does not exist here, clone the publisher's git remote into DIR and resume there
+    #[arg(long, value_name = "DIR")]
+    pub clone_to: Option<std::path::PathBuf>,
+
+    /// Resume in DIR instead of the published project path (see [paths] in ~/.pubky/cclink.toml)
+    #[arg(long, value_name = "DIR", conflicts_with = "clone_to")]
+    pub cd: Option<std::path::PathBuf>,
+
+    /// Extra arguments for the agent's resume command, after `--` (e.g. `-- --model opus`)
+    #[arg(last = true, value_name = "AGENT_ARGS")]
+    pub agent_args: Vec<String>,
+}
+
+#[derive(Parser)]
+pub struct InspectArgs {
+    /// Publisher's z32-encoded public key or contact name (defaults to own key)
+    #[arg(value_name = "PUBKEY|CONTACT")]
+    pub pubkey: Option<String>,
+}
+
+#[derive(Parser)]
+pub struct SendArgs {
+    /// File to send (up to 1 MiB, compressing to at most 37.5 KiB)
+    #[arg(value_name = "FILE")]
+    pub file: std::path::PathBuf,
+
+    /// Encrypt for a specific recipient (z32-encoded public key or contact name)
+    #[arg(long, value_name = "PUBKEY|CONTACT")]
+    pub share: Option<String>,
+
+    /// Time-to-live: seconds, or a duration like 30m, 2h, 7d (at most 30d)
+    #[arg(long, default_value = "24h", value_parser = crate::util::parse_ttl)]
+    pub ttl: u64,
+}
+
+#[derive(Parser)]
+pub struct RecvArgs {
+    /// Sender's z32-encoded public key or contact name (defaults to own key)
+    #[arg(value_name = "PUBKEY|CONTACT")]
+    pub pubkey: Option<String>,
+
+    /// Where to write the file (defaults to the sent file name in the current directory)
+    #[arg(long, short = 'o', value_name = "PATH")]
+    pub output: Option<std::path::PathBuf>,
+
+    /// Overwrite the output file if it already exists
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Parser)]
+pub struct MonitorArgs {
+    /// Time between checks: seconds, or a duration like 30m, 2h
+    #[arg(long, default_value = "5m", value_parser = crate::util::parse_duration)]
+    pub interval: u64,
+
+    /// Check once and exit (non-zero on an alert), e.g. from cron
+    #[arg(long)]
+    pub once: bool,
+}
+
+#[derive(Parser)]
+pub struct ListenArgs {
+    /// Publishers to watch (default: your own key and every pinned publisher)
+    #[arg(value_name = "PUBKEY|CONTACT")]
+    pub publishers: Vec<String>,
+
+    /// Time between DHT checks: seconds, or a duration like 30s, 2m
+    #[arg(long, default_value = "30s", value_parser = crate::util::parse_duration)]
+    pub interval: u64,
+}
+
+#[derive(Parser)]
+pub struct AgentArgs {
+    /// How long the key stays unlocked: seconds, or a duration like 30m, 8h
+    #[arg(long, default_value = "1h", value_parser = crate::util::parse_duration)]
+    pub lifetime: u64,
+
+    /// Stay in the foreground instead of starting the agent in the background
+    #[arg(long)]
+    pub foreground: bool,
+
+    /// Stop the running agent, locking the key again
+    #[arg(long, conflicts_with_all = ["lifetime", "foreground"])]
+    pub stop: bool,
+
+    /// Serve a seed read from stdin (the background agent process)
+    #[arg(long, hide = true)]
+    pub serve: bool,
+}
+
+#[derive(Parser)]
+pub struct SessionsArgs {
+    /// Only sessions of this project directory (subdirectories and worktrees count too)
+    #[arg(long, value_name = "DIR")]
+    pub project: Option<std::path::PathBuf>,
+
+    /// Pick a session and publish it; publish options go before the subcommand,
+    /// e.g. `cclink --ttl 1h sessions --pick`
+    #[arg(long)]
+    pub pick: bool,
+}
+
+#[derive(Parser)]
+pub struct ListArgs {
+    /// Publishers to look up: z32 public keys or contact names (default: yourself)
+    #[arg(value_name = "PUBKEY|CONTACT")]
+    pub pubkeys: Vec<String>,
+
+    /// Look up yourself and every contact
+    #[arg(long)]
+    pub all: bool,
+
+    /// Only handoffs whose project path matches this glob (e.g. '*/code/app*')
+    #[arg(long, value_name = "GLOB")]
+    pub project: Option<String>,
+
+    /// Only burn-after-read handoffs
+    #[arg(long)]
+    pub burn: bool,
+
+    /// Only handoffs shared with a recipient
+    #[arg(long)]
+    pub shared: bool,
+
+    /// Only expired handoffs the DHT still serves, instead of active ones
+    #[arg(long)]
+    pub expired: bool,
+
+    /// Sort order
+    #[arg(long, value_enum)]
+    pub sort: Option<crate::commands::list::SortKey>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "table")]
+    pub format: crate::commands::list::Format,
+
+    /// Look up again every INTERVAL (default 10s) and redraw until Ctrl-C
+    #[arg(
+        long,
+        value_name = "INTERVAL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "10s",
+        value_parser = crate::util::parse_duration
+    )]
+    pub watch: Option<u64>,
+}
+
+#[derive(Parser)]
+pub struct ReceiptsArgs {
+    /// How many of the most recent handoffs to look up
+    #[arg(long, default_value_t = 10)]
+    pub limit: usize,
+
+    /// Revoke your current handoff if its picker's receipt has arrived
+    #[arg(long)]
+    pub revoke: bool,
+}
+
+#[derive(Parser)]
+pub struct HistoryArgs {
+    /// Without a subcommand, show the whole history as a table
+    #[command(subcommand)]
+    pub command: Option<HistoryCommand>,
+}
+
+#[derive(Subcommand)]
+pub enum HistoryCommand {
+    /// Show history entries as a timeline, oldest first
+    Show(HistoryFilterArgs),
+    /// Write history entries to stdout as CSV or JSON lines
+    Export {
+        /// Output format
+        #[arg(long, value_enum, default_value = "jsonl")]
+        format: crate::history::Format,
+        #[command(flatten)]
+        filter: HistoryFilterArgs,
+    },
+}
+
+#[derive(clap::Args, Default)]
+pub struct HistoryFilterArgs {
+    /// Only entries from this long ago or later: seconds, or a duration like 30d
+    #[arg(long, value_parser = crate::util::parse_duration)]
+    pub since: Option<u64>,
+    /// Only handoffs and files sent (out) or received (in)
+    #[arg(long, value_enum)]
+    pub direction: Option<crate::history::Direction>,
+    /// Only entries exchanged with this contact name or z32 public key
+    #[arg(long, value_name = "PUBKEY|CONTACT")]
+    pub peer: Option<String>,
+    /// Only handoffs of this project directory or its subdirectories
+    #[arg(long, value_name = "DIR")]
+    pub project: Option<std::path::PathBuf>,
+}
+
+#[derive(Parser)]
+pub struct KeyArgs {
+    #[command(subcommand)]
+    pub command: KeyCommand,
+}
+
+#[derive(Subcommand)]
+pub enum KeyCommand {
+    /// Split the secret key into Shamir shares for escrow
+    Split {
+        /// Number of shares needed to reconstruct the key
+        #[arg(long, default_value_t = 2)]
+        threshold: u8,
+        /// Number of shares to create
+        #[arg(long, default_value_t = 3)]
+        shares: u8,
+        /// Also render each share as a QR code
+        #[arg(long)]
+        qr: bool,
+    },
+    /// Reconstruct the secret key from Shamir shares (prints hex for `cclink init --import -`)
+    Combine,
+    /// Print a backup of the secret key
+    #[command(group(clap::ArgGroup::new("format").required(true)))]
+    Backup {
+        /// As a 24-word BIP39 recovery phrase (restore with `cclink init --from-mnemonic`)
+        #[arg(long, group = "format")]
+        mnemonic: bool,
+        /// As a QR code of the passphrase-encrypted key (restore with `cclink init --import-qr`)
+        #[arg(long, group = "format")]
+        qr: bool,
+        /// With --qr, write the code to this SVG file for printing instead of the terminal
+        #[arg(long, short = 'o', value_name = "FILE", requires = "qr")]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Store the key passphrase in the OS keyring so commands stop prompting for it
+    Remember,
+    /// Remove the key passphrase from the OS keyring
+    Forget,
+    /// Sign a revocation certificate for the current key, to keep offline
+    RevokeCert {
+        /// Reason shown to anyone picking up from the revoked key
+        #[arg(long)]
+        reason: Option<String>,
+        /// Write the certificate to this file instead of stdout
+        #[arg(long, short = 'o', value_name = "FILE")]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Publish a revocation certificate so pickups stop trusting its key
+    PublishRevocation {
+        /// Certificate file from `cclink key revoke-cert` (`-` reads stdin)
+        #[arg(value_name = "FILE")]
+        cert: String,
+    },
+    /// Replace the key with a new one, announcing the change with a cross-signed record
+    Rotate {
+        /// How long the old key keeps serving its handoff and decrypting pickups (e.g. 7d)
+        #[arg(long, default_value = "7d", value_parser = crate::util::parse_duration)]
+        grace: u64,
+        /// Store the new key without passphrase protection
+        #[arg(long)]
+        no_passphrase: bool,
+        /// Skip confirmation prompt
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+    /// Time key-file passphrase derivation on this machine and suggest [kdf] settings
+    Bench {
+        /// Unlock time to aim for, in milliseconds
+        #[arg(long, value_name = "MS", default_value_t = 1000)]
+        target: u64,
+    },
 }
 
 #[derive(Parser)]
@@ -93,3 +643,154 @@ pub struct RevokeArgs {
     #[arg(long, short = 'y')]
     pub yes: bool,
 }
+
+#[derive(Parser)]
+pub struct WipeArgs {
+    /// Skip the confirmation prompt
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+}
+
+#[derive(Parser)]
+pub struct BackupArgs {
+    /// File to write the backup to
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: std::path::PathBuf,
+}
+
+#[derive(Parser)]
+pub struct RestoreArgs {
+    /// Backup file written by `cclink backup`
+    pub file: std::path::PathBuf,
+}
+
+#[derive(Parser)]
+pub struct PruneArgs {
+    /// Prune by age instead: records published at least this long ago, expired or not (e.g. 7d)
+    #[arg(long, value_name = "DURATION", value_parser = crate::util::parse_duration)]
+    pub older_than: Option<u64>,
+
+    /// Show what would be pruned without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Parser)]
+pub struct ContactsArgs {
+    #[command(subcommand)]
+    pub command: ContactsCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ContactsCommand {
+    /// Add or update a contact
+    Add {
+        /// Short name to use in place of the key
+        name: String,
+        /// z32-encoded public key (as shown by `cclink whoami`)
+        pubkey: String,
+    },
+    /// List contacts
+    List,
+    /// Remove a contact
+    Remove {
+        /// Contact name
+        name: String,
+    },
+}
+
+#[derive(Parser)]
+pub struct DeviceArgs {
+    #[command(subcommand)]
+    pub command: DeviceCommand,
+}
+
+#[derive(Subcommand)]
+pub enum DeviceCommand {
+    /// Give this machine its own device key and add it to the registry under NAME
+    Register {
+        /// Short name for this machine, used with `cclink --to`
+        name: String,
+    },
+    /// List registered devices
+    List,
+    /// Remove a device from the registry
+    Remove {
+        /// Device name
+        name: String,
+    },
+}
+
+#[derive(Parser)]
+pub struct ProfileArgs {
+    #[command(subcommand)]
+    pub command: ProfileCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ProfileCommand {
+    /// Sign and publish NAME as the display name of your key
+    SetName {
+        /// Display name, e.g. "Alice"
+        name: String,
+    },
+    /// Show the display name published for a key (defaults to own key)
+    Show {
+        /// z32-encoded public key or contact name
+        #[arg(value_name = "PUBKEY|CONTACT")]
+        pubkey: Option<String>,
+    },
+}
+
+#[derive(Parser)]
+pub struct EnvArgs {
+    /// Shell to emit integration for (detected from $SHELL if omitted)
+    #[arg(value_enum)]
+    pub shell: Option<Shell>,
+
+    /// Print the number of active handoffs from the local cache (for prompts; no network)
+    #[arg(long, conflicts_with = "shell")]
+    pub prompt: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+#[derive(Parser)]
+pub struct VerifyArgs {
+    /// The other party's z32-encoded public key or contact name
+    #[arg(value_name = "PUBKEY|CONTACT")]
+    pub pubkey: String,
+
+    /// Give up if the other party has not joined within this long (e.g. 5m)
+    #[arg(long, default_value = "5m", value_parser = crate::util::parse_duration)]
+    pub timeout: u64,
+}
+
+#[derive(Parser)]
+pub struct TrustArgs {
+    #[command(subcommand)]
+    pub command: TrustCommand,
+}
+
+#[derive(Subcommand)]
+pub enum TrustCommand {
+    /// List pinned publisher keys
+    List,
+    /// Forget a pinned key (by name or public key) so the next pickup re-pins it
+    Remove {
+        /// Contact name or z32 public key
+        name: String,
+    },
+}
+
+#[derive(Parser)]
+pub struct ExplainArgs {
+    /// Error code, e.g. E006 (lists all codes if omitted)
+    #[arg(value_name = "CODE")]
+    pub code: Option<String>,
+}
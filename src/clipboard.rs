@@ -0,0 +1,127 @@
+//! Clipboard providers, tried in order until one accepts the text.
+//!
+//! The native system clipboard (`arboard`) fails over SSH and inside many terminal
+//! multiplexers, where there is no display server to talk to. The OSC 52 provider
+//! instead writes an escape sequence to the controlling terminal, which asks the
+//! *local* terminal emulator to set its clipboard — so copies from a remote shell
+//! still land on the machine in front of the user. Whether the terminal honours
+//! OSC 52 cannot be detected; a successful write is reported as best-effort.
+
+use std::io::{IsTerminal, Write};
+
+use base64::Engine;
+
+/// A way of putting text on the user's clipboard.
+pub trait ClipboardProvider {
+    /// Short name shown to the user ("system clipboard", "OSC 52").
+    fn name(&self) -> &'static str;
+
+    /// Copy `text`, failing if this provider is unavailable here.
+    fn copy(&self, text: &str) -> anyhow::Result<()>;
+}
+
+/// The native clipboard via `arboard` (X11/Wayland, macOS, Windows).
+pub struct NativeClipboard;
+
+impl ClipboardProvider for NativeClipboard {
+    fn name(&self) -> &'static str {
+        "system clipboard"
+    }
+
+    fn copy(&self, text: &str) -> anyhow::Result<()> {
+        arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set_text(text))
+            .map_err(|e| anyhow::anyhow!("system clipboard unavailable: {}", e))
+    }
+}
+
+/// OSC 52 escape sequence written to the controlling terminal.
+pub struct Osc52Clipboard;
+
+impl ClipboardProvider for Osc52Clipboard {
+    fn name(&self) -> &'static str {
+        "OSC 52"
+    }
+
+    fn copy(&self, text: &str) -> anyhow::Result<()> {
+        if std::env::var("TERM").is_ok_and(|t| t == "dumb") {
+            anyhow::bail!("terminal does not support escape sequences");
+        }
+        let sequence = osc52_sequence(text, std::env::var_os("TMUX").is_some());
+        let mut tty = open_tty()?;
+        tty.write_all(sequence.as_bytes())?;
+        tty.flush()?;
+        Ok(())
+    }
+}
+
+/// Providers in the order they are tried: native, then OSC 52.
+pub fn providers() -> Vec<Box<dyn ClipboardProvider>> {
+    vec![Box::new(NativeClipboard), Box::new(Osc52Clipboard)]
+}
+
+/// Copy `text` with the first provider that succeeds, returning its name.
+/// `None` means no provider worked and the caller should ask for a manual copy.
+pub fn copy(text: &str) -> Option<&'static str> {
+    providers().into_iter().find_map(|provider| {
+        match provider.copy(text) {
+            Ok(()) => Some(provider.name()),
+            Err(e) => {
+                tracing::debug!(provider = provider.name(), error = %e, "clipboard provider failed");
+                None
+            }
+        }
+    })
+}
+
+/// Read text from the native clipboard. OSC 52 reads are not attempted: few terminals
+/// allow them.
+pub fn paste() -> anyhow::Result<String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|e| anyhow::anyhow!("system clipboard unavailable: {}", e))
+}
+
+/// Build the OSC 52 "set clipboard" sequence for `text`.
+///
+/// Inside tmux the sequence is wrapped in a DCS passthrough (with inner escapes
+/// doubled) so tmux forwards it to the outer terminal.
+fn osc52_sequence(text: &str, in_tmux: bool) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let osc = format!("\x1b]52;c;{}\x07", encoded);
+    if in_tmux {
+        format!("\x1bPtmux;{}\x1b\\", osc.replace('\x1b', "\x1b\x1b"))
+    } else {
+        osc
+    }
+}
+
+/// The controlling terminal: `/dev/tty` on Unix, otherwise stderr if it is a terminal.
+fn open_tty() -> anyhow::Result<Box<dyn Write>> {
+    #[cfg(unix)]
+    if let Ok(tty) = std::fs::OpenOptions::new().write(true).open("/dev/tty") {
+        return Ok(Box::new(tty));
+    }
+    if std::io::stderr().is_terminal() {
+        return Ok(Box::new(std::io::stderr()));
+    }
+    anyhow::bail!("no controlling terminal")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("hi", false), "\x1b]52;c;aGk=\x07");
+    }
+
+    #[test]
+    fn test_osc52_sequence_tmux_passthrough() {
+        assert_eq!(
+            osc52_sequence("hi", true),
+            "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+/// Agent command — unlocks the key once and keeps the seed in a background process
+/// (see `crate::keys::agent`) so later commands do not prompt for the passphrase.
+///
+/// The foreground process prompts and decrypts, then hands the seed to a copy of
+/// itself (`cclink agent --serve`) over a pipe and exits; the copy serves it until its
+/// lifetime ends.
+use std::io::{BufRead, Write};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::Context;
+use zeroize::Zeroizing;
+
+use crate::cli::AgentArgs;
+use crate::keys::agent;
+use crate::ui::Paint;
+
+pub fn run_agent(args: AgentArgs) -> anyhow::Result<()> {
+    if args.stop {
+        if agent::stop()? {
+            crate::status!("Agent stopped; the key is locked again.");
+        } else {
+            crate::status!("No agent is running.");
+        }
+        return Ok(());
+    }
+    let lifetime = Duration::from_secs(args.lifetime.max(1));
+    if args.serve {
+        return serve_from_stdin(lifetime);
+    }
+
+    // ── 1. Unlock the key ────────────────────────────────────────────────
+    let key_path = crate::keys::store::secret_key_path()?;
+    let keypair = crate::keys::store::load_keypair()?;
+    let raw = std::fs::read(&key_path)
+        .with_context(|| format!("Failed to read key file: {}", key_path.display()))?;
+    if !raw.starts_with(b"CCLINKEK") {
+        crate::status!(
+            "The key file is not passphrase-protected; there is nothing for an agent to unlock."
+        );
+        return Ok(());
+    }
+    let seed = Zeroizing::new(keypair.secret_key());
+    let key_hash = agent::key_file_hash(&raw);
+
+    // ── 2. Serve, here or in the background ──────────────────────────────
+    let until = crate::util::human_duration(lifetime.as_secs());
+    if args.foreground {
+        crate::status!("Key unlocked for {} (Ctrl-C to lock it again)", until);
+        return agent::serve(seed, &key_hash, lifetime);
+    }
+    let mut command = Command::new(std::env::current_exe()?);
+    if let Some(profile) = crate::keys::store::profile() {
+        command.args(["--profile", profile]);
+    }
+    let mut child = command
+        .args(["agent", "--serve", "--lifetime"])
+        .arg(lifetime.as_secs().to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to start the agent process")?;
+    {
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let line = Zeroizing::new(format!("{} {}\n", crate::util::to_hex(&*seed), key_hash));
+        stdin.write_all(line.as_bytes())?;
+    }
+    crate::status!(
+        "Agent started (pid {}); the key stays unlocked for {}.",
+        child.id(),
+        until.identity()
+    );
+    crate::status!("Lock it sooner with: cclink agent --stop");
+    Ok(())
+}
+
+/// The background agent: read `<seed hex> <key hash>` from stdin and serve it.
+fn serve_from_stdin(lifetime: Duration) -> anyhow::Result<()> {
+    let mut line = Zeroizing::new(String::new());
+    std::io::stdin().lock().read_line(&mut line)?;
+    let (seed_hex, key_hash) = line
+        .trim()
+        .split_once(' ')
+        .ok_or_else(|| anyhow::anyhow!("agent expects '<seed> <hash>' on stdin"))?;
+    let bytes = Zeroizing::new(crate::util::from_hex(seed_hex)?);
+    let mut seed = Zeroizing::new([0u8; 32]);
+    if bytes.len() != seed.len() {
+        anyhow::bail!("agent expects a 32-byte seed");
+    }
+    seed.copy_from_slice(&bytes);
+    agent::serve(seed, key_hash, lifetime)
+}
@@ -0,0 +1,192 @@
+/// Backup and restore commands — save every record this identity keeps on the DHT to
+/// one encrypted file, and put them back.
+///
+/// DHT nodes drop packets nobody republishes, so a device registry or display name
+/// set up once can vanish. `cclink backup` saves the signed packets of the handoff,
+/// the display name, the device registry, the revocation certificate and key
+/// transition, and the file slot of every recipient this machine sent to, chunks
+/// included, age-encrypted to this key. `cclink restore` republishes them unchanged:
+/// the packets carry their own signatures, and a slot whose DHT copy is already as
+/// new is left alone.
+use std::path::Path;
+use std::time::SystemTime;
+
+use anyhow::Context;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::transport::DhtClient;
+use crate::ui::Paint;
+
+/// Version of the archive layout.
+const ARCHIVE_VERSION: u32 = 1;
+
+/// Decrypted contents of a backup file.
+#[derive(Serialize, Deserialize)]
+struct Archive {
+    version: u32,
+    /// Unix seconds when the backup was taken.
+    created_at: u64,
+    /// z32 key of the identity backed up.
+    pubkey: String,
+    slots: Vec<Slot>,
+}
+
+/// The packets of one slot, base64 of `SignedPacket::serialize`, in publish order.
+#[derive(Serialize, Deserialize)]
+struct Slot {
+    label: String,
+    packets: Vec<String>,
+}
+
+pub fn run_backup(args: crate::cli::BackupArgs) -> anyhow::Result<()> {
+    // ── 1. Load keypair and list slots ──────────────────────────────────
+    let keypair = crate::keys::store::load_keypair()?;
+    let own = keypair.public_key();
+    let mut slots = vec![
+        ("handoff".to_string(), own.clone()),
+        (
+            "display name".to_string(),
+            crate::crypto::profile_keypair(&own).public_key(),
+        ),
+        (
+            "device registry".to_string(),
+            crate::crypto::devices_keypair(&keypair).public_key(),
+        ),
+        (
+            "revocation certificate".to_string(),
+            crate::crypto::revocation_keypair(&own).public_key(),
+        ),
+        (
+            "key transition".to_string(),
+            crate::crypto::transition_keypair(&own).public_key(),
+        ),
+    ];
+    slots.extend(
+        crate::commands::prune::file_targets(&keypair)?
+            .into_iter()
+            .map(|target| (target.label, target.slot.public_key())),
+    );
+
+    // ── 2. Fetch what each slot holds ───────────────────────────────────
+    let client = DhtClient::new()?;
+    let mut archive = Archive {
+        version: ARCHIVE_VERSION,
+        created_at: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs(),
+        pubkey: own.to_z32(),
+        slots: vec![],
+    };
+    for (label, slot) in slots {
+        let packets = client
+            .snapshot(&slot)
+            .with_context(|| format!("Failed to back up the {}", label))?;
+        if packets.is_empty() {
+            crate::status!("  {} (nothing published)", label);
+            continue;
+        }
+        crate::status!("  {}", label);
+        archive.slots.push(Slot {
+            label,
+            packets: packets
+                .iter()
+                .map(|p| base64::engine::general_purpose::STANDARD.encode(p.serialize()))
+                .collect(),
+        });
+    }
+    if archive.slots.is_empty() {
+        anyhow::bail!("nothing of {} is on the DHT to back up", archive.pubkey);
+    }
+
+    // ── 3. Encrypt to this key and write ────────────────────────────────
+    let json = serde_json::to_vec(&archive)?;
+    let recipient =
+        crate::crypto::age_recipient(&crate::crypto::ed25519_to_x25519_public(&keypair));
+    let ciphertext = crate::crypto::age_encrypt(&json, &recipient)?;
+    std::fs::write(&args.output, ciphertext)
+        .with_context(|| format!("Failed to write {}", args.output.display()))?;
+    crate::status!(
+        "{} Backed up {} record(s) to {}",
+        "✓".success(),
+        archive.slots.len(),
+        args.output.display()
+    );
+    Ok(())
+}
+
+pub fn run_restore(args: crate::cli::RestoreArgs) -> anyhow::Result<()> {
+    // ── 1. Decrypt the archive with this key ────────────────────────────
+    let keypair = crate::keys::store::load_keypair()?;
+    let archive = read_archive(&args.file, &keypair)?;
+    if archive.version > ARCHIVE_VERSION {
+        anyhow::bail!(
+            "{} is a version {} backup; this cclink reads up to version {}",
+            args.file.display(),
+            archive.version,
+            ARCHIVE_VERSION
+        );
+    }
+    crate::status!(
+        "Restoring {} record(s) backed up {}",
+        archive.slots.len(),
+        crate::util::format_utc(archive.created_at)
+    );
+
+    // ── 2. Republish every packet the DHT no longer has ─────────────────
+    let client = DhtClient::new()?;
+    let mut failed = 0;
+    for slot in &archive.slots {
+        match restore_slot(&client, slot) {
+            Ok(0) => crate::status!("  {} (already current)", slot.label),
+            Ok(_) => crate::status!("  Restored {}", slot.label),
+            Err(e) => {
+                failed += 1;
+                eprintln!("  Failed to restore {}: {}", slot.label, e);
+            }
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!(
+            "{} of {} record(s) failed to restore",
+            failed,
+            archive.slots.len()
+        );
+    }
+    Ok(())
+}
+
+fn read_archive(path: &Path, keypair: &pkarr::Keypair) -> anyhow::Result<Archive> {
+    let ciphertext =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let identity = crate::crypto::age_identity(&crate::crypto::ed25519_to_x25519_secret(keypair));
+    let json = crate::crypto::age_decrypt(&ciphertext, &identity).map_err(|e| {
+        anyhow::anyhow!(
+            "cannot decrypt {} with this key (was it made by another identity?): {}",
+            path.display(),
+            e
+        )
+    })?;
+    serde_json::from_slice(&json)
+        .map_err(|e| anyhow::anyhow!("{} is not a cclink backup: {}", path.display(), e))
+}
+
+/// Republish the packets of `slot` that are newer than the DHT's copy. Returns how
+/// many were republished.
+fn restore_slot(client: &DhtClient, slot: &Slot) -> anyhow::Result<usize> {
+    let mut restored = 0;
+    for encoded in &slot.packets {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| anyhow::anyhow!("corrupted packet: {}", e))?;
+        let packet = pkarr::SignedPacket::deserialize(&bytes)
+            .map_err(|e| anyhow::anyhow!("corrupted packet: {}", e))?;
+        let current = client.packet_timestamp(&packet.public_key());
+        if current.is_some_and(|t| t >= packet.timestamp()) {
+            continue;
+        }
+        client.republish(&packet)?;
+        restored += 1;
+    }
+    Ok(restored)
+}
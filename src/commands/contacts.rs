@@ -0,0 +1,60 @@
+/// Contacts command — manage the local alias book used by `--share` and `pickup`.
+use crate::cli::ContactsCommand;
+use crate::contacts::{contacts_path, ContactBook};
+use crate::ui::Paint;
+
+pub fn run_contacts(args: crate::cli::ContactsArgs) -> anyhow::Result<()> {
+    let path = contacts_path()?;
+    let mut book = ContactBook::load_from(&path)?;
+
+    match args.command {
+        ContactsCommand::Add { name, pubkey } => {
+            crate::keys::store::ensure_key_dir()?;
+            let previous = book.add(&name, &pubkey)?;
+            book.save_to(&path)?;
+            match previous {
+                Some(old) => crate::status!("Updated {} (was {}).", name.identity(), old),
+                None => crate::status!("Added {}.", name.identity()),
+            }
+        }
+        ContactsCommand::List => {
+            if book.is_empty() {
+                println!("No contacts. Add one with: cclink contacts add <name> <pubkey>");
+                return Ok(());
+            }
+            // Pickup counts are a nicety; a missing state database shows none.
+            let state = crate::state::State::open()
+                .map_err(|e| tracing::debug!(error = %e, "state database unavailable"))
+                .ok();
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::SystemTime::UNIX_EPOCH)?
+                .as_secs();
+            let mut table = comfy_table::Table::new();
+            table.set_header(vec!["Name", "Public Key", "Last pickup"]);
+            for (name, z32) in book.iter() {
+                let activity = state
+                    .as_ref()
+                    .and_then(|state| state.contact_activity(z32).ok().flatten());
+                let last_pickup = match activity {
+                    Some(a) => format!(
+                        "{} ago ({} total)",
+                        crate::util::human_duration(now.saturating_sub(a.last_pickup_at)),
+                        a.pickups
+                    ),
+                    None => "never".to_string(),
+                };
+                table.add_row(vec![name, z32, last_pickup.as_str()]);
+            }
+            println!("{table}");
+        }
+        ContactsCommand::Remove { name } => {
+            if book.remove(&name).is_none() {
+                anyhow::bail!("no contact named '{}'", name);
+            }
+            book.save_to(&path)?;
+            crate::status!("Removed {}.", name);
+        }
+    }
+
+    Ok(())
+}
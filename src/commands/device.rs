@@ -0,0 +1,82 @@
+/// Device command — register this machine by name and manage the device registry.
+///
+/// The registry is published to the DHT (see `crate::record::devices`), so every
+/// machine holding the key sees the same devices; each device's secret stays on it.
+use crate::cli::DeviceCommand;
+use crate::keys::device::Device;
+use crate::ui::Paint;
+
+pub fn run_device(args: crate::cli::DeviceArgs) -> anyhow::Result<()> {
+    let keypair = crate::keys::store::load_keypair()?;
+    let client = crate::transport::DhtClient::new()?;
+    let mut registry = client.resolve_devices(&keypair)?;
+    let local = crate::keys::device::load()?;
+
+    match args.command {
+        DeviceCommand::Register { name } => {
+            // Keep this machine's device key across a rename.
+            let device = match local {
+                Some(device) => {
+                    if device.name != name
+                        && registry.get(&device.name) == Some(device.recipient().as_str())
+                    {
+                        registry.remove(&device.name);
+                    }
+                    Device {
+                        name: name.clone(),
+                        identity: device.identity,
+                    }
+                }
+                None => Device::generate(&name),
+            };
+            let recipient = device.recipient();
+            if registry.get(&name).is_some_and(|r| r != recipient) {
+                anyhow::bail!(
+                    "device name '{}' is already registered to another machine; \
+                     remove it first with `cclink device remove {}`",
+                    name,
+                    name
+                );
+            }
+            registry.add(&name, &recipient)?;
+            client.publish_devices(&keypair, &registry)?;
+            crate::keys::store::ensure_key_dir()?;
+            crate::keys::device::save(&device)?;
+            crate::status!(
+                "Registered this machine as {}. Publish to it with: cclink --to {}",
+                name.identity(),
+                name
+            );
+        }
+        DeviceCommand::List => {
+            if registry.is_empty() {
+                println!("No devices. Register this machine with: cclink device register <name>");
+                return Ok(());
+            }
+            let this = local.as_ref().map(Device::recipient);
+            let mut table = comfy_table::Table::new();
+            table.set_header(vec!["Name", "Recipient", ""]);
+            for (name, recipient) in registry.iter() {
+                let marker = if this.as_deref() == Some(recipient) {
+                    "this machine"
+                } else {
+                    ""
+                };
+                table.add_row(vec![name, recipient, marker]);
+            }
+            println!("{table}");
+        }
+        DeviceCommand::Remove { name } => {
+            let Some(recipient) = registry.remove(&name) else {
+                anyhow::bail!("no device named '{}'", name);
+            };
+            client.publish_devices(&keypair, &registry)?;
+            if local.is_some_and(|device| device.recipient() == recipient) {
+                std::fs::remove_file(crate::keys::device::device_path()?)?;
+            }
+            crate::status!("Removed {}.", name);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,245 @@
+/// Doctor command — runs local and network diagnostics, printing pass/fail for each
+/// check together with a remediation hint.
+///
+/// Checks: key file presence, 0600 permissions, key file format (CCLINKEK header or
+/// plaintext hex), `~/.claude/projects/` presence, `claude` on PATH, and Mainline DHT
+/// reachability. Nothing is decrypted and no passphrase is requested.
+use crate::ui::Paint;
+
+/// Outcome of a single diagnostic check.
+#[derive(Debug, PartialEq)]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// A finished check: what was checked, how it went, and how to fix it.
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Check {
+            name,
+            status: Status::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Check {
+            name,
+            status: Status::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Check {
+            name,
+            status: Status::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn print(&self) {
+        let marker = match self.status {
+            Status::Pass => format!("{}", "ok  ".success()),
+            Status::Warn => format!("{}", "warn".warning()),
+            Status::Fail => format!("{}", "FAIL".error()),
+        };
+        println!("[{}] {}: {}", marker, self.name, self.detail);
+        if let Some(ref hint) = self.hint {
+            println!("       {}", hint);
+        }
+    }
+}
+
+/// Run all diagnostics. Returns an error if any check failed (warnings do not fail).
+pub fn run_doctor() -> anyhow::Result<()> {
+    let mut failures = 0;
+    let mut report = |check: Check| {
+        check.print();
+        if check.status == Status::Fail {
+            failures += 1;
+        }
+    };
+
+    // ── 1. Key file ──────────────────────────────────────────────────────
+    for check in check_key_file()? {
+        report(check);
+    }
+
+    // ── 2. Claude Code installation ──────────────────────────────────────
+    report(check_claude_projects());
+    report(check_claude_binary());
+
+    // ── 3. DHT connectivity (slowest — runs last) ────────────────────────
+    report(check_dht());
+
+    println!();
+    if failures > 0 {
+        anyhow::bail!("{} check(s) failed", failures);
+    }
+    println!("{}", "All checks passed.".success());
+    Ok(())
+}
+
+/// Existence, permissions, and format of `~/.pubky/secret_key`.
+fn check_key_file() -> anyhow::Result<Vec<Check>> {
+    let path = crate::keys::store::secret_key_path()?;
+    if !path.exists() {
+        return Ok(vec![Check::fail(
+            "key file",
+            format!("{} not found", path.display()),
+            "Run `cclink init` to generate a keypair.",
+        )]);
+    }
+
+    let mut checks = vec![Check::pass("key file", path.display().to_string())];
+
+    checks.push(match crate::keys::store::check_key_permissions(&path) {
+        Ok(()) => Check::pass("key permissions", "0600"),
+        Err(_) => Check::fail(
+            "key permissions",
+            "readable by group or others (expected 0600)",
+            format!("Fix with: chmod 600 {}", path.display()),
+        ),
+    });
+
+    checks.push(match std::fs::read(&path) {
+        Ok(raw) => check_key_format(&raw, &path),
+        Err(e) => Check::fail(
+            "key format",
+            format!("unreadable: {}", e),
+            "Check file ownership, or re-import the key with `cclink init --import`.",
+        ),
+    });
+
+    Ok(checks)
+}
+
+/// Classify key file contents without decrypting anything.
+fn check_key_format(raw: &[u8], path: &std::path::Path) -> Check {
+    if raw.starts_with(b"CCLINKEK") {
+        return match crate::crypto::key_envelope_params(raw) {
+            Ok(params) => Check::pass(
+                "key format",
+                format!(
+                    "passphrase-protected (Argon2id m={} KiB, t={}, p={})",
+                    params.m_cost, params.t_cost, params.p_cost
+                ),
+            ),
+            Err(e) => Check::fail(
+                "key format",
+                e.to_string(),
+                "The encrypted key file is damaged. Restore it from a backup or run `cclink init`.",
+            ),
+        };
+    }
+
+    let is_hex_key = std::str::from_utf8(raw)
+        .map(|s| s.trim())
+        .is_ok_and(|s| s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit()));
+    if is_hex_key {
+        Check::warn(
+            "key format",
+            "plaintext (not passphrase-protected)",
+            format!(
+                "Encrypt it with: cclink init --import {} --yes",
+                path.display()
+            ),
+        )
+    } else {
+        Check::fail(
+            "key format",
+            "unrecognized key file contents",
+            "Restore the key from a backup or run `cclink init`.",
+        )
+    }
+}
+
+fn check_claude_projects() -> Check {
+    let Some(home) = dirs::home_dir() else {
+        return Check::fail(
+            "claude sessions",
+            "cannot determine home directory",
+            "Set $HOME.",
+        );
+    };
+    let projects = home.join(".claude/projects");
+    if projects.is_dir() {
+        Check::pass("claude sessions", projects.display().to_string())
+    } else {
+        Check::warn(
+            "claude sessions",
+            format!("{} not found", projects.display()),
+            "Start a session with `claude` on this machine, or sync ~/.claude/ from the publishing machine.",
+        )
+    }
+}
+
+fn check_claude_binary() -> Check {
+    match crate::util::find_executable("claude") {
+        Some(path) => Check::pass("claude binary", path.display().to_string()),
+        None => Check::fail(
+            "claude binary",
+            "`claude` not found on PATH",
+            "Install Claude Code, or add its install directory to PATH — pickup execs `claude --resume`.",
+        ),
+    }
+}
+
+fn check_dht() -> Check {
+    let client = match crate::transport::DhtClient::new() {
+        Ok(c) => c,
+        Err(e) => {
+            return Check::fail(
+                "DHT",
+                e.to_string(),
+                "Check that a UDP socket can be opened on this machine.",
+            )
+        }
+    };
+    if client.bootstrapped() {
+        Check::pass("DHT", "Mainline DHT reachable")
+    } else {
+        Check::fail(
+            "DHT",
+            "no DHT nodes answered the bootstrap query",
+            "Check network connectivity; outbound UDP must be allowed (some corporate networks block it).",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_format_plaintext_warns() {
+        let raw = "ab".repeat(32);
+        let check = check_key_format(raw.as_bytes(), std::path::Path::new("/k"));
+        assert_eq!(check.status, Status::Warn);
+    }
+
+    #[test]
+    fn test_key_format_truncated_envelope_fails() {
+        let check = check_key_format(b"CCLINKEK\x01short", std::path::Path::new("/k"));
+        assert_eq!(check.status, Status::Fail);
+    }
+
+    #[test]
+    fn test_key_format_garbage_fails() {
+        let check = check_key_format(b"not a key", std::path::Path::new("/k"));
+        assert_eq!(check.status, Status::Fail);
+    }
+}
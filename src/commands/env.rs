@@ -0,0 +1,89 @@
+/// Env command — prints shell integration snippets, or the prompt-segment value.
+///
+/// `eval "$(cclink env bash)"` (or zsh / fish) defines:
+///   - `cch` — publish the current directory's session (`cclink`)
+///   - `ccp` — pick up the latest handoff, with confirmation (`cclink pickup`)
+///   - `cclink_prompt` — prints `cc:N ` while N handoffs from this machine are active
+///
+/// `cclink env --prompt` prints the active-handoff count from the local cache only:
+/// no key load, no passphrase, no network — cheap enough to run on every prompt.
+use crate::cli::Shell;
+
+const POSIX_SNIPPET: &str = r#"# cclink shell integration
+cch() { cclink "$@"; }
+ccp() { cclink pickup "$@"; }
+cclink_prompt() {
+  local n
+  n="$(cclink env --prompt 2>/dev/null)" || return 0
+  [ -n "$n" ] && [ "$n" != "0" ] && printf 'cc:%s ' "$n"
+  return 0
+}
+"#;
+
+const FISH_SNIPPET: &str = r#"# cclink shell integration
+function cch --description "Publish this directory's Claude Code session"
+    cclink $argv
+end
+function ccp --description 'Pick up the latest cclink handoff'
+    cclink pickup $argv
+end
+function cclink_prompt
+    set -l n (cclink env --prompt 2>/dev/null)
+    and test -n "$n" -a "$n" != 0
+    and printf 'cc:%s ' $n
+end
+"#;
+
+pub fn run_env(args: crate::cli::EnvArgs) -> anyhow::Result<()> {
+    if args.prompt {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)?
+            .as_secs();
+        println!("{}", crate::cache::active_count(now));
+        return Ok(());
+    }
+
+    let shell = match args.shell {
+        Some(shell) => shell,
+        None => detect_shell().ok_or_else(|| {
+            anyhow::anyhow!("cannot detect shell from $SHELL; pass one of: bash, zsh, fish")
+        })?,
+    };
+    print!("{}", snippet(shell));
+    Ok(())
+}
+
+fn snippet(shell: Shell) -> &'static str {
+    match shell {
+        Shell::Bash | Shell::Zsh => POSIX_SNIPPET,
+        Shell::Fish => FISH_SNIPPET,
+    }
+}
+
+/// Guess the user's shell from the basename of `$SHELL`.
+fn detect_shell() -> Option<Shell> {
+    let shell = std::env::var_os("SHELL")?;
+    let name = std::path::Path::new(&shell).file_name()?.to_str()?;
+    match name {
+        "bash" => Some(Shell::Bash),
+        "zsh" => Some(Shell::Zsh),
+        "fish" => Some(Shell::Fish),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippets_define_all_functions() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let text = snippet(shell);
+            for name in ["cch", "ccp", "cclink_prompt"] {
+                assert!(text.contains(name), "{} missing from snippet", name);
+            }
+            assert!(text.contains("cclink env --prompt"));
+        }
+    }
+}
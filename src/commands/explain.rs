@@ -0,0 +1,35 @@
+/// Explain command — prints the long-form description of an error code from the
+/// embedded table in `error::ERROR_DOCS`.
+use crate::error::{lookup, ERROR_DOCS};
+use crate::ui::Paint;
+
+pub fn run_explain(args: crate::cli::ExplainArgs) -> anyhow::Result<()> {
+    let Some(code) = args.code else {
+        for doc in ERROR_DOCS {
+            println!("{}  {}", doc.code, doc.title);
+        }
+        return Ok(());
+    };
+
+    let doc = lookup(&code).ok_or_else(|| {
+        anyhow::anyhow!(
+            "unknown error code '{}' (run `cclink explain` to list codes)",
+            code
+        )
+    })?;
+
+    println!("{} {}", doc.code.emphasis(), doc.title.emphasis());
+    println!();
+    println!("{}", doc.description);
+    println!();
+    println!("Likely causes:");
+    for cause in doc.causes {
+        println!("  - {}", cause);
+    }
+    println!();
+    println!("What to do:");
+    for step in doc.remediation {
+        println!("  - {}", step);
+    }
+    Ok(())
+}
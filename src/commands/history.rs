@@ -0,0 +1,96 @@
+/// History command — shows or exports the local log of handoffs and files published
+/// from, and picked up on, this machine (see `crate::history`).
+use std::time::SystemTime;
+
+use crate::cli::{HistoryArgs, HistoryCommand, HistoryFilterArgs};
+use crate::history::{Direction, Entry, Filter, Kind, EXPORT_SCHEMA_VERSION};
+
+pub fn run_history(args: HistoryArgs) -> anyhow::Result<()> {
+    match args
+        .command
+        .unwrap_or_else(|| HistoryCommand::Show(HistoryFilterArgs::default()))
+    {
+        HistoryCommand::Show(filter) => {
+            let entries = filtered(filter)?;
+            if entries.is_empty() {
+                println!("No history yet.");
+                return Ok(());
+            }
+            let contacts =
+                crate::contacts::ContactBook::load_from(&crate::contacts::contacts_path()?)?;
+            let mut table = comfy_table::Table::new();
+            table.set_header(vec!["When", "", "What", "Name", "Peer", "Project"]);
+            for entry in &entries {
+                let peer = entry.peer.as_deref().map(|peer| {
+                    contacts.iter().find(|(_, key)| *key == peer).map_or_else(
+                        || peer[..12.min(peer.len())].to_string(),
+                        |(n, _)| n.to_string(),
+                    )
+                });
+                table.add_row(vec![
+                    crate::util::format_utc(entry.at),
+                    direction_arrow(entry).to_string(),
+                    kind_label(entry).to_string(),
+                    entry.name.clone(),
+                    peer.unwrap_or_default(),
+                    entry.project.clone().unwrap_or_default(),
+                ]);
+            }
+            println!("{table}");
+        }
+        HistoryCommand::Export { format, filter } => {
+            let entries = filtered(filter)?;
+            let mut out = std::io::stdout().lock();
+            crate::history::export(&entries, format, &mut out)?;
+            tracing::debug!(
+                rows = entries.len(),
+                schema = EXPORT_SCHEMA_VERSION,
+                "history exported"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// History entries matching `args`, oldest first.
+fn filtered(args: HistoryFilterArgs) -> anyhow::Result<Vec<Entry>> {
+    // ── 1. Filters ───────────────────────────────────────────────────────
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+    let peer = args
+        .peer
+        .as_deref()
+        .map(crate::contacts::resolve)
+        .transpose()?;
+    // Relative paths are matched from the current directory, like --cd.
+    let project = args.project.map(|p| std::path::absolute(&p)).transpose()?;
+    let filter = Filter {
+        since: args.since.map(|age| now.saturating_sub(age)),
+        direction: args.direction,
+        peer,
+        project,
+    };
+
+    // ── 2. Load ──────────────────────────────────────────────────────────
+    Ok(crate::history::load()?
+        .into_iter()
+        .filter(|e| filter.matches(e))
+        .collect())
+}
+
+fn direction_arrow(entry: &Entry) -> &'static str {
+    match entry.direction {
+        Direction::Out => "→",
+        Direction::In => "←",
+    }
+}
+
+fn kind_label(entry: &Entry) -> &'static str {
+    match (entry.kind, entry.direction) {
+        (Kind::Handoff, Direction::Out) => "published",
+        (Kind::Handoff, Direction::In) => "picked up",
+        (Kind::File, Direction::Out) => "sent file",
+        (Kind::File, Direction::In) => "received file",
+    }
+}
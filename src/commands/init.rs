@@ -8,8 +8,23 @@ use crate::cli::InitArgs;
 use crate::keys::{fingerprint, store};
 
 pub fn run_init(args: InitArgs) -> anyhow::Result<()> {
-    // Step 1: Ensure ~/.pubky/ directory exists
-    store::ensure_key_dir().context("Failed to create ~/.pubky/ directory")?;
+    // Step 1: Ensure the key directory (~/.pubky/, or the profile's) exists
+    store::ensure_key_dir().with_context(|| match store::key_dir() {
+        Ok(dir) => format!("Failed to create {} directory", dir.display()),
+        Err(_) => "Failed to create key directory".to_string(),
+    })?;
+
+    // Age identities sit next to the key, which stays as it is.
+    if let Some(source) = &args.import_age {
+        let added = crate::keys::age_identity::import(source)?;
+        crate::status!(
+            "Imported {} new age identit{} into {}",
+            added,
+            if added == 1 { "y" } else { "ies" },
+            crate::keys::age_identity::identities_path()?.display()
+        );
+        return Ok(());
+    }
 
     // Step 2: Get the destination path
     let secret_key_path = store::secret_key_path()?;
@@ -18,13 +33,21 @@ pub fn run_init(args: InitArgs) -> anyhow::Result<()> {
     if store::keypair_exists()? && !args.yes {
         let should_overwrite = prompt_overwrite(&secret_key_path)?;
         if !should_overwrite {
-            println!("Aborted.");
+            crate::status!("Aborted.");
             return Ok(());
         }
     }
 
     // Step 4: Generate or import keypair
-    let (keypair, action) = if let Some(import_path) = &args.import {
+    // A QR backup's envelope is stored as it is, keeping the backup's passphrase.
+    let mut envelope = None;
+    let (keypair, action) = if let Some(source) = &args.import_qr {
+        let (kp, raw) = import_from_qr(source)?;
+        envelope = Some(raw);
+        (kp, "imported")
+    } else if args.from_mnemonic {
+        (import_from_mnemonic()?, "imported")
+    } else if let Some(import_path) = &args.import {
         if import_path == "-" {
             let kp = import_from_stdin(&secret_key_path)?;
             (kp, "imported")
@@ -38,30 +61,12 @@ pub fn run_init(args: InitArgs) -> anyhow::Result<()> {
     };
 
     // Step 5: Write keypair — encrypted (default) or plaintext (--no-passphrase)
-    if args.no_passphrase {
-        // Plaintext path (v1.2-compatible)
-        store::write_keypair_atomic(&keypair, &secret_key_path)
-            .context("Failed to write keypair")?;
-    } else {
-        // Encrypted path (v1.3 default)
-        if !io::stdin().is_terminal() {
-            anyhow::bail!("Use --no-passphrase for non-interactive init");
+    match envelope {
+        Some(ref envelope) if !args.no_passphrase => {
+            store::write_encrypted_keypair_atomic(envelope, &secret_key_path)
+                .context("Failed to write encrypted keypair")?;
         }
-        let passphrase = Zeroizing::new(
-            dialoguer::Password::new()
-                .with_prompt("Enter key passphrase (min 8 chars)")
-                .with_confirmation("Confirm passphrase", "Passphrases don't match")
-                .interact()
-                .map_err(|e| anyhow::anyhow!("Passphrase prompt failed: {}", e))?,
-        );
-        if passphrase.len() < 8 {
-            eprintln!("Error: Passphrase must be at least 8 characters");
-            std::process::exit(1);
-        }
-        let seed: [u8; 32] = keypair.secret_key();
-        let envelope = crate::crypto::encrypt_key_envelope(&seed, &passphrase)?;
-        store::write_encrypted_keypair_atomic(&envelope, &secret_key_path)
-            .context("Failed to write encrypted keypair")?;
+        _ => write_keypair(&keypair, &secret_key_path, args.no_passphrase)?,
     }
 
     // Step 6: Success output
@@ -72,24 +77,80 @@ pub fn run_init(args: InitArgs) -> anyhow::Result<()> {
         "Keypair imported successfully."
     };
 
-    println!("{}", success_verb);
-    println!();
-    println!("Public Key:  {}", pub_key.to_uri_string());
+    crate::status!("{}", success_verb);
+    crate::status!();
+    crate::status!("Public Key:  {}", pub_key.to_uri_string());
     if args.no_passphrase {
-        println!("Key file:    {} (plaintext)", secret_key_path.display());
+        crate::status!("Key file:    {} (plaintext)", secret_key_path.display());
     } else {
-        println!(
+        crate::status!(
             "Key file:    {} (passphrase-protected)",
             secret_key_path.display()
         );
     }
 
-    println!();
-    println!("Next: run 'cclink' to publish your first session handoff.");
+    crate::status!();
+    crate::status!("Next: run 'cclink' to publish your first session handoff.");
 
     Ok(())
 }
 
+/// Write `keypair` to `path`: passphrase-protected with the passphrase supplied for
+/// this run or prompted for, or plaintext with `no_passphrase`.
+pub(crate) fn write_keypair(
+    keypair: &pkarr::Keypair,
+    path: &Path,
+    no_passphrase: bool,
+) -> anyhow::Result<()> {
+    if no_passphrase {
+        // Plaintext path (v1.2-compatible)
+        store::write_keypair_atomic(keypair, path).context("Failed to write keypair")?;
+    } else {
+        // Encrypted path (v1.3 default)
+        let passphrase = match crate::keys::input::passphrase()? {
+            Some(passphrase) => {
+                if passphrase.len() < 8 {
+                    anyhow::bail!("Passphrase must be at least 8 characters");
+                }
+                passphrase
+            }
+            None if !io::stdin().is_terminal() => anyhow::bail!(
+                "Use --no-passphrase, CCLINK_PASSPHRASE or --passphrase-file to write the key \
+                 non-interactively"
+            ),
+            None => prompt_new_passphrase()?,
+        };
+        let seed = Zeroizing::new(keypair.secret_key());
+        let envelope = seal_seed(&seed, &passphrase)?;
+        store::write_encrypted_keypair_atomic(&envelope, path)
+            .context("Failed to write encrypted keypair")?;
+    }
+    Ok(())
+}
+
+/// Encrypt `seed` into a CCLINKEK envelope with the Argon2 parameters set in `[kdf]`
+/// of cclink.toml (the built-in defaults when unset).
+pub(crate) fn seal_seed(seed: &[u8; 32], passphrase: &str) -> anyhow::Result<Vec<u8>> {
+    let params = crate::config::load()?.kdf.params()?;
+    crate::crypto::encrypt_key_envelope_with(seed, passphrase, params)
+}
+
+/// Ask for a new key passphrase, twice, and enforce the minimum length.
+pub(crate) fn prompt_new_passphrase() -> anyhow::Result<Zeroizing<String>> {
+    let passphrase = Zeroizing::new(
+        dialoguer::Password::new()
+            .with_prompt("Enter key passphrase (min 8 chars)")
+            .with_confirmation("Confirm passphrase", "Passphrases don't match")
+            .interact()
+            .map_err(|e| anyhow::anyhow!("Passphrase prompt failed: {}", e))?,
+    );
+    if passphrase.len() < 8 {
+        eprintln!("Error: Passphrase must be at least 8 characters");
+        std::process::exit(1);
+    }
+    Ok(passphrase)
+}
+
 fn prompt_overwrite(existing_key_path: &Path) -> anyhow::Result<bool> {
     // Check if stdin is a terminal — if not, we can't prompt
     if !io::stdin().is_terminal() {
@@ -124,8 +185,84 @@ fn prompt_overwrite(existing_key_path: &Path) -> anyhow::Result<bool> {
     Ok(input.trim().eq_ignore_ascii_case("y"))
 }
 
+fn import_from_mnemonic() -> anyhow::Result<pkarr::Keypair> {
+    let phrase = if io::stdin().is_terminal() {
+        Zeroizing::new(
+            dialoguer::Password::new()
+                .with_prompt("Recovery phrase (24 words)")
+                .interact()
+                .map_err(|e| anyhow::anyhow!("Recovery phrase prompt failed: {}", e))?,
+        )
+    } else {
+        let mut buf = Zeroizing::new(String::new());
+        io::stdin()
+            .read_to_string(&mut buf)
+            .context("Failed to read from stdin")?;
+        buf
+    };
+    let seed = crate::keys::mnemonic::from_phrase(&phrase)?;
+    Ok(pkarr::Keypair::from_secret_key(&seed))
+}
+
+/// Use the seed of an OpenSSH ed25519 private key, prompting for its passphrase if
+/// it is encrypted.
+fn import_from_openssh(text: &str) -> anyhow::Result<pkarr::Keypair> {
+    let seed = crate::keys::ssh::seed_from_openssh(text, || {
+        if !io::stdin().is_terminal() {
+            anyhow::bail!("Encrypted SSH key requires interactive terminal for passphrase entry");
+        }
+        Ok(Zeroizing::new(
+            dialoguer::Password::new()
+                .with_prompt("SSH key passphrase")
+                .interact()
+                .map_err(|e| anyhow::anyhow!("Passphrase prompt failed: {}", e))?,
+        ))
+    })?;
+    Ok(pkarr::Keypair::from_secret_key(&seed))
+}
+
+/// Read a QR key backup from a scanned image or pasted text (`-`) and decrypt it.
+/// Returns the keypair and the backup's envelope.
+fn import_from_qr(source: &str) -> anyhow::Result<(pkarr::Keypair, Vec<u8>)> {
+    let text = if source == "-" {
+        if io::stdin().is_terminal() {
+            dialoguer::Input::<String>::new()
+                .with_prompt("Paste the QR code text (cclink-key1:...)")
+                .interact_text()
+                .map_err(|e| anyhow::anyhow!("Paste prompt failed: {}", e))?
+        } else {
+            let mut buf = String::new();
+            io::stdin()
+                .read_to_string(&mut buf)
+                .context("Failed to read from stdin")?;
+            buf
+        }
+    } else {
+        crate::keys::paper::scan_image(Path::new(source))?
+    };
+    let envelope = crate::keys::paper::decode(&text)?;
+
+    if !io::stdin().is_terminal() {
+        anyhow::bail!("Decrypting a QR key backup requires an interactive terminal");
+    }
+    let passphrase = Zeroizing::new(
+        dialoguer::Password::new()
+            .with_prompt("Backup passphrase")
+            .interact()
+            .map_err(|e| anyhow::anyhow!("Passphrase prompt failed: {}", e))?,
+    );
+    let seed = crate::crypto::decrypt_key_envelope(&envelope, &passphrase)
+        .map_err(|_| anyhow::anyhow!("Wrong passphrase for this key backup"))?;
+    Ok((pkarr::Keypair::from_secret_key(&seed), envelope))
+}
+
 fn import_from_file(path_str: &str) -> anyhow::Result<pkarr::Keypair> {
     let path = Path::new(path_str);
+    if let Ok(text) = std::fs::read_to_string(path).map(Zeroizing::new) {
+        if crate::keys::ssh::is_openssh(&text) {
+            return import_from_openssh(&text);
+        }
+    }
     pkarr::Keypair::from_secret_key_file(path)
         .map_err(|e| anyhow::anyhow!("Invalid key file at {}: {}", path_str, e))
 }
@@ -137,6 +274,10 @@ fn import_from_stdin(dest_parent: &Path) -> anyhow::Result<pkarr::Keypair> {
         .read_to_string(&mut buf)
         .context("Failed to read from stdin")?;
 
+    let buf = Zeroizing::new(buf);
+    if crate::keys::ssh::is_openssh(&buf) {
+        return import_from_openssh(&buf);
+    }
     let hex = buf.trim();
 
     if hex.is_empty() {
@@ -0,0 +1,225 @@
+/// Inspect command — fetches a handoff record and dumps its envelope metadata.
+///
+/// Nothing is decrypted and nothing is launched: only the cleartext envelope
+/// fields, the blob size, and the result of verifying the inner signature are shown.
+use base64::Engine;
+
+use crate::record::HandoffRecord;
+use crate::transport::Freshness;
+use crate::ui::Paint;
+use crate::util::human_duration;
+
+pub fn run_inspect(args: crate::cli::InspectArgs) -> anyhow::Result<()> {
+    // ── 1. Resolve target ────────────────────────────────────────────────
+    let target_z32 = match args.pubkey {
+        Some(ref input) => crate::contacts::resolve(input)?,
+        None => crate::keys::store::load_keypair()?.public_key().to_z32(),
+    };
+    let pubkey = pkarr::PublicKey::try_from(target_z32.as_str())
+        .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+
+    // ── 2. Fetch record (signature checked below, not here) ──────────────
+    let client = crate::transport::DhtClient::new()?;
+    let record = match client.resolve_record_unverified(&target_z32) {
+        Ok(r) => r,
+        Err(e) => {
+            if e.downcast_ref::<crate::error::CclinkError>()
+                .is_some_and(|ce| matches!(ce, crate::error::CclinkError::RecordNotFound))
+            {
+                println!("No handoff record published by {}.", target_z32);
+                return Ok(());
+            }
+            return Err(e);
+        }
+    };
+    let signature = crate::record::verify_record(&record, &pubkey);
+    let freshness = client.freshness(&target_z32);
+    let name = client.display_name(&pubkey);
+
+    // ── 3. Dump envelope ─────────────────────────────────────────────────
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)?
+        .as_secs();
+    for (label, value) in envelope_lines(&record, name.as_deref(), freshness, now) {
+        println!("{:<12} {}", format!("{}:", label), value);
+    }
+    match signature {
+        Ok(()) => println!("{:<12} {}", "Signature:", "valid".success()),
+        Err(e) => println!(
+            "{:<12} {}",
+            "Signature:",
+            format!("INVALID ({})", e).error()
+        ),
+    }
+
+    Ok(())
+}
+
+/// Label/value pairs describing a record's cleartext envelope, and how long the
+/// packet holding it may be cached (when known).
+fn envelope_lines(
+    record: &HandoffRecord,
+    name: Option<&str>,
+    freshness: Option<Freshness>,
+    now: u64,
+) -> Vec<(&'static str, String)> {
+    let expires_at = record.created_at.saturating_add(record.ttl);
+    let expiry = if now >= expires_at {
+        format!("expired {} ago", human_duration(now - expires_at))
+    } else {
+        format!("expires in {}", human_duration(expires_at - now))
+    };
+    let blob_bytes = if record.blob.is_empty() {
+        "chunked (ciphertext stored in separate chunk slots)".to_string()
+    } else {
+        base64::engine::general_purpose::STANDARD
+            .decode(&record.blob)
+            .map(|b| format!("{} bytes ciphertext", b.len()))
+            .unwrap_or_else(|_| "not valid base64".to_string())
+    };
+    let json_bytes = serde_json::to_string(record).map_or(0, |j| j.len());
+
+    let mut lines = vec![
+        (
+            "Publisher",
+            crate::commands::profile::with_name(&record.pubkey, name),
+        ),
+        (
+            "Created",
+            format!(
+                "{} ({} ago)",
+                record.created_at,
+                human_duration(now.saturating_sub(record.created_at))
+            ),
+        ),
+        ("TTL", format!("{}s ({})", record.ttl, expiry)),
+        (
+            "Cache",
+            freshness.map_or_else(
+                || "unknown".to_string(),
+                |f| cache_summary(f, expires_at, now),
+            ),
+        ),
+        ("Burn", burn_summary(record)),
+        (
+            "Recipient",
+            record
+                .recipient
+                .clone()
+                .unwrap_or_else(|| "none (self-encrypted)".to_string()),
+        ),
+        ("PIN", yes_no(record.pin_salt.is_some()).to_string()),
+        ("Compressed", yes_no(record.compressed).to_string()),
+        ("Blob", blob_bytes),
+        ("Record", format!("{} bytes JSON", json_bytes)),
+    ];
+    // Pre-v1.1 records carried these in cleartext.
+    if !record.hostname.is_empty() {
+        lines.push(("Hostname", record.hostname.clone()));
+    }
+    if !record.project.is_empty() {
+        lines.push(("Project", record.project.clone()));
+    }
+    lines
+}
+
+/// `yes`/`no`, with the use count and grace window of a multi-use burn.
+fn burn_summary(record: &HandoffRecord) -> String {
+    let mut summary = yes_no(record.burn).to_string();
+    if record.burn {
+        if let Some(uses) = record.burn_uses {
+            summary.push_str(&format!(", {} pickups", uses));
+        }
+        if let Some(grace) = record.burn_grace {
+            summary.push_str(&format!(", {} grace", human_duration(grace)));
+        }
+    }
+    summary
+}
+
+/// e.g. `max-age 3600s, fresh for 50m`, noting when caches may outlive the record.
+fn cache_summary(freshness: Freshness, expires_at: u64, now: u64) -> String {
+    let mut summary = format!(
+        "max-age {}s, fresh for {}",
+        freshness.max_age,
+        human_duration(u64::from(freshness.fresh_for()))
+    );
+    if let Some(extra) = freshness.outlives(expires_at, now) {
+        summary.push_str(&format!(
+            " (caches may serve it {} past expiry)",
+            human_duration(extra)
+        ));
+    }
+    summary
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> HandoffRecord {
+        HandoffRecord {
+            blob: "dGVzdA==".to_string(),
+            blob_hash: None,
+            burn: true,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
+            created_at: 1_000,
+            hostname: String::new(),
+            pin_salt: None,
+            project: String::new(),
+            pubkey: "pubkey".to_string(),
+            recipient: None,
+            signature: String::new(),
+            ttl: 3600,
+            version: crate::record::migrate::CURRENT_VERSION,
+        }
+    }
+
+    #[test]
+    fn test_envelope_lines_live_record() {
+        let lines = envelope_lines(&record(), None, None, 1_060);
+        let get = |label| lines.iter().find(|(l, _)| *l == label).unwrap().1.clone();
+        assert_eq!(get("TTL"), "3600s (expires in 59m)");
+        assert_eq!(get("Cache"), "unknown");
+        assert_eq!(get("Burn"), "yes");
+        assert_eq!(get("Blob"), "4 bytes ciphertext");
+        assert!(lines.iter().all(|(l, _)| *l != "Hostname"));
+    }
+
+    #[test]
+    fn test_envelope_lines_expired_record() {
+        let lines = envelope_lines(&record(), None, None, 1_000 + 3600 + 7200);
+        assert!(lines.contains(&("TTL", "3600s (expired 2h ago)".to_string())));
+    }
+
+    #[test]
+    fn test_cache_summary() {
+        let fresh = Freshness {
+            max_age: 3600,
+            age: 600,
+        };
+        assert_eq!(
+            cache_summary(fresh, 10_000, 1_000),
+            "max-age 3600s, fresh for 50m"
+        );
+        // Published with the old fixed 24h DNS TTL: cached copies outlive the record.
+        let legacy = Freshness {
+            max_age: 86400,
+            age: 0,
+        };
+        assert_eq!(
+            cache_summary(legacy, 1_000 + 3600, 1_000),
+            "max-age 86400s, fresh for 24h (caches may serve it 23h past expiry)"
+        );
+    }
+}
@@ -0,0 +1,506 @@
+/// Key command — secret-key management: recovery-phrase and QR backup, Shamir escrow split
+/// and combine, passphrase storage in the OS keyring, revocation certificates, rotation
+/// to a new key announced by a cross-signed transition record, and Argon2 benchmarking.
+use std::io::{IsTerminal, Read};
+use std::time::SystemTime;
+
+use anyhow::Context;
+use base64::Engine;
+
+use zeroize::Zeroizing;
+
+use crate::cli::KeyCommand;
+use crate::crypto::shamir::{self, Share};
+use crate::error::CclinkError;
+use crate::record::HandoffRecord;
+use crate::ui::Paint;
+
+pub fn run_key(args: crate::cli::KeyArgs) -> anyhow::Result<()> {
+    match args.command {
+        KeyCommand::Split {
+            threshold,
+            shares,
+            qr,
+        } => run_split(threshold, shares, qr),
+        KeyCommand::Combine => run_combine(),
+        KeyCommand::Backup {
+            qr: true, output, ..
+        } => run_backup_qr(output),
+        KeyCommand::Backup { .. } => run_backup_mnemonic(),
+        KeyCommand::Remember => run_remember(),
+        KeyCommand::Forget => run_forget(),
+        KeyCommand::RevokeCert { reason, output } => run_revoke_cert(reason, output),
+        KeyCommand::PublishRevocation { cert } => run_publish_revocation(&cert),
+        KeyCommand::Rotate {
+            grace,
+            no_passphrase,
+            yes,
+        } => run_rotate(grace, no_passphrase, yes),
+        KeyCommand::Bench { target } => run_bench(target),
+    }
+}
+
+fn run_split(threshold: u8, count: u8, qr: bool) -> anyhow::Result<()> {
+    let keypair = crate::keys::store::load_keypair()?;
+    let public_key = keypair.public_key();
+    let seed = Zeroizing::new(keypair.secret_key());
+    let shares = shamir::split(&*seed, threshold, count, shamir::set_id(&public_key))?;
+
+    crate::status!(
+        "Split {} ({}) into {} shares; any {} reconstruct it.",
+        public_key.to_uri_string(),
+        crate::keys::fingerprint::short_fingerprint(&public_key),
+        count,
+        threshold
+    );
+    println!(
+        "{}",
+        "Give each share to a different custodian. Fewer than the threshold reveal nothing about the key.".warning()
+    );
+    for share in &shares {
+        let text = Zeroizing::new(share.encode());
+        println!("\nShare {} of {}:", share.index, count);
+        println!("{}", text.as_str());
+        if qr {
+            qr2term::print_qr(text.as_str())
+                .map_err(|e| anyhow::anyhow!("QR code render failed: {}", e))?;
+        }
+    }
+    crate::status!("\nRecover with: cclink key combine | cclink init --import -");
+
+    Ok(())
+}
+
+fn run_combine() -> anyhow::Result<()> {
+    // ── 1. Collect shares ────────────────────────────────────────────────
+    let mut shares: Vec<Share> = Vec::new();
+    if std::io::stdin().is_terminal() {
+        // Prompt until the threshold (learned from the first share) is reached.
+        loop {
+            let input = Zeroizing::new(
+                dialoguer::Password::new()
+                    .with_prompt(format!("Share {}", shares.len() + 1))
+                    .interact()
+                    .map_err(|e| anyhow::anyhow!("share prompt failed: {}", e))?,
+            );
+            shares.push(Share::parse(&input)?);
+            if shares.len() >= shares[0].threshold as usize {
+                break;
+            }
+        }
+    } else {
+        let mut input = Zeroizing::new(String::new());
+        std::io::stdin().read_to_string(&mut input)?;
+        for line in input.lines().filter(|l| !l.trim().is_empty()) {
+            shares.push(Share::parse(line)?);
+        }
+    }
+
+    // ── 2. Reconstruct and check against the set id ──────────────────────
+    let secret = shamir::combine(&shares)?;
+    let seed: [u8; 32] = secret
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("shares do not hold a 32-byte key"))?;
+    let seed = Zeroizing::new(seed);
+    let keypair = pkarr::Keypair::from_secret_key(&seed);
+    if shamir::set_id(&keypair.public_key()) != shares[0].set {
+        anyhow::bail!("shares reconstruct the wrong key — one of them is corrupted");
+    }
+
+    // ── 3. Output ────────────────────────────────────────────────────────
+    // Status goes to stderr so stdout can be piped straight into `init --import -`.
+    eprintln!(
+        "Recovered {} ({})",
+        keypair.public_key().to_uri_string(),
+        crate::keys::fingerprint::short_fingerprint(&keypair.public_key())
+    );
+    println!("{}", Zeroizing::new(crate::util::to_hex(&*seed)).as_str());
+
+    Ok(())
+}
+
+fn run_backup_mnemonic() -> anyhow::Result<()> {
+    let keypair = crate::keys::store::load_keypair()?;
+    let seed = Zeroizing::new(keypair.secret_key());
+    let phrase = crate::keys::mnemonic::to_phrase(&seed);
+
+    eprintln!(
+        "Recovery phrase for {} ({}):",
+        keypair.public_key().to_uri_string(),
+        crate::keys::fingerprint::short_fingerprint(&keypair.public_key())
+    );
+    println!("{}", phrase.as_str());
+    eprintln!(
+        "{}",
+        "Write these words down and keep them offline. Anyone who has them has your key."
+            .warning()
+            .stderr()
+    );
+    eprintln!("Restore with: cclink init --from-mnemonic");
+    Ok(())
+}
+
+fn run_backup_qr(output: Option<std::path::PathBuf>) -> anyhow::Result<()> {
+    // ── 1. The encrypted key envelope ────────────────────────────────────
+    let key_path = crate::keys::store::secret_key_path()?;
+    let keypair = crate::keys::store::load_keypair()?;
+    let raw = std::fs::read(&key_path)
+        .with_context(|| format!("Failed to read key file: {}", key_path.display()))?;
+    let envelope = if raw.starts_with(b"CCLINKEK") {
+        raw
+    } else {
+        // A plaintext key file is never printed as is.
+        eprintln!("The key file is not passphrase-protected; choose one for the backup.");
+        let passphrase = crate::commands::init::prompt_new_passphrase()?;
+        crate::commands::init::seal_seed(&Zeroizing::new(keypair.secret_key()), &passphrase)?
+    };
+    let text = crate::keys::paper::encode(&envelope);
+
+    // ── 2. Render ────────────────────────────────────────────────────────
+    eprintln!(
+        "Key backup for {} ({}):",
+        keypair.public_key().to_uri_string(),
+        crate::keys::fingerprint::short_fingerprint(&keypair.public_key())
+    );
+    match output {
+        Some(path) => {
+            std::fs::write(&path, crate::keys::paper::svg(&text)?)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!("Wrote the QR code to {}", path.display());
+        }
+        None => {
+            qr2term::print_qr(&text).map_err(|e| anyhow::anyhow!("QR code render failed: {}", e))?
+        }
+    }
+    println!("{}", text);
+    eprintln!(
+        "{}",
+        "The code is encrypted: keep the passphrase somewhere other than the printout."
+            .warning()
+            .stderr()
+    );
+    eprintln!(
+        "Restore with: cclink init --import-qr <scan.png>   (or --import-qr - to paste the text)"
+    );
+    Ok(())
+}
+
+fn run_remember() -> anyhow::Result<()> {
+    let key_path = crate::keys::store::secret_key_path()?;
+    if !key_path.exists() {
+        return Err(CclinkError::NoKeypairFound.into());
+    }
+    let raw = std::fs::read(&key_path)
+        .with_context(|| format!("Failed to read key file: {}", key_path.display()))?;
+    if !raw.starts_with(b"CCLINKEK") {
+        crate::status!("The key file is not passphrase-protected; there is nothing to remember.");
+        return Ok(());
+    }
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!("Remembering the passphrase requires an interactive terminal");
+    }
+    let passphrase = Zeroizing::new(
+        dialoguer::Password::new()
+            .with_prompt("Enter key passphrase")
+            .interact()
+            .map_err(|e| anyhow::anyhow!("Passphrase prompt failed: {}", e))?,
+    );
+    // Only a passphrase that opens the key is worth storing.
+    crate::keys::store::load_encrypted_keypair_with_passphrase(&raw, &passphrase)
+        .map_err(|_| anyhow::anyhow!("Wrong passphrase"))?;
+    crate::keys::keychain::remember(&key_path, &passphrase)?;
+    crate::status!("Passphrase stored in the OS keyring; cclink will stop prompting for it.");
+    crate::status!("Undo with: cclink key forget");
+    Ok(())
+}
+
+fn run_forget() -> anyhow::Result<()> {
+    let key_path = crate::keys::store::secret_key_path()?;
+    if crate::keys::keychain::forget(&key_path)? {
+        crate::status!("Passphrase removed from the OS keyring.");
+    } else {
+        crate::status!("No passphrase was stored in the OS keyring.");
+    }
+    Ok(())
+}
+
+fn run_revoke_cert(
+    reason: Option<String>,
+    output: Option<std::path::PathBuf>,
+) -> anyhow::Result<()> {
+    let keypair = crate::keys::store::load_keypair()?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+    let revocation = crate::record::revocation::seal_revocation(&keypair, now, reason)?;
+    let json = serde_json::to_string_pretty(&revocation)?;
+
+    // Status goes to stderr so stdout can be redirected into a file.
+    match output {
+        Some(path) => {
+            std::fs::write(&path, format!("{}\n", json))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!("Wrote revocation certificate to {}", path.display());
+        }
+        None => println!("{}", json),
+    }
+    eprintln!(
+        "{}",
+        "Store it offline. Anyone holding it can revoke your key with `cclink key publish-revocation`.".warning().stderr()
+    );
+    Ok(())
+}
+
+fn run_publish_revocation(cert: &str) -> anyhow::Result<()> {
+    let json = if cert == "-" {
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input)?;
+        input
+    } else {
+        std::fs::read_to_string(cert).with_context(|| format!("Failed to read {}", cert))?
+    };
+    let revocation: crate::record::revocation::Revocation =
+        serde_json::from_str(&json).context("Not a cclink revocation certificate")?;
+    let pubkey = pkarr::PublicKey::try_from(revocation.pubkey.as_str())
+        .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+    crate::record::revocation::verify_revocation(&revocation, &pubkey)?;
+
+    let client = crate::transport::DhtClient::new()?;
+    client.publish_revocation(&revocation)?;
+    // With the revoked key still here, also put the certificate in its own packet,
+    // where nobody else can overwrite it.
+    if crate::keys::store::keypair_exists()? {
+        let keypair = crate::keys::store::load_keypair()?;
+        if keypair.public_key() == pubkey {
+            client.publish_revocation_in_place(&keypair, &revocation)?;
+            crate::cache::forget();
+        }
+    }
+    crate::state::State::open()?.record_revocation(&revocation)?;
+    crate::status!(
+        "Revoked {}. Pickups from this key now fail.",
+        revocation.pubkey.identity()
+    );
+    crate::status!("Tell your contacts your new key, and run `cclink init` for a fresh one.");
+    Ok(())
+}
+
+fn run_rotate(grace: u64, no_passphrase: bool, yes: bool) -> anyhow::Result<()> {
+    // ── 1. Load the current key and confirm ──────────────────────────────
+    let old = crate::keys::store::load_keypair()?;
+    if !yes {
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("Use --yes to rotate the key non-interactively");
+        }
+        let confirmed = dialoguer::Confirm::new()
+            .with_prompt(format!(
+                "Replace key {}? Other machines using it will need the new one.",
+                crate::keys::fingerprint::short_fingerprint(&old.public_key())
+            ))
+            .default(false)
+            .interact()
+            .map_err(|e| anyhow::anyhow!("prompt failed: {}", e))?;
+        if !confirmed {
+            crate::status!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    // ── 2. New key, written beside the current one until published ───────
+    let new = pkarr::Keypair::random();
+    let key_path = crate::keys::store::secret_key_path()?;
+    let staged_path = key_path.with_extension("new");
+    crate::commands::init::write_keypair(&new, &staged_path, no_passphrase)?;
+
+    // ── 3. Carry the handoff over and announce the transition ────────────
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+    let transition =
+        crate::record::transition::seal_transition(&old, &new, now, now.saturating_add(grace))?;
+    let published = crate::transport::DhtClient::new().and_then(|client| {
+        let carried = carry_handoff(&client, &old, &new, now)?;
+        client.publish_transition(&transition)?;
+        Ok(carried)
+    });
+    let carried = match published {
+        Ok(carried) => carried,
+        Err(e) => {
+            // Nothing points at the new key yet; keep using the old one.
+            let _ = std::fs::remove_file(&staged_path);
+            return Err(e.context("Key not rotated"));
+        }
+    };
+
+    // ── 4. Swap the key files ────────────────────────────────────────────
+    // The old key stays for the grace period: its handoff is still live and
+    // handoffs shared with it still need to be decrypted.
+    let previous_path = crate::keys::store::previous_key_path()?;
+    std::fs::rename(&key_path, &previous_path)
+        .with_context(|| format!("Failed to move {}", key_path.display()))?;
+    std::fs::rename(&staged_path, &key_path)
+        .with_context(|| format!("Failed to move {}", staged_path.display()))?;
+    let rotation_path = crate::keys::store::rotation_path()?;
+    std::fs::write(&rotation_path, serde_json::to_string_pretty(&transition)?)
+        .with_context(|| format!("Failed to write {}", rotation_path.display()))?;
+    match carried {
+        Some(ref record) => crate::cache::remember(record),
+        None => crate::cache::forget(),
+    }
+
+    // ── 5. Output ────────────────────────────────────────────────────────
+    crate::status!(
+        "Rotated {} → {}",
+        old.public_key().to_z32(),
+        new.public_key().to_z32().identity()
+    );
+    if carried.is_some() {
+        crate::status!("Republished the active handoff under the new key.");
+    }
+    crate::status!(
+        "The old key is kept until {} and still serves its last handoff until then.",
+        crate::util::format_utc(transition.grace_until)
+    );
+    println!(
+        "{}",
+        "Import the new key on your other machines (cclink init --import), and ask contacts to update theirs.".warning()
+    );
+
+    Ok(())
+}
+
+/// Republish `old`'s live handoff under `new`, keeping its creation time and expiry.
+///
+/// A self-encrypted handoff is re-encrypted to the new key. Shared and PIN-protected
+/// ones are encrypted to someone else's key or the PIN and carry over as they are.
+/// Returns the new record, or `None` when nothing live was published.
+fn carry_handoff(
+    client: &crate::transport::DhtClient,
+    old: &pkarr::Keypair,
+    new: &pkarr::Keypair,
+    now: u64,
+) -> anyhow::Result<Option<HandoffRecord>> {
+    let record = match client.resolve_record(&old.public_key().to_z32()) {
+        Ok(record) => record,
+        Err(e)
+            if e.downcast_ref::<CclinkError>()
+                .is_some_and(|ce| matches!(ce, CclinkError::RecordNotFound)) =>
+        {
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+    if now >= record.created_at.saturating_add(record.ttl) {
+        return Ok(None);
+    }
+
+    let ciphertext = if record.blob.is_empty() {
+        client.fetch_chunked_blob(&old.public_key(), &old.public_key(), &record)?
+    } else {
+        base64::engine::general_purpose::STANDARD
+            .decode(&record.blob)
+            .map_err(|e| anyhow::anyhow!("invalid blob base64: {}", e))?
+    };
+    let ciphertext = if record.recipient.is_none() && record.pin_salt.is_none() {
+        let x25519_secret = crate::crypto::ed25519_to_x25519_secret(old);
+        let identity = crate::crypto::age_identity(&x25519_secret);
+        let plaintext = Zeroizing::new(crate::crypto::age_decrypt(&ciphertext, &identity)?);
+        let recipient = crate::crypto::recipient_from_z32(&new.public_key().to_z32())?;
+        crate::crypto::age_encrypt(&plaintext, &recipient)?
+    } else {
+        ciphertext
+    };
+    let pin_salt = record
+        .pin_salt
+        .as_deref()
+        .map(|salt| -> anyhow::Result<[u8; 32]> {
+            base64::engine::general_purpose::STANDARD
+                .decode(salt)
+                .map_err(|e| anyhow::anyhow!("invalid pin_salt base64: {}", e))?
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("pin_salt must be exactly 32 bytes"))
+        })
+        .transpose()?;
+
+    let sealed = HandoffRecord::builder(ciphertext, record.ttl)
+        .burn(record.burn)
+        .compressed(record.compressed)
+        .created_at(record.created_at)
+        .pin_salt(pin_salt)
+        .recipient(record.recipient.clone())
+        .seal(new)?;
+    client.publish_sealed(new, &sealed)?;
+    Ok(Some(sealed.record))
+}
+
+/// Memory costs tried by `cclink key bench`, in KiB: 19 MiB up to 1 GiB.
+const BENCH_M_COSTS: [u32; 6] = [
+    crate::crypto::KdfParams::MIN_M_COST,
+    65536,
+    131072,
+    262144,
+    524288,
+    1048576,
+];
+
+fn run_bench(target_ms: u64) -> anyhow::Result<()> {
+    let configured = crate::config::load()?.kdf.params()?;
+    let elapsed = crate::crypto::benchmark_kdf(configured)?;
+    println!(
+        "Configured: m_cost={} KiB, t_cost={}, p_cost={}: {} ms",
+        configured.m_cost,
+        configured.t_cost,
+        configured.p_cost,
+        elapsed.as_millis()
+    );
+
+    // Raise memory first (it is what makes GPU guessing expensive), stopping at the
+    // first cost over the target so the slowest trial is at most one step too slow.
+    println!(
+        "\nTrying t_cost={}, p_cost={}:",
+        configured.t_cost, configured.p_cost
+    );
+    let mut suggested = None;
+    for m_cost in BENCH_M_COSTS {
+        let params = crate::crypto::KdfParams {
+            m_cost,
+            ..configured
+        };
+        let elapsed = crate::crypto::benchmark_kdf(params)?;
+        println!("  m_cost={:>7} KiB: {:>5} ms", m_cost, elapsed.as_millis());
+        if elapsed.as_millis() > u128::from(target_ms) {
+            break;
+        }
+        suggested = Some(params);
+    }
+
+    println!();
+    match suggested {
+        Some(params) => {
+            println!(
+                "Suggested for a {} ms unlock, in {}:",
+                target_ms,
+                crate::config::config_path()?.display()
+            );
+            println!();
+            println!("[kdf]");
+            println!("m_cost = {}", params.m_cost);
+            println!("t_cost = {}", params.t_cost);
+            println!("p_cost = {}", params.p_cost);
+            println!();
+            println!(
+                "{}",
+                "Applies to key files written from now on; re-encrypt the current one with `cclink key rotate` or `cclink init --import`.".muted()
+            );
+        }
+        None => println!(
+            "{}",
+            format!(
+                "Even the minimum memory cost takes longer than {} ms here; keep the defaults or raise --target.",
+                target_ms
+            ).warning()
+        ),
+    }
+    Ok(())
+}
@@ -1,105 +1,592 @@
-/// List command — displays the active handoff record from the DHT.
+/// List command — displays active handoff records from the DHT: yours, another
+/// publisher's, or those of all your contacts, filtered and sorted.
+use std::io::{IsTerminal, Write};
+use std::time::{Duration, Instant};
+
 use base64::Engine;
-use owo_colors::{OwoColorize, Stream::Stdout};
 
+use crate::record::HandoffRecord;
+use crate::transport::Freshness;
+use crate::ui::Paint;
 use crate::util::human_duration;
 
-/// Show the active handoff record on the DHT.
-///
-/// Resolves the current identity's SignedPacket, extracts the HandoffRecord,
-/// checks expiry, and renders a comfy-table with one row.
-pub fn run_list() -> anyhow::Result<()> {
-    use comfy_table::{Cell, Color, Table};
+/// Order of the listed handoffs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortKey {
+    /// Newest first
+    Age,
+    /// Least time left first
+    Ttl,
+    /// By project path
+    Project,
+}
+
+/// Output format of `cclink list`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Human-readable table
+    Table,
+    /// JSON array, one object per handoff with the full signed record
+    Json,
+    /// CSV with a header row
+    Csv,
+    /// Publisher keys only, one per line (for `cclink pickup`)
+    Quiet,
+}
+
+/// Columns of a CSV row, in order.
+const CSV_COLUMNS: [&str; 9] = [
+    "publisher",
+    "pubkey",
+    "project",
+    "created_at",
+    "expires_at",
+    "burn",
+    "pin",
+    "recipient",
+    "expired",
+];
+
+/// One handoff as `list` shows it.
+pub struct Listed {
+    /// "you", the contact name, or the z32 key of the publisher.
+    pub publisher: String,
+    /// z32 key of the publisher.
+    pub pubkey: String,
+    /// Display name the publisher published (`cclink profile`), looked up only for
+    /// publishers shown by key rather than by contact name.
+    name: Option<String>,
+    pub record: HandoffRecord,
+    /// Project path, when the payload is encrypted to us.
+    project: Option<String>,
+    /// Why the project is unknown, when it is.
+    hidden: &'static str,
+    /// Registered device name of the recipient, for handoffs published with `--to`.
+    device: Option<String>,
+    freshness: Option<Freshness>,
+}
+
+impl Listed {
+    pub fn expires_at(&self) -> u64 {
+        self.record.created_at.saturating_add(self.record.ttl)
+    }
+
+    pub fn project_display(&self) -> &str {
+        self.project.as_deref().unwrap_or(self.hidden)
+    }
+}
+
+/// Which handoffs to show; the flags narrow the selection.
+#[derive(Debug, Default)]
+struct Filter {
+    project: Option<glob::Pattern>,
+    burn: bool,
+    shared: bool,
+    expired: bool,
+}
 
-    // ── 1. Load keypair ──────────────────────────────────────────────────
+impl Filter {
+    fn matches(&self, item: &Listed, now: u64) -> bool {
+        (now >= item.expires_at()) == self.expired
+            && (!self.burn || item.record.burn)
+            && (!self.shared || item.record.recipient.is_some())
+            && self.project.as_ref().is_none_or(|pattern| {
+                item.project
+                    .as_deref()
+                    .is_some_and(|project| pattern.matches(project))
+            })
+    }
+}
+
+/// Show active handoff records on the DHT.
+///
+/// Resolves the SignedPacket of the current identity (or of each publisher given),
+/// extracts the HandoffRecord, applies the filters, and renders a comfy-table.
+pub fn run_list(args: crate::cli::ListArgs) -> anyhow::Result<()> {
+    // ── 1. Load keypair, pick publishers ─────────────────────────────────
     let keypair = crate::keys::store::load_keypair()?;
-    let own_z32 = keypair.public_key().to_z32();
-    let client = crate::transport::DhtClient::new()?;
-
-    // ── 2. Resolve record from DHT ───────────────────────────────────────
-    let record = match client.resolve_record(&own_z32) {
-        Ok(r) => r,
-        Err(e) => {
-            if e.downcast_ref::<crate::error::CclinkError>()
-                .is_some_and(|ce| matches!(ce, crate::error::CclinkError::RecordNotFound))
-            {
-                println!(
-                    "{}",
-                    "No active handoffs. Publish one with cclink."
-                        .if_supports_color(Stdout, |t| t.yellow())
-                );
-                return Ok(());
-            }
-            return Err(e);
+    let targets = targets(&keypair, args.all, &args.pubkeys)?;
+    let filter = Filter {
+        project: args
+            .project
+            .as_deref()
+            .map(glob::Pattern::new)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("invalid --project pattern: {}", e))?,
+        burn: args.burn,
+        shared: args.shared,
+        expired: args.expired,
+    };
+    let client = crate::transport::DhtClient::cached()?;
+    let filtered = args.project.is_some() || args.burn || args.shared || args.expired;
+
+    if let Some(interval) = args.watch {
+        if args.format != Format::Table {
+            anyhow::bail!("--watch only works with the table format");
         }
+        return watch(
+            &client, &keypair, &targets, &filter, args.sort, filtered, interval,
+        );
+    }
+
+    // ── 2. Resolve records from DHT ──────────────────────────────────────
+    let listed = resolve(&client, &keypair, targets)?;
+
+    // ── 3. Filter and sort ───────────────────────────────────────────────
+    let now_secs = now_secs();
+    let listed = select(listed, &filter, args.sort, now_secs);
+
+    if args.format != Format::Table {
+        let mut out = std::io::stdout().lock();
+        return write_machine(&listed, args.format, now_secs, &mut out);
+    }
+
+    // ── 4. Render comfy-table ────────────────────────────────────────────
+    show(&listed, filtered, now_secs);
+    Ok(())
+}
+
+/// Look `targets` up again every `interval` seconds until interrupted.
+///
+/// On a terminal the screen is cleared and the table redrawn every second, so TTLs
+/// count down between lookups; otherwise each lookup is printed once. A failed lookup
+/// keeps the last table on screen and is retried at the next interval.
+fn watch(
+    client: &crate::transport::DhtClient,
+    keypair: &pkarr::Keypair,
+    targets: &[(String, String)],
+    filter: &Filter,
+    sort_key: Option<SortKey>,
+    filtered: bool,
+    interval: u64,
+) -> anyhow::Result<()> {
+    let interval = Duration::from_secs(interval.max(1));
+    let redraw = std::io::stdout().is_terminal();
+    let tick = if redraw {
+        Duration::from_secs(1)
+    } else {
+        interval
     };
+    let mut listed = Vec::new();
+    loop {
+        let looked_up = Instant::now();
+        let failure = match resolve(client, keypair, targets.to_vec()) {
+            Ok(found) => {
+                listed = select(found, filter, sort_key, now_secs());
+                None
+            }
+            Err(e) => Some(format!("{:#}", e)),
+        };
+        while looked_up.elapsed() < interval {
+            if redraw {
+                // Clear the screen and move the cursor home.
+                print!("\x1b[2J\x1b[H");
+            }
+            println!(
+                "Every {}, looked up {} ago. Ctrl-C to stop.",
+                human_duration(interval.as_secs()),
+                human_duration(looked_up.elapsed().as_secs())
+            );
+            if let Some(ref failure) = failure {
+                println!("{}", format!("Lookup failed: {}", failure).warning());
+            }
+            println!();
+            show(&listed, filtered, now_secs());
+            std::io::stdout().flush()?;
+            std::thread::sleep(tick);
+        }
+    }
+}
 
-    // ── 3. Check expiry ──────────────────────────────────────────────────
-    let now_secs = std::time::SystemTime::now()
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
         .duration_since(std::time::SystemTime::UNIX_EPOCH)
         .map(|d| d.as_secs())
-        .unwrap_or(0);
+        .unwrap_or(0)
+}
 
-    let expires_at = record.created_at.saturating_add(record.ttl);
-    if now_secs >= expires_at {
-        println!(
-            "{}",
+/// The handoffs of `listed` that pass `filter` at `now_secs`, in `sort_key` order.
+fn select(
+    listed: Vec<Listed>,
+    filter: &Filter,
+    sort_key: Option<SortKey>,
+    now_secs: u64,
+) -> Vec<Listed> {
+    let mut listed: Vec<Listed> = listed
+        .into_iter()
+        .filter(|item| filter.matches(item, now_secs))
+        .collect();
+    if let Some(key) = sort_key {
+        sort(&mut listed, key);
+    }
+    listed
+}
+
+/// Print the table of `listed`, or why it is empty.
+fn show(listed: &[Listed], filtered: bool, now_secs: u64) {
+    if listed.is_empty() {
+        let message = if filtered {
+            "No handoffs match the filters."
+        } else {
             "No active handoffs. Publish one with cclink."
-                .if_supports_color(Stdout, |t| t.yellow())
-        );
-        return Ok(());
+        };
+        println!("{}", message.warning());
+        return;
     }
+    println!("{}", table(listed, now_secs));
+}
 
-    // ── 4. Decrypt project from payload ──────────────────────────────────
-    let project_display = if record.pin_salt.is_some() {
-        "(PIN-protected)".to_string()
-    } else if record.recipient.is_some() {
-        "(shared)".to_string()
-    } else {
-        // Self-encrypted: decrypt blob to extract project path
-        let ciphertext = base64::engine::general_purpose::STANDARD
-            .decode(&record.blob)
-            .unwrap_or_default();
-        let x25519_secret = crate::crypto::ed25519_to_x25519_secret(&keypair);
-        let identity = crate::crypto::age_identity(&x25519_secret);
-        match crate::crypto::age_decrypt(&ciphertext, &identity) {
-            Ok(plaintext) => {
-                match serde_json::from_slice::<crate::record::Payload>(&plaintext) {
-                    Ok(payload) => payload.project,
-                    Err(_) => record.project.clone(), // old format fallback
-                }
-            }
-            Err(_) => "(encrypted)".to_string(),
+/// Publishers to look up, as (label, z32 key): yourself unless keys are given, every
+/// contact with `all`, and each of `pubkeys`.
+fn targets(
+    keypair: &pkarr::Keypair,
+    all: bool,
+    pubkeys: &[String],
+) -> anyhow::Result<Vec<(String, String)>> {
+    let own_z32 = keypair.public_key().to_z32();
+    let mut targets: Vec<(String, String)> = Vec::new();
+    if all || pubkeys.is_empty() {
+        targets.push(("you".to_string(), own_z32.clone()));
+    }
+    if all {
+        let contacts = crate::contacts::ContactBook::load_from(&crate::contacts::contacts_path()?)?;
+        for (name, pubkey) in contacts.iter() {
+            targets.push((name.to_string(), pubkey.to_string()));
         }
-    };
-
-    // ── 5. Build and render comfy-table ──────────────────────────────────
-    let mut table = Table::new();
-    table.set_header(vec!["Project", "Age", "TTL Left", "Burn", "Recipient"]);
-
-    let age_secs = now_secs.saturating_sub(record.created_at);
-    let ttl_left = expires_at.saturating_sub(now_secs);
-    let burn_display = if record.burn { "yes" } else { "" };
-    let recipient_display = record.recipient.as_deref().unwrap_or("");
-    let recipient_short = if recipient_display.len() > 8 {
-        &recipient_display[..8]
-    } else {
-        recipient_display
-    };
+    }
+    for name_or_key in pubkeys {
+        let z32 = crate::contacts::resolve(name_or_key)?;
+        let label = if z32 == own_z32 {
+            "you".to_string()
+        } else {
+            name_or_key.clone()
+        };
+        targets.push((label, z32));
+    }
+    Ok(targets)
+}
 
-    table.add_row(vec![
-        Cell::new(&project_display),
-        Cell::new(human_duration(age_secs)),
-        Cell::new(human_duration(ttl_left)),
-        if record.burn {
-            Cell::new(burn_display).fg(Color::Yellow)
+/// The handoffs of `targets` that are on the DHT, unfiltered.
+fn resolve(
+    client: &crate::transport::DhtClient,
+    keypair: &pkarr::Keypair,
+    targets: Vec<(String, String)>,
+) -> anyhow::Result<Vec<Listed>> {
+    let own_z32 = keypair.public_key().to_z32();
+    let device = crate::keys::device::load()?;
+    let mut registry = None;
+    let mut listed = Vec::new();
+    for (publisher, z32) in targets {
+        let record = match client.resolve_record(&z32) {
+            Ok(r) => r,
+            Err(e)
+                if e.downcast_ref::<crate::error::CclinkError>()
+                    .is_some_and(|ce| matches!(ce, crate::error::CclinkError::RecordNotFound)) =>
+            {
+                continue;
+            }
+            Err(e) => return Err(e.context(format!("Failed to look up {}", publisher))),
+        };
+        let (project, hidden) = project_of(&record, keypair, device.as_ref(), &own_z32, &z32);
+        // Our own handoffs may be sent to one of our devices; name it.
+        let device_name = match record.recipient {
+            Some(ref recipient) if z32 == own_z32 => {
+                let registry = registry
+                    .get_or_insert_with(|| client.resolve_devices(keypair).unwrap_or_default());
+                registry.name_of(recipient).map(str::to_string)
+            }
+            _ => None,
+        };
+        let name = if publisher == z32 {
+            pkarr::PublicKey::try_from(z32.as_str())
+                .ok()
+                .and_then(|pubkey| client.display_name(&pubkey))
         } else {
-            Cell::new(burn_display)
+            None
+        };
+        listed.push(Listed {
+            publisher,
+            pubkey: z32.clone(),
+            name,
+            freshness: client.freshness(&z32),
+            record,
+            project,
+            hidden,
+            device: device_name,
+        });
+    }
+    Ok(listed)
+}
+
+/// Your handoff and every contact's that is still active, as `cclink list --all`
+/// shows them. For `cclink ui`.
+pub fn active_handoffs(keypair: &pkarr::Keypair) -> anyhow::Result<Vec<Listed>> {
+    let client = crate::transport::DhtClient::cached()?;
+    let listed = resolve(&client, keypair, targets(keypair, true, &[])?)?;
+    Ok(select(listed, &Filter::default(), None, now_secs()))
+}
+
+/// The project of `record` published by `publisher_z32`, or why it is unknown.
+///
+/// Readable when it is our own self-encrypted handoff, one shared with us, or one
+/// sent to this machine's device key with `--to`.
+fn project_of(
+    record: &HandoffRecord,
+    keypair: &pkarr::Keypair,
+    device: Option<&crate::keys::device::Device>,
+    own_z32: &str,
+    publisher_z32: &str,
+) -> (Option<String>, &'static str) {
+    let to_device = device.filter(|d| record.recipient.as_deref() == Some(d.recipient().as_str()));
+    let readable = match record.recipient {
+        Some(ref recipient) => recipient == own_z32 || to_device.is_some(),
+        None => publisher_z32 == own_z32,
+    };
+    if record.pin_salt.is_some() {
+        return (None, "(PIN-protected)");
+    }
+    if !readable {
+        return (None, "(shared)");
+    }
+    // Encrypted to our key: decrypt blob to extract project path
+    let ciphertext = base64::engine::general_purpose::STANDARD
+        .decode(&record.blob)
+        .unwrap_or_default();
+    let x25519_secret = crate::crypto::ed25519_to_x25519_secret(keypair);
+    let own_identity = crate::crypto::age_identity(&x25519_secret);
+    let identity: &dyn age::Identity = match to_device {
+        Some(device) => &device.identity,
+        None => &own_identity,
+    };
+    match crate::crypto::age_decrypt(&ciphertext, identity) {
+        Ok(plaintext) => match serde_json::from_slice::<crate::record::Payload>(&plaintext) {
+            Ok(payload) => (Some(payload.project), ""),
+            Err(_) => (Some(record.project.clone()), ""), // old format fallback
         },
-        Cell::new(recipient_short),
-    ]);
+        Err(_) => (None, "(encrypted)"),
+    }
+}
 
-    println!("{table}");
+fn sort(listed: &mut [Listed], key: SortKey) {
+    match key {
+        SortKey::Age => listed.sort_by_key(|item| std::cmp::Reverse(item.record.created_at)),
+        SortKey::Ttl => listed.sort_by_key(Listed::expires_at),
+        SortKey::Project => listed.sort_by(|a, b| a.project_display().cmp(b.project_display())),
+    }
+}
 
+/// Write `listed` to `out` in one of the machine-readable formats.
+fn write_machine<W: std::io::Write>(
+    listed: &[Listed],
+    format: Format,
+    now_secs: u64,
+    out: &mut W,
+) -> anyhow::Result<()> {
+    match format {
+        Format::Table => unreachable!("tables are rendered by comfy-table"),
+        Format::Json => {
+            let items: Vec<serde_json::Value> = listed
+                .iter()
+                .map(|item| {
+                    serde_json::json!({
+                        "publisher": item.publisher,
+                        "pubkey": item.pubkey,
+                        "name": item.name,
+                        "project": item.project,
+                        "expires_at": item.expires_at(),
+                        "expired": now_secs >= item.expires_at(),
+                        "record": item.record,
+                    })
+                })
+                .collect();
+            writeln!(out, "{}", serde_json::to_string_pretty(&items)?)?;
+        }
+        Format::Csv => {
+            writeln!(out, "{}", CSV_COLUMNS.join(","))?;
+            for item in listed {
+                let record = &item.record;
+                let fields = [
+                    item.publisher.clone(),
+                    item.pubkey.clone(),
+                    item.project.clone().unwrap_or_default(),
+                    record.created_at.to_string(),
+                    item.expires_at().to_string(),
+                    record.burn.to_string(),
+                    record.pin_salt.is_some().to_string(),
+                    record.recipient.clone().unwrap_or_default(),
+                    (now_secs >= item.expires_at()).to_string(),
+                ];
+                let row: Vec<String> = fields.iter().map(|f| crate::util::csv_field(f)).collect();
+                writeln!(out, "{}", row.join(","))?;
+            }
+        }
+        Format::Quiet => {
+            for item in listed {
+                writeln!(out, "{}", item.pubkey)?;
+            }
+        }
+    }
     Ok(())
 }
+
+fn table(listed: &[Listed], now_secs: u64) -> comfy_table::Table {
+    use comfy_table::{Cell, Table};
+
+    let mut table = Table::new();
+    table.set_header(vec![
+        "Publisher",
+        "Project",
+        "Age",
+        "TTL Left",
+        "Cache",
+        "Burn",
+        "Recipient",
+    ]);
+    for item in listed {
+        let record = &item.record;
+        let expires_at = item.expires_at();
+        let age_secs = now_secs.saturating_sub(record.created_at);
+        let ttl_left = expires_at.saturating_sub(now_secs);
+        // How long caches in front of the DHT may keep serving this packet; flagged
+        // when that is past the record's expiry.
+        let cache_cell = match item.freshness {
+            Some(f) if f.outlives(expires_at, now_secs).is_some() => {
+                Cell::new(human_duration(u64::from(f.fresh_for())).warning())
+            }
+            Some(f) => Cell::new(human_duration(u64::from(f.fresh_for()))),
+            None => Cell::new(""),
+        };
+        let burn_display = if record.burn { "yes" } else { "" };
+        let recipient_display = record.recipient.as_deref().unwrap_or("");
+        let recipient_short = match item.device {
+            Some(ref name) => name.as_str(),
+            None if recipient_display.len() > 8 => &recipient_display[..8],
+            None => recipient_display,
+        };
+
+        table.add_row(vec![
+            Cell::new(
+                crate::commands::profile::with_name(&item.publisher, item.name.as_deref())
+                    .identity(),
+            ),
+            Cell::new(item.project_display()),
+            Cell::new(human_duration(age_secs)),
+            Cell::new(human_duration(ttl_left)),
+            cache_cell,
+            if record.burn {
+                Cell::new(burn_display.warning())
+            } else {
+                Cell::new(burn_display)
+            },
+            Cell::new(recipient_short),
+        ]);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listed(created_at: u64, ttl: u64, project: Option<&str>, burn: bool) -> Listed {
+        let record = HandoffRecord::builder(vec![0], ttl)
+            .burn(burn)
+            .created_at(created_at)
+            .sign(&pkarr::Keypair::from_secret_key(&[1u8; 32]))
+            .unwrap();
+        Listed {
+            publisher: "you".to_string(),
+            pubkey: record.pubkey.clone(),
+            name: None,
+            record,
+            project: project.map(str::to_string),
+            hidden: "(shared)",
+            device: None,
+            freshness: None,
+        }
+    }
+
+    #[test]
+    fn test_filter_hides_expired_unless_asked() {
+        let live = listed(1_000, 600, Some("/code/app"), false);
+        let dead = listed(100, 60, Some("/code/app"), false);
+        let filter = Filter::default();
+        assert!(filter.matches(&live, 1_100) && !filter.matches(&dead, 1_100));
+        let expired = Filter {
+            expired: true,
+            ..Filter::default()
+        };
+        assert!(!expired.matches(&live, 1_100) && expired.matches(&dead, 1_100));
+    }
+
+    #[test]
+    fn test_filter_by_project_glob_and_flags() {
+        let app = listed(1_000, 600, Some("/home/me/code/app"), true);
+        let hidden = listed(1_000, 600, None, true);
+        let filter = Filter {
+            project: Some(glob::Pattern::new("*/code/*").unwrap()),
+            burn: true,
+            ..Filter::default()
+        };
+        assert!(filter.matches(&app, 1_100));
+        assert!(
+            !filter.matches(&hidden, 1_100),
+            "unknown projects never match"
+        );
+        let shared = Filter {
+            shared: true,
+            ..Filter::default()
+        };
+        assert!(!shared.matches(&app, 1_100));
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let mut items = vec![
+            listed(1_000, 600, Some("/b"), false),
+            listed(1_200, 100, Some("/c"), false),
+            listed(900, 2_000, Some("/a"), false),
+        ];
+        let order = |items: &[Listed]| {
+            items
+                .iter()
+                .map(|i| i.record.created_at)
+                .collect::<Vec<_>>()
+        };
+        sort(&mut items, SortKey::Age);
+        assert_eq!(order(&items), vec![1_200, 1_000, 900]);
+        sort(&mut items, SortKey::Ttl);
+        assert_eq!(order(&items), vec![1_200, 1_000, 900]);
+        sort(&mut items, SortKey::Project);
+        assert_eq!(order(&items), vec![900, 1_000, 1_200]);
+    }
+
+    #[test]
+    fn test_machine_formats() {
+        let mut app = listed(1_000, 600, Some("/code/a,b"), true);
+        app.publisher = "alice".to_string();
+        let render = |format| {
+            let mut out = Vec::new();
+            write_machine(std::slice::from_ref(&app), format, 1_100, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let json: serde_json::Value = serde_json::from_str(&render(Format::Json)).unwrap();
+        assert_eq!(json[0]["publisher"], "alice");
+        assert_eq!(json[0]["expires_at"], 1_600);
+        assert_eq!(json[0]["record"]["burn"], true);
+
+        let csv = render(Format::Csv);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next().unwrap(), CSV_COLUMNS.join(","));
+        assert_eq!(
+            lines.next().unwrap(),
+            format!(
+                "alice,{},\"/code/a,b\",1000,1600,true,false,,false",
+                app.pubkey
+            )
+        );
+
+        assert_eq!(render(Format::Quiet), format!("{}\n", app.pubkey));
+    }
+}
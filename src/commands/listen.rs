@@ -0,0 +1,242 @@
+/// Listen command — waits for new handoffs from trusted publishers and raises a
+/// desktop notification for each, offering to resume it with one keystroke.
+///
+/// Every watched publisher gets its own DHT subscription (see
+/// `DhtClient::subscribe`) on a worker thread; new records arrive on one channel, so
+/// handoffs are announced in the order they are seen.
+use std::io::IsTerminal;
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime};
+
+use crate::cli::ListenArgs;
+use crate::record::HandoffRecord;
+use crate::trust::TrustEntry;
+use crate::ui::Paint;
+
+/// A publisher being listened to.
+#[derive(Debug, PartialEq)]
+struct Watched {
+    /// What the user knows the publisher as: a contact or pin name, or the key.
+    label: String,
+    z32: String,
+    /// This machine's own key (handoffs from the user's other machines).
+    own: bool,
+}
+
+pub fn run_listen(args: ListenArgs) -> anyhow::Result<()> {
+    // ── 1. Publishers ────────────────────────────────────────────────────
+    let own_z32 = crate::keys::store::load_keypair()?.public_key().to_z32();
+    let watched = if args.publishers.is_empty() {
+        let store = crate::trust::TrustStore::load_from(&crate::trust::trust_path()?)?;
+        default_watch_list(&own_z32, store.iter())
+    } else {
+        args.publishers
+            .iter()
+            .map(|input| {
+                let z32 = crate::contacts::resolve(input)?;
+                Ok(Watched {
+                    label: input.clone(),
+                    own: z32 == own_z32,
+                    z32,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?
+    };
+    crate::status!(
+        "Listening for handoffs from {} (Ctrl-C to stop)",
+        watched
+            .iter()
+            .map(|w| if w.own { "you" } else { w.label.as_str() })
+            .collect::<Vec<_>>()
+            .join(", ")
+            .identity()
+    );
+
+    // ── 2. Subscribe and announce ────────────────────────────────────────
+    let client = crate::transport::DhtClient::cached()?;
+    let interval = Duration::from_secs(args.interval.max(1));
+    let interactive = std::io::stdin().is_terminal();
+    let (tx, rx) = mpsc::channel::<(usize, anyhow::Result<HandoffRecord>)>();
+
+    std::thread::scope(|scope| -> anyhow::Result<()> {
+        for (index, publisher) in watched.iter().enumerate() {
+            let subscription = client.subscribe(&publisher.z32, interval)?;
+            let tx = tx.clone();
+            scope.spawn(move || {
+                for record in subscription {
+                    if tx.send((index, record)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        for (index, record) in rx {
+            let publisher = &watched[index];
+            match record {
+                Ok(record) if publisher.own && published_here(&record) => {
+                    tracing::debug!("skipping a handoff published from this machine");
+                }
+                Ok(record) => announce(publisher, &record, interactive)?,
+                Err(e) => eprintln!(
+                    "{}",
+                    format!(
+                        "Warning: ignoring a handoff from {}: {}",
+                        publisher.label, e
+                    )
+                    .warning()
+                    .stderr()
+                ),
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Your own key, then every pinned publisher not already listed.
+fn default_watch_list<'a>(
+    own_z32: &str,
+    pins: impl Iterator<Item = (&'a str, &'a TrustEntry)>,
+) -> Vec<Watched> {
+    let mut watched = vec![Watched {
+        label: own_z32.to_string(),
+        z32: own_z32.to_string(),
+        own: true,
+    }];
+    for (name, entry) in pins {
+        if watched.iter().all(|w| w.z32 != entry.pubkey) {
+            watched.push(Watched {
+                label: name.to_string(),
+                z32: entry.pubkey.clone(),
+                own: false,
+            });
+        }
+    }
+    watched
+}
+
+/// Whether `record` is the handoff this machine last published (per the handoff cache).
+fn published_here(record: &HandoffRecord) -> bool {
+    crate::cache::load()
+        .ok()
+        .flatten()
+        .and_then(|cached| cached.record_hash)
+        .is_some_and(|hash| hash == crate::cache::record_hash(record))
+}
+
+/// Arguments for `cclink pickup` to fetch `publisher`'s handoff.
+fn pickup_args(publisher: &Watched) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(profile) = crate::keys::store::profile() {
+        args.extend(["--profile".to_string(), profile.to_string()]);
+    }
+    args.push("pickup".to_string());
+    if !publisher.own {
+        // The name keeps pickup's trust check under the pin it was made with, as long
+        // as it still resolves to the same key.
+        let by_name = crate::contacts::resolve(&publisher.label).is_ok_and(|k| k == publisher.z32);
+        args.push(if by_name {
+            publisher.label.clone()
+        } else {
+            publisher.z32.clone()
+        });
+    }
+    args
+}
+
+/// Notify about a new handoff and, on a terminal, offer to resume it.
+fn announce(publisher: &Watched, record: &HandoffRecord, interactive: bool) -> anyhow::Result<()> {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+    let from = if publisher.own {
+        "your other machine"
+    } else {
+        publisher.label.as_str()
+    };
+    let command = format!("cclink {}", pickup_args(publisher).join(" "));
+    println!(
+        "New handoff from {} (published {} ago, expires in {}) — {}",
+        from.identity(),
+        crate::util::human_duration(now.saturating_sub(record.created_at)),
+        crate::util::human_duration(
+            record
+                .created_at
+                .saturating_add(record.ttl)
+                .saturating_sub(now)
+        ),
+        command
+    );
+    if let Err(e) = crate::notify::notify(&format!("cclink: new handoff from {}", from), &command) {
+        tracing::debug!(error = %e, "desktop notification failed");
+    }
+
+    if !interactive {
+        return Ok(());
+    }
+    let resume = dialoguer::Confirm::new()
+        .with_prompt("Resume it now?")
+        .default(true)
+        .interact()
+        .map_err(|e| anyhow::anyhow!("prompt failed: {}", e))?;
+    if resume {
+        // A child process, so listening carries on once the resumed session exits.
+        let status = std::process::Command::new(std::env::current_exe()?)
+            .args(pickup_args(publisher))
+            .arg("--yes")
+            .status()?;
+        if !status.success() {
+            eprintln!("{}", "Pickup failed.".error().stderr());
+        }
+        crate::status!("Listening for handoffs again...");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pubkey: &str) -> TrustEntry {
+        TrustEntry {
+            first_seen: 1_000,
+            pubkey: pubkey.to_string(),
+            verified_at: None,
+        }
+    }
+
+    #[test]
+    fn test_default_watch_list_puts_own_key_first_and_dedupes() {
+        let alice = entry("alicekey");
+        let me = entry("ownkey");
+        let alias = entry("alicekey");
+        let pins = [("alice", &alice), ("me", &me), ("work-alice", &alias)];
+        let watched = default_watch_list("ownkey", pins.into_iter());
+        assert_eq!(
+            watched,
+            [
+                Watched {
+                    label: "ownkey".to_string(),
+                    z32: "ownkey".to_string(),
+                    own: true,
+                },
+                Watched {
+                    label: "alice".to_string(),
+                    z32: "alicekey".to_string(),
+                    own: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pickup_args_for_own_key_has_no_publisher() {
+        let own = Watched {
+            label: "ownkey".to_string(),
+            z32: "ownkey".to_string(),
+            own: true,
+        };
+        assert_eq!(pickup_args(&own), ["pickup"]);
+    }
+}
@@ -1,6 +1,29 @@
+pub mod agent;
+pub mod backup;
+pub mod contacts;
+pub mod device;
+pub mod doctor;
+pub mod env;
+pub mod explain;
+pub mod history;
 pub mod init;
+pub mod inspect;
+pub mod key;
 pub mod list;
+pub mod listen;
+pub mod monitor;
 pub mod pickup;
+pub mod profile;
+pub mod prune;
 pub mod publish;
+pub mod receipts;
+pub mod recv;
 pub mod revoke;
+pub mod send;
+pub mod sessions;
+pub mod status;
+pub mod trust;
+pub mod ui;
+pub mod verify;
 pub mod whoami;
+pub mod wipe;
@@ -0,0 +1,209 @@
+/// Monitor command — periodically re-fetches this identity's handoff from the DHT and
+/// checks it is exactly the record this machine last published.
+///
+/// Each check resolves through a fresh DHT client, so the answer comes from DHT nodes
+/// rather than a local cache, and compares it with the hash kept by the handoff cache
+/// (see `crate::cache`). A record that fails verification, a published record that has
+/// gone missing, a different record in its place, or a record this machine never
+/// published all raise an alert.
+use std::time::{Duration, SystemTime};
+
+use crate::cache::CachedHandoff;
+use crate::cli::MonitorArgs;
+use crate::error::CclinkError;
+use crate::record::HandoffRecord;
+use crate::ui::Paint;
+
+/// Outcome of one check.
+#[derive(Debug, PartialEq)]
+enum Finding {
+    Ok(String),
+    Alert(String),
+}
+
+pub fn run_monitor(args: MonitorArgs) -> anyhow::Result<()> {
+    // ── 1. Identity ──────────────────────────────────────────────────────
+    // The cache already holds the public key; only fall back to the key file
+    // (which may prompt for a passphrase) when nothing has been published yet.
+    let pubkey_z32 = match load_cache() {
+        Some(cached) => cached.pubkey,
+        None => crate::keys::store::load_keypair()?.public_key().to_z32(),
+    };
+    if !args.once {
+        crate::status!(
+            "Monitoring {} every {} (Ctrl-C to stop)",
+            pubkey_z32,
+            crate::util::human_duration(args.interval)
+        );
+    }
+
+    // ── 2. Check loop ────────────────────────────────────────────────────
+    loop {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs();
+        // Re-read every round: a publish or revoke since the last check changes
+        // what is expected.
+        let expected = load_cache().filter(|c| c.pubkey == pubkey_z32);
+        let fetched = fetch(&pubkey_z32);
+        let finding = assess(expected.as_ref(), fetched, now);
+        let stamp = crate::util::format_utc(now);
+        match finding {
+            Finding::Ok(ref message) => println!("{} {}", stamp, message.success()),
+            Finding::Alert(ref message) => eprintln!(
+                "{} {}",
+                stamp,
+                format!("ALERT: {}", message).error().stderr()
+            ),
+        }
+
+        if args.once {
+            if let Finding::Alert(message) = finding {
+                anyhow::bail!("Monitor alert: {}", message);
+            }
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_secs(args.interval));
+    }
+}
+
+fn load_cache() -> Option<CachedHandoff> {
+    crate::cache::load().unwrap_or_else(|e| {
+        tracing::debug!(error = %e, "failed to read handoff cache");
+        None
+    })
+}
+
+/// Resolve the record through a fresh client. `Ok(None)`: nothing is served.
+/// `Err`: something is served but it does not parse or verify.
+fn fetch(pubkey_z32: &str) -> Result<Option<HandoffRecord>, String> {
+    let client = crate::transport::DhtClient::new().map_err(|e| e.to_string())?;
+    match client.resolve_record(pubkey_z32) {
+        Ok(record) => Ok(Some(record)),
+        Err(e)
+            if e.downcast_ref::<CclinkError>()
+                .is_some_and(|ce| matches!(ce, CclinkError::RecordNotFound)) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Compare what the DHT served with what this machine last published.
+fn assess(
+    expected: Option<&CachedHandoff>,
+    fetched: Result<Option<HandoffRecord>, String>,
+    now: u64,
+) -> Finding {
+    let fetched = match fetched {
+        Ok(fetched) => fetched,
+        Err(e) => {
+            return Finding::Alert(format!(
+                "the DHT serves a record that fails verification: {}",
+                e
+            ))
+        }
+    };
+    let expected = expected.filter(|c| c.is_active(now));
+
+    match (expected, fetched) {
+        (None, None) => Finding::Ok("no active handoff published; none served".to_string()),
+        (None, Some(record)) => {
+            if now >= record.created_at.saturating_add(record.ttl) {
+                Finding::Ok(
+                    "no active handoff published; only an expired record is served".to_string(),
+                )
+            } else {
+                Finding::Alert(format!(
+                    "the DHT serves a handoff created {} that was not published from this machine",
+                    crate::util::format_utc(record.created_at)
+                ))
+            }
+        }
+        (Some(cached), None) => Finding::Alert(format!(
+            "the handoff published {} is missing from the DHT",
+            crate::util::format_utc(cached.created_at)
+        )),
+        (Some(cached), Some(record)) => {
+            let matches = match cached.record_hash {
+                Some(ref hash) => *hash == crate::cache::record_hash(&record),
+                // Cache written before hashes were kept: the creation time is all we have.
+                None => cached.created_at == record.created_at,
+            };
+            if matches {
+                Finding::Ok(format!(
+                    "handoff published {} is served unchanged",
+                    crate::util::format_utc(cached.created_at)
+                ))
+            } else if record.created_at > cached.created_at {
+                Finding::Alert(format!(
+                    "the handoff published {} was replaced by one created {} elsewhere with this key",
+                    crate::util::format_utc(cached.created_at),
+                    crate::util::format_utc(record.created_at)
+                ))
+            } else {
+                Finding::Alert(format!(
+                    "the DHT serves a different record than the handoff published {}",
+                    crate::util::format_utc(cached.created_at)
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn published(created_at: u64) -> (HandoffRecord, CachedHandoff) {
+        let keypair = pkarr::Keypair::from_secret_key(&[9u8; 32]);
+        let record = HandoffRecord::builder(vec![1, 2, 3], 600)
+            .created_at(created_at)
+            .sign(&keypair)
+            .unwrap();
+        let cached = CachedHandoff::from_record(&record);
+        (record, cached)
+    }
+
+    fn is_alert(finding: &Finding) -> bool {
+        matches!(finding, Finding::Alert(_))
+    }
+
+    #[test]
+    fn test_unchanged_record_is_ok() {
+        let (record, cached) = published(1_000);
+        assert!(!is_alert(&assess(Some(&cached), Ok(Some(record)), 1_100)));
+        assert!(!is_alert(&assess(None, Ok(None), 1_100)));
+    }
+
+    #[test]
+    fn test_modified_missing_and_extra_records_alert() {
+        let (record, cached) = published(1_000);
+
+        let mut modified = record.clone();
+        modified.burn = true;
+        assert!(is_alert(&assess(Some(&cached), Ok(Some(modified)), 1_100)));
+        assert!(is_alert(&assess(Some(&cached), Ok(None), 1_100)));
+        assert!(is_alert(&assess(None, Ok(Some(record.clone())), 1_100)));
+        assert!(is_alert(&assess(
+            Some(&cached),
+            Err("signature mismatch".to_string()),
+            1_100
+        )));
+
+        let (newer, _) = published(1_050);
+        match assess(Some(&cached), Ok(Some(newer)), 1_100) {
+            Finding::Alert(message) => assert!(message.contains("replaced")),
+            other => panic!("expected alert, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_expired_records_are_not_alerts() {
+        let (record, cached) = published(1_000);
+        // Cached handoff expired and the DHT has dropped it, or still serves it.
+        assert!(!is_alert(&assess(Some(&cached), Ok(None), 1_600)));
+        assert!(!is_alert(&assess(Some(&cached), Ok(Some(record)), 1_600)));
+    }
+}
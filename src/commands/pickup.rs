@@ -1,152 +1,805 @@
 /// Pickup command — retrieves the handoff from the PKARR DHT, verifies its
 /// signature, checks TTL, decrypts the session ID, shows a confirmation prompt,
-/// and execs `claude --resume`.
+/// and execs the agent's resume command (`claude --resume` for Claude Code; the
+/// payload names other agents, see `crate::session::Agent`).
 ///
 /// Self-pickup (no pubkey arg): resolves own public key from the DHT.
 /// Cross-user pickup (pubkey arg): resolves the specified public key.
 /// Burn-after-read: on self-pickup of a --burn record, publishes an empty packet
-/// to revoke the record before exec.
+/// to revoke the record before exec. A `--burn=N` record spends one of its uses
+/// instead, and is revoked by the pickup that spends the last (see `crate::record::burn`).
+/// Pickup receipts: if the payload carries a receipt key (`--revoke-after-pickup`),
+/// a signed receipt is published into the handoff's receipt slot before exec.
+/// Platform hints: warns when the publisher's OS/arch or claude version differs.
+/// Path remapping: `--cd` or a `[paths]` entry in the config moves pickup into the
+/// local equivalent of the publisher's project directory before resuming.
+/// Git hints: warns when the local checkout's remote, branch, or commit differs, and
+/// offers to clone the publisher's remote when the project is missing (`--clone-to`).
+/// Transcripts: a handoff published with `--with-transcript` installs the session
+/// file (for Claude Code, JSONL in `~/.claude/projects`) when this machine does not
+/// have it.
+/// Trust on first use: cross-user pickups pin the publisher's key and warn (or,
+/// with `--strict`, fail) if a later pickup under the same name sees another key.
+/// Key rotation: a publisher key with no handoff but a valid transition record is
+/// followed to its successor (and the pin moved along); during a rotation's grace
+/// period, pickups fall back to the retired key (see `cclink key rotate`).
+/// Revocation: a publisher key with a published revocation certificate is refused.
+/// Rollback protection: a handoff older than the newest already seen from the same
+/// publisher is refused (`--allow-rollback` overrides), see `crate::state`.
 use std::io::IsTerminal;
-use std::time::SystemTime;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context;
 
 use zeroize::Zeroizing;
 
-use backon::{BlockingRetryable, ExponentialBuilder};
+use backon::BlockingRetryable;
 use base64::Engine;
-use owo_colors::{OwoColorize, Stream::Stdout};
 
+use crate::error::CclinkError;
+use crate::record::burn::BurnStep;
+use crate::ui::Paint;
 use crate::util::human_duration;
 
-/// Check whether a session JSONL file exists locally under `~/.claude/projects/`.
-///
-/// The directory structure is `~/.claude/projects/<project_hash>/<session_id>.jsonl`.
-/// We don't know the project hash, so we scan all subdirectories.
-fn session_exists_locally(session_id: &str) -> bool {
-    let Some(home) = dirs::home_dir() else {
-        return false;
-    };
-    let projects_dir = home.join(".claude/projects");
-    let Ok(entries) = std::fs::read_dir(&projects_dir) else {
-        return false;
-    };
-    let target = format!("{}.jsonl", session_id);
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_dir() && path.join(&target).exists() {
-            return true;
-        }
-    }
-    false
+/// How often `pickup --watch` checks the DHT for a new handoff.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Times a pickup re-reads and retries the burn counter after losing a race.
+const BURN_COUNTER_ATTEMPTS: usize = 3;
+
+/// How far past this machine's clock a handoff's `created_at` may be.
+const MAX_CLOCK_AHEAD_SECS: u64 = 300;
+
+/// Maximum length of the last-reply snippet shown before the confirmation prompt.
+const LAST_REPLY_CHARS: usize = 120;
+
+/// How long `--code` waits for the publisher's side of the exchange.
+const CODE_WAIT: Duration = Duration::from_secs(5 * 60);
+
+/// How often `--code` looks for the publisher's message.
+const CODE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long `--lan` browses for a machine serving the handoff.
+const LAN_WAIT: Duration = Duration::from_secs(15);
+
+/// The agent's resume command (`claude --resume <session_id>` for Claude Code) with
+/// the arguments given after `--` appended.
+fn resume_command(
+    agent: crate::session::Agent,
+    session_id: &str,
+    extra: &[String],
+) -> std::process::Command {
+    let mut cmd = agent.provider().resume_command(session_id);
+    cmd.args(extra);
+    cmd
 }
 
-/// Launch `claude --resume <session_id>`.
+/// Launch the agent's resume command.
 ///
 /// On Unix, replaces the current process via `exec()` so the shell history entry
-/// is for `cclink`, not `claude`. On non-Unix, spawns a child and waits.
-fn launch_claude_resume(session_id: &str) -> anyhow::Result<()> {
-    let mut cmd = std::process::Command::new("claude");
-    cmd.arg("--resume").arg(session_id);
+/// is for `cclink`, not the agent. On non-Unix, spawns a child and waits.
+fn launch_resume(mut cmd: std::process::Command) -> anyhow::Result<()> {
+    let program = cmd.get_program().to_string_lossy().into_owned();
 
     #[cfg(unix)]
     {
         use std::os::unix::process::CommandExt;
         let err = cmd.exec();
         // exec() only returns if it failed
-        Err(anyhow::anyhow!("failed to exec claude: {}", err))
+        Err(anyhow::anyhow!("failed to exec {}: {}", program, err))
     }
     #[cfg(not(unix))]
     {
         let status = cmd.status()?;
         if !status.success() {
-            anyhow::bail!("claude exited with status {}", status);
+            anyhow::bail!("{} exited with status {}", program, status);
         }
         Ok(())
     }
 }
 
-/// Parse decrypted blob as Payload JSON (new format) or raw session_id (old format).
-fn parse_decrypted(
-    plaintext: Vec<u8>,
+/// Publish a pickup receipt into the slot whose secret `receipt_key` carries.
+fn send_receipt(
+    client: &crate::transport::DhtClient,
+    keypair: &pkarr::Keypair,
+    record: &crate::record::HandoffRecord,
+    receipt_key: &str,
+) -> anyhow::Result<()> {
+    let seed: [u8; 32] = base64::engine::general_purpose::STANDARD
+        .decode(receipt_key)
+        .map_err(|e| anyhow::anyhow!("invalid receipt key: {}", e))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("receipt key must be exactly 32 bytes"))?;
+    let slot = pkarr::Keypair::from_secret_key(&Zeroizing::new(seed));
+
+    let details = crate::record::receipt::ReceiptDetails {
+        hostname: gethostname::gethostname().to_string_lossy().into_owned(),
+    };
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+    let receipt = crate::record::receipt::seal_receipt(
+        record.created_at,
+        &record.pubkey,
+        keypair,
+        &details,
+        now,
+    )?;
+    client.publish_receipt(&slot, &receipt)
+}
+
+/// Pin the publisher's key on first pickup, or compare it with the pinned key.
+///
+/// `input` is what the user typed. Pins are keyed by contact name, so a contact whose
+/// key was swapped shows up as a change. A key typed directly is its own identity and
+/// can never change, so it is not pinned. Either way, the first pickup from a key
+/// this machine has not seen before shows its fingerprint for checking.
+fn check_trust(input: &str, publisher_z32: &str, strict: bool) -> anyhow::Result<()> {
+    let first_contact = !crate::state::State::open()?.has_seen(publisher_z32)?;
+    if pkarr::PublicKey::try_from(input).is_ok() {
+        if first_contact {
+            crate::status!("First pickup from {}.", publisher_z32.identity());
+            print_fingerprint(publisher_z32);
+        }
+        return Ok(());
+    }
+    let name = input;
+    let path = crate::trust::trust_path()?;
+    let mut store = crate::trust::TrustStore::load_from(&path)?;
+
+    match store.check(name, publisher_z32) {
+        crate::trust::TrustCheck::Known => {}
+        crate::trust::TrustCheck::FirstUse => {
+            let now = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)?
+                .as_secs();
+            store.pin(name, publisher_z32, now);
+            crate::keys::store::ensure_key_dir()?;
+            store.save_to(&path)?;
+            crate::status!(
+                "First pickup from {} — trusting this key from now on.",
+                publisher_z32.identity()
+            );
+        }
+        crate::trust::TrustCheck::Changed { pinned } => {
+            eprintln!(
+                "{}",
+                format!(
+                    "WARNING: the public key for {} has changed!\n  pinned:  {}\n  now:     {}\n\
+                     Someone may be impersonating the publisher. If the change is expected, \
+                     run `cclink trust remove {}`.",
+                    name, pinned, publisher_z32, name
+                )
+                .error()
+                .stderr()
+            );
+            if strict {
+                return Err(CclinkError::TrustedKeyChanged(name.to_string()).into());
+            }
+        }
+    }
+    if first_contact {
+        print_fingerprint(publisher_z32);
+    }
+    Ok(())
+}
+
+/// Print the word and emoji fingerprints of `publisher_z32` for checking with the
+/// publisher, who sees the same renderings in `cclink whoami`.
+fn print_fingerprint(publisher_z32: &str) {
+    use crate::keys::fingerprint;
+    let Ok(publisher) = pkarr::PublicKey::try_from(publisher_z32) else {
+        return;
+    };
+    crate::status!("Check it with the publisher, e.g. over a call:");
+    crate::status!(
+        "{}",
+        fingerprint::grouped(&fingerprint::words(&publisher), 6, "  ")
+    );
+    crate::status!(
+        "{}",
+        fingerprint::grouped(&fingerprint::emoji(&publisher), 11, "  ")
+    );
+}
+
+/// Refuse a handoff created more than [`MAX_CLOCK_AHEAD_SECS`] after `now`, or older
+/// than the newest already picked up from `publisher_z32`. With `allow_rollback`,
+/// warn about an older one instead of refusing it.
+fn check_not_rolled_back(
+    publisher_z32: &str,
+    created_at: u64,
+    now: u64,
+    allow_rollback: bool,
+) -> anyhow::Result<()> {
+    // A far-future created_at would stay the newest seen and refuse every real
+    // handoff after it, so it is never accepted, --allow-rollback or not.
+    if created_at > now.saturating_add(MAX_CLOCK_AHEAD_SECS) {
+        return Err(CclinkError::HandoffFromFuture(human_duration(created_at - now)).into());
+    }
+    let state = crate::state::State::open()?;
+
+    if let Some(newest) = state.rolled_back(publisher_z32, created_at)? {
+        let error = CclinkError::HandoffRolledBack {
+            got: crate::util::format_utc(created_at),
+            seen: crate::util::format_utc(newest),
+        };
+        if !allow_rollback {
+            return Err(error.into());
+        }
+        eprintln!(
+            "{}",
+            format!(
+                "Warning: {}. Continuing because of --allow-rollback.",
+                error
+            )
+            .warning()
+            .stderr()
+        );
+    }
+    Ok(())
+}
+
+/// Remember `created_at` as the newest handoff picked up from `publisher_z32`, and
+/// count the pickup.
+fn remember_pickup(publisher_z32: &str, created_at: u64, now: u64) -> anyhow::Result<()> {
+    let state = crate::state::State::open()?;
+    state.record_seen(publisher_z32, created_at)?;
+    state.note_pickup(publisher_z32, now)
+}
+
+/// Spend one use of a multi-use burn `record` published by `owner`, retrying when a
+/// concurrent pickup updates the counter first.
+fn spend_burn_use(
+    client: &crate::transport::DhtClient,
+    owner: &pkarr::Keypair,
     record: &crate::record::HandoffRecord,
-) -> anyhow::Result<(String, String)> {
-    if let Ok(payload) = serde_json::from_slice::<crate::record::Payload>(&plaintext) {
-        Ok((payload.session_id, payload.project))
+    now: u64,
+) -> anyhow::Result<BurnStep> {
+    let slot = crate::crypto::burn_keypair(owner, record.created_at);
+    for _ in 0..BURN_COUNTER_ATTEMPTS {
+        let (counter, seen) = match client.resolve_burn_counter(&slot.public_key())? {
+            Some((counter, seen)) => (Some(counter), Some(seen)),
+            None => (None, None),
+        };
+        let step = crate::record::burn::next_step(record, counter.as_ref(), now);
+        let BurnStep::Spend(ref next) = step else {
+            return Ok(step);
+        };
+        if client.publish_burn_counter(&slot, next, seen, crate::transport::dns_ttl(record))? {
+            return Ok(step);
+        }
+        tracing::debug!("burn counter changed by another pickup, retrying");
+    }
+    anyhow::bail!("could not update the burn counter: other pickups keep changing it")
+}
+
+/// Refuse `publisher_z32` if it was revoked: by a certificate remembered in `state`,
+/// or else by one `published` finds on the DHT, which is remembered from then on. A
+/// certificate overwritten on the DHT later therefore keeps the key refused here.
+fn check_not_revoked(
+    state: &crate::state::State,
+    publisher_z32: &str,
+    published: impl FnOnce() -> anyhow::Result<Option<crate::record::revocation::Revocation>>,
+) -> anyhow::Result<()> {
+    let revocation = match state.revocation(publisher_z32)? {
+        Some(revocation) => revocation,
+        None => match published()? {
+            Some(revocation) => {
+                state.record_revocation(&revocation)?;
+                revocation
+            }
+            None => return Ok(()),
+        },
+    };
+    if let Some(ref reason) = revocation.reason {
+        eprintln!("Reason given: {}", reason);
+    }
+    Err(CclinkError::KeyRevoked {
+        pubkey: revocation.pubkey,
+        at: crate::util::format_utc(revocation.created_at),
+    }
+    .into())
+}
+
+/// The transition to follow from the retired key `old_z32`: the one pinned in
+/// `state`, or else the one `published` finds on the DHT, which is pinned from then
+/// on. Once a transition is pinned, erasing the slot does not hide it, and a
+/// different successor published later is refused.
+fn pinned_transition(
+    state: &crate::state::State,
+    old_z32: &str,
+    published: impl FnOnce() -> anyhow::Result<Option<crate::record::transition::Transition>>,
+) -> anyhow::Result<Option<crate::record::transition::Transition>> {
+    let pinned = state.transition(old_z32)?;
+    match (pinned, published()?) {
+        (Some(pinned), Some(other)) if other.new_pubkey != pinned.new_pubkey => {
+            Err(CclinkError::TrustedKeyChanged(format!(
+                "the successor of {} (pinned {}, now {})",
+                old_z32, pinned.new_pubkey, other.new_pubkey
+            ))
+            .into())
+        }
+        (Some(pinned), _) => Ok(Some(pinned)),
+        (None, Some(transition)) => {
+            state.record_transition(&transition)?;
+            Ok(Some(transition))
+        }
+        (None, None) => Ok(None),
+    }
+}
+
+/// Look up a transition record for `publisher_z32`, which has no handoff published.
+///
+/// On a valid cross-signed transition, says so, moves a pin of the retired key under
+/// `input` over to the successor — the old key vouched for it — and returns the
+/// successor's key. `None` when the key was never rotated.
+fn follow_transition(
+    client: &crate::transport::DhtClient,
+    input: &str,
+    publisher_z32: &str,
+) -> anyhow::Result<Option<String>> {
+    let old = pkarr::PublicKey::try_from(publisher_z32)
+        .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+    let state = crate::state::State::open()?;
+    let Some(transition) =
+        pinned_transition(&state, publisher_z32, || client.resolve_transition(&old))?
+    else {
+        return Ok(None);
+    };
+    crate::status!(
+        "{} rotated its key on {}; following the signed transition to {}",
+        input.identity(),
+        crate::util::format_utc(transition.created_at),
+        transition.new_pubkey.identity()
+    );
+
+    // Only contact names are pinned; see `check_trust`.
+    let named = pkarr::PublicKey::try_from(input).is_err();
+    let path = crate::trust::trust_path()?;
+    let mut store = crate::trust::TrustStore::load_from(&path)?;
+    if named && store.check(input, publisher_z32) == crate::trust::TrustCheck::Known {
+        store.pin(input, &transition.new_pubkey, transition.created_at);
+        store.save_to(&path)?;
+    }
+    if named && crate::contacts::resolve(input).is_ok_and(|k| k == publisher_z32) {
+        crate::status!(
+            "Update the contact with: cclink contacts add {} {}",
+            input,
+            transition.new_pubkey
+        );
+    }
+    Ok(Some(transition.new_pubkey))
+}
+
+/// The record's ciphertext: decoded from the blob, or reassembled from the DHT
+/// chunk slots when the handoff was too large for one packet (empty blob).
+/// `publisher_z32` is the key the record was resolved and verified under.
+fn load_ciphertext(
+    client: &crate::transport::DhtClient,
+    record: &crate::record::HandoffRecord,
+    publisher_z32: &str,
+) -> anyhow::Result<Vec<u8>> {
+    let publisher = pkarr::PublicKey::try_from(publisher_z32)
+        .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+    if record.blob.is_empty() {
+        crate::status!("Fetching chunked handoff...");
+    }
+    crate::handoff::ciphertext(client, &publisher, &publisher, record)
+}
+
+/// Block until the publisher publishes a new handoff, and return it.
+fn wait_for_handoff(
+    client: &crate::transport::DhtClient,
+    publisher_z32: &str,
+) -> anyhow::Result<crate::record::HandoffRecord> {
+    crate::status!(
+        "Waiting for a new handoff from {} (Ctrl-C to stop)...",
+        publisher_z32.identity()
+    );
+    client
+        .subscribe(publisher_z32, WATCH_POLL_INTERVAL)?
+        .next()
+        .expect("subscription never ends")
+}
+
+/// Read the record carried by a `--qr-payload` QR code and verify its signature.
+///
+/// `source` is the code's text, `-` to paste it (or pipe it in), or a scanned image.
+fn read_qr_record(source: &str) -> anyhow::Result<crate::record::HandoffRecord> {
+    let text = if source == "-" {
+        if std::io::stdin().is_terminal() {
+            dialoguer::Input::<String>::new()
+                .with_prompt("Paste the QR code text (cclink-handoff1:...)")
+                .interact_text()
+                .map_err(|e| anyhow::anyhow!("Paste prompt failed: {}", e))?
+        } else {
+            std::io::read_to_string(std::io::stdin()).context("Failed to read from stdin")?
+        }
+    } else if crate::record::compact::is_encoded(source) {
+        source.to_string()
+    } else {
+        crate::keys::paper::scan_image(Path::new(source))?
+    };
+    checked(crate::record::compact::decode(&text)?)
+}
+
+/// Verify the signature of a record that arrived without a DHT lookup.
+fn checked(record: crate::record::HandoffRecord) -> anyhow::Result<crate::record::HandoffRecord> {
+    let pubkey = pkarr::PublicKey::try_from(record.pubkey.as_str())
+        .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+    crate::record::verify_record(&record, &pubkey)?;
+    Ok(record)
+}
+
+/// Run the picking side of a one-time-code exchange: agree on a key with the
+/// publisher through SPAKE2 and wait for it to reveal its public key.
+///
+/// Returns the publisher's z32 key and the identity its handoff is encrypted to.
+fn receive_code(
+    client: &crate::transport::DhtClient,
+    input: &str,
+) -> anyhow::Result<(String, age::x25519::Identity)> {
+    use crate::record::wormhole::{self, WormholeMessage};
+
+    let code = wormhole::normalize_code(input)?;
+    let publisher_slot = crate::crypto::wormhole_keypair(&code, "publisher").public_key();
+    let deadline = std::time::Instant::now() + CODE_WAIT;
+    let wait = |ready: fn(&WormholeMessage) -> bool| loop {
+        if let Some(message) = client.resolve_wormhole(&publisher_slot) {
+            if ready(&message) {
+                return Ok(message);
+            }
+        }
+        if std::time::Instant::now() >= deadline {
+            anyhow::bail!("the publisher did not answer; check the code and try again");
+        }
+        std::thread::sleep(CODE_POLL_INTERVAL);
+    };
+
+    let theirs = wait(|_| true)?;
+    let (state, message) = wormhole::start(&code);
+    client.publish_wormhole(
+        &crate::crypto::wormhole_keypair(&code, "picker"),
+        &WormholeMessage {
+            pake: base64::engine::general_purpose::STANDARD.encode(message),
+            publisher: None,
+        },
+    )?;
+    let identity = wormhole::finish(state, &theirs.pake)?;
+
+    crate::status!("Code accepted. Waiting for the publisher...");
+    let revealed = wait(|m| m.publisher.is_some())?;
+    let ciphertext = base64::engine::general_purpose::STANDARD
+        .decode(revealed.publisher.as_deref().unwrap_or_default())
+        .map_err(|e| anyhow::anyhow!("invalid publisher message: {}", e))?;
+    let publisher = crate::crypto::age_decrypt(&ciphertext, &identity)
+        .ok()
+        .and_then(|plaintext| String::from_utf8(plaintext).ok())
+        .filter(|z32| pkarr::PublicKey::try_from(z32.as_str()).is_ok())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "could not open the publisher's reply: the code was mistyped, or someone \
+                 else entered it first"
+            )
+        })?;
+    Ok((publisher, identity))
+}
+
+/// Clone the publisher's repository when the handoff's project does not exist on
+/// this machine, and move into the clone so the resumed session starts there.
+///
+/// `--clone-to` clones without asking; otherwise an interactive pickup is offered a
+/// clone into `./<project name>`. A destination that is already a checkout of the
+/// same remote (an earlier pickup) is reused. Returns whether pickup moved into a clone.
+fn clone_missing_project(
+    project: &str,
+    git: &crate::record::GitState,
+    clone_to: Option<&Path>,
+    interactive: bool,
+) -> anyhow::Result<bool> {
+    let Some(ref remote) = git.remote else {
+        return Ok(false);
+    };
+    if Path::new(project).exists() {
+        return Ok(false);
+    }
+
+    let dest = match clone_to {
+        Some(dir) => dir.to_path_buf(),
+        None if interactive => {
+            let confirmed = dialoguer::Confirm::new()
+                .with_prompt(format!(
+                    "Project {} does not exist here. Clone {}?",
+                    project, remote
+                ))
+                .default(true)
+                .interact()
+                .map_err(|e| anyhow::anyhow!("prompt failed: {}", e))?;
+            if !confirmed {
+                return Ok(false);
+            }
+            let name = Path::new(project)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "project".to_string());
+            let default = std::env::current_dir()?.join(name);
+            let input: String = dialoguer::Input::new()
+                .with_prompt("Clone into")
+                .default(default.display().to_string())
+                .interact_text()
+                .map_err(|e| anyhow::anyhow!("prompt failed: {}", e))?;
+            PathBuf::from(input)
+        }
+        None => {
+            eprintln!(
+                "{}",
+                format!(
+                    "Project {} does not exist here. Pass --clone-to <DIR> to clone {}.",
+                    project, remote
+                )
+                .warning()
+                .stderr()
+            );
+            return Ok(false);
+        }
+    };
+
+    let existing = crate::git::current(&dest)
+        .and_then(|state| state.remote)
+        .is_some_and(|ours| crate::git::same_remote(remote, &ours));
+    if existing {
+        crate::status!("Using existing clone at {}", dest.display());
     } else {
-        // Old format: raw session_id string, metadata in outer record
-        let session_id = String::from_utf8(plaintext)
-            .map_err(|e| anyhow::anyhow!("session ID is not valid UTF-8: {}", e))?;
-        Ok((session_id, record.project.clone()))
+        crate::git::clone(remote, &dest, git.branch.as_deref())?;
+        crate::status!("Cloned {} into {}", remote, dest.display());
     }
+    std::env::set_current_dir(&dest)
+        .with_context(|| format!("Failed to enter {}", dest.display()))?;
+    Ok(true)
+}
+
+/// Pick up the handoff `cclink copy` put on the clipboard. Works like
+/// `cclink pickup --from-qr` with the clipboard's text.
+pub fn run_paste(mut args: crate::cli::PickupArgs) -> anyhow::Result<()> {
+    if args.pubkey.is_some() || args.code.is_some() || args.from_qr.is_some() || args.watch {
+        anyhow::bail!(
+            "cclink paste takes the handoff from the clipboard; use cclink pickup to look one up"
+        );
+    }
+    let text = crate::clipboard::paste()?;
+    if !crate::record::compact::is_encoded(&text) {
+        anyhow::bail!(
+            "the clipboard holds no cclink handoff (run `cclink copy` on the other machine)"
+        );
+    }
+    args.from_qr = Some(text);
+    run_pickup(args)
+}
+
+/// Pick up an armored handoff written by `cclink export`, from a file or stdin.
+pub fn run_import(args: crate::cli::ImportArgs) -> anyhow::Result<()> {
+    let text = match args.file {
+        Some(ref path) if path.as_os_str() != "-" => std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?,
+        _ => std::io::read_to_string(std::io::stdin()).context("Failed to read from stdin")?,
+    };
+    let record = checked(crate::record::armor::decode(&text)?)?;
+    let pickup = crate::cli::PickupArgs {
+        pubkey: None,
+        code: None,
+        from_qr: None,
+        dir: None,
+        lan: false,
+        tmux: None,
+        agent_args: args.agent_args,
+        yes: args.yes,
+        qr: false,
+        strict: args.strict,
+        allow_rollback: args.allow_rollback,
+        watch: false,
+        clone_to: args.clone_to,
+        cd: args.cd,
+    };
+    pickup_record(pickup, Some(record))
 }
 
 /// Run the pickup flow.
 pub fn run_pickup(args: crate::cli::PickupArgs) -> anyhow::Result<()> {
+    // `--from-qr` carries the signed record itself, so it is not looked up.
+    let qr_record = args.from_qr.as_deref().map(read_qr_record).transpose()?;
+    pickup_record(args, qr_record)
+}
+
+/// Run the pickup flow, with `carried` in place of a DHT lookup when it is given.
+fn pickup_record(
+    args: crate::cli::PickupArgs,
+    carried: Option<crate::record::HandoffRecord>,
+) -> anyhow::Result<()> {
     // ── 1. Load keypair ──────────────────────────────────────────────────
     let keypair = crate::keys::store::load_keypair()?;
     let own_z32 = keypair.public_key().to_z32();
 
-    let is_cross_user = args.pubkey.is_some();
-    let target_z32 = args.pubkey.as_deref().unwrap_or(&own_z32);
+    let client = crate::progress::attach(crate::transport::DhtClient::new()?, "Downloading");
 
-    let client = crate::transport::DhtClient::new()?;
+    // The publisher may be given as a contact name as well as a public key, be named
+    // by a record carried off the DHT, or be learned through a one-time code along with
+    // the key the handoff is encrypted to.
+    let (target, code_identity) = match (args.code.as_deref(), carried.as_ref()) {
+        (Some(code), _) => {
+            let (publisher, identity) = receive_code(&client, code)?;
+            (Some(publisher), Some(identity))
+        }
+        (None, Some(record)) => (
+            Some(record.pubkey.clone()).filter(|pk| *pk != own_z32),
+            None,
+        ),
+        (None, None) => (
+            args.pubkey
+                .as_deref()
+                .map(crate::contacts::resolve)
+                .transpose()?,
+            None,
+        ),
+    };
+    let is_cross_user = target.is_some();
+    let target_z32 = target.as_deref().unwrap_or(&own_z32);
+    tracing::debug!(target = %target_z32, cross_user = is_cross_user, "starting pickup");
 
     // ── 2. Retrieve record with retry/backoff ────────────────────────────
+    // A shared folder stands in for the DHT, unless a one-time code was given.
+    let dir = match args.dir {
+        Some(ref dir) => Some(dir.clone()),
+        None if carried.is_none() && args.code.is_none() && !args.lan => {
+            crate::config::load()?.transport.dir
+        }
+        None => None,
+    };
+    if args.watch && dir.is_some() {
+        anyhow::bail!("--watch cannot be used with a transport directory");
+    }
     let target_z32_owned = target_z32.to_string();
-    let record = (|| client.resolve_record(&target_z32_owned))
-        .retry(
-            ExponentialBuilder::default()
-                .with_min_delay(std::time::Duration::from_secs(2))
-                .with_max_delay(std::time::Duration::from_secs(8))
-                .with_total_delay(Some(std::time::Duration::from_secs(30))),
-        )
+    let resolved = if let Some(record) = carried {
+        Ok(record)
+    } else if args.lan {
+        crate::status!("Looking for the handoff on the local network...");
+        crate::transport::lan::discover(target_z32, LAN_WAIT)
+    } else if let Some(ref dir) = dir {
+        crate::transport::file::FileTransport::at(dir)
+            .and_then(|transport| transport.resolve_record(target_z32))
+    } else if args.watch {
+        wait_for_handoff(&client, target_z32)
+    } else {
+        (|| client.resolve_record(&target_z32_owned))
+        .retry(crate::config::network().backoff())
         .sleep(std::thread::sleep)
+        .notify(|e, retry_in| {
+            tracing::debug!(error = %e, retry_in = ?retry_in, "record resolve failed, retrying");
+        })
         .when(|e| {
             // Retry on transient errors; stop immediately on RecordNotFound (permanent)
-            !e.downcast_ref::<crate::error::CclinkError>()
-                .is_some_and(|ce| matches!(ce, crate::error::CclinkError::RecordNotFound))
+            !e.downcast_ref::<CclinkError>()
+                .is_some_and(|ce| matches!(ce, CclinkError::RecordNotFound))
         })
         .call()
-        .map_err(|e| anyhow::anyhow!("Failed to retrieve handoff after retries: {}", e))?;
+        .map_err(|e| e.context("Failed to retrieve handoff after retries"))
+    };
+    // A key with nothing published may have been rotated away.
+    let mut retired: Option<pkarr::Keypair> = None;
+    let mut target_z32 = target_z32.to_string();
+    let record = match resolved {
+        Ok(record) => record,
+        Err(e)
+            if e.downcast_ref::<CclinkError>()
+                .is_some_and(|ce| matches!(ce, CclinkError::RecordNotFound)) =>
+        {
+            // A revoked key has no successor to follow: its certificate may be
+            // all that is left in its packet.
+            let revoked = pkarr::PublicKey::try_from(target_z32.as_str())
+                .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+            check_not_revoked(&crate::state::State::open()?, &target_z32, || {
+                client.resolve_revocation(&revoked)
+            })?;
+            if let Some(ref input) = args.pubkey {
+                let Some(successor) = follow_transition(&client, input, &target_z32)? else {
+                    return Err(e);
+                };
+                target_z32 = successor;
+                client.resolve_record(&target_z32)?
+            } else {
+                let Some(previous) = crate::keys::store::load_previous_keypair()? else {
+                    return Err(e);
+                };
+                tracing::debug!("no handoff under the new key, trying the retired one");
+                let record = client.resolve_record(&previous.public_key().to_z32())?;
+                target_z32 = previous.public_key().to_z32();
+                retired = Some(previous);
+                record
+            }
+        }
+        Err(e) => return Err(e),
+    };
+    let target_z32 = target_z32.as_str();
+
+    // ── Revocation check ─────────────────────────────────────────────────
+    let publisher = pkarr::PublicKey::try_from(target_z32)
+        .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+    check_not_revoked(&crate::state::State::open()?, target_z32, || {
+        client.resolve_revocation(&publisher)
+    })?;
+
+    // The publisher's self-chosen display name, shown next to its key. A contact
+    // name the user gave the key takes precedence, so it is only looked up for keys.
+    let publisher_name = match args.pubkey {
+        Some(ref input) if pkarr::PublicKey::try_from(input.as_str()).is_ok() => {
+            client.display_name(&publisher)
+        }
+        _ => None,
+    };
+
+    // ── Trust-on-first-use check ─────────────────────────────────────────
+    if let Some(ref input) = args.pubkey {
+        check_trust(input, target_z32, args.strict)?;
+    }
 
-    // ── 3. TTL expiry check ──────────────────────────────────────────────
     let now_secs = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
         .map(|d| d.as_secs())
         .unwrap_or(0);
 
+    // ── Rollback check ───────────────────────────────────────────────────
+    check_not_rolled_back(target_z32, record.created_at, now_secs, args.allow_rollback)?;
+
+    // ── 3. TTL expiry check ──────────────────────────────────────────────
+    let config = crate::config::load()?;
+
     let expires_at = record.created_at.saturating_add(record.ttl);
+    tracing::debug!(
+        created_at = record.created_at,
+        ttl = record.ttl,
+        now = now_secs,
+        burn = record.burn,
+        pin = record.pin_salt.is_some(),
+        shared = record.recipient.is_some(),
+        "record retrieved"
+    );
     if now_secs >= expires_at {
         let expired_secs = now_secs.saturating_sub(expires_at);
-        let expired_human = human_duration(expired_secs);
-        eprintln!(
-            "{}",
-            format!(
-                "Error: This handoff expired {} ago. Publish a new one with cclink.",
-                expired_human
-            )
-            .if_supports_color(Stdout, |t| t.red())
-        );
-        anyhow::bail!(
-            "This handoff expired {} ago. Publish a new one with cclink.",
-            expired_human
-        );
+        return Err(CclinkError::HandoffExpired(human_duration(expired_secs)).into());
+    }
+    // A burn handoff must be claimed within the safety window even if its own TTL
+    // is longer (published by an older cclink, or with a looser config).
+    if record.burn {
+        let max_age = config.burn.max_age;
+        let age_secs = now_secs.saturating_sub(record.created_at);
+        if age_secs >= max_age {
+            return Err(CclinkError::BurnWindowExceeded {
+                age: human_duration(age_secs),
+                limit: human_duration(max_age),
+            }
+            .into());
+        }
     }
 
     // ── 4. Decrypt or show metadata ──────────────────────────────────────
     let age_secs = now_secs.saturating_sub(record.created_at);
     let human_age = human_duration(age_secs);
 
-    let session_id: String;
-    let display_project: String;
+    let payload: crate::record::Payload;
+    let ciphertext = load_ciphertext(&client, &record, target_z32)?;
 
     // ── PIN-protected record detection ───────────────────────────────────
     if let Some(ref pin_salt_b64) = record.pin_salt {
-        // Non-interactive guard: PIN prompt requires a terminal
-        if !std::io::stdin().is_terminal() {
-            anyhow::bail!("PIN-protected handoff requires interactive terminal for PIN entry");
+        // Non-interactive guard: without a supplied PIN, the prompt requires a terminal
+        let supplied_pin = crate::keys::input::pin()?;
+        if supplied_pin.is_none() && !std::io::stdin().is_terminal() {
+            anyhow::bail!(
+                "PIN-protected handoff requires interactive terminal for PIN entry \
+                 (or set CCLINK_PIN / --pin-file)"
+            );
         }
 
-        // PIN-protected record: prompt for PIN and decrypt
+        // PIN-protected record: prompt for PIN (unless supplied) and decrypt
         let salt_bytes = base64::engine::general_purpose::STANDARD
             .decode(pin_salt_b64)
             .map_err(|e| anyhow::anyhow!("invalid pin_salt base64: {}", e))?;
@@ -154,64 +807,91 @@ pub fn run_pickup(args: crate::cli::PickupArgs) -> anyhow::Result<()> {
             .try_into()
             .map_err(|_| anyhow::anyhow!("pin_salt must be exactly 32 bytes"))?;
 
-        let ciphertext = base64::engine::general_purpose::STANDARD
-            .decode(&record.blob)
-            .map_err(|e| anyhow::anyhow!("failed to decode blob: {}", e))?;
-
-        let pin = Zeroizing::new(
-            dialoguer::Password::new()
-                .with_prompt("Enter PIN")
-                .interact()
-                .map_err(|e| anyhow::anyhow!("PIN prompt failed: {}", e))?,
-        );
+        let pin = match supplied_pin {
+            Some(pin) => pin,
+            None => Zeroizing::new(
+                dialoguer::Password::new()
+                    .with_prompt("Enter PIN")
+                    .interact()
+                    .map_err(|e| anyhow::anyhow!("PIN prompt failed: {}", e))?,
+            ),
+        };
 
         match crate::crypto::pin_decrypt(&ciphertext, &pin, &salt) {
             Ok(plaintext) => {
-                let (sid, proj) = parse_decrypted(plaintext, &record)?;
-                session_id = sid;
-                display_project = proj;
-            }
-            Err(_) => {
-                eprintln!(
-                    "{}",
-                    "Error: Incorrect PIN. Cannot decrypt this handoff."
-                        .if_supports_color(Stdout, |t| t.red())
-                );
-                anyhow::bail!("Incorrect PIN — decryption failed");
+                payload = crate::record::decode_payload(plaintext, &record)?;
             }
+            Err(_) => return Err(CclinkError::IncorrectPin.into()),
         }
     } else if is_cross_user {
         // Cross-user pickup: attempt decryption with own key.
-        let ciphertext = base64::engine::general_purpose::STANDARD
-            .decode(&record.blob)
-            .map_err(|e| anyhow::anyhow!("failed to decode blob: {}", e))?;
         let x25519_secret = crate::crypto::ed25519_to_x25519_secret(&keypair);
         let identity = crate::crypto::age_identity(&x25519_secret);
 
-        match crate::crypto::age_decrypt(&ciphertext, &identity) {
+        let decrypted = crate::crypto::age_decrypt(&ciphertext, &identity)
+            .or_else(|e| {
+                // Shared with the key this machine retired, before the rotation.
+                match crate::keys::store::load_previous_keypair() {
+                    Ok(Some(previous)) => {
+                        let x25519_secret = crate::crypto::ed25519_to_x25519_secret(&previous);
+                        let identity = crate::crypto::age_identity(&x25519_secret);
+                        crate::crypto::age_decrypt(&ciphertext, &identity)
+                    }
+                    _ => Err(e),
+                }
+            })
+            .or_else(|e| {
+                // Shared to a standard age recipient whose identity was imported.
+                match record.recipient.as_deref() {
+                    Some(r) if crate::keys::age_identity::is_recipient(r) => {
+                        let identities = crate::keys::age_identity::load()?;
+                        if identities.is_empty() {
+                            return Err(e);
+                        }
+                        crate::crypto::age_decrypt_any(&ciphertext, &identities)
+                    }
+                    _ => Err(e),
+                }
+            })
+            .or_else(|e| match code_identity {
+                // Encrypted to the key agreed through `--code`.
+                Some(ref identity) => crate::crypto::age_decrypt(&ciphertext, identity),
+                None => Err(e),
+            });
+        match decrypted {
             Ok(plaintext) => {
-                let (sid, proj) = parse_decrypted(plaintext, &record)?;
-                session_id = sid;
-                display_project = proj;
+                payload = crate::record::decode_payload(plaintext, &record)?;
             }
             Err(_) => {
                 // Cannot decrypt — metadata is encrypted in the blob
                 println!(
                     "Handoff from {}",
-                    record.pubkey.if_supports_color(Stdout, |t| t.cyan())
+                    crate::commands::profile::with_name(&record.pubkey, publisher_name.as_deref())
+                        .identity()
                 );
                 println!("  Created: {} ago", human_age);
-                if record.recipient.is_some() {
+                if let Some(age) = record
+                    .recipient
+                    .as_deref()
+                    .filter(|r| crate::keys::age_identity::is_recipient(r))
+                {
+                    println!(
+                        "{}",
+                        format!(
+                            "This handoff was shared with age recipient {}. Import its identity with `cclink init --import-age <FILE>`.",
+                            age
+                        ).warning()
+                    );
+                } else if record.recipient.is_some() {
                     println!(
                         "{}",
-                        "This handoff was encrypted for a specific recipient. Your key cannot decrypt it."
-                            .if_supports_color(Stdout, |t| t.yellow())
+                        "This handoff was encrypted for a specific recipient. Your key cannot decrypt it.".warning()
                     );
                 } else {
                     println!(
                         "{}",
                         "This handoff is self-encrypted. Only the publisher can decrypt it."
-                            .if_supports_color(Stdout, |t| t.yellow())
+                            .warning()
                     );
                 }
                 return Ok(());
@@ -220,53 +900,169 @@ pub fn run_pickup(args: crate::cli::PickupArgs) -> anyhow::Result<()> {
     } else {
         // Self-pickup path
 
-        // Check if this is the publisher's own --share record
-        if let Some(ref intended_recipient) = record.recipient {
+        // Published with `--to` for this machine: decrypt with its device key.
+        if let Some(device) = crate::handoff::device_for(&record)? {
+            payload = crate::handoff::open(&ciphertext, &device.identity, &record)?;
+        } else if let Some(ref intended_recipient) = record.recipient {
+            // The publisher's own --share record, or one sent to another device
+            let device_name = client.resolve_devices(&keypair).ok().and_then(|registry| {
+                registry
+                    .name_of(intended_recipient)
+                    .map(|name| format!("device {}", name))
+            });
             eprintln!(
                 "{}",
                 format!(
                     "Error: This handoff was shared with {}. Only the recipient can decrypt it.",
-                    intended_recipient
+                    device_name.as_deref().unwrap_or(intended_recipient)
                 )
-                .if_supports_color(Stdout, |t| t.red())
+                .error()
             );
             println!("  Created: {} ago", human_age);
             return Ok(());
+        } else {
+            // Self-encrypt path: decrypt with own key (the retired one for its handoff)
+            let x25519_secret =
+                crate::crypto::ed25519_to_x25519_secret(retired.as_ref().unwrap_or(&keypair));
+            let identity = crate::crypto::age_identity(&x25519_secret);
+            payload = crate::handoff::open(&ciphertext, &identity, &record)?;
         }
+    }
+    // Where the handoff came from: its registered device name, else its hostname,
+    // after the publisher's display name for cross-user pickups.
+    let machine = payload
+        .device
+        .clone()
+        .or_else(|| Some(payload.hostname.clone()).filter(|h| !h.is_empty()));
+    let origin = match (publisher_name.as_deref(), machine) {
+        (Some(name), Some(machine)) => Some(format!(
+            "{} on {}",
+            crate::commands::profile::quoted(name),
+            machine
+        )),
+        (Some(name), None) => Some(crate::commands::profile::quoted(name)),
+        (None, machine) => machine,
+    };
+    let session_id = payload.session_id;
+    let display_project = payload.project;
+    let agent = crate::session::Agent::from_payload(payload.agent.as_deref())?;
+    let provider = agent.provider();
+    let hook_env = || {
+        crate::hooks::HookEnv::new()
+            .set("PUBLISHER", target_z32)
+            .set("SESSION_ID", &session_id)
+            .set("PROJECT", &display_project)
+            .set("AGENT", agent.name())
+            .set("HOSTNAME", &payload.hostname)
+            .set("CREATED_AT", record.created_at)
+            .set("TTL", record.ttl)
+            .set("BURN", record.burn)
+    };
+    crate::hooks::run(&config.hooks, crate::hooks::Event::PrePickup, hook_env())?;
 
-        // Self-encrypt path: decrypt with own key
-        let ciphertext = base64::engine::general_purpose::STANDARD
-            .decode(&record.blob)
-            .map_err(|e| anyhow::anyhow!("failed to decode blob: {}", e))?;
-        let x25519_secret = crate::crypto::ed25519_to_x25519_secret(&keypair);
-        let identity = crate::crypto::age_identity(&x25519_secret);
-        let plaintext = crate::crypto::age_decrypt(&ciphertext, &identity)?;
-        let (sid, proj) = parse_decrypted(plaintext, &record)?;
-        session_id = sid;
-        display_project = proj;
+    // ── Platform hints ───────────────────────────────────────────────────
+    if let Some(ref published) = payload.platform {
+        for warning in crate::platform::mismatches(published, &crate::platform::current()) {
+            eprintln!("{}", format!("Warning: {}", warning).warning().stderr());
+        }
+    }
+
+    // ── Project directory ────────────────────────────────────────────────
+    // The agent resumes in the current directory. `--cd` or a `[paths]`
+    // mapping names where the published project lives on this machine.
+    let remapped = args
+        .cd
+        .clone()
+        .or_else(|| config.map_project(&display_project));
+    let mut local_project = match remapped {
+        Some(dir) if dir.is_dir() => {
+            std::env::set_current_dir(&dir)
+                .with_context(|| format!("Failed to enter {}", dir.display()))?;
+            crate::status!("Resuming in {}", dir.display());
+            std::env::current_dir()?.display().to_string()
+        }
+        Some(dir) if args.cd.is_some() => {
+            anyhow::bail!("--cd {}: no such directory", dir.display())
+        }
+        // A mapped path that does not exist yet may still be cloned below.
+        Some(dir) => dir.display().to_string(),
+        None => display_project.clone(),
+    };
+
+    // ── Git hints ────────────────────────────────────────────────────────
+    // The checkout the session will see is the current directory — after
+    // cloning, the clone.
+    if let Some(ref published) = payload.git {
+        let interactive = !args.yes && std::io::stdin().is_terminal();
+        if clone_missing_project(
+            &local_project,
+            published,
+            args.clone_to.as_deref(),
+            interactive,
+        )? {
+            local_project = std::env::current_dir()?.display().to_string();
+        }
+        let local = std::env::current_dir()
+            .ok()
+            .and_then(|cwd| crate::git::current(&cwd));
+        for warning in crate::git::mismatches(published, local.as_ref()) {
+            eprintln!("{}", format!("Warning: {}", warning).warning().stderr());
+        }
     }
 
     // ── 5. Burn-after-read ───────────────────────────────────────────────
     // Only attempt revoke on self-pickup: we have the keypair to sign a new packet.
     // Cross-user pickup cannot revoke the publisher's record.
     if record.burn && !is_cross_user {
-        if let Err(e) = client.revoke(&keypair) {
-            eprintln!(
-                "{}",
-                format!("Warning: burn revocation failed: {}", e)
-                    .if_supports_color(Stdout, |t| t.yellow())
-            );
+        let owner = retired.as_ref().unwrap_or(&keypair);
+        let step = if crate::record::burn::is_counted(&record) {
+            spend_burn_use(&client, owner, &record, now_secs)?
+        } else {
+            BurnStep::Delete
+        };
+        match step {
+            BurnStep::Spend(ref counter) if counter.remaining > 0 => {
+                crate::status!("Burn handoff: {} pickup(s) left.", counter.remaining)
+            }
+            BurnStep::Spend(_) | BurnStep::Grace => {
+                crate::status!("Burn handoff: last pickup used; it stays up for its grace period.")
+            }
+            BurnStep::Delete | BurnStep::Exhausted => {
+                if let Err(e) = client.revoke(owner) {
+                    eprintln!(
+                        "{}",
+                        format!("Warning: burn revocation failed: {}", e).warning()
+                    );
+                }
+            }
+        }
+        if step == BurnStep::Exhausted {
+            anyhow::bail!("This burn handoff has used all of its pickups and its grace period");
         }
     }
 
     // ── 6. Confirmation prompt ───────────────────────────────────────────
+    let session_file = provider.find_session_file(&session_id);
     let skip_confirm = args.yes || !std::io::stdin().is_terminal();
     if !skip_confirm {
+        // Show where the conversation left off so the user can tell it is the
+        // one they expect.
+        if let Some(snippet) = session_file
+            .as_deref()
+            .and_then(|path| provider.last_assistant_message(path, LAST_REPLY_CHARS))
+        {
+            println!("{}", format!("Last reply: \"{}\"", snippet).muted());
+        }
+        let from = origin
+            .as_deref()
+            .map(|origin| format!(" from {}", origin))
+            .unwrap_or_default();
         let confirmed = dialoguer::Confirm::new()
             .with_prompt(format!(
-                "Resume session {} ({}) published {} ago?",
+                "Resume session {} ({}){} published {} ago?",
                 &session_id[..8.min(session_id.len())],
                 display_project,
+                from,
                 human_age
             ))
             .default(true)
@@ -274,7 +1070,7 @@ pub fn run_pickup(args: crate::cli::PickupArgs) -> anyhow::Result<()> {
             .map_err(|e| anyhow::anyhow!("prompt failed: {}", e))?;
 
         if !confirmed {
-            println!("Aborted.");
+            crate::status!("Aborted.");
             return Ok(());
         }
     }
@@ -286,40 +1082,155 @@ pub fn run_pickup(args: crate::cli::PickupArgs) -> anyhow::Result<()> {
     }
 
     // ── 8. Pre-flight: verify session exists locally ──────────────────
-    if !session_exists_locally(&session_id) {
-        eprintln!(
-            "{}",
-            format!(
-                "Error: Session {} was published from another machine, but the session \
-                 data doesn't exist locally.",
-                &session_id[..8.min(session_id.len())]
-            )
-            .if_supports_color(Stdout, |t| t.red())
-        );
-        eprintln!();
-        eprintln!(
-            "{}",
-            "claude --resume requires the session files in ~/.claude/projects/ to be \
-             present on this machine. To resume sessions across machines, sync your \
-             ~/.claude/ directory (e.g. via Syncthing, NFS, rsync, or a shared \
-             filesystem over Tailscale/SSH)."
-                .if_supports_color(Stdout, |t| t.yellow())
-        );
-        eprintln!();
+    // A handoff published with --with-transcript carries the session itself, so a
+    // machine that never had it can install it and resume anyway.
+    let session_file = match (session_file, payload.transcript) {
+        (Some(path), _) => Some(path),
+        (None, Some(transcript)) => {
+            let path = provider.install_transcript(&session_id, &local_project, &transcript)?;
+            crate::status!("Installed session transcript to {}", path.display());
+            Some(path)
+        }
+        (None, None) => None,
+    };
+    if session_file.is_none() {
         eprintln!("Session ID: {}", session_id);
-        anyhow::bail!("session data not found locally");
+        return Err(CclinkError::SessionDataMissing.into());
+    }
+
+    // Only now is the handoff picked up; one that failed to decrypt, or was
+    // declined, must not raise the rollback mark.
+    remember_pickup(target_z32, record.created_at, now_secs)?;
+
+    // ── 9. Pickup receipt ────────────────────────────────────────────────
+    // Best-effort: a failed receipt only means the publisher's handoff stays up
+    // until it expires.
+    if let Some(ref receipt_key) = payload.receipt_key {
+        if let Err(e) = send_receipt(&client, &keypair, &record, receipt_key) {
+            eprintln!(
+                "{}",
+                format!("Warning: could not send pickup receipt: {}", e).warning()
+            );
+        }
     }
 
-    // ── 9. Launch claude --resume ────────────────────────────────────────
-    println!(
+    let history_entry = crate::history::Entry {
+        peer: is_cross_user.then(|| target_z32.to_string()),
+        project: Some(local_project.clone()),
+        ..crate::history::Entry::now(
+            crate::history::Direction::In,
+            crate::history::Kind::Handoff,
+            record.created_at,
+            &session_id,
+        )
+    };
+
+    crate::hooks::run(
+        &config.hooks,
+        crate::hooks::Event::PostPickup,
+        hook_env().set("LOCAL_PROJECT", &local_project),
+    )?;
+
+    // ── 10. Launch the agent's resume command ───────────────────────────
+    crate::status!(
         "{}",
         format!(
             "Resuming session {}...",
             &session_id[..8.min(session_id.len())]
         )
-        .if_supports_color(Stdout, |t| t.green())
+        .success()
     );
-    launch_claude_resume(&session_id)?;
+    let cmd = resume_command(agent, &session_id, &args.agent_args);
+    match args.tmux {
+        Some(ref session) => {
+            let window = crate::tmux::launch(&cmd, session.as_deref(), &std::env::current_dir()?)?;
+            crate::history::record(crate::history::Entry {
+                tmux: Some(window.clone()),
+                ..history_entry
+            });
+            println!(
+                "Running in tmux window {}. Attach with: tmux attach -t {}",
+                window.identity(),
+                window
+            );
+        }
+        None => {
+            crate::history::record(history_entry);
+            launch_resume(cmd)?;
+        }
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{check_not_revoked, pinned_transition};
+    use crate::error::CclinkError;
+    use crate::record::revocation::seal_revocation;
+    use crate::record::transition::seal_transition;
+
+    fn is_revoked(result: anyhow::Result<()>) -> bool {
+        result.is_err_and(|e| {
+            matches!(
+                e.downcast_ref::<CclinkError>(),
+                Some(CclinkError::KeyRevoked { .. })
+            )
+        })
+    }
+
+    #[test]
+    fn test_revocation_survives_an_overwritten_slot() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let state = crate::state::State::open_at(&dir.path().join("state.db")).unwrap();
+        let keypair = pkarr::Keypair::from_secret_key(&[42u8; 32]);
+        let z32 = keypair.public_key().to_z32();
+        let revocation = seal_revocation(&keypair, 1_700_000_000, None).unwrap();
+
+        assert!(check_not_revoked(&state, &z32, || Ok(None)).is_ok());
+        assert!(is_revoked(check_not_revoked(&state, &z32, || {
+            Ok(Some(revocation.clone()))
+        })));
+        // The slot now holds junk, or nothing: the remembered certificate still
+        // refuses the key, without asking the DHT.
+        assert!(is_revoked(check_not_revoked(&state, &z32, || {
+            panic!("a remembered revocation needs no lookup")
+        })));
+        assert!(check_not_revoked(&state, "other", || Ok(None)).is_ok());
+    }
+
+    #[test]
+    fn test_pinned_transition_survives_a_rewritten_slot() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let state = crate::state::State::open_at(&dir.path().join("state.db")).unwrap();
+        let old = pkarr::Keypair::from_secret_key(&[42u8; 32]);
+        let z32 = old.public_key().to_z32();
+        let real = seal_transition(
+            &old,
+            &pkarr::Keypair::from_secret_key(&[43u8; 32]),
+            1_000,
+            2_000,
+        )
+        .unwrap();
+        let rogue = seal_transition(
+            &old,
+            &pkarr::Keypair::from_secret_key(&[44u8; 32]),
+            3_000,
+            4_000,
+        )
+        .unwrap();
+
+        assert_eq!(pinned_transition(&state, &z32, || Ok(None)).unwrap(), None);
+        let followed = pinned_transition(&state, &z32, || Ok(Some(real.clone()))).unwrap();
+        assert_eq!(followed.as_ref(), Some(&real));
+        // An erased slot no longer hides the rotation.
+        let erased = pinned_transition(&state, &z32, || Ok(None)).unwrap();
+        assert_eq!(erased.as_ref(), Some(&real));
+        // A transition to another key, even one the old key signed, is refused.
+        let err = pinned_transition(&state, &z32, || Ok(Some(rogue))).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CclinkError>(),
+            Some(CclinkError::TrustedKeyChanged(_))
+        ));
+    }
+}
@@ -0,0 +1,67 @@
+/// Profile command — publish a display name for this key, or look one up.
+///
+/// The name is signed by the key and stored in a slot anyone can derive from the
+/// public key (see `crate::record::profile`), so others see it next to your pubkey.
+use std::time::SystemTime;
+
+use crate::cli::ProfileCommand;
+use crate::ui::Paint;
+
+pub fn run_profile(args: crate::cli::ProfileArgs) -> anyhow::Result<()> {
+    let client = crate::transport::DhtClient::new()?;
+
+    match args.command {
+        ProfileCommand::SetName { name } => {
+            let keypair = crate::keys::store::load_keypair()?;
+            let now = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)?
+                .as_secs();
+            let profile = crate::record::profile::seal_profile(&keypair, now, &name)?;
+            client.publish_profile(&profile)?;
+            crate::status!(
+                "Published display name {} for {}",
+                quoted(&profile.name).identity(),
+                profile.pubkey
+            );
+        }
+        ProfileCommand::Show { pubkey } => {
+            let target_z32 = match pubkey {
+                Some(ref input) => crate::contacts::resolve(input)?,
+                None => crate::keys::store::load_keypair()?.public_key().to_z32(),
+            };
+            let target = pkarr::PublicKey::try_from(target_z32.as_str())
+                .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+            match client.resolve_profile(&target)? {
+                Some(profile) => println!("{} {}", target_z32, quoted(&profile.name)),
+                None => println!("No display name published for {}.", target_z32),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A display name as shown to users: quoted, since it is claimed by the key's owner
+/// and not vouched for by anyone.
+pub fn quoted(name: &str) -> String {
+    format!("\"{}\"", name)
+}
+
+/// `pubkey "Name"` when the key has published a display name, else just `pubkey`.
+pub fn with_name(label: &str, name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!("{} {}", label, quoted(name)),
+        None => label.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_name() {
+        assert_eq!(with_name("abc123", Some("Alice")), "abc123 \"Alice\"");
+        assert_eq!(with_name("abc123", None), "abc123");
+    }
+}
@@ -0,0 +1,237 @@
+/// Prune command — clears expired records this identity still has on the DHT.
+///
+/// DHT nodes keep serving a signed packet until they drop it on their own schedule,
+/// whatever TTL the record inside carries, so an expired handoff or file stays
+/// readable (and shows up in `cclink list --expired`) for hours. Prune overwrites each
+/// expired record with an empty packet, as `cclink revoke` does. It checks this
+/// identity's handoff and the file slot of every recipient `cclink send` sent to
+/// from this machine (taken from the local history).
+use std::time::SystemTime;
+
+use crate::error::CclinkError;
+use crate::history::{Direction, Entry, Kind};
+use crate::record::HandoffRecord;
+use crate::transport::DhtClient;
+use crate::ui::Paint;
+
+/// A DHT slot holding one of this identity's records.
+pub struct Target {
+    /// What the slot holds, for output: "handoff", or "file to <peer>".
+    pub label: String,
+    pub slot: pkarr::Keypair,
+    own_handoff: bool,
+}
+
+/// A record found due for pruning.
+struct Due {
+    label: String,
+    created_at: u64,
+}
+
+pub fn run_prune(args: crate::cli::PruneArgs) -> anyhow::Result<()> {
+    let keypair = crate::keys::store::load_keypair()?;
+    let client = DhtClient::new()?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+
+    let due = prune(&client, &keypair, now, args.older_than, args.dry_run)?;
+    if due.is_empty() {
+        crate::status!("Nothing to prune.");
+        return Ok(());
+    }
+    let verb = if args.dry_run {
+        "Would prune"
+    } else {
+        "Pruned"
+    };
+    for item in &due {
+        crate::status!(
+            "  {} {} (published {} ago)",
+            verb,
+            item.label.identity(),
+            crate::util::human_duration(now.saturating_sub(item.created_at))
+        );
+    }
+    crate::status!("{}", format!("{} {} record(s).", verb, due.len()).success());
+    Ok(())
+}
+
+/// Prune expired file records after a publish. The handoff itself was just replaced,
+/// so only file slots are checked. Failures are logged, not returned — pruning must
+/// never make a publish fail.
+pub fn after_publish(client: &DhtClient, keypair: &pkarr::Keypair, now: u64) {
+    let result = file_targets(keypair).and_then(|targets| {
+        let mut pruned = 0;
+        for target in targets {
+            if prune_target(client, keypair, &target, now, None, false)?.is_some() {
+                pruned += 1;
+            }
+        }
+        Ok(pruned)
+    });
+    match result {
+        Ok(0) => {}
+        Ok(pruned) => crate::status!(
+            "{}",
+            format!("Pruned {} expired file record(s).", pruned).muted()
+        ),
+        Err(e) => tracing::debug!(error = %e, "pruning after publish failed"),
+    }
+}
+
+/// Check every slot of `keypair` and clear the records that are due, unless `dry_run`.
+/// Returns the records found due.
+fn prune(
+    client: &DhtClient,
+    keypair: &pkarr::Keypair,
+    now: u64,
+    older_than: Option<u64>,
+    dry_run: bool,
+) -> anyhow::Result<Vec<Due>> {
+    let mut targets = vec![Target {
+        label: "handoff".to_string(),
+        slot: keypair.clone(),
+        own_handoff: true,
+    }];
+    targets.extend(file_targets(keypair)?);
+
+    let mut due = Vec::new();
+    for target in &targets {
+        if let Some(item) = prune_target(client, keypair, target, now, older_than, dry_run)? {
+            due.push(item);
+        }
+    }
+    Ok(due)
+}
+
+/// Clear the record in `target` if it is due. A slot that is empty or was already
+/// cleared counts as not due.
+fn prune_target(
+    client: &DhtClient,
+    keypair: &pkarr::Keypair,
+    target: &Target,
+    now: u64,
+    older_than: Option<u64>,
+    dry_run: bool,
+) -> anyhow::Result<Option<Due>> {
+    let location = target.slot.public_key();
+    let record = match client.resolve_slot_record(&location, &keypair.public_key()) {
+        Ok(record) => record,
+        Err(e)
+            if e.downcast_ref::<CclinkError>()
+                .is_some_and(|ce| matches!(ce, CclinkError::RecordNotFound)) =>
+        {
+            return Ok(None);
+        }
+        Err(e) => return Err(e.context(format!("Failed to look up {}", target.label))),
+    };
+    if !is_due(&record, now, older_than) {
+        return Ok(None);
+    }
+    if !dry_run {
+        client.revoke(&target.slot)?;
+        if target.own_handoff {
+            crate::cache::forget();
+        }
+    }
+    Ok(Some(Due {
+        label: target.label.clone(),
+        created_at: record.created_at,
+    }))
+}
+
+/// The file slots `cclink send` has written from this machine, one per recipient.
+pub fn file_targets(keypair: &pkarr::Keypair) -> anyhow::Result<Vec<Target>> {
+    let entries = crate::history::load()?;
+    let own = keypair.public_key();
+    let mut targets = Vec::new();
+    for peer in sent_file_peers(&entries) {
+        let (label, recipient) = match peer {
+            None => ("file to you".to_string(), own.clone()),
+            Some(z32) => match pkarr::PublicKey::try_from(z32) {
+                Ok(key) => (format!("file to {}", z32), key),
+                Err(e) => {
+                    tracing::debug!(error = %e, peer = z32, "skipping invalid history peer");
+                    continue;
+                }
+            },
+        };
+        targets.push(Target {
+            label,
+            slot: crate::crypto::file_slot_keypair(keypair, &own, &recipient),
+            own_handoff: false,
+        });
+    }
+    Ok(targets)
+}
+
+/// Distinct recipients of files sent from this machine, in order of first send.
+/// `None` is a file sent to yourself.
+fn sent_file_peers(entries: &[Entry]) -> Vec<Option<&str>> {
+    let mut peers = Vec::new();
+    for entry in entries {
+        if entry.direction != Direction::Out || entry.kind != Kind::File {
+            continue;
+        }
+        let peer = entry.peer.as_deref();
+        if !peers.contains(&peer) {
+            peers.push(peer);
+        }
+    }
+    peers
+}
+
+/// Whether `record` should be pruned: once its TTL has elapsed or, with `older_than`,
+/// once it was published at least that many seconds ago, expired or not.
+fn is_due(record: &HandoffRecord, now: u64, older_than: Option<u64>) -> bool {
+    match older_than {
+        Some(age) => now.saturating_sub(record.created_at) >= age,
+        None => now >= record.created_at.saturating_add(record.ttl),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(created_at: u64, ttl: u64) -> HandoffRecord {
+        HandoffRecord::builder(b"blob".to_vec(), ttl)
+            .created_at(created_at)
+            .sign(&pkarr::Keypair::from_secret_key(&[3u8; 32]))
+            .expect("sign")
+    }
+
+    #[test]
+    fn test_is_due_after_ttl() {
+        let r = record(1_000, 600);
+        assert!(!is_due(&r, 1_599, None));
+        assert!(is_due(&r, 1_600, None));
+    }
+
+    #[test]
+    fn test_is_due_older_than_ignores_ttl() {
+        let r = record(1_000, 30 * 86400);
+        assert!(!is_due(&r, 1_000 + 86400, Some(7 * 86400)));
+        assert!(is_due(&r, 1_000 + 7 * 86400, Some(7 * 86400)));
+        // An expired record younger than --older-than is kept.
+        let short = record(1_000, 60);
+        assert!(!is_due(&short, 2_000, Some(7 * 86400)));
+    }
+
+    #[test]
+    fn test_sent_file_peers_dedupes_and_skips_other_entries() {
+        let sent = |peer: Option<&str>| Entry {
+            peer: peer.map(str::to_string),
+            ..Entry::now(Direction::Out, Kind::File, 1, "notes.md")
+        };
+        let entries = vec![
+            sent(Some("bob")),
+            Entry::now(Direction::Out, Kind::Handoff, 2, "session"),
+            Entry::now(Direction::In, Kind::File, 3, "theirs.md"),
+            sent(None),
+            sent(Some("bob")),
+        ];
+        assert_eq!(sent_file_peers(&entries), vec![Some("bob"), None]);
+    }
+}
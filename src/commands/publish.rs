@@ -1,13 +1,41 @@
 /// Publish command — discovers or uses a specified Claude Code session, encrypts it,
 /// signs the record, publishes to the PKARR DHT, and prints colored output.
+///
+/// With `--revoke-after-pickup`, the payload carries the secret of a per-handoff
+/// receipt slot. With `--wait-receipt` as well, publish stays in the foreground for a
+/// while polling that slot and revokes the handoff as soon as a valid pickup receipt
+/// appears; otherwise `cclink receipts --revoke` does that later.
+///
+/// `cclink copy` runs the same flow but puts the sealed record on the clipboard
+/// instead of the DHT, for `cclink paste` on a machine that shares the clipboard;
+/// `cclink export` writes it as armored text for `cclink import`. With `--dir` (or
+/// `[transport] dir`) the record goes into a shared folder instead, and `cclink serve
+/// --lan` serves it on the local network until it expires.
 use std::io::IsTerminal;
-use std::time::SystemTime;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
 
+use anyhow::Context;
 use base64::Engine;
-use owo_colors::{OwoColorize, Stream::Stderr, Stream::Stdout};
 use zeroize::Zeroizing;
 
 use crate::error::CclinkError;
+use crate::record::receipt::Receipt;
+use crate::record::wormhole::{self, WormholeMessage};
+use crate::record::HandoffRecord;
+use crate::ui::Paint;
+
+/// How often `--revoke-after-pickup` polls the receipt slot.
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Longest message shown by `--preview`, in characters.
+const PREVIEW_CHARS: usize = 200;
+
+/// How long `--code` waits for someone to enter the code.
+const CODE_WAIT: Duration = Duration::from_secs(10 * 60);
+
+/// How often `--code` looks for the other side's message.
+const CODE_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 /// Validate PIN strength before encryption.
 ///
@@ -66,73 +94,179 @@ fn validate_pin(pin: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Where a sealed handoff goes.
+#[derive(Clone, Copy)]
+enum Destination<'a> {
+    /// Published to the DHT under this identity.
+    Dht,
+    /// Placed on the clipboard as text for `cclink paste`.
+    Clipboard,
+    /// Written as armored text for `cclink import`, to a file or stdout.
+    Armor(Option<&'a Path>),
+    /// Written into a folder shared with the other machine.
+    Directory(&'a Path),
+    /// Served on the local network; with `once`, only until the first pickup.
+    Lan { once: bool },
+}
+
 /// Run the publish flow.
 ///
 /// If `cli.session_id` is `Some`, publish that session directly.
 /// Otherwise, discover active sessions and prompt if multiple exist.
 pub fn run_publish(cli: &crate::cli::Cli) -> anyhow::Result<()> {
+    publish_session(cli, None)
+}
+
+/// Publish `session`, or the session [`run_publish`] would pick when `None`, with the
+/// publish options in `cli`. `cclink sessions --pick` passes the session it picked.
+pub fn publish_session(
+    cli: &crate::cli::Cli,
+    session: Option<crate::session::SessionInfo>,
+) -> anyhow::Result<()> {
+    let dir = match cli.dir {
+        Some(ref dir) => Some(dir.clone()),
+        None => crate::config::load()?.transport.dir,
+    };
+    match dir {
+        Some(ref dir) => {
+            check_unpublished(cli, "a transport directory")?;
+            publish_to(cli, session, Destination::Directory(dir))
+        }
+        None => publish_to(cli, session, Destination::Dht),
+    }
+}
+
+/// Run the publish flow, but put the sealed record on the clipboard instead of the
+/// DHT. For machines that share a clipboard (a remote desktop session, say) but have
+/// no network path to the DHT; the other side runs `cclink paste`.
+pub fn run_copy(cli: &crate::cli::Cli) -> anyhow::Result<()> {
+    check_unpublished(cli, "cclink copy")?;
+    publish_to(cli, None, Destination::Clipboard)
+}
+
+/// Run the publish flow, but write the sealed record as armored text instead of
+/// publishing it, so it can travel by email, chat or file to `cclink import`.
+pub fn run_export(cli: &crate::cli::Cli, args: &crate::cli::ExportArgs) -> anyhow::Result<()> {
+    check_unpublished(cli, "cclink export")?;
+    publish_to(cli, None, Destination::Armor(args.output.as_deref()))
+}
+
+/// Run the publish flow, but serve the sealed record on the local network instead of
+/// publishing it, for `cclink pickup --lan` on a machine with no path to the DHT.
+pub fn run_serve(cli: &crate::cli::Cli, args: &crate::cli::ServeArgs) -> anyhow::Result<()> {
+    check_unpublished(cli, "cclink serve")?;
+    publish_to(cli, None, Destination::Lan { once: args.once })
+}
+
+/// Refuse publish options that act on a record published to the DHT when `what`
+/// keeps the handoff off it.
+fn check_unpublished(cli: &crate::cli::Cli, what: &str) -> anyhow::Result<()> {
+    let published_only = [
+        ("--burn", cli.burn.is_some()),
+        ("--code", cli.code),
+        ("--copy", cli.copy),
+        ("--qr", cli.qr),
+        ("--revoke-after-pickup", cli.revoke_after_pickup),
+        ("--verify", cli.verify),
+    ];
+    if let Some((flag, _)) = published_only.iter().find(|(_, set)| *set) {
+        anyhow::bail!("{} cannot be used with {}", flag, what);
+    }
+    Ok(())
+}
+
+fn publish_to(
+    cli: &crate::cli::Cli,
+    session: Option<crate::session::SessionInfo>,
+    destination: Destination,
+) -> anyhow::Result<()> {
     // ── 1. Load keypair ────────────────────────────────────────────────
     let keypair = crate::keys::store::load_keypair()?;
 
-    // ── 2. Resolve session ────────────────────────────────────────────────
-    let session = if let Some(ref id) = cli.session_id {
-        // Explicit session ID provided — use it directly
-        let project = std::env::current_dir()
-            .map(|p| p.display().to_string())
-            .unwrap_or_else(|_| ".".to_string());
-        crate::session::SessionInfo {
-            session_id: id.clone(),
-            project,
-            mtime: SystemTime::now(),
+    // `--share` accepts a contact name or a standard age recipient as well as a public key.
+    let share = cli
+        .share
+        .as_deref()
+        .map(|share| {
+            if crate::keys::age_identity::is_recipient(share) {
+                Ok(share.to_string())
+            } else {
+                crate::contacts::resolve(share)
+            }
+        })
+        .transpose()?;
+
+    // `--to` encrypts for one registered device's own key instead of the shared key.
+    let device_recipient = match cli.to {
+        Some(ref name) => {
+            let registry = crate::transport::DhtClient::new()?.resolve_devices(&keypair)?;
+            let recipient = registry.get(name).ok_or_else(|| {
+                anyhow::anyhow!("no device named '{}' (see `cclink device list`)", name)
+            })?;
+            Some(recipient.to_string())
         }
+        None => None,
+    };
+
+    // `--code` agrees on a key with whoever enters the code, and encrypts to it.
+    let code_offer = if cli.code {
+        Some(offer_code(&crate::transport::DhtClient::new()?)?)
     } else {
-        // Auto-discover sessions from ~/.claude/projects/, scoped to the
-        // current working directory so unrelated project sessions are excluded.
-        let cwd = std::env::current_dir().ok();
-        let mut sessions = crate::session::discover_sessions(cwd.as_deref())?;
-        match sessions.len() {
-            0 => {
-                // No active session found
-                eprintln!(
-                    "{} No Claude Code session found. Start a session with 'claude' first.",
-                    "Error:".if_supports_color(Stderr, |t| t.red())
-                );
-                return Err(CclinkError::SessionNotFound.into());
-            }
-            1 => sessions.remove(0),
-            _ => {
-                // Multiple sessions — prompt unless stdin is not a TTY
-                if !std::io::stdin().is_terminal() {
-                    // Non-interactive: use the most recent (index 0, already sorted desc)
-                    sessions.remove(0)
-                } else {
-                    let items: Vec<String> = sessions
-                        .iter()
-                        .map(|s| {
-                            let id_prefix: String = s.session_id.chars().take(8).collect();
-                            format!("{} ({})", id_prefix, s.project)
-                        })
-                        .collect();
+        None
+    };
+    let share = share.or_else(|| code_offer.as_ref().map(CodeOffer::recipient));
 
-                    let selection = dialoguer::Select::new()
-                        .with_prompt("Multiple sessions found — pick one")
-                        .items(&items)
-                        .default(0)
-                        .interact()
-                        .map_err(|e| anyhow::anyhow!("session selection failed: {}", e))?;
+    // Burn handoffs never wait unclaimed longer than the burn safety window.
+    let config = crate::config::load()?;
+    let ttl = if cli.burn.is_some() && cli.ttl > config.burn.max_age {
+        crate::status!(
+            "{}",
+            format!(
+                "Burn handoffs expire within {}; TTL reduced from {} (burn.max_age in ~/.pubky/cclink.toml).",
+                crate::util::human_duration(config.burn.max_age),
+                crate::util::human_duration(cli.ttl)
+            ).muted()
+        );
+        config.burn.max_age
+    } else {
+        cli.ttl
+    };
 
-                    sessions.remove(selection)
-                }
-            }
-        }
+    // ── 2. Resolve session ────────────────────────────────────────────────
+    let session = match session {
+        Some(session) => session,
+        None => resolve_session(cli)?,
     };
 
+    tracing::debug!(
+        session = %session.session_id,
+        project = %session.project,
+        "session selected"
+    );
+
     // ── 3. Display discovered session ─────────────────────────────────────
-    println!(
-        "Session: {} in {}",
-        session.session_id.if_supports_color(Stdout, |t| t.cyan()),
-        session.project.if_supports_color(Stdout, |t| t.cyan())
+    crate::status!(
+        "Session: {} in {} ({})",
+        session.session_id.identity(),
+        session.project.identity(),
+        session.agent.name()
     );
+    if let Some(count) = cli.preview {
+        if !preview_session(&session, count)? {
+            crate::status!("Aborted.");
+            return Ok(());
+        }
+    }
+    let hook_env = || {
+        crate::hooks::HookEnv::new()
+            .set("SESSION_ID", &session.session_id)
+            .set("PROJECT", &session.project)
+            .set("AGENT", session.agent.name())
+            .set("TTL", ttl)
+            .set("BURN", cli.burn.is_some())
+            .set_opt("RECIPIENT", share.as_ref())
+    };
+    crate::hooks::run(&config.hooks, crate::hooks::Event::PrePublish, hook_env())?;
 
     // ── 4. Build encrypted payload ──────────────────────────────────────
     // Encrypt hostname, project path, and session ID together into the blob
@@ -140,124 +274,247 @@ pub fn run_publish(cli: &crate::cli::Cli) -> anyhow::Result<()> {
     let created_at = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)?
         .as_secs();
-    let hostname = gethostname::gethostname().to_string_lossy().into_owned();
 
+    let receipt_key = (cli.receipt || cli.revoke_after_pickup).then(|| {
+        let slot = crate::crypto::receipt_keypair(&keypair, created_at);
+        base64::engine::general_purpose::STANDARD.encode(slot.secret_key())
+    });
+    let transcript = if cli.with_transcript {
+        let provider = session.agent.provider();
+        if !provider.transcripts() {
+            anyhow::bail!(
+                "--with-transcript is not supported for {} sessions",
+                session.agent.name()
+            );
+        }
+        let path = provider
+            .find_session_file(&session.session_id)
+            .ok_or_else(|| {
+                anyhow::anyhow!("no transcript found for session {}", session.session_id)
+            })?;
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Some(text)
+    } else {
+        None
+    };
     let payload = crate::record::Payload {
-        hostname,
-        project: session.project.clone(),
-        session_id: session.session_id.clone(),
+        receipt_key,
+        transcript,
+        ..crate::handoff::session_payload(&session)
     };
-    let payload_bytes = serde_json::to_vec(&payload)
-        .map_err(|e| anyhow::anyhow!("failed to serialize payload: {}", e))?;
-
-    let (blob, pin_salt_value) = if cli.pin {
-        // PIN-protected: prompt for PIN, validate strength, encrypt with PIN-derived key
-        let pin = Zeroizing::new(
-            dialoguer::Password::new()
-                .with_prompt("Enter PIN for this handoff")
-                .with_confirmation("Confirm PIN", "PINs don't match")
-                .interact()
-                .map_err(|e| anyhow::anyhow!("PIN prompt failed: {}", e))?,
+    // Compressed when it helps; the record's signed `compressed` flag tells pickup.
+    let (payload_bytes, compressed) = crate::handoff::encode_payload(&payload)?;
+    // Everything but a shared folder or the LAN goes through `seal`, which chunks the
+    // ciphertext up to the DHT budget. Refuse what cannot fit before asking for a PIN.
+    let chunked = !matches!(
+        destination,
+        Destination::Directory(_) | Destination::Lan { .. }
+    );
+    if chunked && payload_bytes.len() > crate::record::chunked::MAX_BLOB_BYTES {
+        anyhow::bail!(
+            "handoff compresses to {} bytes; the DHT budget is {} bytes \
+             (a shared folder with --dir, or --lan, has no such limit)",
+            payload_bytes.len(),
+            crate::record::chunked::MAX_BLOB_BYTES
         );
+    }
+
+    let (ciphertext, pin_salt) = if cli.pin {
+        // PIN-protected: take the supplied PIN or prompt for one, validate strength,
+        // encrypt with PIN-derived key
+        let pin = match crate::keys::input::pin()? {
+            Some(pin) => pin,
+            None => Zeroizing::new(
+                dialoguer::Password::new()
+                    .with_prompt("Enter PIN for this handoff")
+                    .with_confirmation("Confirm PIN", "PINs don't match")
+                    .interact()
+                    .map_err(|e| anyhow::anyhow!("PIN prompt failed: {}", e))?,
+            ),
+        };
 
         // Validate PIN strength before any encryption or network call.
         // Uses eprintln! + process::exit(1) to avoid double-printing via anyhow's
         // error formatter when the error propagates out of main().
         if let Err(reason) = validate_pin(&pin) {
-            eprintln!(
-                "{} {}",
-                "Error:".if_supports_color(Stderr, |t| t.red()),
-                reason
-            );
+            eprintln!("{} {}", "Error:".error().stderr(), reason);
             std::process::exit(1);
         }
 
         let (ciphertext, salt) = crate::crypto::pin_encrypt(&payload_bytes, &pin)?;
-        let blob = base64::engine::general_purpose::STANDARD.encode(&ciphertext);
-        let salt_b64 = base64::engine::general_purpose::STANDARD.encode(salt);
-        (blob, Some(salt_b64))
+        (ciphertext, Some(salt))
     } else {
-        // Existing path: age encrypt to recipient (self or --share)
-        let recipient = if let Some(ref share_pubkey) = cli.share {
-            crate::crypto::recipient_from_z32(share_pubkey)?
-        } else {
-            let x25519_pubkey = crate::crypto::ed25519_to_x25519_public(&keypair);
-            crate::crypto::age_recipient(&x25519_pubkey)
-        };
-        let ciphertext = crate::crypto::age_encrypt(&payload_bytes, &recipient)?;
-        let blob = base64::engine::general_purpose::STANDARD.encode(&ciphertext);
-        (blob, None)
+        // Existing path: age encrypt to recipient (self, --share, or --to device)
+        let recipient =
+            crate::handoff::recipient(&keypair, share.as_deref().or(device_recipient.as_deref()))?;
+        let ciphertext = crate::crypto::age_encrypt(&payload_bytes, recipient.as_ref())?;
+        (ciphertext, None)
     };
 
     // ── 5. Build and sign record ──────────────────────────────────────────
-    // Outer hostname and project are empty — sensitive metadata lives only
-    // inside the encrypted blob.
-    let signable = crate::record::HandoffRecordSignable {
-        blob,
-        burn: cli.burn,
-        created_at,
-        hostname: String::new(),
-        pin_salt: pin_salt_value.clone(),
-        project: String::new(),
-        pubkey: keypair.public_key().to_z32(),
-        recipient: cli.share.clone(),
-        ttl: cli.ttl,
+    // A blob too large for the packet moves into chunk slots: the record keeps an
+    // empty blob and a signed manifest is published next to it. A file in a shared
+    // folder has no such limit, so it always carries the whole blob.
+    let builder = HandoffRecord::builder(ciphertext, ttl)
+        .burn(cli.burn.is_some())
+        .burn_limit(cli.burn.unwrap_or(1), cli.burn_grace.unwrap_or(0))
+        .compressed(compressed)
+        .created_at(created_at)
+        .pin_salt(pin_salt)
+        .recipient(share.clone().or_else(|| device_recipient.clone()));
+    let sealed = match destination {
+        Destination::Directory(_) | Destination::Lan { .. } => crate::record::SealedHandoff {
+            record: builder.sign(&keypair)?,
+            chunks: None,
+        },
+        _ => builder.seal(&keypair)?,
     };
-    let signature = crate::record::sign_record(&signable, &keypair)?;
-    let record = crate::record::HandoffRecord {
-        blob: signable.blob,
-        burn: cli.burn,
-        created_at: signable.created_at,
-        hostname: signable.hostname,
-        pin_salt: pin_salt_value,
-        project: signable.project,
-        pubkey: signable.pubkey,
-        recipient: cli.share.clone(),
-        signature,
-        ttl: signable.ttl,
+    let record = &sealed.record;
+    let qr_payload = if cli.qr_payload {
+        Some(crate::record::compact::encode(record)?)
+    } else {
+        None
     };
 
-    // ── 6. Publish to DHT ──────────────────────────────────────────────
+    // ── 6. Publish to DHT (or elsewhere, off the DHT) ──────────────────
     let pubkey_z32 = keypair.public_key().to_z32();
-    let client = crate::transport::DhtClient::new()?;
-    client.publish(&keypair, &record)?;
+    let client = match destination {
+        Destination::Dht => {
+            let client = crate::progress::attach(crate::transport::DhtClient::new()?, "Publishing");
+            if let Some((ref frames, _)) = sealed.chunks {
+                crate::status!("Publishing {} chunks...", frames.len());
+            }
+            client.publish_sealed(&keypair, &sealed)?;
+            crate::cache::remember(record);
+            if let Some(ref offer) = code_offer {
+                offer.reveal_publisher(&client, &keypair.public_key())?;
+            }
+            if cli.verify {
+                verify_published(record)?;
+            }
+            Some(client)
+        }
+        Destination::Clipboard => {
+            copy_record(record)?;
+            None
+        }
+        Destination::Armor(output) => {
+            export_record(record, output)?;
+            None
+        }
+        Destination::Directory(dir) => {
+            crate::transport::file::FileTransport::at(dir)?.publish(record)?;
+            None
+        }
+        Destination::Lan { .. } => None,
+    };
+    crate::history::record(crate::history::Entry {
+        peer: share.clone(),
+        project: Some(session.project.clone()),
+        ..crate::history::Entry::now(
+            crate::history::Direction::Out,
+            crate::history::Kind::Handoff,
+            created_at,
+            &session.session_id,
+        )
+    });
+    crate::hooks::run(
+        &config.hooks,
+        crate::hooks::Event::PostPublish,
+        hook_env()
+            .set("PUBKEY", keypair.public_key().to_z32())
+            .set("CREATED_AT", created_at),
+    )?;
 
     // ── 7. Output success ─────────────────────────────────────────────────
-    if cli.burn {
-        println!(
+    if let Some(uses) = cli.burn {
+        let after = match uses {
+            1 => "the first successful pickup".to_string(),
+            n => format!("{} successful pickups", n),
+        };
+        let grace = match cli.burn_grace {
+            Some(secs) => format!(", plus {} grace", crate::util::human_duration(secs)),
+            None => String::new(),
+        };
+        crate::status!(
             "{}",
-            "Warning: This handoff will be deleted after the first successful pickup."
-                .if_supports_color(Stdout, |t| t.yellow())
+            format!(
+                "Warning: This handoff will be deleted after {}{}.",
+                after, grace
+            )
+            .warning()
         );
     }
     if cli.pin {
-        println!(
+        crate::status!(
             "{}",
-            "PIN-protected: recipient must enter the PIN to decrypt."
-                .if_supports_color(Stdout, |t| t.yellow())
+            "PIN-protected: recipient must enter the PIN to decrypt.".warning()
         );
     }
-    println!(
-        "\n{}",
-        "Published!".if_supports_color(Stdout, |t| t.green())
-    );
-    if cli.share.is_some() {
+    let done = match destination {
+        Destination::Dht | Destination::Directory(_) => "Published!",
+        Destination::Lan { .. } => "Ready to serve!",
+        Destination::Clipboard => "Copied!",
+        Destination::Armor(_) => "Exported!",
+    };
+    crate::status!("\n{}", done.success());
+    let pickup_command = if let Destination::Clipboard = destination {
+        crate::status!("  Run on the machine you paste into:");
+        "cclink paste".to_string()
+    } else if let Destination::Armor(output) = destination {
+        crate::status!("  Recipient pickup command:");
+        match output {
+            Some(path) => format!("cclink import {}", path.display()),
+            None => "cclink import <file with the text above>".to_string(),
+        }
+    } else if let Destination::Lan { .. } = destination {
+        if share.is_some() {
+            crate::status!("  Recipient pickup command (on the same network):");
+            format!("cclink pickup {} --lan", pubkey_z32)
+        } else {
+            crate::status!("  Run on another machine on this network:");
+            "cclink pickup --lan".to_string()
+        }
+    } else if let Destination::Directory(dir) = destination {
+        if share.is_some() {
+            crate::status!("  Recipient pickup command:");
+            format!("cclink pickup {} --dir {}", pubkey_z32, dir.display())
+        } else {
+            crate::status!("  Run on another machine that shares the folder:");
+            format!("cclink pickup --dir {}", dir.display())
+        }
+    } else if let Some(ref offer) = code_offer {
+        // The recipient's pickup is already waiting for this.
+        crate::status!("  Recipient pickup command (if it is not already running):");
+        format!("cclink pickup --code {}", offer.code)
+    } else if share.is_some() {
         // Shared: recipient needs to specify the publisher's pubkey to pick up
-        println!("  Recipient pickup command:");
-        println!(
-            "  {}",
-            format!("cclink pickup {}", pubkey_z32).if_supports_color(Stdout, |t| t.bold())
-        );
+        crate::status!("  Recipient pickup command:");
+        format!("cclink pickup {}", pubkey_z32)
+    } else if let Some(ref device) = cli.to {
+        crate::status!("  Run on {}:", device.identity());
+        "cclink pickup".to_string()
     } else {
         // Self: pickup resolves via own public key
-        println!("  Run on another machine:");
-        println!(
-            "  {}",
-            "cclink pickup".if_supports_color(Stdout, |t| t.bold())
-        );
+        crate::status!("  Run on another machine:");
+        "cclink pickup".to_string()
+    };
+    crate::status!("  {}", pickup_command.emphasis());
+    crate::status!(
+        "  Expires in {} ({})",
+        crate::util::human_duration(ttl),
+        crate::util::format_utc(created_at.saturating_add(ttl))
+    );
+
+    if cli.copy {
+        match crate::clipboard::copy(&pickup_command) {
+            Some(provider) => {
+                crate::status!("  Pickup command copied to clipboard ({}).", provider)
+            }
+            None => println!("  (Clipboard unavailable — copy the command manually)"),
+        }
     }
-    let hours = cli.ttl / 3600;
-    println!("  Expires in {}h", hours);
 
     // ── 8. Optional QR code ───────────────────────────────────────────────
     if cli.qr {
@@ -265,13 +522,404 @@ pub fn run_publish(cli: &crate::cli::Cli) -> anyhow::Result<()> {
         qr2term::print_qr(format!("cclink pickup {}", pubkey_z32))
             .map_err(|e| anyhow::anyhow!("QR code render failed: {}", e))?;
     }
+    if let Some(ref text) = qr_payload {
+        println!();
+        qr2term::print_qr(text).map_err(|e| anyhow::anyhow!("QR code render failed: {}", e))?;
+        crate::status!("  Scan it and run: cclink pickup --from-qr <image or text>");
+    }
+
+    if let Destination::Lan { once } = destination {
+        serve_lan(record, once)?;
+    }
+    let Some(client) = client else {
+        return Ok(());
+    };
+
+    // ── 9. Clear expired sent files while the client is warm ─────────────
+    crate::commands::prune::after_publish(&client, &keypair, created_at);
+
+    // ── 10. Optional: wait for pickup receipt, then revoke ────────────────
+    match cli.wait_receipt {
+        Some(wait) => wait_and_revoke(&client, &keypair, record, Duration::from_secs(wait))?,
+        None if cli.revoke_after_pickup => crate::status!(
+            "Run {} once it has been picked up.",
+            "cclink receipts --revoke".emphasis()
+        ),
+        None => {}
+    }
+
+    Ok(())
+}
 
+/// Put `record` on the clipboard as text for `cclink paste`. Without a working
+/// clipboard the text is printed for a manual copy instead.
+fn copy_record(record: &HandoffRecord) -> anyhow::Result<()> {
+    let text = crate::record::compact::encode(record)?;
+    match crate::clipboard::copy(&text) {
+        Some(provider) => crate::status!("Handoff copied to clipboard ({}).", provider),
+        None => println!(
+            "Clipboard unavailable — copy this text to the other machine:\n{}",
+            text
+        ),
+    }
+    Ok(())
+}
+
+/// Advertise `record` on the local network and serve it until it expires, or with
+/// `once` until the first machine has fetched it.
+fn serve_lan(record: &HandoffRecord, once: bool) -> anyhow::Result<()> {
+    let server = crate::transport::lan::LanServer::start(record)?;
+    crate::status!("\nServing on port {} (Ctrl-C to stop)...", server.port());
+    let expires_at = record.created_at.saturating_add(record.ttl);
+    loop {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs();
+        if now >= expires_at {
+            crate::status!("Handoff expired; stopped serving.");
+            return Ok(());
+        }
+        if let Some(peer) = server.serve_one(Duration::from_secs(1))? {
+            crate::status!("  Sent to {}", peer.ip().identity());
+            if once {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Write `record` as armored text to `output`, or to stdout.
+fn export_record(record: &HandoffRecord, output: Option<&Path>) -> anyhow::Result<()> {
+    let armored = crate::record::armor::encode(record)?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, &armored)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            crate::status!("Handoff written to {}.", path.display());
+        }
+        None => print!("\n{}", armored),
+    }
+    Ok(())
+}
+
+/// Print the last `count` messages of `session` and, on a terminal, ask whether to
+/// publish it. Returns `false` if the user declined.
+fn preview_session(session: &crate::session::SessionInfo, count: usize) -> anyhow::Result<bool> {
+    let provider = session.agent.provider();
+    let messages = provider
+        .find_session_file(&session.session_id)
+        .map(|path| provider.recent_messages(&path, count, PREVIEW_CHARS))
+        .unwrap_or_default();
+    if messages.is_empty() {
+        println!("{}", "(no messages to preview for this session)".muted());
+    }
+    for message in &messages {
+        println!(
+            "  {:>10} {}",
+            format!("{}:", message.role.name()).muted(),
+            message.text
+        );
+    }
+    if !std::io::stdin().is_terminal() {
+        return Ok(true);
+    }
+    dialoguer::Confirm::new()
+        .with_prompt("Publish this session?")
+        .default(true)
+        .interact()
+        .map_err(|e| anyhow::anyhow!("prompt failed: {}", e))
+}
+
+/// The session to publish: `--session-id`, or the current project's active session
+/// (picked interactively when there are several).
+fn resolve_session(cli: &crate::cli::Cli) -> anyhow::Result<crate::session::SessionInfo> {
+    Ok(if let Some(ref id) = cli.session_id {
+        // Explicit session ID provided — use it directly
+        let project = std::env::current_dir()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|_| ".".to_string());
+        crate::session::SessionInfo {
+            agent: cli.agent.unwrap_or(crate::session::Agent::Claude),
+            session_id: id.clone(),
+            project,
+            mtime: SystemTime::now(),
+        }
+    } else {
+        // Auto-discover sessions (of `--agent`, or of every agent), scoped to the
+        // current working directory so unrelated project sessions are excluded.
+        let cwd = std::env::current_dir().ok();
+        let mut sessions = crate::session::discover_sessions(cli.agent, cwd.as_deref())?;
+        match sessions.len() {
+            0 => return Err(CclinkError::SessionNotFound.into()),
+            1 => sessions.remove(0),
+            _ => {
+                // Multiple sessions — prompt unless stdin is not a TTY
+                if !std::io::stdin().is_terminal() {
+                    // Non-interactive: use the most recent (index 0, already sorted desc)
+                    sessions.remove(0)
+                } else {
+                    let items: Vec<String> = sessions
+                        .iter()
+                        .map(|s| {
+                            let id_prefix: String = s.session_id.chars().take(8).collect();
+                            let provider = s.agent.provider();
+                            let snippet = provider
+                                .find_session_file(&s.session_id)
+                                .and_then(|path| provider.last_assistant_message(&path, 60));
+                            let label = format!("{} {} ({})", s.agent.name(), id_prefix, s.project);
+                            match snippet {
+                                Some(text) => format!("{} — \"{}\"", label, text),
+                                None => label,
+                            }
+                        })
+                        .collect();
+
+                    let selection = dialoguer::Select::new()
+                        .with_prompt("Multiple sessions found — pick one")
+                        .items(&items)
+                        .default(0)
+                        .interact()
+                        .map_err(|e| anyhow::anyhow!("session selection failed: {}", e))?;
+
+                    sessions.remove(selection)
+                }
+            }
+        }
+    })
+}
+
+/// Read `record` back from the DHT through a fresh client and compare it byte-for-byte.
+///
+/// A new `DhtClient` has an empty cache, so the record must come from DHT nodes
+/// rather than from this process. Retries for up to 30s to allow for propagation.
+fn verify_published(record: &HandoffRecord) -> anyhow::Result<()> {
+    use backon::BlockingRetryable;
+
+    let fresh = crate::transport::DhtClient::new()?;
+    let fetched = (|| fresh.resolve_record(&record.pubkey))
+        .retry(crate::config::network().backoff())
+        .sleep(std::thread::sleep)
+        .notify(|e, retry_in| {
+            tracing::debug!(error = %e, retry_in = ?retry_in, "read-back failed, retrying");
+        })
+        .call()
+        .map_err(|e| {
+            anyhow::anyhow!("Verification failed: could not read the record back: {}", e)
+        })?;
+
+    if serde_json::to_string(&fetched)? != serde_json::to_string(record)? {
+        anyhow::bail!(
+            "Verification failed: the DHT returned a different record (created_at {}, expected {})",
+            fetched.created_at,
+            record.created_at
+        );
+    }
+    crate::status!(
+        "{}",
+        "Verified: record read back from the DHT with a valid signature.".success()
+    );
+    Ok(())
+}
+
+/// The publishing side of a one-time-code exchange, after the key agreement.
+struct CodeOffer {
+    code: String,
+    /// Our rendezvous slot.
+    slot: pkarr::Keypair,
+    /// Our SPAKE2 message, base64, republished alongside the publisher key.
+    pake: String,
+    /// The key agreed with whoever entered the code.
+    identity: age::x25519::Identity,
+}
+
+impl CodeOffer {
+    /// The age recipient to encrypt the handoff to.
+    fn recipient(&self) -> String {
+        self.identity.to_public().to_string()
+    }
+
+    /// Tell the picker which key the handoff is published under, encrypted to the
+    /// agreed key so only the code's holder learns it.
+    fn reveal_publisher(
+        &self,
+        client: &crate::transport::DhtClient,
+        publisher: &pkarr::PublicKey,
+    ) -> anyhow::Result<()> {
+        let ciphertext =
+            crate::crypto::age_encrypt(publisher.to_z32().as_bytes(), &self.identity.to_public())?;
+        let message = WormholeMessage {
+            pake: self.pake.clone(),
+            publisher: Some(base64::engine::general_purpose::STANDARD.encode(ciphertext)),
+        };
+        client.publish_wormhole(&self.slot, &message)
+    }
+}
+
+/// Print a fresh one-time code and wait for someone to enter it.
+fn offer_code(client: &crate::transport::DhtClient) -> anyhow::Result<CodeOffer> {
+    let code = wormhole::generate_code();
+    let (state, message) = wormhole::start(&code);
+    let pake = base64::engine::general_purpose::STANDARD.encode(message);
+    let slot = crate::crypto::wormhole_keypair(&code, "publisher");
+    client.publish_wormhole(
+        &slot,
+        &WormholeMessage {
+            pake: pake.clone(),
+            publisher: None,
+        },
+    )?;
+
+    println!("One-time code: {}", code.emphasis());
+    println!("  On the other machine run: cclink pickup --code {}", code);
+    crate::status!("Waiting for the code to be entered (Ctrl-C to stop)...");
+    let picker = crate::crypto::wormhole_keypair(&code, "picker").public_key();
+    let deadline = std::time::Instant::now() + CODE_WAIT;
+    let theirs = loop {
+        if let Some(message) = client.resolve_wormhole(&picker) {
+            break message;
+        }
+        if std::time::Instant::now() >= deadline {
+            anyhow::bail!(
+                "nobody entered the code within {}",
+                crate::util::human_duration(CODE_WAIT.as_secs())
+            );
+        }
+        std::thread::sleep(CODE_POLL_INTERVAL);
+    };
+    let identity = wormhole::finish(state, &theirs.pake)?;
+    Ok(CodeOffer {
+        code,
+        slot,
+        pake,
+        identity,
+    })
+}
+
+/// Poll the handoff's receipt slot until a valid receipt arrives, then revoke the
+/// handoff. Gives up after `wait`, or when the handoff expires first.
+///
+/// The handoff is left alone if a newer one has replaced it in the meantime, so a
+/// long-running wait never deletes a handoff it did not publish.
+fn wait_and_revoke(
+    client: &crate::transport::DhtClient,
+    keypair: &pkarr::Keypair,
+    record: &HandoffRecord,
+    wait: Duration,
+) -> anyhow::Result<()> {
+    let slot = crate::crypto::receipt_keypair(keypair, record.created_at).public_key();
+    let expires_at = record.created_at.saturating_add(record.ttl);
+    let deadline = std::time::Instant::now() + wait;
+
+    crate::status!();
+    crate::status!(
+        "{}",
+        format!(
+            "Waiting up to {} for a pickup receipt (Ctrl-C to stop; the handoff stays published)...",
+            crate::util::human_duration(wait.as_secs())
+        )
+        .muted()
+    );
+
+    let receipt = loop {
+        match client.resolve_receipt(&slot) {
+            Ok(Some(receipt)) => match check_receipt(&receipt, record) {
+                Ok(()) => break receipt,
+                Err(e) => tracing::debug!(error = %e, "ignoring receipt"),
+            },
+            Ok(None) => {}
+            Err(e) => tracing::debug!(error = %e, "receipt poll failed"),
+        }
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs();
+        if now >= expires_at {
+            crate::status!("Handoff expired without a pickup receipt.");
+            return Ok(());
+        }
+        if std::time::Instant::now() >= deadline {
+            crate::status!(
+                "No pickup receipt yet. Run {} once it has been picked up.",
+                "cclink receipts --revoke".emphasis()
+            );
+            return Ok(());
+        }
+        std::thread::sleep(
+            RECEIPT_POLL_INTERVAL
+                .min(deadline.saturating_duration_since(std::time::Instant::now())),
+        );
+    };
+
+    revoke_picked_up(client, keypair, record, &receipt)
+}
+
+/// Revoke `record`, whose `receipt` has arrived, if the DHT still holds it.
+pub fn revoke_picked_up(
+    client: &crate::transport::DhtClient,
+    keypair: &pkarr::Keypair,
+    record: &HandoffRecord,
+    receipt: &Receipt,
+) -> anyhow::Result<()> {
+    match client.resolve_record(&record.pubkey) {
+        Ok(current) if current.created_at != record.created_at => {
+            crate::status!(
+                "Picked up, but a newer handoff has replaced this one — leaving it in place."
+            );
+            return Ok(());
+        }
+        Err(e)
+            if e.downcast_ref::<CclinkError>()
+                .is_some_and(|ce| matches!(ce, CclinkError::RecordNotFound)) =>
+        {
+            crate::status!("Picked up; the handoff is already gone.");
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    client.revoke(keypair)?;
+    crate::cache::forget();
+    let picked_up_on = crate::record::receipt::open_receipt(receipt, keypair)
+        .map(|d| d.hostname)
+        .unwrap_or_else(|_| "an unknown host".to_string());
+    crate::status!(
+        "{} Picked up on {}; handoff revoked.",
+        "Done.".success(),
+        picked_up_on.identity()
+    );
+    Ok(())
+}
+
+/// Check that `receipt` acknowledges `record` and was signed by the expected picker.
+///
+/// Shared handoffs must be acknowledged by the recipient and self handoffs by the
+/// publisher's own key. PIN handoffs and handoffs shared to an age recipient have no
+/// known picker key, so any correctly signed receipt is accepted — only those able to
+/// decrypt can learn the receipt slot.
+pub fn check_receipt(receipt: &Receipt, record: &HandoffRecord) -> anyhow::Result<()> {
+    if receipt.handoff != record.created_at {
+        anyhow::bail!("receipt is for a different handoff");
+    }
+    let picker = crate::record::receipt::verify_receipt(receipt)?.to_z32();
+    let expected = match (&record.recipient, &record.pin_salt) {
+        (Some(recipient), _) if crate::keys::age_identity::is_recipient(recipient) => None,
+        (Some(recipient), _) => Some(recipient),
+        (None, None) => Some(&record.pubkey),
+        (None, Some(_)) => None,
+    };
+    if let Some(expected) = expected {
+        if picker != *expected {
+            anyhow::bail!("receipt signed by unexpected key {}", picker);
+        }
+    }
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::validate_pin;
+    use super::{check_receipt, validate_pin};
+    use crate::record::receipt::{seal_receipt, ReceiptDetails};
+    use crate::record::HandoffRecord;
 
     // ── Length check ────────────────────────────────────────────────────────
 
@@ -396,4 +1044,58 @@ mod tests {
         let result = validate_pin("validpin");
         assert_eq!(result, Ok(()));
     }
+
+    // ── Receipt acceptance ───────────────────────────────────────────────────
+
+    fn keypair(byte: u8) -> pkarr::Keypair {
+        pkarr::Keypair::from_secret_key(&[byte; 32])
+    }
+
+    fn shared_record(publisher: &pkarr::Keypair, recipient: &pkarr::Keypair) -> HandoffRecord {
+        HandoffRecord::builder(Vec::new(), 3600)
+            .created_at(1_700_000_000)
+            .recipient(recipient.public_key().to_z32())
+            .sign(publisher)
+            .expect("sign")
+    }
+
+    fn receipt_from(
+        picker: &pkarr::Keypair,
+        record: &HandoffRecord,
+    ) -> crate::record::receipt::Receipt {
+        let details = ReceiptDetails {
+            hostname: "laptop".to_string(),
+        };
+        seal_receipt(
+            record.created_at,
+            &record.pubkey,
+            picker,
+            &details,
+            1_700_000_100,
+        )
+        .expect("seal_receipt")
+    }
+
+    #[test]
+    fn test_check_receipt_accepts_recipient() {
+        let record = shared_record(&keypair(1), &keypair(2));
+        let receipt = receipt_from(&keypair(2), &record);
+        assert!(check_receipt(&receipt, &record).is_ok());
+    }
+
+    #[test]
+    fn test_check_receipt_rejects_other_picker() {
+        let record = shared_record(&keypair(1), &keypair(2));
+        let receipt = receipt_from(&keypair(3), &record);
+        assert!(check_receipt(&receipt, &record).is_err());
+    }
+
+    #[test]
+    fn test_check_receipt_rejects_other_handoff() {
+        let record = shared_record(&keypair(1), &keypair(2));
+        let mut older = record.clone();
+        older.created_at -= 60;
+        let receipt = receipt_from(&keypair(2), &older);
+        assert!(check_receipt(&receipt, &record).is_err());
+    }
 }
@@ -0,0 +1,174 @@
+/// Receipts command — shows where and when your recent handoffs were picked up.
+///
+/// Handoffs published with `--receipt` (or `--revoke-after-pickup`) ask the picker
+/// to publish a signed receipt into a slot derived from your key and the handoff's
+/// `created_at`. The handoffs to look up come from the local history, so only
+/// handoffs published from this machine are listed. With `--revoke`, the current
+/// handoff is revoked once its receipt is in, for `--revoke-after-pickup` publishes
+/// that did not wait for it.
+use crate::error::CclinkError;
+use crate::history::{Direction, Kind};
+use crate::record::receipt::Receipt;
+use crate::ui::Paint;
+use crate::util::{format_utc, human_duration};
+
+pub fn run_receipts(args: crate::cli::ReceiptsArgs) -> anyhow::Result<()> {
+    let keypair = crate::keys::store::load_keypair()?;
+    let entries = crate::history::load()?;
+    let published = recent_handoffs(&entries, args.limit);
+    if published.is_empty() {
+        println!("No handoffs published from this machine yet.");
+        return Ok(());
+    }
+
+    let contacts = crate::contacts::ContactBook::load_from(&crate::contacts::contacts_path()?)?;
+    let client = crate::transport::DhtClient::new()?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)?
+        .as_secs();
+
+    let mut table = comfy_table::Table::new();
+    table.set_header(vec!["Published", "Session", "Picked Up", "Host", "Picker"]);
+    let mut found = 0;
+    for entry in published {
+        let slot = crate::crypto::receipt_keypair(&keypair, entry.created_at).public_key();
+        let receipt = match client.resolve_receipt(&slot) {
+            Ok(Some(receipt)) if is_valid_for(&receipt, entry.created_at) => Some(receipt),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::debug!(error = %e, "receipt lookup failed");
+                None
+            }
+        };
+        let session = entry.name.chars().take(8).collect::<String>();
+        let published_at = format_utc(entry.created_at);
+        let Some(receipt) = receipt else {
+            table.add_row(vec![published_at, session, "—".to_string()]);
+            continue;
+        };
+        found += 1;
+        let host = crate::record::receipt::open_receipt(&receipt, &keypair)
+            .map(|d| d.hostname)
+            .unwrap_or_else(|_| "?".to_string());
+        let picker = contacts
+            .iter()
+            .find(|(_, key)| *key == receipt.pubkey)
+            .map(|(name, _)| name.to_string())
+            .unwrap_or_else(|| {
+                if receipt.pubkey == keypair.public_key().to_z32() {
+                    "you".to_string()
+                } else {
+                    receipt.pubkey.chars().take(8).collect()
+                }
+            });
+        table.add_row(vec![
+            published_at,
+            session,
+            format!(
+                "{} ago",
+                human_duration(now.saturating_sub(receipt.created_at))
+            ),
+            host,
+            picker,
+        ]);
+    }
+
+    println!("{table}");
+    if found == 0 {
+        println!(
+            "{}",
+            "No receipts yet. Publish with --receipt to ask pickers for one.".muted()
+        );
+    }
+    if args.revoke {
+        revoke_if_picked_up(&client, &keypair)?;
+    }
+    Ok(())
+}
+
+/// Revoke the current handoff if a receipt from its expected picker has arrived.
+fn revoke_if_picked_up(
+    client: &crate::transport::DhtClient,
+    keypair: &pkarr::Keypair,
+) -> anyhow::Result<()> {
+    let record = match client.resolve_record(&keypair.public_key().to_z32()) {
+        Ok(record) => record,
+        Err(e)
+            if e.downcast_ref::<CclinkError>()
+                .is_some_and(|ce| matches!(ce, CclinkError::RecordNotFound)) =>
+        {
+            println!("No handoff is published; nothing to revoke.");
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+    let slot = crate::crypto::receipt_keypair(keypair, record.created_at).public_key();
+    match client.resolve_receipt(&slot)? {
+        Some(receipt) if crate::commands::publish::check_receipt(&receipt, &record).is_ok() => {
+            crate::commands::publish::revoke_picked_up(client, keypair, &record, &receipt)
+        }
+        _ => {
+            println!("Your current handoff has no pickup receipt yet; leaving it published.");
+            Ok(())
+        }
+    }
+}
+
+/// The `limit` most recently published handoffs, newest first.
+fn recent_handoffs(entries: &[crate::history::Entry], limit: usize) -> Vec<&crate::history::Entry> {
+    entries
+        .iter()
+        .rev()
+        .filter(|e| e.direction == Direction::Out && e.kind == Kind::Handoff)
+        .take(limit)
+        .collect()
+}
+
+/// Whether `receipt` is correctly signed and acknowledges the handoff created at
+/// `handoff`.
+fn is_valid_for(receipt: &Receipt, handoff: u64) -> bool {
+    receipt.handoff == handoff && crate::record::receipt::verify_receipt(receipt).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::Entry;
+
+    #[test]
+    fn test_recent_handoffs_newest_first() {
+        let entries = vec![
+            Entry::now(Direction::Out, Kind::Handoff, 1, "a"),
+            Entry::now(Direction::In, Kind::Handoff, 2, "b"),
+            Entry::now(Direction::Out, Kind::File, 3, "c"),
+            Entry::now(Direction::Out, Kind::Handoff, 4, "d"),
+            Entry::now(Direction::Out, Kind::Handoff, 5, "e"),
+        ];
+        let names: Vec<&str> = recent_handoffs(&entries, 2)
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["e", "d"]);
+    }
+
+    #[test]
+    fn test_receipt_must_match_handoff_and_signature() {
+        let picker = pkarr::Keypair::from_secret_key(&[3u8; 32]);
+        let publisher = pkarr::Keypair::from_secret_key(&[4u8; 32]);
+        let receipt = crate::record::receipt::seal_receipt(
+            100,
+            &publisher.public_key().to_z32(),
+            &picker,
+            &crate::record::receipt::ReceiptDetails {
+                hostname: "laptop".to_string(),
+            },
+            200,
+        )
+        .unwrap();
+        assert!(is_valid_for(&receipt, 100));
+        assert!(!is_valid_for(&receipt, 101));
+        let mut forged = receipt.clone();
+        forged.created_at += 1;
+        assert!(!is_valid_for(&forged, 100));
+    }
+}
@@ -0,0 +1,115 @@
+/// Recv command — fetches, verifies, and decrypts a file published with `cclink send`.
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::Context;
+
+use crate::error::CclinkError;
+use crate::ui::Paint;
+
+pub fn run_recv(args: crate::cli::RecvArgs) -> anyhow::Result<()> {
+    // ── 1. Load keypair and locate the file slot ─────────────────────────
+    let keypair = crate::keys::store::load_keypair()?;
+    let sender_z32 = match args.pubkey {
+        Some(ref input) => crate::contacts::resolve(input)?,
+        None => keypair.public_key().to_z32(),
+    };
+    let sender = pkarr::PublicKey::try_from(sender_z32.as_str())
+        .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+    let slot =
+        crate::crypto::file_slot_keypair(&keypair, &sender, &keypair.public_key()).public_key();
+
+    // ── 2. Retrieve and verify the record ────────────────────────────────
+    let client = crate::progress::attach(crate::transport::DhtClient::new()?, "Downloading");
+    let record = match client.resolve_slot_record(&slot, &sender) {
+        Ok(r) => r,
+        Err(e) => {
+            if e.downcast_ref::<CclinkError>()
+                .is_some_and(|ce| matches!(ce, CclinkError::RecordNotFound))
+            {
+                crate::status!("No file waiting from {}.", sender_z32);
+                return Ok(());
+            }
+            return Err(e);
+        }
+    };
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+    let expires_at = record.created_at.saturating_add(record.ttl);
+    if now >= expires_at {
+        return Err(
+            CclinkError::HandoffExpired(crate::util::human_duration(now - expires_at)).into(),
+        );
+    }
+
+    // ── 3. Decrypt and write the file ────────────────────────────────────
+    if record.blob.is_empty() {
+        crate::status!("Fetching chunked file...");
+    }
+    let ciphertext = crate::handoff::ciphertext(&client, &slot, &sender, &record)?;
+    let x25519_secret = crate::crypto::ed25519_to_x25519_secret(&keypair);
+    let identity = crate::crypto::age_identity(&x25519_secret);
+    let decrypted = crate::crypto::age_decrypt_reader(&ciphertext[..], &identity)?;
+    let mut plaintext: Box<dyn Read> = if record.compressed {
+        Box::new(crate::record::decompress_reader(decrypted)?)
+    } else {
+        Box::new(decrypted)
+    };
+    let name = crate::record::file::read_header(&mut plaintext)?;
+
+    let path = args.output.unwrap_or_else(|| name.clone().into());
+    if path.exists() && !args.force {
+        anyhow::bail!(
+            "{} already exists (use --force to overwrite)",
+            path.display()
+        );
+    }
+    // Stream into a sibling temp file and rename once the whole payload has been
+    // authenticated, so a failed transfer never leaves a partial file at `path`.
+    let mut tmp = path.clone().into_os_string();
+    tmp.push(".part");
+    let tmp = PathBuf::from(tmp);
+    let written = match write_capped(&mut plaintext, &tmp) {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(e);
+        }
+    };
+    std::fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+    crate::history::record(crate::history::Entry {
+        peer: (sender_z32 != keypair.public_key().to_z32()).then(|| sender_z32.clone()),
+        ..crate::history::Entry::now(
+            crate::history::Direction::In,
+            crate::history::Kind::File,
+            record.created_at,
+            &name,
+        )
+    });
+
+    crate::status!(
+        "{} {} ({} bytes, sent {} ago)",
+        "Received".success(),
+        path.display(),
+        written,
+        crate::util::human_duration(now.saturating_sub(record.created_at))
+    );
+
+    Ok(())
+}
+
+/// Copy `plaintext` into a new file at `path`, failing past `MAX_PAYLOAD_BYTES`.
+fn write_capped(plaintext: &mut dyn Read, path: &Path) -> anyhow::Result<u64> {
+    let limit = crate::record::MAX_PAYLOAD_BYTES as u64;
+    let mut file = std::fs::File::create(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    let written = std::io::copy(&mut plaintext.take(limit + 1), &mut file)
+        .map_err(|e| anyhow::anyhow!("failed to decrypt file: {}", e))?;
+    if written > limit {
+        anyhow::bail!("file is larger than the {} byte limit", limit);
+    }
+    Ok(written)
+}
@@ -2,7 +2,7 @@
 use base64::Engine;
 use std::io::IsTerminal;
 
-use owo_colors::{OwoColorize, Stream::Stdout};
+use crate::ui::Paint;
 
 /// Revoke the active handoff record from the DHT.
 ///
@@ -22,7 +22,7 @@ pub fn run_revoke(args: crate::cli::RevokeArgs) -> anyhow::Result<()> {
             if e.downcast_ref::<crate::error::CclinkError>()
                 .is_some_and(|ce| matches!(ce, crate::error::CclinkError::RecordNotFound))
             {
-                println!("No active handoffs.");
+                crate::status!("No active handoffs.");
                 return Ok(());
             }
             return Err(e);
@@ -58,18 +58,15 @@ pub fn run_revoke(args: crate::cli::RevokeArgs) -> anyhow::Result<()> {
             .interact()
             .map_err(|e| anyhow::anyhow!("prompt failed: {}", e))?;
         if !confirmed {
-            println!("Aborted.");
+            crate::status!("Aborted.");
             return Ok(());
         }
     }
 
     // ── 5. Revoke by publishing empty packet ─────────────────────────────
     client.revoke(&keypair)?;
-    println!(
-        "{} ({})",
-        "Revoked.".if_supports_color(Stdout, |t| t.green()),
-        project_display
-    );
+    crate::cache::forget();
+    crate::status!("{} ({})", "Revoked.".success(), project_display);
 
     Ok(())
 }
@@ -0,0 +1,111 @@
+/// Send command — encrypts a file and publishes it for `cclink recv`.
+///
+/// Files live in a slot derived from the sender and recipient keys
+/// (`crypto::file_slot_keypair`), not in the sender's own packet, so sending a file
+/// never replaces an active session handoff. Each sender→recipient pair has one slot:
+/// a new `send` to the same recipient replaces the previous file.
+use std::time::SystemTime;
+
+use anyhow::Context;
+
+use crate::ui::Paint;
+
+pub fn run_send(args: crate::cli::SendArgs) -> anyhow::Result<()> {
+    // ── 1. Check the file size before any passphrase prompt ──────────────
+    let file = std::fs::File::open(&args.file)
+        .with_context(|| format!("Failed to read {}", args.file.display()))?;
+    let size = file.metadata()?.len();
+    // Two limits: `recv` decompresses at most MAX_PAYLOAD_BYTES, and the compressed
+    // ciphertext must fit the DHT chunk budget, checked while it is encrypted.
+    if size > crate::record::MAX_PAYLOAD_BYTES as u64 {
+        anyhow::bail!(
+            "{} is {} bytes; cclink send takes files of up to {} bytes that compress to \
+             about {} bytes",
+            args.file.display(),
+            size,
+            crate::record::MAX_PAYLOAD_BYTES,
+            crate::record::chunked::MAX_BLOB_BYTES
+        );
+    }
+
+    // ── 2. Load keypair and resolve recipient ────────────────────────────
+    let keypair = crate::keys::store::load_keypair()?;
+    let own_z32 = keypair.public_key().to_z32();
+    let share = args
+        .share
+        .as_deref()
+        .map(crate::contacts::resolve)
+        .transpose()?;
+    let recipient_z32 = share.clone().unwrap_or_else(|| own_z32.clone());
+    let recipient = pkarr::PublicKey::try_from(recipient_z32.as_str())
+        .map_err(|e| anyhow::anyhow!("invalid recipient pubkey: {}", e))?;
+
+    // ── 3. Stream the file through compression and encryption ────────────
+    let name = args
+        .file
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // Only the ciphertext is held in memory. Encryption stops as soon as it outgrows
+    // the DHT chunk budget, so an input that cannot fit is not read to the end.
+    let plaintext = crate::record::compress_reader(crate::record::file::reader(&name, file)?)?;
+    let mut writer = crate::record::chunked::BlobWriter::new();
+    let encrypted = crate::crypto::age_encrypt_stream(
+        plaintext,
+        &mut writer,
+        &crate::crypto::recipient_from_z32(&recipient_z32)?,
+    );
+    if writer.overflowed() {
+        anyhow::bail!(
+            "{} compresses and encrypts to more than {} bytes, the most a handoff carries",
+            args.file.display(),
+            crate::record::chunked::MAX_BLOB_BYTES
+        );
+    }
+    encrypted?;
+    let ciphertext = writer.into_inner();
+
+    // ── 4. Seal and publish into the file slot ───────────────────────────
+    let created_at = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+    let sealed = crate::record::HandoffRecord::builder(ciphertext, args.ttl)
+        .compressed(true)
+        .created_at(created_at)
+        .recipient(share.clone())
+        .seal(&keypair)?;
+    let slot = crate::crypto::file_slot_keypair(&keypair, &keypair.public_key(), &recipient);
+
+    if let Some((ref frames, _)) = sealed.chunks {
+        crate::status!("Publishing {} chunks...", frames.len());
+    }
+    let client = crate::progress::attach(crate::transport::DhtClient::new()?, "Sending");
+    client.publish_sealed(&slot, &sealed)?;
+    crate::history::record(crate::history::Entry {
+        peer: share.clone(),
+        ..crate::history::Entry::now(
+            crate::history::Direction::Out,
+            crate::history::Kind::File,
+            created_at,
+            &name,
+        )
+    });
+
+    // ── 5. Output ────────────────────────────────────────────────────────
+    crate::status!("{} {} ({} bytes)", "Sent".success(), name, size);
+    let recv_command = if share.is_some() {
+        crate::status!("  Recipient command:");
+        format!("cclink recv {}", own_z32)
+    } else {
+        crate::status!("  Run on another machine:");
+        "cclink recv".to_string()
+    };
+    crate::status!("  {}", recv_command.emphasis());
+    crate::status!(
+        "  Expires in {} ({})",
+        crate::util::human_duration(args.ttl),
+        crate::util::format_utc(created_at.saturating_add(args.ttl))
+    );
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+/// Sessions command — lists the agent sessions cclink can publish from this machine,
+/// and optionally picks one to hand to the default publish flow.
+use std::io::IsTerminal;
+use std::time::SystemTime;
+
+use crate::cli::SessionsArgs;
+use crate::session::{Agent, SessionInfo};
+use crate::ui::Paint;
+use crate::util::human_duration;
+
+/// List active sessions (of `agent`, or of every agent). With `--pick`, returns the
+/// session the user chose to publish.
+pub fn run_sessions(
+    args: &SessionsArgs,
+    agent: Option<Agent>,
+) -> anyhow::Result<Option<SessionInfo>> {
+    use comfy_table::{Cell, Table};
+
+    // ── 1. Discover ──────────────────────────────────────────────────────
+    let project = args.project.as_ref().map(std::path::absolute).transpose()?;
+    let mut sessions = crate::session::discover_sessions(agent, project.as_deref())?;
+    if sessions.is_empty() {
+        println!("{}", "No sessions active in the last 24 hours.".warning());
+        return Ok(None);
+    }
+
+    // ── 2. Pick, or list ─────────────────────────────────────────────────
+    let now = SystemTime::now();
+    let rows: Vec<[String; 5]> = sessions.iter().map(|s| row(s, now)).collect();
+    if args.pick {
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!(
+                "--pick needs an interactive terminal; publish with --session-id instead"
+            );
+        }
+        let items: Vec<String> = rows
+            .iter()
+            .map(|[agent, id, project, age, size]| {
+                format!("{} {} ({}) — {} ago, {}", agent, id, project, age, size)
+            })
+            .collect();
+        let selection = dialoguer::Select::new()
+            .with_prompt("Pick a session to publish")
+            .items(&items)
+            .default(0)
+            .interact()
+            .map_err(|e| anyhow::anyhow!("session selection failed: {}", e))?;
+        return Ok(Some(sessions.remove(selection)));
+    }
+
+    let mut table = Table::new();
+    table.set_header(vec!["Agent", "Session", "Project", "Age", "Size"]);
+    for cells in rows {
+        table.add_row(cells.map(Cell::new));
+    }
+    println!("{table}");
+    Ok(None)
+}
+
+/// Agent, session ID, project, age, and transcript size of `session`.
+fn row(session: &SessionInfo, now: SystemTime) -> [String; 5] {
+    let age = now
+        .duration_since(session.mtime)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let size = session
+        .agent
+        .provider()
+        .find_session_file(&session.session_id)
+        .and_then(|path| std::fs::metadata(path).ok())
+        .map_or_else(|| "-".to_string(), |m| human_size(m.len()));
+    [
+        session.agent.name().to_string(),
+        session.session_id.clone(),
+        session.project.clone(),
+        human_duration(age),
+        size,
+    ]
+}
+
+/// Byte count in the largest binary unit that keeps it at or above 1, e.g. `1.5 MiB`.
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!(human_size(0), "0 B");
+        assert_eq!(human_size(1023), "1023 B");
+        assert_eq!(human_size(1536), "1.5 KiB");
+        assert_eq!(human_size(5 * 1024 * 1024), "5.0 MiB");
+    }
+
+    #[test]
+    fn test_row_without_session_file() {
+        let session = SessionInfo {
+            agent: Agent::Codex,
+            session_id: "no-such-session-0000".to_string(),
+            project: "/work/app".to_string(),
+            mtime: SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000),
+        };
+        let now = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000 + 7200);
+        assert_eq!(
+            row(&session, now),
+            ["codex", "no-such-session-0000", "/work/app", "2h", "-"].map(String::from)
+        );
+    }
+}
@@ -0,0 +1,197 @@
+/// Status command — an at-a-glance overview without launching anything: your active
+/// handoff, the latest handoff from each contact, and the sessions active here.
+///
+/// The project of your own handoff comes from the local history rather than from
+/// decrypting the record, so a handoff published from another machine shows without one.
+use std::time::SystemTime;
+
+use crate::error::CclinkError;
+use crate::record::HandoffRecord;
+use crate::ui::Paint;
+use crate::util::human_duration;
+
+/// Local sessions listed before the rest is summarised as a count.
+const MAX_SESSIONS: usize = 5;
+
+pub fn run_status() -> anyhow::Result<()> {
+    let keypair = crate::keys::store::load_keypair()?;
+    let own_z32 = keypair.public_key().to_z32();
+    let client = crate::transport::DhtClient::cached()?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+
+    // ── Your handoff ─────────────────────────────────────────────────────
+    heading("Your handoff");
+    match active_record(&client, &own_z32, now)? {
+        None => println!("  none active"),
+        Some(record) => {
+            let history = crate::history::load()?;
+            let published = history.iter().rev().find(|e| {
+                e.direction == crate::history::Direction::Out
+                    && e.kind == crate::history::Kind::Handoff
+                    && e.created_at == record.created_at
+            });
+            let what = match published {
+                Some(entry) => format!(
+                    "{} in {}",
+                    entry.name.chars().take(8).collect::<String>(),
+                    entry.project.as_deref().unwrap_or("?")
+                ),
+                None => "published from another machine".to_string(),
+            };
+            println!("  {} — {}", what, summary(&record, &own_z32, now));
+        }
+    }
+
+    // ── Contacts ─────────────────────────────────────────────────────────
+    let contacts = crate::contacts::ContactBook::load_from(&crate::contacts::contacts_path()?)?;
+    if !contacts.is_empty() {
+        println!();
+        heading("Contacts");
+        for (name, pubkey) in contacts.iter() {
+            let line = match active_record(&client, pubkey, now) {
+                Ok(Some(record)) => summary(&record, &own_z32, now),
+                Ok(None) => "nothing published".to_string(),
+                Err(e) => format!("lookup failed: {}", e),
+            };
+            println!("  {:<16} {}", name, line);
+        }
+    }
+
+    // ── Local sessions ───────────────────────────────────────────────────
+    println!();
+    heading("Local sessions");
+    let sessions = crate::session::discover_sessions(None, None)?;
+    if sessions.is_empty() {
+        println!("  none active in the last 24 hours");
+    }
+    let wall = SystemTime::now();
+    for session in sessions.iter().take(MAX_SESSIONS) {
+        let age = wall
+            .duration_since(session.mtime)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        println!(
+            "  {:<7} {} {} — {} ago",
+            session.agent.name(),
+            session.session_id.chars().take(8).collect::<String>(),
+            session.project,
+            human_duration(age)
+        );
+    }
+    if sessions.len() > MAX_SESSIONS {
+        println!(
+            "  … and {} more (cclink sessions)",
+            sessions.len() - MAX_SESSIONS
+        );
+    }
+    Ok(())
+}
+
+fn heading(title: &str) {
+    println!("{}", title.emphasis());
+}
+
+/// The unexpired handoff published by `pubkey_z32`, if any.
+fn active_record(
+    client: &crate::transport::DhtClient,
+    pubkey_z32: &str,
+    now: u64,
+) -> anyhow::Result<Option<HandoffRecord>> {
+    match client.resolve_record(pubkey_z32) {
+        Ok(record) if now < record.created_at.saturating_add(record.ttl) => Ok(Some(record)),
+        Ok(_) => Ok(None),
+        Err(e)
+            if e.downcast_ref::<CclinkError>()
+                .is_some_and(|ce| matches!(ce, CclinkError::RecordNotFound)) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Age, time left, and flags of `record`, as seen by the holder of `own_z32`.
+fn summary(record: &HandoffRecord, own_z32: &str, now: u64) -> String {
+    let mut line = format!(
+        "published {} ago, expires in {}",
+        human_duration(now.saturating_sub(record.created_at)),
+        human_duration(
+            record
+                .created_at
+                .saturating_add(record.ttl)
+                .saturating_sub(now)
+        )
+    );
+    for flag in flags(record, own_z32) {
+        line.push_str(&format!(" [{}]", flag));
+    }
+    line
+}
+
+/// Burn, PIN, and sharing flags of `record`.
+fn flags(record: &HandoffRecord, own_z32: &str) -> Vec<String> {
+    let mut flags = Vec::new();
+    if record.burn {
+        flags.push(match record.burn_uses {
+            Some(uses) => format!("burn, {} uses", uses),
+            None => "burn".to_string(),
+        });
+    }
+    if record.pin_salt.is_some() {
+        flags.push("PIN".to_string());
+    }
+    match record.recipient.as_deref() {
+        Some(recipient) if recipient == own_z32 => flags.push("for you".to_string()),
+        Some(recipient) if record.pubkey == own_z32 => flags.push(format!(
+            "shared with {}",
+            recipient.chars().take(8).collect::<String>()
+        )),
+        Some(_) => flags.push("shared with someone else".to_string()),
+        None => {}
+    }
+    flags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keypair(byte: u8) -> pkarr::Keypair {
+        pkarr::Keypair::from_secret_key(&[byte; 32])
+    }
+
+    #[test]
+    fn test_flags_from_each_side() {
+        let publisher = keypair(1);
+        let me = keypair(2).public_key().to_z32();
+        let record = HandoffRecord::builder(vec![0], 600)
+            .burn(true)
+            .recipient(me.clone())
+            .sign(&publisher)
+            .unwrap();
+        assert_eq!(flags(&record, &me), vec!["burn", "for you"]);
+        assert_eq!(
+            flags(&record, &publisher.public_key().to_z32()),
+            vec!["burn".to_string(), format!("shared with {}", &me[..8])]
+        );
+        assert_eq!(
+            flags(&record, &keypair(3).public_key().to_z32()),
+            vec!["burn", "shared with someone else"]
+        );
+    }
+
+    #[test]
+    fn test_summary_reports_age_and_time_left() {
+        let record = HandoffRecord::builder(vec![0], 3600)
+            .created_at(1_000)
+            .pin_salt([1u8; 32])
+            .sign(&keypair(1))
+            .unwrap();
+        assert_eq!(
+            summary(&record, "me", 1_600),
+            "published 10m ago, expires in 50m [PIN]"
+        );
+    }
+}
@@ -0,0 +1,60 @@
+/// Trust command — list or remove the publisher keys pinned on first pickup.
+use crate::cli::TrustCommand;
+use crate::trust::{trust_path, TrustStore};
+
+pub fn run_trust(args: crate::cli::TrustArgs) -> anyhow::Result<()> {
+    let path = trust_path()?;
+    let mut store = TrustStore::load_from(&path)?;
+
+    match args.command {
+        TrustCommand::List => {
+            if store.is_empty() {
+                println!("No pinned keys. Keys are pinned on the first pickup from them.");
+                return Ok(());
+            }
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::SystemTime::UNIX_EPOCH)?
+                .as_secs();
+            let mut table = comfy_table::Table::new();
+            table.set_header(vec![
+                "Name",
+                "Fingerprint",
+                "Public Key",
+                "First Seen",
+                "Verified",
+            ]);
+            for (name, entry) in store.iter() {
+                let fingerprint = pkarr::PublicKey::try_from(entry.pubkey.as_str())
+                    .map(|pk| crate::keys::fingerprint::short_fingerprint(&pk))
+                    .unwrap_or_else(|_| "?".to_string());
+                let age = crate::util::human_duration(now.saturating_sub(entry.first_seen));
+                table.add_row(vec![
+                    name.to_string(),
+                    fingerprint,
+                    entry.pubkey.clone(),
+                    format!("{} ago", age),
+                    entry
+                        .verified_at
+                        .map(|at| {
+                            format!(
+                                "{} ago",
+                                crate::util::human_duration(now.saturating_sub(at))
+                            )
+                        })
+                        .unwrap_or_default(),
+                ]);
+            }
+            println!("{table}");
+        }
+        TrustCommand::Remove { name } => {
+            let removed = store.remove(&name);
+            if removed == 0 {
+                anyhow::bail!("nothing pinned for '{}'", name);
+            }
+            store.save_to(&path)?;
+            crate::status!("Removed {}. The next pickup will pin the key again.", name);
+        }
+    }
+
+    Ok(())
+}
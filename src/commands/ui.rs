@@ -0,0 +1,363 @@
+/// UI command — a full-screen dashboard of local sessions, active handoffs, contacts
+/// and history, with keys to act on the selected row.
+///
+/// The dashboard only reads. Choosing an action (publish, pick up, revoke, inspect)
+/// leaves it and runs the regular command, so prompts for passphrases, PINs and
+/// confirmations work exactly as on the command line.
+use std::time::{Duration, SystemTime};
+
+use clap::Parser;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::commands::list::Listed;
+use crate::session::SessionInfo;
+use crate::util::human_duration;
+
+/// History entries shown, newest last.
+const HISTORY_ROWS: usize = 50;
+
+/// Keys, shown along the bottom.
+const HELP: &str =
+    "Tab pane · ↑↓ select · Enter publish/pick up · r revoke · i inspect · R refresh · q quit";
+
+/// The dashboard panes, in Tab order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Sessions,
+    Handoffs,
+    Contacts,
+    History,
+}
+
+const PANES: [Pane; 4] = [
+    Pane::Sessions,
+    Pane::Handoffs,
+    Pane::Contacts,
+    Pane::History,
+];
+
+/// What to run once the dashboard is closed.
+enum Action {
+    Quit,
+    Publish(SessionInfo),
+    /// Pick up the handoff of this publisher, or your own.
+    Pickup(Option<String>),
+    Revoke,
+    Inspect(String),
+}
+
+struct Dashboard {
+    keypair: pkarr::Keypair,
+    sessions: Vec<SessionInfo>,
+    handoffs: Vec<Listed>,
+    contacts: Vec<(String, String)>,
+    history: Vec<crate::history::Entry>,
+    focus: usize,
+    states: [TableState; 4],
+    /// Shown in place of the key help until the next key press.
+    status: Option<String>,
+}
+
+pub fn run_ui(cli: &crate::cli::Cli) -> anyhow::Result<()> {
+    // ── 1. Load everything before taking over the screen ────────────────
+    let keypair = crate::keys::store::load_keypair()?;
+    println!("Looking up handoffs...");
+    let mut dashboard = Dashboard {
+        keypair,
+        sessions: vec![],
+        handoffs: vec![],
+        contacts: vec![],
+        history: vec![],
+        focus: 0,
+        states: Default::default(),
+        status: None,
+    };
+    dashboard.refresh()?;
+
+    // ── 2. Run until an action is chosen ────────────────────────────────
+    let mut terminal = ratatui::init();
+    let action = dashboard.run(&mut terminal);
+    ratatui::restore();
+
+    // ── 3. Hand over to the regular command ─────────────────────────────
+    match action? {
+        Action::Quit => Ok(()),
+        Action::Publish(session) => crate::commands::publish::publish_session(cli, Some(session)),
+        Action::Pickup(pubkey) => {
+            let args = ["pickup".to_string()].into_iter().chain(pubkey);
+            crate::commands::pickup::run_pickup(crate::cli::PickupArgs::try_parse_from(args)?)
+        }
+        Action::Revoke => {
+            crate::commands::revoke::run_revoke(crate::cli::RevokeArgs::try_parse_from(["revoke"])?)
+        }
+        Action::Inspect(pubkey) => {
+            crate::commands::inspect::run_inspect(crate::cli::InspectArgs::try_parse_from([
+                "inspect", &pubkey,
+            ])?)
+        }
+    }
+}
+
+impl Dashboard {
+    /// Reload every pane. Handoffs come from the DHT; the rest is local.
+    fn refresh(&mut self) -> anyhow::Result<()> {
+        self.sessions = crate::session::discover_sessions(None, None)?;
+        self.handoffs = crate::commands::list::active_handoffs(&self.keypair)?;
+        self.contacts =
+            crate::contacts::ContactBook::load_from(&crate::contacts::contacts_path()?)?
+                .iter()
+                .map(|(name, key)| (name.to_string(), key.to_string()))
+                .collect();
+        let history = crate::history::load()?;
+        self.history = history[history.len().saturating_sub(HISTORY_ROWS)..].to_vec();
+
+        let lens = [
+            self.sessions.len(),
+            self.handoffs.len(),
+            self.contacts.len(),
+            self.history.len(),
+        ];
+        for (state, len) in self.states.iter_mut().zip(lens) {
+            let selected = state.selected().map_or(0, |i| i.min(len.saturating_sub(1)));
+            state.select((len > 0).then_some(selected));
+        }
+        Ok(())
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> anyhow::Result<Action> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            // Redraw every second so TTLs count down.
+            if !event::poll(Duration::from_secs(1))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            self.status = None;
+            let pane = PANES[self.focus];
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(Action::Quit),
+                KeyCode::Tab => self.focus = (self.focus + 1) % PANES.len(),
+                KeyCode::BackTab => self.focus = (self.focus + PANES.len() - 1) % PANES.len(),
+                KeyCode::Down | KeyCode::Char('j') => self.states[self.focus].select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.states[self.focus].select_previous(),
+                KeyCode::Char('R') => {
+                    self.status = Some("Refreshing...".to_string());
+                    terminal.draw(|frame| self.draw(frame))?;
+                    self.status = self
+                        .refresh()
+                        .err()
+                        .map(|e| format!("Refresh failed: {:#}", e));
+                }
+                KeyCode::Char('r') => {
+                    if self.handoffs.iter().any(|h| self.is_own(h)) {
+                        return Ok(Action::Revoke);
+                    }
+                    self.status = Some("You have no active handoff to revoke.".to_string());
+                }
+                KeyCode::Enter => match (pane, self.selected()) {
+                    (Pane::Sessions, Some(i)) => {
+                        return Ok(Action::Publish(self.sessions.remove(i)));
+                    }
+                    (Pane::Handoffs, Some(i)) => {
+                        let handoff = &self.handoffs[i];
+                        let own = self.is_own(handoff);
+                        return Ok(Action::Pickup((!own).then(|| handoff.pubkey.clone())));
+                    }
+                    (Pane::Contacts, Some(i)) => {
+                        return Ok(Action::Pickup(Some(self.contacts[i].1.clone())));
+                    }
+                    _ => {}
+                },
+                KeyCode::Char('i') => match (pane, self.selected()) {
+                    (Pane::Handoffs, Some(i)) => {
+                        return Ok(Action::Inspect(self.handoffs[i].pubkey.clone()));
+                    }
+                    (Pane::Contacts, Some(i)) => {
+                        return Ok(Action::Inspect(self.contacts[i].1.clone()));
+                    }
+                    _ => self.status = Some("Select a handoff or contact to inspect.".to_string()),
+                },
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether `handoff` is this identity's own, by key: a contact may be named "you".
+    fn is_own(&self, handoff: &crate::commands::list::Listed) -> bool {
+        handoff.pubkey == self.keypair.public_key().to_z32()
+    }
+
+    fn selected(&self) -> Option<usize> {
+        self.states[self.focus].selected()
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [top, bottom, help] = Layout::vertical([
+            Constraint::Percentage(50),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [sessions, handoffs] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(top);
+        let [contacts, history] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(bottom);
+
+        let now = SystemTime::now();
+        let now_secs = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let rows = self.sessions.iter().map(|s| {
+            let age = now
+                .duration_since(s.mtime)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            Row::new([
+                s.agent.name().to_string(),
+                s.project.clone(),
+                human_duration(age),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(8),
+                Constraint::Fill(1),
+                Constraint::Length(8),
+            ],
+        )
+        .header(Row::new(["Agent", "Project", "Age"]).bold());
+        render(
+            frame,
+            sessions,
+            Pane::Sessions,
+            self.focus,
+            table,
+            &mut self.states[0],
+        );
+
+        let rows = self.handoffs.iter().map(|h| {
+            Row::new([
+                h.publisher.clone(),
+                h.project_display().to_string(),
+                human_duration(h.expires_at().saturating_sub(now_secs)),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(14),
+                Constraint::Fill(1),
+                Constraint::Length(9),
+            ],
+        )
+        .header(Row::new(["Publisher", "Project", "TTL Left"]).bold());
+        render(
+            frame,
+            handoffs,
+            Pane::Handoffs,
+            self.focus,
+            table,
+            &mut self.states[1],
+        );
+
+        let rows = self
+            .contacts
+            .iter()
+            .map(|(name, key)| Row::new([name.clone(), key.clone()]));
+        let table = Table::new(rows, [Constraint::Length(14), Constraint::Fill(1)])
+            .header(Row::new(["Name", "Public key"]).bold());
+        render(
+            frame,
+            contacts,
+            Pane::Contacts,
+            self.focus,
+            table,
+            &mut self.states[2],
+        );
+
+        let rows = self.history.iter().map(|e| {
+            let arrow = match e.direction {
+                crate::history::Direction::Out => "→",
+                crate::history::Direction::In => "←",
+            };
+            Row::new([
+                crate::util::format_utc(e.at),
+                arrow.to_string(),
+                e.name.clone(),
+                e.project.clone().unwrap_or_default(),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(20),
+                Constraint::Length(1),
+                Constraint::Length(12),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(Row::new(["When", "", "Name", "Project"]).bold());
+        render(
+            frame,
+            history,
+            Pane::History,
+            self.focus,
+            table,
+            &mut self.states[3],
+        );
+
+        let footer = match self.status {
+            Some(ref status) => Line::from(status.as_str()).yellow(),
+            None => Line::from(HELP).dim(),
+        };
+        frame.render_widget(footer, help);
+    }
+}
+
+/// Draw `table` in `area` as `pane`, highlighted when it has the `focus`.
+fn render(
+    frame: &mut Frame,
+    area: Rect,
+    pane: Pane,
+    focus: usize,
+    table: Table,
+    state: &mut TableState,
+) {
+    let focused = PANES[focus] == pane;
+    let title = match pane {
+        Pane::Sessions => " Local sessions ",
+        Pane::Handoffs => " Active handoffs ",
+        Pane::Contacts => " Contacts ",
+        Pane::History => " History ",
+    };
+    let block = Block::bordered().title(title);
+    let block = if focused {
+        block.border_style(Style::new().cyan())
+    } else {
+        block
+    };
+    let highlight = if focused {
+        Style::new().reversed()
+    } else {
+        Style::new()
+    };
+    frame.render_stateful_widget(
+        table.block(block).row_highlight_style(highlight),
+        area,
+        state,
+    );
+}
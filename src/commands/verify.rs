@@ -0,0 +1,203 @@
+/// Verify command — confirm another user's key by comparing a short code with them.
+///
+/// Both users run `cclink verify <other>`, then read the code shown to each other
+/// (over a call, or in person). When it matches, the key is pinned in the trust
+/// store and marked verified. See `crate::record::sas` for how the code is derived.
+use std::io::IsTerminal;
+use std::time::{Duration, Instant, SystemTime};
+
+use rand::Rng;
+
+use crate::record::sas::{self, VerifyOffer};
+use crate::transport::DhtClient;
+use crate::ui::Paint;
+
+/// How often to look for the other side's offer.
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+pub fn run_verify(args: crate::cli::VerifyArgs) -> anyhow::Result<()> {
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!("cclink verify needs an interactive terminal to compare codes");
+    }
+
+    // ── 1. Load keypair, resolve peer ────────────────────────────────────
+    let keypair = crate::keys::store::load_keypair()?;
+    let own = keypair.public_key();
+    let peer_z32 = crate::contacts::resolve(&args.pubkey)?;
+    let peer = pkarr::PublicKey::try_from(peer_z32.as_str())
+        .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+    if peer == own {
+        anyhow::bail!("cannot verify your own key");
+    }
+    // Pins are keyed by contact name, as on pickup. A bare key is recorded under
+    // itself, which only marks it verified.
+    let name = if pkarr::PublicKey::try_from(args.pubkey.as_str()).is_ok() {
+        peer_z32.clone()
+    } else {
+        args.pubkey.clone()
+    };
+
+    // ── 2. Exchange nonces ───────────────────────────────────────────────
+    let client = DhtClient::new()?;
+    let started = now()?;
+    let out_slot = crate::crypto::verify_slot_keypair(&keypair, &own, &peer);
+    let nonce: [u8; 32] = rand::thread_rng().gen();
+    println!(
+        "Waiting for {} to run: cclink verify {}",
+        args.pubkey.identity(),
+        own.to_z32()
+    );
+    let exchanged = exchange(
+        &client,
+        &keypair,
+        &peer,
+        &out_slot,
+        &nonce,
+        started,
+        args.timeout,
+    );
+    let peer_nonce = match exchanged {
+        Ok(peer_nonce) => peer_nonce,
+        Err(e) => {
+            clear(&client, &out_slot);
+            return Err(e);
+        }
+    };
+
+    // ── 3. Compare codes ─────────────────────────────────────────────────
+    let code = sas::sas(&own, &nonce, &peer, &peer_nonce);
+    println!();
+    println!("Compare this code with {}:", args.pubkey);
+    println!();
+    let (high, low) = code.digits.split_at(3);
+    println!("    {} {}", high.emphasis(), low.emphasis());
+    println!("    {}", emoji_line(&code.emoji));
+    println!();
+    let confirmed = dialoguer::Confirm::new()
+        .with_prompt(format!("Does {} see the same code?", args.pubkey))
+        .default(false)
+        .interact()
+        .map_err(|e| anyhow::anyhow!("prompt failed: {}", e));
+    // The peer has read our nonce by now: it shows the code only after reading it.
+    clear(&client, &out_slot);
+    if !confirmed? {
+        anyhow::bail!(
+            "the codes did not match: the key you have for {} may not be theirs. \
+             Do not share with it until you have checked it another way",
+            args.pubkey
+        );
+    }
+
+    // ── 4. Mark verified ─────────────────────────────────────────────────
+    let path = crate::trust::trust_path()?;
+    let mut store = crate::trust::TrustStore::load_from(&path)?;
+    store.mark_verified(&name, &peer_z32, now()?)?;
+    crate::keys::store::ensure_key_dir()?;
+    store.save_to(&path)?;
+    println!("{}", format!("Verified {}.", name).success());
+    Ok(())
+}
+
+/// Commit to `nonce`, wait for the peer's commitment, reveal, and wait for the peer's
+/// nonce. Returns the peer's nonce.
+fn exchange(
+    client: &DhtClient,
+    keypair: &pkarr::Keypair,
+    peer: &pkarr::PublicKey,
+    out_slot: &pkarr::Keypair,
+    nonce: &[u8; 32],
+    started: u64,
+    timeout: u64,
+) -> anyhow::Result<[u8; 32]> {
+    let own = keypair.public_key();
+    let in_slot = crate::crypto::verify_slot_keypair(keypair, peer, &own).public_key();
+    let deadline = Instant::now() + Duration::from_secs(timeout);
+    let offer = |reveal| sas::seal_offer(keypair, peer, started, nonce, reveal);
+
+    client.publish_verify_offer(out_slot, &offer(false)?)?;
+    // An offer from a run that started long before ours was abandoned.
+    let committed = poll(deadline, || {
+        Ok(valid_offer(client, &in_slot, peer, &own)
+            .filter(|(theirs, _)| theirs.created_at.saturating_add(timeout) >= started))
+    })?
+    .0;
+
+    client.publish_verify_offer(out_slot, &offer(true)?)?;
+    poll(deadline, || {
+        let Some((theirs, revealed)) = valid_offer(client, &in_slot, peer, &own) else {
+            return Ok(None);
+        };
+        if theirs.created_at != committed.created_at || theirs.commitment != committed.commitment {
+            anyhow::bail!("the other side restarted; run cclink verify again on both ends");
+        }
+        Ok(revealed)
+    })
+}
+
+/// The peer's current offer in `slot` and its nonce, if it is there and valid.
+fn valid_offer(
+    client: &DhtClient,
+    slot: &pkarr::PublicKey,
+    peer: &pkarr::PublicKey,
+    own: &pkarr::PublicKey,
+) -> Option<(VerifyOffer, Option<[u8; 32]>)> {
+    let offer = client.resolve_verify_offer(slot)?;
+    match sas::verify_offer(&offer, peer, own) {
+        Ok(nonce) => Some((offer, nonce)),
+        Err(e) => {
+            tracing::debug!(error = %e, "ignoring an invalid verification offer");
+            None
+        }
+    }
+}
+
+/// Call `step` every [`POLL_INTERVAL`] until it yields a value or `deadline` passes.
+fn poll<T>(
+    deadline: Instant,
+    mut step: impl FnMut() -> anyhow::Result<Option<T>>,
+) -> anyhow::Result<T> {
+    loop {
+        if let Some(value) = step()? {
+            return Ok(value);
+        }
+        if Instant::now() >= deadline {
+            anyhow::bail!("timed out waiting for the other side");
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Remove our offer. Failures are logged, not returned: it expires on its own.
+fn clear(client: &DhtClient, out_slot: &pkarr::Keypair) {
+    if let Err(e) = client.revoke(out_slot) {
+        tracing::debug!(error = %e, "failed to clear verification offer");
+    }
+}
+
+/// e.g. `🐶 dog   🍕 pizza   🔑 key   🚀 rocket`
+fn emoji_line(indices: &[usize]) -> String {
+    indices
+        .iter()
+        .map(|&i| {
+            let (emoji, name) = crate::keys::fingerprint::EMOJI[i];
+            format!("{} {}", emoji, name)
+        })
+        .collect::<Vec<_>>()
+        .join("   ")
+}
+
+fn now() -> anyhow::Result<u64> {
+    Ok(SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emoji_line() {
+        assert_eq!(emoji_line(&[0, 1]), "🐶 dog   🐱 cat");
+    }
+}
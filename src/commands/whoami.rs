@@ -1,12 +1,5 @@
 use crate::keys;
 
-fn try_copy_to_clipboard(text: &str) -> bool {
-    match arboard::Clipboard::new() {
-        Ok(mut clipboard) => clipboard.set_text(text).is_ok(),
-        Err(_) => false,
-    }
-}
-
 pub fn run_whoami() -> anyhow::Result<()> {
     let keypair = keys::store::load_keypair()?;
     let public_key = keypair.public_key();
@@ -17,10 +10,23 @@ pub fn run_whoami() -> anyhow::Result<()> {
     println!("Public Key:  {}", pubkey_uri);
     println!("Fingerprint: {}", fingerprint);
     println!("Key file:    {}", key_path.display());
-    println!();
+    if let Some(profile) = keys::store::profile() {
+        println!("Profile:     {}", profile);
+    }
+    println!("Words:");
+    println!(
+        "{}",
+        keys::fingerprint::grouped(&keys::fingerprint::words(&public_key), 6, "  ")
+    );
+    println!("Emoji:");
+    println!(
+        "{}",
+        keys::fingerprint::grouped(&keys::fingerprint::emoji(&public_key), 11, "  ")
+    );
+    crate::status!();
 
-    if try_copy_to_clipboard(&pubkey_uri) {
-        println!("Public key copied to clipboard.");
+    if let Some(provider) = crate::clipboard::copy(&pubkey_uri) {
+        crate::status!("Public key copied to clipboard ({}).", provider);
     } else {
         println!("(Clipboard unavailable — copy public key manually)");
     }
@@ -0,0 +1,91 @@
+/// Wipe command — clears every record this identity keeps on the DHT, for leaving
+/// cclink or retiring a key.
+///
+/// The handoff, the display name, the device registry, and the file slot of every
+/// recipient `cclink send` sent to from this machine are overwritten with empty
+/// packets, as `cclink revoke` does for the handoff. Revocation certificates and
+/// transition records are left alone: they exist to outlive the key. The key files
+/// stay on disk.
+use std::io::IsTerminal;
+
+use crate::transport::DhtClient;
+use crate::ui::Paint;
+
+/// Characters of the public key typed to confirm a wipe.
+const CONFIRM_CHARS: usize = 8;
+
+pub fn run_wipe(args: crate::cli::WipeArgs) -> anyhow::Result<()> {
+    // ── 1. Load keypair and list slots ──────────────────────────────────
+    let keypair = crate::keys::store::load_keypair()?;
+    let own = keypair.public_key();
+    let mut slots = vec![
+        ("handoff".to_string(), keypair.clone()),
+        (
+            "display name".to_string(),
+            crate::crypto::profile_keypair(&own),
+        ),
+        (
+            "device registry".to_string(),
+            crate::crypto::devices_keypair(&keypair),
+        ),
+    ];
+    slots.extend(
+        crate::commands::prune::file_targets(&keypair)?
+            .into_iter()
+            .map(|target| (target.label, target.slot)),
+    );
+
+    // ── 2. Confirmation: type the start of the public key ──────────────
+    if !args.yes {
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("cclink wipe needs a terminal to confirm; pass --yes to skip");
+        }
+        let z32 = own.to_z32();
+        let expected = &z32[..CONFIRM_CHARS];
+        println!(
+            "This clears {} record(s) of {} from the DHT:",
+            slots.len(),
+            z32.identity()
+        );
+        for (label, _) in &slots {
+            println!("  {}", label);
+        }
+        let typed: String = dialoguer::Input::new()
+            .with_prompt(format!(
+                "Type the first {} characters of the key ({}) to confirm",
+                CONFIRM_CHARS, expected
+            ))
+            .interact_text()
+            .map_err(|e| anyhow::anyhow!("prompt failed: {}", e))?;
+        if typed.trim() != expected {
+            crate::status!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    // ── 3. Clear every slot, reporting failures at the end ─────────────
+    let client = DhtClient::new()?;
+    let mut failed = 0;
+    for (label, slot) in &slots {
+        match client.revoke(slot) {
+            Ok(()) => crate::status!("  Cleared {}", label),
+            Err(e) => {
+                failed += 1;
+                eprintln!("  Failed to clear {}: {}", label, e);
+            }
+        }
+    }
+    crate::cache::forget();
+    if failed > 0 {
+        anyhow::bail!(
+            "{} of {} record(s) could not be cleared; run cclink wipe again",
+            failed,
+            slots.len()
+        );
+    }
+    crate::status!(
+        "{}",
+        "Wiped. Your key files are still in ~/.pubky; delete them if nothing needs them.".success()
+    );
+    Ok(())
+}
@@ -0,0 +1,382 @@
+//! User configuration: optional policy overrides read from `~/.pubky/cclink.toml`.
+//!
+//! Every setting has a default, so a missing file (the common case) behaves exactly
+//! like an empty one. Unknown keys are rejected rather than ignored, so a typo in a
+//! security setting fails loudly instead of silently keeping the default.
+//!
+//! ```toml
+//! [burn]
+//! max_age = "30m"   # seconds, or a duration like 30m, 2h
+//!
+//! [paths]           # publisher's project prefix = local prefix
+//! "/Users/me/code" = "/home/me/code"
+//!
+//! [hooks]           # shell commands run around handoffs (see `crate::hooks`)
+//! pre_pickup = "unison -batch code"
+//!
+//! [kdf]             # Argon2id cost of newly written key files (`cclink key bench`)
+//! m_cost = 131072   # KiB
+//! t_cost = 3
+//! p_cost = 1
+//!
+//! [transport]       # hand off through a shared folder instead of the DHT
+//! dir = "/home/me/Sync/cclink"
+//!
+//! [network]         # DHT timeouts and retries (`--timeout`, `--max-retries`)
+//! timeout = "10s"
+//! max_retries = 5
+//! min_backoff = "2s"
+//! max_backoff = "8s"
+//! max_response = 4194304   # bytes
+//!
+//! [theme]           # colors of messages (see `crate::ui`)
+//! warning = "bright yellow"
+//! identity = "#5fafff"
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use anyhow::Context;
+use serde::{Deserialize, Deserializer};
+
+/// Default [`BurnConfig::max_age`]: one hour.
+pub const DEFAULT_BURN_MAX_AGE: u64 = 3600;
+
+/// Network settings in effect, set once at startup from the config and global flags.
+static NETWORK: OnceLock<NetworkConfig> = OnceLock::new();
+
+/// Path of the config file inside the key directory.
+pub fn config_path() -> anyhow::Result<PathBuf> {
+    Ok(crate::keys::store::key_dir()?.join("cclink.toml"))
+}
+
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub burn: BurnConfig,
+    /// Project path prefixes on other machines and where they live on this one, so a
+    /// handoff from macOS or Windows resumes in the matching local directory.
+    pub paths: BTreeMap<String, String>,
+    pub hooks: HooksConfig,
+    pub kdf: KdfConfig,
+    pub transport: TransportConfig,
+    pub network: NetworkConfig,
+    pub theme: crate::ui::Theme,
+}
+
+/// How long DHT requests may take and how often failed lookups are retried.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// Longest a single DHT request may wait for answers, in seconds. Unset keeps
+    /// pkarr's default.
+    #[serde(deserialize_with = "optional_duration")]
+    pub timeout: Option<u64>,
+    /// Retries after a failed lookup in pickup and `--verify`.
+    pub max_retries: usize,
+    /// First delay between retries, in seconds; it doubles after each retry.
+    #[serde(deserialize_with = "duration")]
+    pub min_backoff: u64,
+    /// Longest delay between retries, in seconds.
+    #[serde(deserialize_with = "duration")]
+    pub max_backoff: u64,
+    /// Largest handoff read from the DHT, a shared folder or the local network, in
+    /// bytes.
+    pub max_response: u64,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        NetworkConfig {
+            timeout: None,
+            max_retries: 3,
+            min_backoff: 2,
+            max_backoff: 8,
+            max_response: crate::transport::DEFAULT_MAX_RESPONSE,
+        }
+    }
+}
+
+impl NetworkConfig {
+    /// Backoff for retried DHT lookups, giving up after 30s of waiting in total.
+    pub fn backoff(&self) -> backon::ExponentialBuilder {
+        backon::ExponentialBuilder::default()
+            .with_min_delay(Duration::from_secs(self.min_backoff))
+            .with_max_delay(Duration::from_secs(self.max_backoff.max(self.min_backoff)))
+            .with_max_times(self.max_retries)
+            .with_total_delay(Some(Duration::from_secs(30)))
+    }
+}
+
+/// Use `network` for the rest of the process. Its timeout applies to every
+/// [`crate::transport::DhtClient`] created from now on.
+pub fn set_network(network: NetworkConfig) -> anyhow::Result<()> {
+    crate::transport::set_max_response(network.max_response)?;
+    if let Some(secs) = network.timeout {
+        crate::transport::set_request_timeout(Duration::from_secs(secs))?;
+    }
+    NETWORK
+        .set(network)
+        .map_err(|_| anyhow::anyhow!("network settings are already set"))
+}
+
+/// The network settings in effect: those given to [`set_network`], else the defaults.
+pub fn network() -> NetworkConfig {
+    NETWORK.get().cloned().unwrap_or_default()
+}
+
+/// Where handoffs go instead of the DHT.
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransportConfig {
+    /// A folder shared with the other machines (see `crate::transport::file`). Publish
+    /// and pickup use it unless `--dir` names another.
+    pub dir: Option<PathBuf>,
+}
+
+/// Argon2id parameters for passphrase-protected key files written from now on.
+/// Existing files keep the parameters recorded in their header.
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct KdfConfig {
+    /// Memory cost in KiB.
+    pub m_cost: u32,
+    /// Iteration count.
+    pub t_cost: u32,
+    /// Parallelism.
+    pub p_cost: u32,
+}
+
+impl Default for KdfConfig {
+    fn default() -> Self {
+        let defaults = crate::crypto::KdfParams::default();
+        KdfConfig {
+            m_cost: defaults.m_cost,
+            t_cost: defaults.t_cost,
+            p_cost: defaults.p_cost,
+        }
+    }
+}
+
+impl KdfConfig {
+    /// The configured parameters, rejected if Argon2 would refuse them or they fall
+    /// below the minimum memory cost.
+    pub fn params(&self) -> anyhow::Result<crate::crypto::KdfParams> {
+        let params = crate::crypto::KdfParams {
+            m_cost: self.m_cost,
+            t_cost: self.t_cost,
+            p_cost: self.p_cost,
+        };
+        params
+            .validate()
+            .context("invalid [kdf] settings in cclink.toml")?;
+        Ok(params)
+    }
+}
+
+/// Shell commands run around publish and pickup. Unset hooks are skipped.
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+    /// Before a session is published; failing stops the publish.
+    pub pre_publish: Option<String>,
+    /// After the handoff is on the DHT.
+    pub post_publish: Option<String>,
+    /// After a handoff is fetched and decrypted, before the project directory is
+    /// checked; failing stops the pickup.
+    pub pre_pickup: Option<String>,
+    /// Right before the agent resumes the session.
+    pub post_pickup: Option<String>,
+}
+
+/// Safety window for burn-after-read handoffs.
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct BurnConfig {
+    /// Longest a burn handoff may wait unclaimed, in seconds. Publish caps a burn
+    /// record's TTL to this and pickup refuses burn records older than it, whatever
+    /// TTL they carry.
+    #[serde(deserialize_with = "duration")]
+    pub max_age: u64,
+}
+
+impl Default for BurnConfig {
+    fn default() -> Self {
+        BurnConfig {
+            max_age: DEFAULT_BURN_MAX_AGE,
+        }
+    }
+}
+
+impl Config {
+    /// Load the config from `path`. A missing file is the default config.
+    pub fn load_from(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&raw).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Translate a published project path through `[paths]`, or `None` if no entry
+    /// matches. The longest matching prefix wins; prefixes only match whole path
+    /// components, and the remainder's `/` or `\` separators become local ones.
+    pub fn map_project(&self, project: &str) -> Option<PathBuf> {
+        let (from, to) = self
+            .paths
+            .iter()
+            .filter(|(from, _)| {
+                let from = from.trim_end_matches(['/', '\\']);
+                project.strip_prefix(from).is_some_and(|rest| {
+                    rest.is_empty() || rest.starts_with('/') || rest.starts_with('\\')
+                })
+            })
+            .max_by_key(|(from, _)| from.trim_end_matches(['/', '\\']).len())?;
+        let rest = &project[from.trim_end_matches(['/', '\\']).len()..];
+        let mut local = PathBuf::from(to);
+        local.extend(rest.split(['/', '\\']).filter(|part| !part.is_empty()));
+        Some(local)
+    }
+}
+
+/// Load `~/.pubky/cclink.toml`.
+pub fn load() -> anyhow::Result<Config> {
+    Config::load_from(&config_path()?)
+}
+
+/// Accept a duration as integer seconds or as a string like `"30m"`.
+fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Secs(u64),
+        Text(String),
+    }
+    match Raw::deserialize(deserializer)? {
+        Raw::Secs(secs) => Ok(secs),
+        Raw::Text(text) => crate::util::parse_duration(&text).map_err(serde::de::Error::custom),
+    }
+}
+
+/// [`duration`] for an optional setting.
+fn optional_duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    duration(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_file_is_default() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = Config::load_from(&dir.path().join("cclink.toml")).unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(config.burn.max_age, DEFAULT_BURN_MAX_AGE);
+    }
+
+    #[test]
+    fn test_burn_max_age_accepts_seconds_and_durations() {
+        let config: Config = toml::from_str("[burn]\nmax_age = \"30m\"").unwrap();
+        assert_eq!(config.burn.max_age, 1800);
+        let config: Config = toml::from_str("[burn]\nmax_age = 90").unwrap();
+        assert_eq!(config.burn.max_age, 90);
+    }
+
+    #[test]
+    fn test_transport_dir() {
+        assert_eq!(Config::default().transport.dir, None);
+        let config: Config = toml::from_str("[transport]\ndir = \"/srv/sync/cclink\"").unwrap();
+        assert_eq!(
+            config.transport.dir,
+            Some(PathBuf::from("/srv/sync/cclink"))
+        );
+        assert!(toml::from_str::<Config>("[transport]\nurl = \"https://example.com\"").is_err());
+    }
+
+    #[test]
+    fn test_network_settings() {
+        assert_eq!(Config::default().network.timeout, None);
+        let config: Config = toml::from_str(
+            "[network]\ntimeout = \"10s\"\nmax_retries = 0\nmin_backoff = 1\nmax_backoff = \"1m\"",
+        )
+        .unwrap();
+        assert_eq!(config.network.timeout, Some(10));
+        assert_eq!(config.network.max_retries, 0);
+        assert_eq!(config.network.min_backoff, 1);
+        assert_eq!(config.network.max_backoff, 60);
+        assert_eq!(
+            config.network.max_response,
+            crate::transport::DEFAULT_MAX_RESPONSE
+        );
+        assert!(toml::from_str::<Config>("[network]\nproxy = \"socks5://x\"").is_err());
+    }
+
+    #[test]
+    fn test_kdf_defaults_and_validation() {
+        let config = Config::default();
+        assert_eq!(
+            config.kdf.params().unwrap(),
+            crate::crypto::KdfParams::default()
+        );
+        let config: Config = toml::from_str("[kdf]\nm_cost = 262144").unwrap();
+        assert_eq!(config.kdf.params().unwrap().m_cost, 262144);
+        assert_eq!(config.kdf.t_cost, 3);
+        let config: Config = toml::from_str("[kdf]\nm_cost = 1024").unwrap();
+        assert!(config.kdf.params().is_err());
+    }
+
+    #[test]
+    fn test_map_project_uses_longest_component_prefix() {
+        let config: Config = toml::from_str(
+            r#"
+            [paths]
+            "/Users/me/code" = "/home/me/code"
+            "/Users/me/code/work/" = "/srv/work"
+            'C:\Users\me' = "/home/me"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.map_project("/Users/me/code/app"),
+            Some(PathBuf::from("/home/me/code/app"))
+        );
+        assert_eq!(
+            config.map_project("/Users/me/code/work/api"),
+            Some(PathBuf::from("/srv/work/api"))
+        );
+        assert_eq!(
+            config.map_project("/Users/me/code"),
+            Some(PathBuf::from("/home/me/code"))
+        );
+        assert_eq!(
+            config.map_project(r"C:\Users\me\src\app"),
+            Some(PathBuf::from("/home/me/src/app"))
+        );
+        // Not on a component boundary, or no entry at all.
+        assert_eq!(config.map_project("/Users/me/codebase"), None);
+        assert_eq!(config.map_project("/opt/app"), None);
+    }
+
+    #[test]
+    fn test_hooks() {
+        let config: Config =
+            toml::from_str("[hooks]\npre_pickup = \"unison -batch code\"").unwrap();
+        assert_eq!(
+            config.hooks.pre_pickup.as_deref(),
+            Some("unison -batch code")
+        );
+        assert_eq!(config.hooks.post_publish, None);
+        assert!(toml::from_str::<Config>("[hooks]\npre_pikcup = \"true\"").is_err());
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        assert!(toml::from_str::<Config>("[burn]\nmax_agee = 90").is_err());
+        assert!(toml::from_str::<Config>("[brun]\nmax_age = 90").is_err());
+    }
+}
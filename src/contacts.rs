@@ -0,0 +1,154 @@
+//! Contacts: a local alias book mapping short names to z32 public keys.
+//!
+//! Stored as JSON in `~/.pubky/cclink_contacts.json`. Anywhere cclink accepts a
+//! public key from the user (`--share`, `pickup <PUBKEY>`), a contact name can be
+//! given instead and is resolved through [`resolve`].
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+/// Path of the contacts file inside the key directory.
+pub fn contacts_path() -> anyhow::Result<PathBuf> {
+    Ok(crate::keys::store::key_dir()?.join("cclink_contacts.json"))
+}
+
+/// Name → z32 public key, kept sorted by name.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ContactBook {
+    contacts: BTreeMap<String, String>,
+}
+
+impl ContactBook {
+    /// Load the contact book from `path`. A missing file is an empty book.
+    pub fn load_from(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read contacts from {}", path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("Failed to parse contacts in {}", path.display()))
+    }
+
+    /// Write the contact book to `path` (temp file + rename).
+    pub fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        crate::util::write_atomic(path, json)
+    }
+
+    /// Add or replace a contact. Returns the previous key if the name existed.
+    ///
+    /// The key is validated and normalized to bare z32 (a `pk:` prefix is accepted).
+    pub fn add(&mut self, name: &str, pubkey: &str) -> anyhow::Result<Option<String>> {
+        validate_name(name)?;
+        let z32 = pkarr::PublicKey::try_from(pubkey)
+            .map_err(|e| anyhow::anyhow!("invalid public key '{}': {}", pubkey, e))?
+            .to_z32();
+        Ok(self.contacts.insert(name.to_string(), z32))
+    }
+
+    /// Remove a contact. Returns its key, or `None` if the name was unknown.
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        self.contacts.remove(name)
+    }
+
+    /// Look up a contact's z32 key by name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.contacts.get(name).map(String::as_str)
+    }
+
+    /// Iterate over `(name, z32)` pairs in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.contacts.iter().map(|(n, k)| (n.as_str(), k.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contacts.is_empty()
+    }
+
+    /// Resolve a user-supplied public key or contact name to a z32 key.
+    pub fn resolve(&self, name_or_key: &str) -> anyhow::Result<String> {
+        if let Ok(pubkey) = pkarr::PublicKey::try_from(name_or_key) {
+            return Ok(pubkey.to_z32());
+        }
+        self.get(name_or_key).map(str::to_string).ok_or_else(|| {
+            anyhow::anyhow!(
+                "'{}' is neither a public key nor a known contact (see `cclink contacts list`)",
+                name_or_key
+            )
+        })
+    }
+}
+
+/// Resolve a public key or contact name using the on-disk contact book.
+pub fn resolve(name_or_key: &str) -> anyhow::Result<String> {
+    ContactBook::load_from(&contacts_path()?)?.resolve(name_or_key)
+}
+
+/// Contact names must be non-empty, free of whitespace, and not parse as a key
+/// themselves (otherwise they could never be looked up).
+fn validate_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty() || name.chars().any(char::is_whitespace) {
+        anyhow::bail!("contact name must be non-empty and contain no whitespace");
+    }
+    if pkarr::PublicKey::try_from(name).is_ok() {
+        anyhow::bail!("contact name '{}' looks like a public key", name);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn z32(byte: u8) -> String {
+        pkarr::Keypair::from_secret_key(&[byte; 32])
+            .public_key()
+            .to_z32()
+    }
+
+    #[test]
+    fn test_contacts_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("cclink_contacts.json");
+
+        let mut book = ContactBook::load_from(&path).expect("missing file is empty");
+        assert!(book.is_empty());
+        book.add("alice", &z32(1)).unwrap();
+        book.add("bob", &format!("pk:{}", z32(2))).unwrap();
+        book.save_to(&path).unwrap();
+
+        let mut book = ContactBook::load_from(&path).unwrap();
+        let names: Vec<&str> = book.iter().map(|(n, _)| n).collect();
+        assert_eq!(names, ["alice", "bob"]);
+        assert_eq!(
+            book.get("bob"),
+            Some(z32(2).as_str()),
+            "pk: prefix stripped"
+        );
+
+        assert_eq!(book.remove("alice"), Some(z32(1)));
+        assert_eq!(book.remove("alice"), None);
+    }
+
+    #[test]
+    fn test_resolve_prefers_keys_then_names() {
+        let mut book = ContactBook::default();
+        book.add("alice", &z32(1)).unwrap();
+        assert_eq!(book.resolve("alice").unwrap(), z32(1));
+        assert_eq!(book.resolve(&z32(2)).unwrap(), z32(2));
+        assert!(book.resolve("carol").is_err());
+    }
+
+    #[test]
+    fn test_add_rejects_bad_input() {
+        let mut book = ContactBook::default();
+        assert!(book.add("alice", "not-a-key").is_err());
+        assert!(book.add("", &z32(1)).is_err());
+        assert!(book.add("two words", &z32(1)).is_err());
+        assert!(book.add(&z32(2), &z32(1)).is_err());
+    }
+}
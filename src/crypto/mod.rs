@@ -5,12 +5,14 @@
 //! bytes to avoid type conflicts between curve25519-dalek 4 (age) and
 //! curve25519-dalek 5 (pkarr).
 
+pub mod shamir;
+
 use argon2::{Algorithm, Argon2, Params, Version};
 use bech32::{ToBase32, Variant};
 use hkdf::Hkdf;
 use rand::Rng;
 use sha2::Sha256;
-use std::io::Write;
+use std::io::{Read, Write};
 use zeroize::Zeroizing;
 
 // ── CCLINKEK binary envelope constants ──────────────────────────────────────
@@ -27,6 +29,36 @@ const ENVELOPE_HEADER_LEN: usize = 53;
 /// HKDF info string for key envelope derivation (distinct from cclink-pin-v1).
 const KEY_HKDF_INFO: &[u8] = b"cclink-key-v1";
 
+/// HKDF info string for deriving a handoff's receipt-slot keypair.
+const RECEIPT_HKDF_INFO: &[u8] = b"cclink-receipt-v1";
+
+/// HKDF info string for deriving a handoff's burn-counter-slot keypair.
+const BURN_HKDF_INFO: &[u8] = b"cclink-burn-v1";
+
+/// HKDF info string for deriving the file-transfer slot shared by two keys.
+const FILE_HKDF_INFO: &[u8] = b"cclink-file-v1";
+
+/// HKDF info string for deriving the keypairs of a chunked transfer's chunk slots.
+const CHUNK_HKDF_INFO: &[u8] = b"cclink-chunk-v1";
+
+/// HKDF info string for deriving the slot holding a retired key's transition record.
+const TRANSITION_HKDF_INFO: &[u8] = b"cclink-transition-v1";
+
+/// HKDF info string for deriving the slot holding a key's revocation certificate.
+const REVOCATION_HKDF_INFO: &[u8] = b"cclink-revocation-v1";
+
+/// HKDF info string for deriving the slot holding a key's device registry.
+const DEVICES_HKDF_INFO: &[u8] = b"cclink-devices-v1";
+
+/// HKDF info string for the key-verification slots (`cclink verify`).
+const VERIFY_HKDF_INFO: &[u8] = b"cclink-verify-v1";
+
+/// HKDF info string for one-time-code rendezvous slots (`--code`).
+const WORMHOLE_HKDF_INFO: &[u8] = b"cclink-wormhole-v1";
+
+/// HKDF info string for the profile slot (`cclink profile`).
+const PROFILE_HKDF_INFO: &[u8] = b"cclink-profile-v1";
+
 /// Default Argon2id memory cost (64 MB) — stored in envelope header on encryption.
 const KDF_M_COST: u32 = 65536;
 
@@ -36,6 +68,47 @@ const KDF_T_COST: u32 = 3;
 /// Default Argon2id parallelism — stored in envelope header on encryption.
 const KDF_P_COST: u32 = 1;
 
+/// Argon2id cost parameters stored in a CCLINKEK envelope header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Memory cost in KiB.
+    pub m_cost: u32,
+    /// Iteration count.
+    pub t_cost: u32,
+    /// Parallelism.
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        KdfParams {
+            m_cost: KDF_M_COST,
+            t_cost: KDF_T_COST,
+            p_cost: KDF_P_COST,
+        }
+    }
+}
+
+impl KdfParams {
+    /// Lowest memory cost accepted for new envelopes (19 MiB, the OWASP minimum).
+    pub const MIN_M_COST: u32 = 19456;
+
+    /// Check that Argon2 accepts these parameters and the memory cost is not below
+    /// [`Self::MIN_M_COST`].
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.m_cost < Self::MIN_M_COST {
+            anyhow::bail!(
+                "Argon2 m_cost {} KiB is below the minimum of {} KiB",
+                self.m_cost,
+                Self::MIN_M_COST
+            );
+        }
+        Params::new(self.m_cost, self.t_cost, self.p_cost, Some(32))
+            .map_err(|e| anyhow::anyhow!("invalid Argon2 parameters: {}", e))?;
+        Ok(())
+    }
+}
+
 /// Derive the X25519 secret scalar from an Ed25519 keypair.
 ///
 /// Uses SHA-512(seed)[0..32] via ed25519-dalek's `to_scalar_bytes()`.
@@ -101,40 +174,91 @@ pub fn recipient_from_z32(z32: &str) -> anyhow::Result<age::x25519::Recipient> {
     Ok(age_recipient(&x25519_bytes))
 }
 
-/// Encrypt plaintext with an age X25519 Recipient.
+/// Encrypt plaintext to an age recipient: cclink's derived X25519 one, or a standard
+/// age or plugin recipient.
 ///
 /// Returns the full age ciphertext including the age header (which contains
 /// the ephemeral public key). The complete blob must be stored and passed
 /// intact to `age_decrypt`. Do not strip or truncate the header.
-pub fn age_encrypt(
-    plaintext: &[u8],
-    recipient: &age::x25519::Recipient,
-) -> anyhow::Result<Vec<u8>> {
-    let encryptor =
-        age::Encryptor::with_recipients(std::iter::once(recipient as &dyn age::Recipient))
-            .expect("non-empty recipients list");
+pub fn age_encrypt(plaintext: &[u8], recipient: &dyn age::Recipient) -> anyhow::Result<Vec<u8>> {
     let mut ciphertext = vec![];
-    let mut writer = encryptor.wrap_output(&mut ciphertext)?;
-    writer.write_all(plaintext)?;
-    writer.finish()?;
+    age_encrypt_stream(plaintext, &mut ciphertext, recipient)?;
     Ok(ciphertext)
 }
 
-/// Decrypt age ciphertext with an age X25519 Identity.
+/// Decrypt age ciphertext with an age identity.
 ///
 /// Expects the full age ciphertext blob (including the age header).
 /// Returns an error if the identity does not match or the ciphertext is malformed.
-pub fn age_decrypt(ciphertext: &[u8], identity: &age::x25519::Identity) -> anyhow::Result<Vec<u8>> {
+pub fn age_decrypt(ciphertext: &[u8], identity: &dyn age::Identity) -> anyhow::Result<Vec<u8>> {
+    let mut plaintext = vec![];
+    age_decrypt_stream(ciphertext, &mut plaintext, identity)?;
+    Ok(plaintext)
+}
+
+/// Decrypt age ciphertext with whichever of `identities` it was encrypted to.
+pub fn age_decrypt_any(
+    ciphertext: &[u8],
+    identities: &[Box<dyn age::Identity>],
+) -> anyhow::Result<Vec<u8>> {
     let decryptor = age::Decryptor::new(ciphertext)
         .map_err(|e| anyhow::anyhow!("age decryptor error: {}", e))?;
     let mut reader = decryptor
-        .decrypt(std::iter::once(identity as &dyn age::Identity))
+        .decrypt(identities.iter().map(|i| i.as_ref()))
         .map_err(|e| anyhow::anyhow!("age decrypt error: {}", e))?;
     let mut plaintext = vec![];
-    std::io::Read::read_to_end(&mut reader, &mut plaintext)?;
+    reader.read_to_end(&mut plaintext)?;
     Ok(plaintext)
 }
 
+/// Encrypt everything read from `reader` to `recipient`, writing age ciphertext to `writer`.
+///
+/// Plaintext is copied through a fixed-size buffer and age seals it in 64 KiB STREAM
+/// chunks as they fill, so memory use is constant in the input size. Returns the number
+/// of plaintext bytes encrypted.
+pub fn age_encrypt_stream<R: Read, W: Write>(
+    mut reader: R,
+    writer: W,
+    recipient: &dyn age::Recipient,
+) -> anyhow::Result<u64> {
+    let encryptor = age::Encryptor::with_recipients(std::iter::once(recipient))
+        .map_err(|e| anyhow::anyhow!("age encryptor error: {}", e))?;
+    let mut stream = encryptor.wrap_output(writer)?;
+    let copied = std::io::copy(&mut reader, &mut stream)?;
+    stream.finish()?.flush()?;
+    Ok(copied)
+}
+
+/// Wrap age ciphertext from `reader` in a reader that yields the decrypted plaintext.
+///
+/// Only the header is read up front. Each 64 KiB STREAM chunk is authenticated before
+/// any of its plaintext is returned, so a truncated or tampered ciphertext surfaces as
+/// a read error rather than as silently short output.
+pub fn age_decrypt_reader<R: Read>(
+    reader: R,
+    identity: &dyn age::Identity,
+) -> anyhow::Result<impl Read> {
+    let decryptor =
+        age::Decryptor::new(reader).map_err(|e| anyhow::anyhow!("age decryptor error: {}", e))?;
+    decryptor
+        .decrypt(std::iter::once(identity))
+        .map_err(|e| anyhow::anyhow!("age decrypt error: {}", e))
+}
+
+/// Decrypt age ciphertext from `reader` into `writer` in constant memory.
+///
+/// Returns the number of plaintext bytes written.
+pub fn age_decrypt_stream<R: Read, W: Write>(
+    reader: R,
+    mut writer: W,
+    identity: &dyn age::Identity,
+) -> anyhow::Result<u64> {
+    let mut plaintext = age_decrypt_reader(reader, identity)?;
+    let copied = std::io::copy(&mut plaintext, &mut writer)?;
+    writer.flush()?;
+    Ok(copied)
+}
+
 /// Derive a 32-byte key from a PIN and 32-byte salt using Argon2id + HKDF-SHA256.
 ///
 /// Parameters: t_cost=3 (time), m_cost=65536 (64 MB memory), p_cost=1 (parallelism).
@@ -148,10 +272,15 @@ pub fn pin_derive_key(pin: &str, salt: &[u8; 32]) -> anyhow::Result<Zeroizing<[u
     let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
 
     // Hash the PIN into 32 bytes using the salt; Zeroizing ensures zeroing on drop
+    let started = std::time::Instant::now();
     let mut argon2_output = Zeroizing::new([0u8; 32]);
     argon2
         .hash_password_into(pin.as_bytes(), salt, argon2_output.as_mut())
         .map_err(|e| anyhow::anyhow!("argon2 hash error: {}", e))?;
+    tracing::debug!(
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "PIN key derivation (argon2id) complete"
+    );
 
     // Expand via HKDF-SHA256 with domain-separation info; Zeroizing ensures zeroing on drop
     let hkdf = Hkdf::<Sha256>::new(None, &*argon2_output);
@@ -203,6 +332,165 @@ pub fn pin_decrypt(ciphertext: &[u8], pin: &str, salt: &[u8; 32]) -> anyhow::Res
     age_decrypt(ciphertext, &identity)
 }
 
+/// Derive the keypair owning a handoff's receipt slot on the DHT.
+///
+/// HKDF-SHA256 over the publisher's Ed25519 seed, salted with the handoff's
+/// `created_at` and domain-separated with `"cclink-receipt-v1"`. The publisher can
+/// re-derive the slot for any of its handoffs without keeping local state; pickers
+/// learn the slot's secret from the encrypted payload so they can publish into it.
+pub fn receipt_keypair(keypair: &pkarr::Keypair, created_at: u64) -> pkarr::Keypair {
+    handoff_slot_keypair(keypair, created_at, RECEIPT_HKDF_INFO)
+}
+
+/// Derive the keypair owning a multi-use burn handoff's use counter on the DHT.
+///
+/// Derived like [`receipt_keypair`], domain-separated with `"cclink-burn-v1"`. Unlike
+/// the receipt slot, its secret never leaves the publisher's key, so only machines
+/// holding that key can spend uses.
+pub fn burn_keypair(keypair: &pkarr::Keypair, created_at: u64) -> pkarr::Keypair {
+    handoff_slot_keypair(keypair, created_at, BURN_HKDF_INFO)
+}
+
+fn handoff_slot_keypair(keypair: &pkarr::Keypair, created_at: u64, info: &[u8]) -> pkarr::Keypair {
+    let seed = Zeroizing::new(keypair.secret_key());
+    let hkdf = Hkdf::<Sha256>::new(Some(&created_at.to_be_bytes()), &*seed);
+    let mut okm = Zeroizing::new([0u8; 32]);
+    hkdf.expand(info, okm.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    pkarr::Keypair::from_secret_key(&okm)
+}
+
+/// Derive the keypair owning chunk slot `index` of a chunked transfer.
+///
+/// HKDF-SHA256 over the manifest's `first_hash`, salted with the chunk index and
+/// domain-separated with `"cclink-chunk-v1"`. Anyone holding the manifest can derive
+/// the slots — that is how pickers find the chunks. Integrity does not depend on the
+/// slot keys: every frame is checked against the hash chain of the signed manifest.
+pub fn chunk_keypair(first_hash: &[u8; 32], index: u32) -> pkarr::Keypair {
+    let hkdf = Hkdf::<Sha256>::new(Some(&index.to_be_bytes()), first_hash);
+    let mut okm = Zeroizing::new([0u8; 32]);
+    hkdf.expand(CHUNK_HKDF_INFO, okm.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    pkarr::Keypair::from_secret_key(&okm)
+}
+
+/// Derive the keypair owning the file-transfer slot from `sender` to `recipient`.
+///
+/// `own` must be one of the two parties. The slot seed is HKDF-SHA256 over the X25519
+/// shared secret of the two keys (Ed25519 converted as for age), salted with the
+/// sender's public key so that A→B and B→A use different slots. Both ends derive the
+/// same slot without exchanging anything beyond their public keys, and nobody else can.
+/// Sending to yourself is the degenerate case `sender == recipient`.
+pub fn file_slot_keypair(
+    own: &pkarr::Keypair,
+    sender: &pkarr::PublicKey,
+    recipient: &pkarr::PublicKey,
+) -> pkarr::Keypair {
+    pair_slot_keypair(own, sender, recipient, FILE_HKDF_INFO)
+}
+
+/// Derive the keypair owning the `cclink verify` slot from `sender` to `recipient`.
+///
+/// Derived like [`file_slot_keypair`], with its own info string, so only the two
+/// parties can find each other's verification offers.
+pub fn verify_slot_keypair(
+    own: &pkarr::Keypair,
+    sender: &pkarr::PublicKey,
+    recipient: &pkarr::PublicKey,
+) -> pkarr::Keypair {
+    pair_slot_keypair(own, sender, recipient, VERIFY_HKDF_INFO)
+}
+
+/// HKDF-SHA256 over the X25519 shared secret of `own` and the other party, salted
+/// with `sender`, domain-separated with `info`.
+fn pair_slot_keypair(
+    own: &pkarr::Keypair,
+    sender: &pkarr::PublicKey,
+    recipient: &pkarr::PublicKey,
+    info: &[u8],
+) -> pkarr::Keypair {
+    let peer = if own.public_key() == *sender {
+        recipient
+    } else {
+        sender
+    };
+    let secret = x25519_dalek::StaticSecret::from(*ed25519_to_x25519_secret(own));
+    let peer_point = x25519_dalek::PublicKey::from(peer.verifying_key().to_montgomery().to_bytes());
+    let shared = secret.diffie_hellman(&peer_point);
+
+    let hkdf = Hkdf::<Sha256>::new(Some(sender.as_bytes()), shared.as_bytes());
+    let mut okm = Zeroizing::new([0u8; 32]);
+    hkdf.expand(info, okm.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    pkarr::Keypair::from_secret_key(&okm)
+}
+
+/// Derive the keypair owning the transition slot of a rotated key `old`.
+///
+/// HKDF-SHA256 over the old public key, domain-separated with `"cclink-transition-v1"`.
+/// The slot is derived from public data on purpose: anyone who knew the old key can
+/// find where it points. Integrity does not depend on the slot key — the transition
+/// record inside is signed by both the old and the new key, and pickup pins the first
+/// one it follows, so a later rewrite of the slot can neither hide nor redirect it.
+pub fn transition_keypair(old: &pkarr::PublicKey) -> pkarr::Keypair {
+    public_slot_keypair(old, TRANSITION_HKDF_INFO)
+}
+
+/// Derive the keypair owning the revocation slot of `pubkey`.
+///
+/// Derived from public data like [`transition_keypair`], so a revocation certificate
+/// can be published by anyone holding it — even after the key itself is lost. The
+/// certificate inside is signed by the revoked key. The flip side: anyone can also
+/// overwrite a published certificate, hiding it from pickers that have not seen it
+/// yet. Pickers remember certificates they have seen, and the copy that
+/// `publish-revocation` puts into the revoked key's own packet only that key can replace.
+pub fn revocation_keypair(pubkey: &pkarr::PublicKey) -> pkarr::Keypair {
+    public_slot_keypair(pubkey, REVOCATION_HKDF_INFO)
+}
+
+/// Derive the keypair owning the profile slot of `pubkey` (`cclink profile`).
+///
+/// Derived from public data like [`transition_keypair`], so anyone holding `pubkey`
+/// can look up its display name. The profile inside is signed by `pubkey` itself.
+pub fn profile_keypair(pubkey: &pkarr::PublicKey) -> pkarr::Keypair {
+    public_slot_keypair(pubkey, PROFILE_HKDF_INFO)
+}
+
+/// Derive the keypair owning one side's rendezvous slot for a one-time `code`.
+///
+/// HKDF-SHA256 over the code, salted with `side` (`"publisher"` or `"picker"`) and
+/// domain-separated with `"cclink-wormhole-v1"`. Whoever holds the code can derive
+/// both slots; the SPAKE2 messages inside reveal nothing about it.
+pub fn wormhole_keypair(code: &str, side: &str) -> pkarr::Keypair {
+    let hkdf = Hkdf::<Sha256>::new(Some(side.as_bytes()), code.as_bytes());
+    let mut okm = Zeroizing::new([0u8; 32]);
+    hkdf.expand(WORMHOLE_HKDF_INFO, okm.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    pkarr::Keypair::from_secret_key(&okm)
+}
+
+/// Derive the keypair owning the device registry of `keypair` (`cclink device`).
+///
+/// HKDF-SHA256 over the secret key, domain-separated with `"cclink-devices-v1"`.
+/// Only machines holding the key can find or update the registry.
+pub fn devices_keypair(keypair: &pkarr::Keypair) -> pkarr::Keypair {
+    let seed = Zeroizing::new(keypair.secret_key());
+    let hkdf = Hkdf::<Sha256>::new(None, &*seed);
+    let mut okm = Zeroizing::new([0u8; 32]);
+    hkdf.expand(DEVICES_HKDF_INFO, okm.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    pkarr::Keypair::from_secret_key(&okm)
+}
+
+/// HKDF-SHA256 over a public key, domain-separated with `info`.
+fn public_slot_keypair(pubkey: &pkarr::PublicKey, info: &[u8]) -> pkarr::Keypair {
+    let hkdf = Hkdf::<Sha256>::new(None, pubkey.as_bytes());
+    let mut okm = Zeroizing::new([0u8; 32]);
+    hkdf.expand(info, okm.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    pkarr::Keypair::from_secret_key(&okm)
+}
+
 // ── CCLINKEK binary envelope functions ──────────────────────────────────────
 
 /// Derive a 32-byte key-encryption key from a passphrase and 32-byte salt using Argon2id + HKDF-SHA256.
@@ -222,10 +510,18 @@ fn key_derive_key(
     let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
 
     // Hash the passphrase into 32 bytes using the salt; Zeroizing ensures zeroing on drop
+    let started = std::time::Instant::now();
     let mut argon2_output = Zeroizing::new([0u8; 32]);
     argon2
         .hash_password_into(passphrase.as_bytes(), salt, argon2_output.as_mut())
         .map_err(|e| anyhow::anyhow!("argon2 hash error: {}", e))?;
+    tracing::debug!(
+        m_cost,
+        t_cost,
+        p_cost,
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "key envelope derivation (argon2id) complete"
+    );
 
     // Expand via HKDF-SHA256 with domain-separation info; Zeroizing ensures zeroing on drop
     let hkdf = Hkdf::<Sha256>::new(None, &*argon2_output);
@@ -253,12 +549,26 @@ fn key_derive_key(
 /// 53      N     Age ciphertext (variable length)
 /// ```
 pub fn encrypt_key_envelope(seed: &[u8; 32], passphrase: &str) -> anyhow::Result<Vec<u8>> {
+    encrypt_key_envelope_with(seed, passphrase, KdfParams::default())
+}
+
+/// [`encrypt_key_envelope`] with the given Argon2 parameters instead of the defaults.
+/// They are recorded in the header, so decryption needs no configuration.
+pub fn encrypt_key_envelope_with(
+    seed: &[u8; 32],
+    passphrase: &str,
+    params: KdfParams,
+) -> anyhow::Result<Vec<u8>> {
+    params.validate()?;
+
     // Generate a fresh random 32-byte salt
     let salt: [u8; 32] = rand::thread_rng().gen();
 
-    let m_cost = KDF_M_COST;
-    let t_cost = KDF_T_COST;
-    let p_cost = KDF_P_COST;
+    let KdfParams {
+        m_cost,
+        t_cost,
+        p_cost,
+    } = params;
 
     // Derive the key-encryption key from passphrase + salt
     let kek = key_derive_key(passphrase, &salt, m_cost, t_cost, p_cost)?;
@@ -283,19 +593,25 @@ pub fn encrypt_key_envelope(seed: &[u8; 32], passphrase: &str) -> anyhow::Result
     Ok(envelope)
 }
 
-/// Decrypt a CCLINKEK binary envelope back to the original 32-byte Ed25519 seed.
-///
-/// Validates the magic header and version byte, decodes Argon2 parameters from the
-/// envelope header (NOT from hardcoded constants — enables forward compatibility),
-/// re-derives the key-encryption key, and decrypts the age ciphertext.
+/// Time one key-envelope derivation with `params` on this machine, for `cclink key bench`.
+pub fn benchmark_kdf(params: KdfParams) -> anyhow::Result<std::time::Duration> {
+    let salt: [u8; 32] = rand::thread_rng().gen();
+    let started = std::time::Instant::now();
+    key_derive_key(
+        "cclink benchmark passphrase",
+        &salt,
+        params.m_cost,
+        params.t_cost,
+        params.p_cost,
+    )?;
+    Ok(started.elapsed())
+}
+
+/// Validate a CCLINKEK envelope header and return the Argon2 parameters it declares.
 ///
-/// Returns a clear error (not a panic) when the passphrase is wrong or the envelope
-/// is malformed. The recovered seed is wrapped in `Zeroizing<[u8;32]>` for automatic
-/// zeroing on drop.
-pub fn decrypt_key_envelope(
-    envelope: &[u8],
-    passphrase: &str,
-) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+/// Checks length, magic bytes, and version without deriving any key material, so it
+/// is cheap enough for diagnostics (`cclink doctor`).
+pub fn key_envelope_params(envelope: &[u8]) -> anyhow::Result<KdfParams> {
     // Validate minimum length (full fixed header must be present)
     if envelope.len() < ENVELOPE_HEADER_LEN {
         anyhow::bail!(
@@ -315,11 +631,33 @@ pub fn decrypt_key_envelope(
         anyhow::bail!("Unsupported key envelope version: {}", envelope[8]);
     }
 
-    // Decode Argon2 params from header bytes (NOT from constants — forward compat)
     // Safety: unwrap is safe here because length check above guarantees bytes exist
-    let m_cost = u32::from_be_bytes(envelope[9..13].try_into().unwrap());
-    let t_cost = u32::from_be_bytes(envelope[13..17].try_into().unwrap());
-    let p_cost = u32::from_be_bytes(envelope[17..21].try_into().unwrap());
+    Ok(KdfParams {
+        m_cost: u32::from_be_bytes(envelope[9..13].try_into().unwrap()),
+        t_cost: u32::from_be_bytes(envelope[13..17].try_into().unwrap()),
+        p_cost: u32::from_be_bytes(envelope[17..21].try_into().unwrap()),
+    })
+}
+
+/// Decrypt a CCLINKEK binary envelope back to the original 32-byte Ed25519 seed.
+///
+/// Validates the magic header and version byte, decodes Argon2 parameters from the
+/// envelope header (NOT from hardcoded constants — enables forward compatibility),
+/// re-derives the key-encryption key, and decrypts the age ciphertext.
+///
+/// Returns a clear error (not a panic) when the passphrase is wrong or the envelope
+/// is malformed. The recovered seed is wrapped in `Zeroizing<[u8;32]>` for automatic
+/// zeroing on drop.
+pub fn decrypt_key_envelope(
+    envelope: &[u8],
+    passphrase: &str,
+) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+    // Validate the header and decode Argon2 params (NOT from constants — forward compat)
+    let KdfParams {
+        m_cost,
+        t_cost,
+        p_cost,
+    } = key_envelope_params(envelope)?;
 
     // Extract 32-byte salt from header
     // Safety: unwrap is safe here because length check above guarantees bytes exist
@@ -443,6 +781,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_age_stream_round_trip_spans_chunks() {
+        let keypair = fixed_keypair();
+        let identity = age_identity(&ed25519_to_x25519_secret(&keypair));
+        let recipient = age_recipient(&ed25519_to_x25519_public(&keypair));
+
+        // Several 64 KiB age STREAM chunks plus a partial one.
+        let plaintext: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        let mut ciphertext = Vec::new();
+        let written = age_encrypt_stream(&plaintext[..], &mut ciphertext, &recipient).unwrap();
+        assert_eq!(written, plaintext.len() as u64);
+        assert_eq!(age_decrypt(&ciphertext, &identity).unwrap(), plaintext);
+
+        let mut decrypted = Vec::new();
+        age_decrypt_stream(&ciphertext[..], &mut decrypted, &identity).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn test_age_stream_truncated_ciphertext_fails() {
+        let keypair = fixed_keypair();
+        let identity = age_identity(&ed25519_to_x25519_secret(&keypair));
+        let recipient = age_recipient(&ed25519_to_x25519_public(&keypair));
+
+        let ciphertext = age_encrypt(&[7u8; 100_000], &recipient).unwrap();
+        let truncated = &ciphertext[..ciphertext.len() - 100];
+        assert!(age_decrypt_stream(truncated, std::io::sink(), &identity).is_err());
+    }
+
     #[test]
     fn test_recipient_from_z32_round_trip() {
         // Create a keypair, derive z32 pubkey, convert to age Recipient
@@ -573,6 +940,104 @@ mod tests {
         );
     }
 
+    // ── Receipt slot tests ───────────────────────────────────────────────────
+
+    #[test]
+    fn test_receipt_keypair_deterministic_per_handoff() {
+        let keypair = fixed_keypair();
+        let a = receipt_keypair(&keypair, 1_700_000_000);
+        let b = receipt_keypair(&keypair, 1_700_000_000);
+        let c = receipt_keypair(&keypair, 1_700_000_001);
+        assert_eq!(a.public_key(), b.public_key(), "same handoff, same slot");
+        assert_ne!(
+            a.public_key(),
+            c.public_key(),
+            "different handoff, new slot"
+        );
+        assert_ne!(
+            a.public_key(),
+            keypair.public_key(),
+            "slot must not be the publisher's own key"
+        );
+    }
+
+    #[test]
+    fn test_burn_slot_differs_from_receipt_slot() {
+        let keypair = fixed_keypair();
+        assert_ne!(
+            burn_keypair(&keypair, 1_700_000_000).public_key(),
+            receipt_keypair(&keypair, 1_700_000_000).public_key(),
+            "pickers know the receipt slot's secret; they must not learn the counter's"
+        );
+    }
+
+    #[test]
+    fn test_transition_keypair_is_a_slot_of_the_old_key() {
+        let old = fixed_keypair().public_key();
+        assert_eq!(
+            transition_keypair(&old).public_key(),
+            transition_keypair(&old).public_key()
+        );
+        assert_ne!(transition_keypair(&old).public_key(), old);
+        assert_ne!(
+            transition_keypair(&old).public_key(),
+            revocation_keypair(&old).public_key()
+        );
+        assert_ne!(
+            transition_keypair(&old).public_key(),
+            transition_keypair(&pkarr::Keypair::random().public_key()).public_key()
+        );
+    }
+
+    #[test]
+    fn test_devices_keypair_is_private_to_the_key() {
+        let keypair = fixed_keypair();
+        assert_eq!(
+            devices_keypair(&keypair).public_key(),
+            devices_keypair(&keypair).public_key()
+        );
+        assert_ne!(devices_keypair(&keypair).public_key(), keypair.public_key());
+        assert_ne!(
+            devices_keypair(&keypair).public_key(),
+            devices_keypair(&pkarr::Keypair::random()).public_key()
+        );
+    }
+
+    #[test]
+    fn test_chunk_keypair_distinct_per_index() {
+        let hash = [7u8; 32];
+        assert_eq!(
+            chunk_keypair(&hash, 0).public_key(),
+            chunk_keypair(&hash, 0).public_key()
+        );
+        assert_ne!(
+            chunk_keypair(&hash, 0).public_key(),
+            chunk_keypair(&hash, 1).public_key()
+        );
+        assert_ne!(
+            chunk_keypair(&hash, 0).public_key(),
+            chunk_keypair(&[8u8; 32], 0).public_key()
+        );
+    }
+
+    #[test]
+    fn test_file_slot_agreed_by_both_parties() {
+        let alice = fixed_keypair();
+        let bob = pkarr::Keypair::from_secret_key(&[7u8; 32]);
+        let (a, b) = (alice.public_key(), bob.public_key());
+
+        let alice_view = file_slot_keypair(&alice, &a, &b);
+        let bob_view = file_slot_keypair(&bob, &a, &b);
+        assert_eq!(alice_view.public_key(), bob_view.public_key());
+
+        let reverse = file_slot_keypair(&bob, &b, &a);
+        assert_ne!(
+            alice_view.public_key(),
+            reverse.public_key(),
+            "each direction has its own slot"
+        );
+    }
+
     // ── Key envelope tests ───────────────────────────────────────────────────
 
     #[test]
@@ -610,6 +1075,44 @@ mod tests {
         assert_eq!(p_cost, 1, "p_cost must be 1 in header");
     }
 
+    #[test]
+    fn test_key_envelope_params_reads_header() {
+        let blob = encrypt_key_envelope(&[1u8; 32], "test").expect("encrypt should succeed");
+        let params = key_envelope_params(&blob).expect("header should parse");
+        assert_eq!(
+            params,
+            KdfParams {
+                m_cost: 65536,
+                t_cost: 3,
+                p_cost: 1
+            }
+        );
+        assert!(key_envelope_params(&blob[..52]).is_err());
+    }
+
+    #[test]
+    fn test_key_envelope_custom_params_round_trip() {
+        let params = KdfParams {
+            m_cost: KdfParams::MIN_M_COST,
+            t_cost: 2,
+            p_cost: 2,
+        };
+        let blob = encrypt_key_envelope_with(&[9u8; 32], "pass", params).unwrap();
+        assert_eq!(key_envelope_params(&blob).unwrap(), params);
+        assert_eq!(*decrypt_key_envelope(&blob, "pass").unwrap(), [9u8; 32]);
+
+        let weak = KdfParams {
+            m_cost: 1024,
+            ..params
+        };
+        assert!(encrypt_key_envelope_with(&[9u8; 32], "pass", weak).is_err());
+        let zero_passes = KdfParams {
+            t_cost: 0,
+            ..params
+        };
+        assert!(zero_passes.validate().is_err());
+    }
+
     #[test]
     fn test_key_envelope_wrong_passphrase() {
         let seed = [42u8; 32];
@@ -0,0 +1,276 @@
+//! Shamir secret sharing over GF(256), and the printable share format used by
+//! `cclink key split` / `cclink key combine`.
+//!
+//! Each byte of the secret is the constant term of its own random polynomial of
+//! degree `threshold - 1`; share `x` holds every polynomial evaluated at `x`. Any
+//! `threshold` shares recover the secret by Lagrange interpolation at 0, while fewer
+//! reveal nothing about it. Field arithmetic uses the AES polynomial (0x11b) and is
+//! branch-free, so timing does not depend on secret bytes.
+//!
+//! ```text
+//! Share format (version 1)
+//! cclink-share1-<threshold>-<index>-<set>-<data>-<check>
+//!   threshold  decimal, 2..=255
+//!   index      decimal x-coordinate, 1..=255
+//!   set        8 hex: first 4 bytes of SHA-256 of the public key the secret belongs to
+//!   data       hex share bytes
+//!   check      8 hex: first 4 bytes of SHA-256 of everything before "-<check>"
+//! ```
+//!
+//! The version lives in the prefix, so a future format is rejected by name instead
+//! of being misparsed. The set id lets `combine` refuse to mix shares of different
+//! keys and confirm the reconstructed key is the one that was split.
+
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+/// Prefix of a version-1 share.
+const SHARE_PREFIX_V1: &str = "cclink-share1-";
+
+/// Prefix shared by all share versions.
+const SHARE_PREFIX: &str = "cclink-share";
+
+/// One share of a split secret.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Share {
+    /// Number of shares needed to reconstruct.
+    pub threshold: u8,
+    /// x-coordinate of this share (1-based; 0 would be the secret itself).
+    pub index: u8,
+    /// Identifies the key the shares belong to (see [`set_id`]).
+    pub set: [u8; 4],
+    /// Share bytes, one per secret byte.
+    pub data: Zeroizing<Vec<u8>>,
+}
+
+/// Set id for the key `pubkey`: the first 4 bytes of SHA-256 of its bytes.
+pub fn set_id(pubkey: &pkarr::PublicKey) -> [u8; 4] {
+    let digest = Sha256::digest(pubkey.as_bytes());
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+/// Split `secret` into `shares` shares, any `threshold` of which reconstruct it.
+pub fn split(secret: &[u8], threshold: u8, shares: u8, set: [u8; 4]) -> anyhow::Result<Vec<Share>> {
+    if threshold < 2 {
+        anyhow::bail!("threshold must be at least 2");
+    }
+    if shares < threshold {
+        anyhow::bail!(
+            "cannot make {} shares with a threshold of {}",
+            shares,
+            threshold
+        );
+    }
+
+    let mut rng = rand::rngs::OsRng;
+    let mut out: Vec<Share> = (1..=shares)
+        .map(|index| Share {
+            threshold,
+            index,
+            set,
+            data: Zeroizing::new(Vec::with_capacity(secret.len())),
+        })
+        .collect();
+
+    let mut coefficients = Zeroizing::new(vec![0u8; threshold as usize]);
+    for &byte in secret {
+        coefficients[0] = byte;
+        rng.fill_bytes(&mut coefficients[1..]);
+        for share in &mut out {
+            share.data.push(eval(&coefficients, share.index));
+        }
+    }
+    Ok(out)
+}
+
+/// Reconstruct the secret from at least `threshold` shares of the same set.
+pub fn combine(shares: &[Share]) -> anyhow::Result<Zeroizing<Vec<u8>>> {
+    let Some(first) = shares.first() else {
+        anyhow::bail!("no shares given");
+    };
+    if shares
+        .iter()
+        .any(|s| s.set != first.set || s.threshold != first.threshold)
+    {
+        anyhow::bail!("shares come from different splits");
+    }
+    if shares.iter().any(|s| s.data.len() != first.data.len()) {
+        anyhow::bail!("shares have different lengths");
+    }
+    let mut indices: Vec<u8> = shares.iter().map(|s| s.index).collect();
+    indices.sort_unstable();
+    indices.dedup();
+    if indices.len() != shares.len() {
+        anyhow::bail!("the same share was given twice");
+    }
+    if shares.len() < first.threshold as usize {
+        anyhow::bail!(
+            "{} shares given; {} are needed",
+            shares.len(),
+            first.threshold
+        );
+    }
+
+    let used = &shares[..first.threshold as usize];
+    let mut secret = Zeroizing::new(vec![0u8; first.data.len()]);
+    for (i, share) in used.iter().enumerate() {
+        // Lagrange basis polynomial for this share, evaluated at 0.
+        let mut basis = 1u8;
+        for (j, other) in used.iter().enumerate() {
+            if i != j {
+                basis = mul(basis, div(other.index, other.index ^ share.index));
+            }
+        }
+        for (out, &y) in secret.iter_mut().zip(share.data.iter()) {
+            *out ^= mul(basis, y);
+        }
+    }
+    Ok(secret)
+}
+
+impl Share {
+    /// Printable form of the share (see the module docs).
+    pub fn encode(&self) -> String {
+        let body = format!(
+            "{}{}-{}-{}-{}",
+            SHARE_PREFIX_V1,
+            self.threshold,
+            self.index,
+            crate::util::to_hex(&self.set),
+            crate::util::to_hex(&self.data)
+        );
+        let check = checksum(&body);
+        format!("{}-{}", body, crate::util::to_hex(&check))
+    }
+
+    /// Parse a printed share, verifying its checksum.
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let text = text.trim();
+        let Some(rest) = text.strip_prefix(SHARE_PREFIX_V1) else {
+            if let Some(version) = text
+                .strip_prefix(SHARE_PREFIX)
+                .and_then(|r| r.split('-').next())
+            {
+                anyhow::bail!("unsupported share format version '{}'", version);
+            }
+            anyhow::bail!("not a cclink share");
+        };
+        let (body, check) = text
+            .rsplit_once('-')
+            .ok_or_else(|| anyhow::anyhow!("share is truncated"))?;
+        if crate::util::from_hex(check).ok().as_deref() != Some(&checksum(body)[..]) {
+            anyhow::bail!("share checksum mismatch — check for typos");
+        }
+
+        let fields: Vec<&str> = rest.split('-').collect();
+        let [threshold, index, set, data, _check] = fields[..] else {
+            anyhow::bail!("share is malformed");
+        };
+        let threshold: u8 = threshold
+            .parse()
+            .map_err(|_| anyhow::anyhow!("invalid share threshold '{}'", threshold))?;
+        let index: u8 = index
+            .parse()
+            .map_err(|_| anyhow::anyhow!("invalid share index '{}'", index))?;
+        if threshold < 2 || index == 0 {
+            anyhow::bail!("share is malformed");
+        }
+        let set: [u8; 4] = crate::util::from_hex(set)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("invalid share set id"))?;
+        Ok(Share {
+            threshold,
+            index,
+            set,
+            data: Zeroizing::new(crate::util::from_hex(data)?),
+        })
+    }
+}
+
+/// Evaluate the polynomial with `coefficients` (constant term first) at `x`.
+fn eval(coefficients: &[u8], x: u8) -> u8 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0u8, |acc, &c| mul(acc, x) ^ c)
+}
+
+/// GF(256) multiplication, reducing by x^8 + x^4 + x^3 + x + 1.
+fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = 0u8.wrapping_sub(a >> 7);
+        a = (a << 1) ^ (0x1b & carry);
+        b >>= 1;
+    }
+    product
+}
+
+/// GF(256) division; `b` must be non-zero. Uses b^254 = b^-1.
+fn div(a: u8, b: u8) -> u8 {
+    let mut inverse = 1u8;
+    let mut power = b;
+    let mut exponent = 254u8;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            inverse = mul(inverse, power);
+        }
+        power = mul(power, power);
+        exponent >>= 1;
+    }
+    mul(a, inverse)
+}
+
+fn checksum(text: &str) -> [u8; 4] {
+    let digest = Sha256::digest(text.as_bytes());
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: [u8; 32] = [0x5a; 32];
+
+    #[test]
+    fn test_gf256_inverse() {
+        for b in 1..=255u8 {
+            assert_eq!(mul(b, div(1, b)), 1, "inverse of {}", b);
+        }
+    }
+
+    #[test]
+    fn test_any_threshold_subset_recovers_secret() {
+        let shares = split(&SECRET, 2, 3, [1, 2, 3, 4]).unwrap();
+        for pair in [[0, 1], [0, 2], [2, 1]] {
+            let subset: Vec<Share> = pair.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(&combine(&subset).unwrap()[..], &SECRET[..]);
+        }
+    }
+
+    #[test]
+    fn test_below_threshold_rejected() {
+        let shares = split(&SECRET, 3, 5, [0; 4]).unwrap();
+        assert!(combine(&shares[..2]).is_err());
+    }
+
+    #[test]
+    fn test_encode_parse_round_trip() {
+        let shares = split(&SECRET, 2, 3, [0xde, 0xad, 0xbe, 0xef]).unwrap();
+        let text = shares[1].encode();
+        assert!(text.starts_with("cclink-share1-2-2-deadbeef-"));
+        assert_eq!(Share::parse(&text).unwrap(), shares[1]);
+    }
+
+    #[test]
+    fn test_parse_rejects_typos_and_unknown_versions() {
+        let text = split(&SECRET, 2, 2, [0; 4]).unwrap()[0].encode();
+        let typo = text.replacen("-2-1-", "-2-2-", 1);
+        assert!(Share::parse(&typo).is_err());
+
+        let err = Share::parse(&text.replacen("share1", "share2", 1)).unwrap_err();
+        assert!(err.to_string().contains("version '2'"));
+    }
+}
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::ui::Paint;
+
 #[derive(Error, Debug)]
 pub enum CclinkError {
     #[error("No keypair found. Run `cclink init` first.")]
@@ -19,4 +21,478 @@ pub enum CclinkError {
 
     #[error("Record not found")]
     RecordNotFound,
+
+    #[error("Chunk {0} failed verification")]
+    ChunkVerificationFailed(u32),
+
+    #[error("Public key for {0} changed since it was first trusted")]
+    TrustedKeyChanged(String),
+
+    #[error("This handoff expired {0} ago. Publish a new one with cclink.")]
+    HandoffExpired(String),
+
+    #[error("Incorrect PIN — decryption failed")]
+    IncorrectPin,
+
+    #[error("Session data not found locally")]
+    SessionDataMissing,
+
+    #[error("DHT publish failed: {0}")]
+    DhtPublishFailed(String),
+
+    #[error("Handoff is {size} bytes; the DHT budget is {limit} bytes")]
+    RecordTooLarge { size: usize, limit: usize },
+
+    #[error("This burn-after-read handoff is {age} old, past the {limit} burn safety window")]
+    BurnWindowExceeded { age: String, limit: String },
+
+    #[error("The {event} hook failed ({status})")]
+    HookFailed { event: &'static str, status: String },
+
+    #[error("The publisher revoked key {pubkey} on {at}")]
+    KeyRevoked { pubkey: String, at: String },
+
+    #[error(
+        "Handoff record uses schema version {found}; this cclink reads up to version {supported}"
+    )]
+    UnsupportedRecordVersion { found: u32, supported: u32 },
+
+    #[error("This handoff was created {got}, before the newest one already seen from this publisher ({seen})")]
+    HandoffRolledBack { got: String, seen: String },
+
+    #[error("Refusing a {size}-byte response; the limit is {limit} bytes")]
+    ResponseTooLarge { size: u64, limit: u64 },
+
+    #[error("This handoff was created {0} in the future by this machine's clock")]
+    HandoffFromFuture(String),
+
+    #[error("Network error: {0}")]
+    NetworkError(String),
+}
+
+impl CclinkError {
+    /// Stable code for `cclink explain`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CclinkError::NoKeypairFound => "E001",
+            CclinkError::AtomicWriteFailed(_) => "E002",
+            CclinkError::HomeDirNotFound => "E003",
+            CclinkError::SignatureVerificationFailed(_) => "E004",
+            CclinkError::SessionNotFound => "E005",
+            CclinkError::RecordNotFound => "E006",
+            CclinkError::ChunkVerificationFailed(_) => "E007",
+            CclinkError::TrustedKeyChanged(_) => "E008",
+            CclinkError::HandoffExpired(_) => "E009",
+            CclinkError::IncorrectPin => "E010",
+            CclinkError::SessionDataMissing => "E011",
+            CclinkError::DhtPublishFailed(_) => "E012",
+            CclinkError::RecordTooLarge { .. } => "E013",
+            CclinkError::BurnWindowExceeded { .. } => "E014",
+            CclinkError::HookFailed { .. } => "E015",
+            CclinkError::KeyRevoked { .. } => "E016",
+            CclinkError::UnsupportedRecordVersion { .. } => "E017",
+            CclinkError::HandoffRolledBack { .. } => "E018",
+            CclinkError::ResponseTooLarge { .. } => "E019",
+            CclinkError::HandoffFromFuture(_) => "E020",
+            CclinkError::NetworkError(_) => "E021",
+        }
+    }
+
+    /// Exit status of a command that fails with this error: 2 when something was
+    /// not found, 3 when a handoff expired, 4 when verification failed, 5 on a
+    /// network failure, and 1 otherwise. Scripts branch on these; keep them stable.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CclinkError::NoKeypairFound
+            | CclinkError::SessionNotFound
+            | CclinkError::RecordNotFound
+            | CclinkError::SessionDataMissing => 2,
+            CclinkError::HandoffExpired(_) | CclinkError::BurnWindowExceeded { .. } => 3,
+            CclinkError::SignatureVerificationFailed(_)
+            | CclinkError::ChunkVerificationFailed(_)
+            | CclinkError::TrustedKeyChanged(_)
+            | CclinkError::IncorrectPin
+            | CclinkError::KeyRevoked { .. }
+            | CclinkError::HandoffRolledBack { .. }
+            | CclinkError::HandoffFromFuture(_) => 4,
+            CclinkError::DhtPublishFailed(_)
+            | CclinkError::ResponseTooLarge { .. }
+            | CclinkError::NetworkError(_) => 5,
+            CclinkError::AtomicWriteFailed(_)
+            | CclinkError::HomeDirNotFound
+            | CclinkError::RecordTooLarge { .. }
+            | CclinkError::HookFailed { .. }
+            | CclinkError::UnsupportedRecordVersion { .. } => 1,
+        }
+    }
+}
+
+/// Long-form documentation for an error code, shown by `cclink explain`.
+pub struct ErrorDoc {
+    pub code: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub causes: &'static [&'static str],
+    pub remediation: &'static [&'static str],
+}
+
+/// Embedded error knowledge table, one entry per [`CclinkError::code`].
+pub const ERROR_DOCS: &[ErrorDoc] = &[
+    ErrorDoc {
+        code: "E001",
+        title: "No keypair found",
+        description: "cclink needs an Ed25519 keypair in ~/.pubky/secret_key to sign and decrypt handoffs.",
+        causes: &[
+            "cclink has never been initialized on this machine",
+            "the key file was moved or deleted",
+            "$HOME points somewhere unexpected (e.g. under sudo)",
+            "a --profile (or $CCLINK_PROFILE) that was never initialized; its key lives in ~/.pubky/profiles/<name>/",
+        ],
+        remediation: &[
+            "Run `cclink init` to generate a new keypair (`cclink --profile <name> init` for a profile)",
+            "Or copy the key from another machine: `cclink init --import <path>`",
+        ],
+    },
+    ErrorDoc {
+        code: "E002",
+        title: "Key file write failed",
+        description: "Writing the key file (temp file + rename) did not complete.",
+        causes: &[
+            "~/.pubky is not writable",
+            "the disk is full",
+            "~/.pubky is on a filesystem that does not support rename",
+        ],
+        remediation: &[
+            "Check ownership and permissions of ~/.pubky",
+            "Free disk space and run the command again",
+        ],
+    },
+    ErrorDoc {
+        code: "E003",
+        title: "Home directory not found",
+        description: "cclink stores keys under ~/.pubky and reads sessions from ~/.claude, so it needs a home directory.",
+        causes: &["$HOME is unset (common in minimal containers and some service managers)"],
+        remediation: &["Set $HOME to the user's home directory"],
+    },
+    ErrorDoc {
+        code: "E004",
+        title: "Signature verification failed",
+        description: "The inner Ed25519 signature of a record or receipt does not match its contents and claimed key.",
+        causes: &[
+            "the record was tampered with",
+            "the record was written by an incompatible cclink version",
+        ],
+        remediation: &[
+            "Do not trust the record; ask the publisher to publish again",
+            "Run `cclink inspect <pubkey>` to see the envelope",
+        ],
+    },
+    ErrorDoc {
+        code: "E005",
+        title: "No Claude Code session found",
+        description: "Publish looks for session files under ~/.claude/projects/ belonging to the current directory.",
+        causes: &[
+            "no `claude` session has been started in this directory",
+            "you are in a different directory than the session's project",
+        ],
+        remediation: &[
+            "Start a session with `claude` first",
+            "Or pass the session ID explicitly: `cclink <session-id>`",
+        ],
+    },
+    ErrorDoc {
+        code: "E006",
+        title: "Record not found",
+        description: "No handoff record exists on the DHT for the requested public key.",
+        causes: &[
+            "pickup used a different key than the publisher (run `cclink whoami` on both machines)",
+            "the record expired, was revoked, or burned after pickup",
+            "DHT propagation has not completed yet",
+        ],
+        remediation: &[
+            "Check the public key, or use --share / --pin when machines have different keys",
+            "Retry after a few seconds",
+            "Publish again with `cclink`",
+        ],
+    },
+    ErrorDoc {
+        code: "E007",
+        title: "Chunk verification failed",
+        description: "A chunk of a chunked transfer did not match the hash chain committed to by its signed manifest.",
+        causes: &["the chunk was corrupted or replaced in transit or storage"],
+        remediation: &["Retry the transfer; if it keeps failing, ask the publisher to publish again"],
+    },
+    ErrorDoc {
+        code: "E008",
+        title: "Trusted key changed",
+        description: "A pickup under a name resolved to a different public key than the one pinned the first time, or a retired key's transition names a different successor than the one this machine followed first.",
+        causes: &[
+            "the contact's key was updated with `cclink contacts add`",
+            "the publisher regenerated their key",
+            "someone is impersonating the publisher, e.g. with a stolen retired key",
+        ],
+        remediation: &[
+            "Confirm the new key with the publisher out of band",
+            "If expected, run `cclink trust remove <name>` and pick up again",
+            "For a changed transition, pick up from the confirmed new key directly",
+        ],
+    },
+    ErrorDoc {
+        code: "E009",
+        title: "Handoff expired",
+        description: "The record's TTL has passed, so pickup refuses to use it.",
+        causes: &["the handoff was published longer ago than its --ttl"],
+        remediation: &["Publish a new handoff, optionally with a longer --ttl (e.g. `--ttl 7d`)"],
+    },
+    ErrorDoc {
+        code: "E010",
+        title: "Incorrect PIN",
+        description: "The PIN entered at pickup did not decrypt the handoff.",
+        causes: &["a typo in the PIN", "the handoff was re-published with a different PIN"],
+        remediation: &["Run pickup again and re-enter the PIN", "Ask the publisher for the current PIN"],
+    },
+    ErrorDoc {
+        code: "E011",
+        title: "Session data missing locally",
+        description: "`claude --resume` needs the session's JSONL file in ~/.claude/projects/ on this machine.",
+        causes: &["~/.claude is not shared or synced between the machines"],
+        remediation: &[
+            "Sync ~/.claude/ between machines (Syncthing, rsync, NFS, a shared filesystem over Tailscale/SSH)",
+        ],
+    },
+    ErrorDoc {
+        code: "E012",
+        title: "DHT publish failed",
+        description: "The signed packet could not be stored on the Mainline DHT.",
+        causes: &[
+            "outbound UDP is blocked on this network",
+            "another publish from the same key raced this one",
+        ],
+        remediation: &[
+            "Run `cclink doctor` to check DHT connectivity",
+            "Retry; if publishing from two machines, wait for the other to finish",
+        ],
+    },
+    ErrorDoc {
+        code: "E013",
+        title: "Record too large",
+        description: "A handoff record must fit in a single 1000-byte PKARR packet (at most 912 bytes of JSON). Larger ciphertext is split into chunks, up to 64 chunks of 600 bytes.",
+        causes: &[
+            "a file passed to `cclink send` that is still over the chunk limit after compression",
+            "a very long project path or hostname in the encrypted payload",
+        ],
+        remediation: &[
+            "Send a smaller file, or compress it first",
+            "Publish from a shorter project path",
+        ],
+    },
+    ErrorDoc {
+        code: "E014",
+        title: "Burn safety window exceeded",
+        description: "Burn-after-read handoffs may only wait unclaimed for a short window (1 hour by default), whatever TTL they carry. Pickup refuses older ones.",
+        causes: &[
+            "the burn handoff was published more than burn.max_age ago",
+            "the publisher used a longer burn.max_age than this machine allows",
+        ],
+        remediation: &[
+            "Ask the publisher for a fresh `cclink --burn` handoff",
+            "Or raise the window on this machine: `[burn] max_age = \"2h\"` in ~/.pubky/cclink.toml",
+        ],
+    },
+    ErrorDoc {
+        code: "E015",
+        title: "Hook failed",
+        description: "A pre-publish or pre-pickup hook from [hooks] in ~/.pubky/cclink.toml exited unsuccessfully, so cclink stopped before publishing or resuming. Post hooks only warn.",
+        causes: &[
+            "the hook command itself failed (a sync, VPN, or mount step that did not succeed)",
+            "the command is not installed or not on PATH for a non-interactive shell",
+        ],
+        remediation: &[
+            "Run the hook command by hand to see its error",
+            "Fix or remove the hook in the [hooks] section of ~/.pubky/cclink.toml",
+        ],
+    },
+    ErrorDoc {
+        code: "E016",
+        title: "Publisher key revoked",
+        description: "The publisher published a revocation certificate for the key, declaring it compromised or retired. Pickup refuses anything signed by a revoked key, and this machine keeps refusing it once it has seen the certificate.",
+        causes: &[
+            "the publisher's key was lost or stolen and they revoked it with `cclink key publish-revocation`",
+            "someone holding the publisher's revocation certificate published it",
+        ],
+        remediation: &[
+            "Do not trust handoffs from this key; ask the publisher for their new key out of band",
+            "Then update the contact (`cclink contacts add`) and drop the old pin (`cclink trust remove`)",
+        ],
+    },
+    ErrorDoc {
+        code: "E017",
+        title: "Record from a newer cclink",
+        description: "The handoff record declares a schema version newer than this cclink understands, so it cannot be verified or decrypted here.",
+        causes: &[
+            "the publisher runs a newer cclink release than this machine",
+        ],
+        remediation: &[
+            "Upgrade cclink on this machine (see Install in the README)",
+            "Or have the publisher republish with the release installed here",
+        ],
+    },
+    ErrorDoc {
+        code: "E018",
+        title: "Stale handoff",
+        description: "The DHT served a validly signed handoff that is older than one already picked up from the same publisher. Pickup refuses it so an old session cannot be replayed in place of the current one.",
+        causes: &[
+            "a DHT node kept an earlier packet and served it instead of the latest (replay or rollback)",
+            "the publisher's clock jumped backwards between two handoffs",
+        ],
+        remediation: &[
+            "Retry later; other nodes usually serve the latest handoff",
+            "If the publisher confirms the handoff is current, pick it up with `cclink pickup --allow-rollback`",
+        ],
+    },
+    ErrorDoc {
+        code: "E019",
+        title: "Response too large",
+        description: "A handoff read from the DHT, a shared folder or the local network was bigger than cclink accepts (1 MiB by default; a chunked handoff is at most 64 chunks). Reading stops at the limit instead of buffering the rest.",
+        causes: &[
+            "a misbehaving LAN advertiser or a stray file in the transport directory",
+            "a chunk manifest declaring more data than any cclink publishes",
+            "a legitimate handoff with a large --with-transcript payload over --dir or --lan",
+        ],
+        remediation: &[
+            "Check where the handoff came from before trusting the source again",
+            "For a large legitimate handoff, raise `[network] max_response` in ~/.pubky/cclink.toml",
+        ],
+    },
+    ErrorDoc {
+        code: "E020",
+        title: "Handoff from the future",
+        description: "The handoff's signed creation time is more than five minutes ahead of this machine's clock. Pickup refuses it: remembered as the newest handoff from its publisher, it would make every later handoff look stale (E018).",
+        causes: &[
+            "the publisher's clock runs fast",
+            "this machine's clock runs slow",
+        ],
+        remediation: &[
+            "Sync both clocks (NTP), then have the publisher publish again",
+        ],
+    },
+    ErrorDoc {
+        code: "E021",
+        title: "Network error",
+        description: "cclink could not reach the Mainline DHT: the client could not start, no DHT node answered a lookup, or part of a chunked handoff could not be fetched.",
+        causes: &[
+            "no network connection, or a firewall that blocks outgoing UDP",
+            "a chunked handoff whose chunks have not spread through the DHT yet",
+        ],
+        remediation: &[
+            "Check connectivity with `cclink doctor`",
+            "Retry in a minute; chunks usually become reachable shortly after publish",
+            "Behind a firewall that blocks the DHT, use `--dir` or `export`/`import`",
+        ],
+    },
+];
+
+/// Look up the documentation for an error code (case-insensitive).
+pub fn lookup(code: &str) -> Option<&'static ErrorDoc> {
+    ERROR_DOCS
+        .iter()
+        .find(|doc| doc.code.eq_ignore_ascii_case(code))
+}
+
+/// Print a failed command's error: one line, plus a pointer to `cclink explain`
+/// when the error carries a code, unless `quiet`.
+pub fn report(error: &anyhow::Error, quiet: bool) {
+    eprintln!("{} {:#}", "Error:".error().stderr(), error);
+    if let Some(code) = cclink_error(error).map(CclinkError::code) {
+        if !quiet {
+            eprintln!("Run `cclink explain {}` for causes and fixes.", code);
+        }
+    }
+}
+
+/// Exit status of a command that failed with `error`: that of the first
+/// [`CclinkError`] in its chain, or 1.
+pub fn exit_code(error: &anyhow::Error) -> i32 {
+    cclink_error(error).map_or(1, CclinkError::exit_code)
+}
+
+fn cclink_error(error: &anyhow::Error) -> Option<&CclinkError> {
+    error.chain().find_map(|e| e.downcast_ref::<CclinkError>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_code_is_documented_once() {
+        let errors = [
+            CclinkError::NoKeypairFound,
+            CclinkError::AtomicWriteFailed(std::io::Error::other("x")),
+            CclinkError::HomeDirNotFound,
+            CclinkError::SignatureVerificationFailed(String::new()),
+            CclinkError::SessionNotFound,
+            CclinkError::RecordNotFound,
+            CclinkError::ChunkVerificationFailed(0),
+            CclinkError::TrustedKeyChanged(String::new()),
+            CclinkError::HandoffExpired(String::new()),
+            CclinkError::IncorrectPin,
+            CclinkError::SessionDataMissing,
+            CclinkError::DhtPublishFailed(String::new()),
+            CclinkError::RecordTooLarge { size: 0, limit: 0 },
+            CclinkError::BurnWindowExceeded {
+                age: String::new(),
+                limit: String::new(),
+            },
+            CclinkError::HookFailed {
+                event: "",
+                status: String::new(),
+            },
+            CclinkError::KeyRevoked {
+                pubkey: String::new(),
+                at: String::new(),
+            },
+            CclinkError::UnsupportedRecordVersion {
+                found: 0,
+                supported: 0,
+            },
+            CclinkError::HandoffRolledBack {
+                got: String::new(),
+                seen: String::new(),
+            },
+            CclinkError::ResponseTooLarge { size: 0, limit: 0 },
+            CclinkError::HandoffFromFuture(String::new()),
+            CclinkError::NetworkError(String::new()),
+        ];
+        for error in &errors {
+            assert!(
+                lookup(error.code()).is_some(),
+                "{} undocumented",
+                error.code()
+            );
+        }
+        assert_eq!(
+            ERROR_DOCS.len(),
+            errors.len(),
+            "stale entries in ERROR_DOCS"
+        );
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        let code = |e: CclinkError| exit_code(&anyhow::Error::new(e).context("while picking up"));
+        assert_eq!(code(CclinkError::RecordNotFound), 2);
+        assert_eq!(code(CclinkError::HandoffExpired(String::new())), 3);
+        assert_eq!(code(CclinkError::IncorrectPin), 4);
+        assert_eq!(code(CclinkError::DhtPublishFailed(String::new())), 5);
+        assert_eq!(code(CclinkError::NetworkError(String::new())), 5);
+        assert_eq!(code(CclinkError::HomeDirNotFound), 1);
+        assert_eq!(exit_code(&anyhow::anyhow!("plain failure")), 1);
+    }
+
+    #[test]
+    fn test_lookup_is_case_insensitive() {
+        assert_eq!(lookup("e006").map(|d| d.code), Some("E006"));
+        assert!(lookup("E999").is_none());
+    }
 }
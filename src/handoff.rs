@@ -0,0 +1,133 @@
+//! Sealing and opening handoff payloads: the steps between a session and the
+//! ciphertext of a record, shared by `cclink publish`, `cclink pickup` and the
+//! [`crate::Cclink`] facade.
+//!
+//! Sealing builds the [`Payload`] of a session, compresses it when that helps and
+//! encrypts it. Opening gets the ciphertext of a verified record, decrypts it and
+//! decodes the payload. PINs, prompts and the choice of fallback keys stay with the
+//! commands.
+
+use base64::Engine;
+
+use crate::record::{HandoffRecord, Payload};
+
+/// The payload describing `session` as published from this machine, without a
+/// receipt key or transcript.
+pub fn session_payload(session: &crate::session::SessionInfo) -> Payload {
+    Payload {
+        agent: session.agent.payload_tag(),
+        device: crate::keys::device::name(),
+        git: crate::git::current(std::path::Path::new(&session.project)),
+        hostname: gethostname::gethostname().to_string_lossy().into_owned(),
+        project: session.project.clone(),
+        session_id: session.session_id.clone(),
+        platform: Some(crate::platform::current()),
+        ..Payload::default()
+    }
+}
+
+/// Serialize `payload` for encryption, zstd-compressed when that makes it smaller.
+/// Also returns whether it was compressed, for the record's `compressed` flag.
+///
+/// Fails past [`crate::record::MAX_PAYLOAD_BYTES`], the most pickup decompresses.
+pub fn encode_payload(payload: &Payload) -> anyhow::Result<(Vec<u8>, bool)> {
+    let json = serde_json::to_vec(payload)
+        .map_err(|e| anyhow::anyhow!("failed to serialize payload: {}", e))?;
+    if json.len() > crate::record::MAX_PAYLOAD_BYTES {
+        anyhow::bail!(
+            "payload is too large to hand off ({} bytes; the limit is {} bytes)",
+            json.len(),
+            crate::record::MAX_PAYLOAD_BYTES
+        );
+    }
+    Ok(match crate::record::compress_payload(&json)? {
+        Some(smaller) => (smaller, true),
+        None => (json, false),
+    })
+}
+
+/// The age recipient to encrypt a handoff of `keypair` to: `recipient` as a standard
+/// age recipient or a z32 public key, or `keypair` itself when `None`.
+pub fn recipient(
+    keypair: &pkarr::Keypair,
+    recipient: Option<&str>,
+) -> anyhow::Result<Box<dyn age::Recipient + Send>> {
+    Ok(match recipient {
+        Some(age) if crate::keys::age_identity::is_recipient(age) => {
+            crate::keys::age_identity::parse_recipient(age)?
+        }
+        Some(z32) => Box::new(crate::crypto::recipient_from_z32(z32)?),
+        None => Box::new(crate::crypto::age_recipient(
+            &crate::crypto::ed25519_to_x25519_public(keypair),
+        )),
+    })
+}
+
+/// The ciphertext of `record`, signed by `signer`: decoded from the blob, or fetched
+/// from the chunk slots next to `location`'s packet when the blob is empty.
+pub fn ciphertext(
+    client: &crate::transport::DhtClient,
+    location: &pkarr::PublicKey,
+    signer: &pkarr::PublicKey,
+    record: &HandoffRecord,
+) -> anyhow::Result<Vec<u8>> {
+    if record.blob.is_empty() {
+        return client.fetch_chunked_blob(location, signer, record);
+    }
+    base64::engine::general_purpose::STANDARD
+        .decode(&record.blob)
+        .map_err(|e| anyhow::anyhow!("failed to decode blob: {}", e))
+}
+
+/// This machine's device key, when `record` was published to it with `--to`.
+pub fn device_for(record: &HandoffRecord) -> anyhow::Result<Option<crate::keys::device::Device>> {
+    let Some(ref recipient) = record.recipient else {
+        return Ok(None);
+    };
+    Ok(crate::keys::device::load()?.filter(|d| d.recipient() == *recipient))
+}
+
+/// Decrypt the `ciphertext` of `record` with `identity` and decode its payload.
+pub fn open(
+    ciphertext: &[u8],
+    identity: &dyn age::Identity,
+    record: &HandoffRecord,
+) -> anyhow::Result<Payload> {
+    let plaintext = crate::crypto::age_decrypt(ciphertext, identity)?;
+    crate::record::decode_payload(plaintext, record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoded_payload_opens_for_recipient() {
+        let me = pkarr::Keypair::from_secret_key(&[1u8; 32]);
+        let payload = Payload {
+            session_id: "abc-123".to_string(),
+            project: "/home/me/code/app".repeat(20),
+            ..Payload::default()
+        };
+        let (plaintext, compressed) = encode_payload(&payload).unwrap();
+        assert!(compressed);
+        let ciphertext =
+            crate::crypto::age_encrypt(&plaintext, recipient(&me, None).unwrap().as_ref()).unwrap();
+        let record = HandoffRecord::builder(ciphertext.clone(), 60)
+            .compressed(compressed)
+            .sign(&me)
+            .unwrap();
+        let identity = crate::crypto::age_identity(&crate::crypto::ed25519_to_x25519_secret(&me));
+        let opened = open(&ciphertext, &identity, &record).unwrap();
+        assert_eq!(opened.project, payload.project);
+    }
+
+    #[test]
+    fn test_oversized_payload_is_refused() {
+        let payload = Payload {
+            transcript: Some("x".repeat(crate::record::MAX_PAYLOAD_BYTES)),
+            ..Payload::default()
+        };
+        assert!(encode_payload(&payload).is_err());
+    }
+}
@@ -0,0 +1,275 @@
+//! Local history of handoffs and files published from, and picked up on, this machine.
+//!
+//! Kept in the `history` table of the [state database](crate::state) and never sent
+//! anywhere. Like the handoff cache, recording is best-effort: a history failure is
+//! logged and never fails the command that triggered it. `cclink history export`
+//! turns the log into versioned CSV or JSONL rows.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Version of the exported row layout. Bump when a column is renamed, removed, or
+/// changes meaning; adding a column at the end does not need a bump.
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Whether this machine sent or received the item.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// Published or sent from this machine
+    Out,
+    /// Picked up or received on this machine
+    In,
+}
+
+/// What was handed over.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    /// A session handoff (`cclink` / `cclink pickup`)
+    Handoff,
+    /// A file (`cclink send` / `cclink recv`)
+    File,
+}
+
+/// One history entry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// Unix seconds when this machine published or picked up.
+    pub at: u64,
+    /// `created_at` of the record involved, which identifies the handoff.
+    pub created_at: u64,
+    pub direction: Direction,
+    pub kind: Kind,
+    /// Session ID (handoffs) or file name (files).
+    pub name: String,
+    /// z32 key of the other side: the recipient of a `--share`, or the publisher of
+    /// a cross-user pickup. `None` between machines sharing one key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer: Option<String>,
+    /// Project path of a handoff.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// tmux window (`session:window`) a handoff was resumed in by `pickup --tmux`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tmux: Option<String>,
+}
+
+impl Entry {
+    /// An entry stamped with the current time.
+    pub fn now(direction: Direction, kind: Kind, created_at: u64, name: &str) -> Self {
+        Entry {
+            at: std::time::SystemTime::now()
+                .duration_since(std::time::SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            created_at,
+            direction,
+            kind,
+            name: name.to_string(),
+            peer: None,
+            project: None,
+            tmux: None,
+        }
+    }
+}
+
+/// Every entry in the history, oldest first.
+pub fn load() -> anyhow::Result<Vec<Entry>> {
+    crate::state::State::open()?.history()
+}
+
+/// Record `entry` in the history. Failures are logged, not returned.
+pub fn record(entry: Entry) {
+    let result = crate::state::State::open().and_then(|state| state.record_entry(&entry));
+    if let Err(e) = result {
+        tracing::debug!(error = %e, "failed to record history");
+    }
+}
+
+/// Criteria an exported entry must meet; `None` matches everything.
+#[derive(Debug, Default)]
+pub struct Filter {
+    /// Earliest `at` to include.
+    pub since: Option<u64>,
+    pub direction: Option<Direction>,
+    /// z32 key of the peer.
+    pub peer: Option<String>,
+    /// Project directory; its subdirectories match too.
+    pub project: Option<PathBuf>,
+}
+
+impl Filter {
+    pub fn matches(&self, entry: &Entry) -> bool {
+        self.since.is_none_or(|since| entry.at >= since)
+            && self.direction.is_none_or(|d| entry.direction == d)
+            && self
+                .peer
+                .as_ref()
+                .is_none_or(|peer| entry.peer.as_ref() == Some(peer))
+            && self.project.as_ref().is_none_or(|project| {
+                entry
+                    .project
+                    .as_ref()
+                    .is_some_and(|p| Path::new(p).starts_with(project))
+            })
+    }
+}
+
+/// Columns of an exported row, in order.
+const COLUMNS: [&str; 10] = [
+    "schema",
+    "at",
+    "at_utc",
+    "direction",
+    "kind",
+    "created_at",
+    "name",
+    "peer",
+    "project",
+    "tmux",
+];
+
+/// Export format for `cclink history export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    Csv,
+    Jsonl,
+}
+
+/// Write `entries` to `out` as rows of schema [`EXPORT_SCHEMA_VERSION`].
+pub fn export<W: Write>(entries: &[Entry], format: Format, out: &mut W) -> anyhow::Result<()> {
+    if format == Format::Csv {
+        writeln!(out, "{}", COLUMNS.join(","))?;
+    }
+    for entry in entries {
+        let at_utc = crate::util::format_utc(entry.at);
+        let direction = enum_name(&entry.direction)?;
+        let kind = enum_name(&entry.kind)?;
+        match format {
+            Format::Csv => {
+                let fields = [
+                    EXPORT_SCHEMA_VERSION.to_string(),
+                    entry.at.to_string(),
+                    at_utc,
+                    direction,
+                    kind,
+                    entry.created_at.to_string(),
+                    entry.name.clone(),
+                    entry.peer.clone().unwrap_or_default(),
+                    entry.project.clone().unwrap_or_default(),
+                    entry.tmux.clone().unwrap_or_default(),
+                ];
+                let row: Vec<String> = fields.iter().map(|f| crate::util::csv_field(f)).collect();
+                writeln!(out, "{}", row.join(","))?;
+            }
+            Format::Jsonl => {
+                let row = serde_json::json!({
+                    "schema": EXPORT_SCHEMA_VERSION,
+                    "at": entry.at,
+                    "at_utc": at_utc,
+                    "direction": direction,
+                    "kind": kind,
+                    "created_at": entry.created_at,
+                    "name": entry.name,
+                    "peer": entry.peer,
+                    "project": entry.project,
+                    "tmux": entry.tmux,
+                });
+                writeln!(out, "{}", row)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The serde name of a unit enum variant.
+pub(crate) fn enum_name<T: Serialize>(value: &T) -> anyhow::Result<String> {
+    match serde_json::to_value(value)? {
+        serde_json::Value::String(name) => Ok(name),
+        other => anyhow::bail!("unexpected enum encoding {}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(at: u64, direction: Direction, peer: Option<&str>, project: Option<&str>) -> Entry {
+        Entry {
+            at,
+            created_at: at - 5,
+            direction,
+            kind: Kind::Handoff,
+            name: "sess-1".to_string(),
+            peer: peer.map(str::to_string),
+            project: project.map(str::to_string),
+            tmux: None,
+        }
+    }
+
+    #[test]
+    fn test_filter() {
+        let out = entry(
+            1_000,
+            Direction::Out,
+            Some("peerkey"),
+            Some("/work/app/sub"),
+        );
+        let inbound = entry(2_000, Direction::In, None, Some("/work/other"));
+
+        assert!(Filter::default().matches(&out));
+        let since = Filter {
+            since: Some(1_500),
+            ..Default::default()
+        };
+        assert!(!since.matches(&out) && since.matches(&inbound));
+        let direction = Filter {
+            direction: Some(Direction::Out),
+            ..Default::default()
+        };
+        assert!(direction.matches(&out) && !direction.matches(&inbound));
+        let peer = Filter {
+            peer: Some("peerkey".to_string()),
+            ..Default::default()
+        };
+        assert!(peer.matches(&out) && !peer.matches(&inbound));
+        let project = Filter {
+            project: Some(PathBuf::from("/work/app")),
+            ..Default::default()
+        };
+        assert!(project.matches(&out) && !project.matches(&inbound));
+    }
+
+    #[test]
+    fn test_export_csv_and_jsonl() {
+        let entries = [entry(
+            1_700_000_000,
+            Direction::Out,
+            None,
+            Some("/work/a,b \"c\""),
+        )];
+
+        let mut csv = Vec::new();
+        export(&entries, Format::Csv, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("schema,at,at_utc,direction,kind,created_at,name,peer,project,tmux")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("1,1700000000,2023-11-14 22:13 UTC,out,handoff,1699999995,sess-1,,\"/work/a,b \"\"c\"\"\",")
+        );
+
+        let mut jsonl = Vec::new();
+        export(&entries, Format::Jsonl, &mut jsonl).unwrap();
+        let row: serde_json::Value = serde_json::from_slice(&jsonl).unwrap();
+        assert_eq!(row["schema"], EXPORT_SCHEMA_VERSION);
+        assert_eq!(row["direction"], "out");
+        assert_eq!(row["peer"], serde_json::Value::Null);
+    }
+}
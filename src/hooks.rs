@@ -0,0 +1,170 @@
+//! User hooks: shell commands from `[hooks]` in `~/.pubky/cclink.toml`, run at fixed
+//! points of publish and pickup so users can sync a project, connect a VPN, or send a
+//! notification around a handoff.
+//!
+//! A hook runs through the platform shell (`sh -c`, or `cmd /C` on Windows) in the
+//! current directory, with the handoff's metadata in `CCLINK_*` environment variables
+//! and its output passed straight through. A failing pre hook stops the command with
+//! E015; a failing post hook only warns, since the handoff has already happened.
+
+use std::process::Command;
+
+use crate::config::HooksConfig;
+use crate::error::CclinkError;
+use crate::ui::Paint;
+
+/// A point in publish or pickup where a hook can run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    PrePublish,
+    PostPublish,
+    PrePickup,
+    PostPickup,
+}
+
+impl Event {
+    /// Name shown to the user and passed as `CCLINK_EVENT`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Event::PrePublish => "pre-publish",
+            Event::PostPublish => "post-publish",
+            Event::PrePickup => "pre-pickup",
+            Event::PostPickup => "post-pickup",
+        }
+    }
+
+    fn command(self, hooks: &HooksConfig) -> Option<&str> {
+        match self {
+            Event::PrePublish => hooks.pre_publish.as_deref(),
+            Event::PostPublish => hooks.post_publish.as_deref(),
+            Event::PrePickup => hooks.pre_pickup.as_deref(),
+            Event::PostPickup => hooks.post_pickup.as_deref(),
+        }
+    }
+
+    fn stops_on_failure(self) -> bool {
+        matches!(self, Event::PrePublish | Event::PrePickup)
+    }
+}
+
+/// Handoff metadata for a hook, set as `CCLINK_<NAME>` environment variables.
+#[derive(Debug, Default)]
+pub struct HookEnv {
+    vars: Vec<(&'static str, String)>,
+}
+
+impl HookEnv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set `CCLINK_<name>` to `value`.
+    pub fn set(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.vars.push((name, value.to_string()));
+        self
+    }
+
+    /// Set `CCLINK_<name>` when `value` is `Some`; hooks see it unset otherwise.
+    pub fn set_opt(self, name: &'static str, value: Option<impl ToString>) -> Self {
+        match value {
+            Some(value) => self.set(name, value),
+            None => self,
+        }
+    }
+
+    fn apply(&self, event: Event, cmd: &mut Command) {
+        cmd.env("CCLINK_EVENT", event.name());
+        for (name, value) in &self.vars {
+            cmd.env(format!("CCLINK_{}", name), value);
+        }
+    }
+}
+
+/// Run the hook configured for `event`, if any.
+pub fn run(hooks: &HooksConfig, event: Event, env: HookEnv) -> anyhow::Result<()> {
+    let Some(command) = event.command(hooks) else {
+        return Ok(());
+    };
+    tracing::debug!(event = event.name(), command, "running hook");
+    let mut cmd = shell(command);
+    env.apply(event, &mut cmd);
+    let status = match cmd.status() {
+        Ok(status) if status.success() => return Ok(()),
+        Ok(status) => status.to_string(),
+        Err(e) => format!("could not start: {}", e),
+    };
+    if event.stops_on_failure() {
+        return Err(CclinkError::HookFailed {
+            event: event.name(),
+            status,
+        }
+        .into());
+    }
+    eprintln!(
+        "{}",
+        format!("Warning: the {} hook failed ({})", event.name(), status)
+            .warning()
+            .stderr()
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_sees_metadata_in_env() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let out = dir.path().join("out");
+        let hooks = HooksConfig {
+            post_publish: Some(format!(
+                "printf '%s %s %s' \"$CCLINK_EVENT\" \"$CCLINK_SESSION_ID\" \"${{CCLINK_RECIPIENT-unset}}\" > {}",
+                out.display()
+            )),
+            ..Default::default()
+        };
+        let env = HookEnv::new()
+            .set("SESSION_ID", "abc-123")
+            .set_opt("RECIPIENT", None::<String>);
+        run(&hooks, Event::PostPublish, env).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "post-publish abc-123 unset"
+        );
+    }
+
+    #[test]
+    fn test_failing_pre_hook_stops_and_post_hook_warns() {
+        let hooks = HooksConfig {
+            pre_pickup: Some("exit 3".to_string()),
+            post_pickup: Some("exit 3".to_string()),
+            ..Default::default()
+        };
+        let err = run(&hooks, Event::PrePickup, HookEnv::new()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CclinkError>(),
+            Some(CclinkError::HookFailed {
+                event: "pre-pickup",
+                ..
+            })
+        ));
+        assert!(run(&hooks, Event::PostPickup, HookEnv::new()).is_ok());
+        // No hook configured for the event: nothing runs.
+        assert!(run(&hooks, Event::PrePublish, HookEnv::new()).is_ok());
+    }
+}
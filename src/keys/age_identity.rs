@@ -0,0 +1,202 @@
+//! Interop with standard age keys and plugins.
+//!
+//! `--share` accepts an age recipient (`age1…`, or `age1<plugin>1…` such as an
+//! age-plugin-yubikey recipient) in place of a cclink public key. The holder of the
+//! matching identity imports it once with `cclink init --import-age <FILE>`; pickup
+//! then tries the imported identities when the cclink key cannot decrypt a handoff.
+//! Plugin identities run their `age-plugin-<name>` binary, which must be on `PATH`.
+
+use std::path::{Path, PathBuf};
+
+use age::secrecy::SecretString;
+use anyhow::Context;
+
+/// Prefix shared by every standard age recipient, native and plugin.
+const RECIPIENT_PREFIX: &str = "age1";
+
+/// Path of the imported identities inside the key directory.
+pub fn identities_path() -> anyhow::Result<PathBuf> {
+    Ok(super::store::key_dir()?.join("cclink_age_identities"))
+}
+
+/// Whether `text` is a standard age recipient rather than a cclink key or contact.
+pub fn is_recipient(text: &str) -> bool {
+    text.starts_with(RECIPIENT_PREFIX)
+}
+
+/// Parse a native (`age1…`) or plugin (`age1<plugin>1…`) age recipient.
+pub fn parse_recipient(text: &str) -> anyhow::Result<Box<dyn age::Recipient + Send>> {
+    if let Ok(recipient) = text.parse::<age::x25519::Recipient>() {
+        return Ok(Box::new(recipient));
+    }
+    let recipient = text
+        .parse::<age::plugin::Recipient>()
+        .map_err(|e| anyhow::anyhow!("invalid age recipient '{}': {}", text, e))?;
+    let plugin = age::plugin::RecipientPluginV1::new(
+        recipient.plugin(),
+        std::slice::from_ref(&recipient),
+        &[],
+        TerminalCallbacks,
+    )
+    .map_err(|e| anyhow::anyhow!("{}", e))?;
+    Ok(Box::new(plugin))
+}
+
+/// Add the identities in the age identity file at `source` to the imported ones.
+/// Returns how many were new.
+pub fn import(source: &Path) -> anyhow::Result<usize> {
+    let text = std::fs::read_to_string(source)
+        .with_context(|| format!("Failed to read {}", source.display()))?;
+    let added = identity_lines(&text)?;
+    if added.is_empty() {
+        anyhow::bail!("{} contains no age identities", source.display());
+    }
+
+    let path = identities_path()?;
+    let existing = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let (merged, new) = merge(&existing, &added);
+    if new > 0 {
+        super::store::write_encrypted_keypair_atomic(merged.as_bytes(), &path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(new)
+}
+
+/// The imported identities; empty when none were imported.
+pub fn load() -> anyhow::Result<Vec<Box<dyn age::Identity>>> {
+    let path = identities_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    age::IdentityFile::from_file(path.display().to_string())
+        .with_context(|| format!("Failed to read {}", path.display()))?
+        .with_callbacks(TerminalCallbacks)
+        .into_identities()
+        .map_err(|e| anyhow::anyhow!("Failed to load age identities: {}", e))
+}
+
+/// Validate an identity file and return its identity lines, comments dropped.
+fn identity_lines(text: &str) -> anyhow::Result<Vec<String>> {
+    age::IdentityFile::from_buffer(text.as_bytes())
+        .map_err(|e| anyhow::anyhow!("not an age identity file: {}", e))?;
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Append the lines of `added` missing from `existing`. Returns the new file text
+/// and the number of lines appended.
+fn merge(existing: &str, added: &[String]) -> (String, usize) {
+    let mut merged = existing.to_string();
+    if !merged.is_empty() && !merged.ends_with('\n') {
+        merged.push('\n');
+    }
+    let mut new = 0;
+    for line in added {
+        if !merged.lines().any(|l| l.trim() == line) {
+            merged.push_str(line);
+            merged.push('\n');
+            new += 1;
+        }
+    }
+    (merged, new)
+}
+
+/// Relays plugin messages and requests (e.g. "touch your YubiKey", a PIN) to the
+/// terminal.
+#[derive(Clone, Copy)]
+struct TerminalCallbacks;
+
+impl age::Callbacks for TerminalCallbacks {
+    fn display_message(&self, message: &str) {
+        eprintln!("{}", message);
+    }
+
+    fn confirm(&self, message: &str, yes_string: &str, no_string: Option<&str>) -> Option<bool> {
+        let prompt = match no_string {
+            Some(no) => format!("{} ({} / {})", message, yes_string, no),
+            None => format!("{} ({})", message, yes_string),
+        };
+        dialoguer::Confirm::new()
+            .with_prompt(prompt)
+            .default(true)
+            .interact()
+            .ok()
+    }
+
+    fn request_public_string(&self, description: &str) -> Option<String> {
+        dialoguer::Input::new()
+            .with_prompt(description)
+            .interact_text()
+            .ok()
+    }
+
+    fn request_passphrase(&self, description: &str) -> Option<SecretString> {
+        dialoguer::Password::new()
+            .with_prompt(description)
+            .interact()
+            .ok()
+            .map(SecretString::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_native_recipient_round_trip() {
+        let identity = age::x25519::Identity::generate();
+        let recipient = identity.to_public().to_string();
+        assert!(is_recipient(&recipient));
+        let parsed = parse_recipient(&recipient).unwrap();
+
+        let ciphertext = crate::crypto::age_encrypt(b"handoff", parsed.as_ref()).unwrap();
+        let identities: Vec<Box<dyn age::Identity>> = vec![
+            Box::new(age::x25519::Identity::generate()),
+            Box::new(identity),
+        ];
+        assert_eq!(
+            crate::crypto::age_decrypt_any(&ciphertext, &identities).unwrap(),
+            b"handoff"
+        );
+    }
+
+    #[test]
+    fn test_rejects_malformed_recipient_and_identity_file() {
+        assert!(parse_recipient("age1notarealrecipient").is_err());
+        assert!(!is_recipient(
+            "qjmqtwt9dhfhf3ndtbzj3ddncct1s75kq13wy9ypkf39jzwpw5iy"
+        ));
+        assert!(identity_lines("not an identity\n").is_err());
+    }
+
+    #[test]
+    fn test_merge_skips_known_identities() {
+        use age::secrecy::ExposeSecret;
+        let a = age::x25519::Identity::generate()
+            .to_string()
+            .expose_secret()
+            .to_string();
+        let b = age::x25519::Identity::generate()
+            .to_string()
+            .expose_secret()
+            .to_string();
+        let file = format!("# created: today\n# public key: age1...\n{}\n", a);
+        let added = identity_lines(&file).unwrap();
+        assert_eq!(added, vec![a.clone()]);
+
+        let (merged, new) = merge("", &added);
+        assert_eq!(new, 1);
+        let (merged, new) = merge(&merged, &[a.clone(), b.clone()]);
+        assert_eq!(new, 1);
+        assert_eq!(merged, format!("{}\n{}\n", a, b));
+    }
+}
@@ -0,0 +1,207 @@
+//! Key agent: a background process holding the decrypted seed in memory, so commands
+//! run while it lives do not prompt for the key passphrase (like `ssh-agent`).
+//!
+//! `cclink agent` unlocks the key once and serves the seed over a unix socket in the
+//! key directory (`cclink-agent.sock`, mode 0600, so only the owner can connect). It
+//! exits after its lifetime, or on `cclink agent --stop`. The seed never touches disk.
+//!
+//! Protocol: one request line per connection. `SEED <hash>` answers the seed as hex
+//! when `<hash>` (SHA-256 of the key file) matches the file the agent unlocked, and
+//! `NO` otherwise, so a replaced key file is never answered with a stale seed.
+//! `STOP` answers `OK` and shuts the agent down.
+
+use std::path::PathBuf;
+
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+/// Path of the agent socket inside the key directory.
+pub fn socket_path() -> anyhow::Result<PathBuf> {
+    Ok(super::store::key_dir()?.join("cclink-agent.sock"))
+}
+
+/// Identifies a key file to the agent: hex SHA-256 of its contents.
+pub fn key_file_hash(raw: &[u8]) -> String {
+    crate::util::to_hex(&Sha256::digest(raw))
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+
+    use anyhow::Context;
+    use zeroize::Zeroizing;
+
+    /// How long a command waits for the agent before prompting instead.
+    const CLIENT_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// How often the agent checks for a connection and for the end of its lifetime.
+    const ACCEPT_POLL: Duration = Duration::from_millis(50);
+
+    /// Send one request line to the agent at `socket` and read its one-line answer.
+    fn request(socket: &Path, line: &str) -> anyhow::Result<Zeroizing<String>> {
+        let mut stream = UnixStream::connect(socket)?;
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+        writeln!(stream, "{}", line)?;
+        let mut answer = Zeroizing::new(String::new());
+        BufReader::new(stream).read_line(&mut answer)?;
+        Ok(Zeroizing::new(answer.trim().to_string()))
+    }
+
+    pub fn request_seed(socket: &Path, key_hash: &str) -> Option<Zeroizing<[u8; 32]>> {
+        let answer = request(socket, &format!("SEED {}", key_hash)).ok()?;
+        let bytes = Zeroizing::new(crate::util::from_hex(&answer).ok()?);
+        let mut seed = Zeroizing::new([0u8; 32]);
+        if bytes.len() != seed.len() {
+            return None;
+        }
+        seed.copy_from_slice(&bytes);
+        Some(seed)
+    }
+
+    pub fn stop(socket: &Path) -> anyhow::Result<bool> {
+        match request(socket, "STOP") {
+            Ok(answer) => Ok(answer.as_str() == "OK"),
+            Err(_) => Ok(false),
+        }
+    }
+
+    pub fn serve(
+        socket: &Path,
+        seed: Zeroizing<[u8; 32]>,
+        key_hash: &str,
+        lifetime: Duration,
+    ) -> anyhow::Result<()> {
+        if UnixStream::connect(socket).is_ok() {
+            anyhow::bail!("a cclink agent is already running (stop it with `cclink agent --stop`)");
+        }
+        // A socket file nobody answers on is left over from an agent that died.
+        let _ = std::fs::remove_file(socket);
+        let listener = UnixListener::bind(socket)
+            .with_context(|| format!("Failed to listen on {}", socket.display()))?;
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(socket, std::fs::Permissions::from_mode(0o600)).with_context(
+                || format!("Failed to set 0600 permissions on {}", socket.display()),
+            )?;
+        }
+
+        // Non-blocking accept, so the loop notices when the lifetime is over.
+        listener.set_nonblocking(true)?;
+        let deadline = Instant::now() + lifetime;
+        let seed_hex = Zeroizing::new(crate::util::to_hex(&*seed));
+        while Instant::now() < deadline {
+            let mut stream = match listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(ACCEPT_POLL);
+                    continue;
+                }
+                Err(_) => continue,
+            };
+            let _ = stream.set_nonblocking(false);
+            let _ = stream.set_read_timeout(Some(CLIENT_TIMEOUT));
+            let mut line = String::new();
+            if BufReader::new(&stream).read_line(&mut line).is_err() {
+                continue;
+            }
+            let answer = match line.trim().split_once(' ') {
+                Some(("SEED", hash)) if hash == key_hash => seed_hex.as_str(),
+                _ if line.trim() == "STOP" => {
+                    let _ = writeln!(stream, "OK");
+                    break;
+                }
+                _ => "NO",
+            };
+            let _ = writeln!(stream, "{}", answer);
+        }
+        let _ = std::fs::remove_file(socket);
+        Ok(())
+    }
+}
+
+/// Ask a running agent for the seed of the key file whose contents are `raw`.
+/// `None` when no agent runs, it holds another key, or it does not answer in time.
+pub fn request_seed(raw: &[u8]) -> Option<Zeroizing<[u8; 32]>> {
+    #[cfg(unix)]
+    {
+        imp::request_seed(&socket_path().ok()?, &key_file_hash(raw))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = raw;
+        None
+    }
+}
+
+/// Stop the running agent. Returns whether one was running.
+pub fn stop() -> anyhow::Result<bool> {
+    #[cfg(unix)]
+    {
+        imp::stop(&socket_path()?)
+    }
+    #[cfg(not(unix))]
+    {
+        Ok(false)
+    }
+}
+
+/// Serve `seed` for the key file hashing to `key_hash` until `lifetime` passes or a
+/// `STOP` request arrives. Blocks; the seed is zeroized when the agent stops.
+pub fn serve(
+    seed: Zeroizing<[u8; 32]>,
+    key_hash: &str,
+    lifetime: std::time::Duration,
+) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        imp::serve(&socket_path()?, seed, key_hash, lifetime)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (seed, key_hash, lifetime);
+        anyhow::bail!("cclink agent needs unix sockets and is not available on this platform")
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_agent_serves_only_the_key_it_unlocked() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let socket = dir.path().join("agent.sock");
+        let key_hash = key_file_hash(b"key file");
+        let server = {
+            let socket = socket.clone();
+            let key_hash = key_hash.clone();
+            std::thread::spawn(move || {
+                imp::serve(
+                    &socket,
+                    Zeroizing::new([9u8; 32]),
+                    &key_hash,
+                    Duration::from_secs(60),
+                )
+            })
+        };
+        while !socket.exists() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(
+            imp::request_seed(&socket, &key_hash).as_deref(),
+            Some(&[9u8; 32])
+        );
+        assert!(imp::request_seed(&socket, &key_file_hash(b"other key")).is_none());
+        assert!(imp::stop(&socket).unwrap());
+        server.join().unwrap().unwrap();
+        assert!(!socket.exists());
+        assert!(imp::request_seed(&socket, &key_hash).is_none());
+    }
+}
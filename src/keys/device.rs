@@ -0,0 +1,90 @@
+//! This machine's device identity for device-targeted handoffs.
+//!
+//! `cclink device register <name>` generates an age X25519 identity that never leaves
+//! this machine and stores it, with the device name, in `~/.pubky/cclink_device`
+//! (0600). Its public half goes into the device registry (see
+//! [`crate::record::devices`]); handoffs published with `--to <name>` are encrypted to
+//! it, so other machines holding the same cclink key cannot read them.
+
+use std::path::PathBuf;
+
+use age::secrecy::ExposeSecret;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+/// Path of the device identity file inside the key directory.
+pub fn device_path() -> anyhow::Result<PathBuf> {
+    Ok(super::store::key_dir()?.join("cclink_device"))
+}
+
+/// The device identity as stored on disk.
+#[derive(Serialize, Deserialize)]
+struct DeviceFile {
+    name: String,
+    /// `AGE-SECRET-KEY-1…`
+    identity: String,
+}
+
+/// This machine's registered name and age identity.
+pub struct Device {
+    pub name: String,
+    pub identity: age::x25519::Identity,
+}
+
+impl Device {
+    /// A new device identity named `name`.
+    pub fn generate(name: &str) -> Self {
+        Device {
+            name: name.to_string(),
+            identity: age::x25519::Identity::generate(),
+        }
+    }
+
+    /// The age recipient other machines encrypt to (`age1…`).
+    pub fn recipient(&self) -> String {
+        self.identity.to_public().to_string()
+    }
+}
+
+/// This machine's device identity, or `None` before `cclink device register`.
+pub fn load() -> anyhow::Result<Option<Device>> {
+    let path = device_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let file: DeviceFile = serde_json::from_str(&raw)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    let identity = file
+        .identity
+        .parse::<age::x25519::Identity>()
+        .map_err(|e| anyhow::anyhow!("invalid device identity in {}: {}", path.display(), e))?;
+    Ok(Some(Device {
+        name: file.name,
+        identity,
+    }))
+}
+
+/// Store `device` as this machine's identity, replacing any previous one.
+pub fn save(device: &Device) -> anyhow::Result<()> {
+    let path = device_path()?;
+    let file = DeviceFile {
+        name: device.name.clone(),
+        identity: device.identity.to_string().expose_secret().to_string(),
+    };
+    let json = zeroize::Zeroizing::new(serde_json::to_string(&file)?);
+    super::store::write_encrypted_keypair_atomic(json.as_bytes(), &path)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Name of this machine's device, if registered. Failures are logged, not returned.
+pub fn name() -> Option<String> {
+    match load() {
+        Ok(device) => device.map(|d| d.name),
+        Err(e) => {
+            tracing::debug!(error = %e, "failed to load device identity");
+            None
+        }
+    }
+}
@@ -1,4 +1,157 @@
+//! Human-comparable renderings of a public key.
+//!
+//! The short fingerprint identifies a key at a glance. The word and emoji renderings
+//! cover the whole key, so two people can read them to each other over a call and
+//! know they hold the same key, not just one with the same prefix.
+
+/// The 64 emoji of the Matrix SAS table, with names for reading aloud. Each encodes
+/// 6 bits.
+pub const EMOJI: [(&str, &str); 64] = [
+    ("🐶", "dog"),
+    ("🐱", "cat"),
+    ("🦁", "lion"),
+    ("🐎", "horse"),
+    ("🦄", "unicorn"),
+    ("🐷", "pig"),
+    ("🐘", "elephant"),
+    ("🐰", "rabbit"),
+    ("🐼", "panda"),
+    ("🐓", "rooster"),
+    ("🐧", "penguin"),
+    ("🐢", "turtle"),
+    ("🐟", "fish"),
+    ("🐙", "octopus"),
+    ("🦋", "butterfly"),
+    ("🌷", "flower"),
+    ("🌳", "tree"),
+    ("🌵", "cactus"),
+    ("🍄", "mushroom"),
+    ("🌏", "globe"),
+    ("🌙", "moon"),
+    ("☁️", "cloud"),
+    ("🔥", "fire"),
+    ("🍌", "banana"),
+    ("🍎", "apple"),
+    ("🍓", "strawberry"),
+    ("🌽", "corn"),
+    ("🍕", "pizza"),
+    ("🎂", "cake"),
+    ("❤️", "heart"),
+    ("😀", "smiley"),
+    ("🤖", "robot"),
+    ("🎩", "hat"),
+    ("👓", "glasses"),
+    ("🔧", "spanner"),
+    ("🎅", "santa"),
+    ("👍", "thumbs up"),
+    ("☂️", "umbrella"),
+    ("⌛", "hourglass"),
+    ("⏰", "clock"),
+    ("🎁", "gift"),
+    ("💡", "light bulb"),
+    ("📕", "book"),
+    ("✏️", "pencil"),
+    ("📎", "paperclip"),
+    ("✂️", "scissors"),
+    ("🔒", "lock"),
+    ("🔑", "key"),
+    ("🔨", "hammer"),
+    ("☎️", "telephone"),
+    ("🏁", "flag"),
+    ("🚂", "train"),
+    ("🚲", "bicycle"),
+    ("✈️", "aeroplane"),
+    ("🚀", "rocket"),
+    ("🏆", "trophy"),
+    ("⚽", "ball"),
+    ("🎸", "guitar"),
+    ("🎺", "trumpet"),
+    ("🔔", "bell"),
+    ("⚓", "anchor"),
+    ("🎧", "headphones"),
+    ("📁", "folder"),
+    ("📌", "pin"),
+];
+
 pub fn short_fingerprint(public_key: &pkarr::PublicKey) -> String {
     let z32 = public_key.to_z32();
     z32[..8].to_string()
 }
+
+/// The whole key as 24 words from the BIP39 English list, the last one a checksum,
+/// so a misheard word is caught.
+pub fn words(public_key: &pkarr::PublicKey) -> Vec<&'static str> {
+    bip39::Mnemonic::from_entropy(public_key.as_bytes())
+        .expect("32 bytes is a valid BIP39 entropy length")
+        .words()
+        .collect()
+}
+
+/// The whole key as 43 emoji (6 bits each, the last one zero-padded).
+pub fn emoji(public_key: &pkarr::PublicKey) -> Vec<&'static str> {
+    emoji_indices(public_key.as_bytes())
+        .into_iter()
+        .map(|i| EMOJI[i].0)
+        .collect()
+}
+
+/// Split `bytes` into 6-bit indices into [`EMOJI`], most significant bits first;
+/// leftover bits are padded with zeros into one last index.
+pub fn emoji_indices(bytes: &[u8]) -> Vec<usize> {
+    let mut indices = Vec::with_capacity((bytes.len() * 8).div_ceil(6));
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        acc = (acc << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 6 {
+            bits -= 6;
+            indices.push(((acc >> bits) & 0x3f) as usize);
+        }
+    }
+    if bits > 0 {
+        indices.push(((acc << (6 - bits)) & 0x3f) as usize);
+    }
+    indices
+}
+
+/// `items` in groups of `per_group` joined by spaces, one group per line, each line
+/// prefixed with `indent`.
+pub fn grouped(items: &[&str], per_group: usize, indent: &str) -> String {
+    items
+        .chunks(per_group)
+        .map(|group| format!("{}{}", indent, group.join(" ")))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> pkarr::PublicKey {
+        pkarr::Keypair::from_secret_key(&[42u8; 32]).public_key()
+    }
+
+    #[test]
+    fn test_words_and_emoji_cover_the_whole_key() {
+        assert_eq!(words(&key()).len(), 24);
+        assert_eq!(emoji(&key()).len(), 43);
+        let other = pkarr::Keypair::from_secret_key(&[43u8; 32]).public_key();
+        assert_ne!(words(&key()), words(&other));
+        assert_ne!(emoji(&key()), emoji(&other));
+    }
+
+    #[test]
+    fn test_emoji_indices_bit_order() {
+        // 0b000001_000010_000011_000100 = 0x04 0x20 0xC4
+        assert_eq!(emoji_indices(&[0x04, 0x20, 0xC4]), vec![1, 2, 3, 4]);
+        // One byte: 6 bits, then 2 bits padded with zeros.
+        assert_eq!(emoji_indices(&[0xFF]), vec![63, 0b110000]);
+    }
+
+    #[test]
+    fn test_grouped() {
+        assert_eq!(grouped(&["a", "b", "c"], 2, "  "), "  a b\n  c");
+    }
+}
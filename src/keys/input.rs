@@ -0,0 +1,103 @@
+//! Non-interactive sources for the key passphrase and handoff PINs.
+//!
+//! CI jobs, scripts, and remote shells without a TTY cannot answer a prompt. They
+//! supply the secret instead through a file (`--passphrase-file`, `--pin-file`) or
+//! an environment variable (`CCLINK_PASSPHRASE`, `CCLINK_PIN`); the file wins when
+//! both are set. One trailing newline is stripped from a file, so `echo` output works.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::Context;
+use zeroize::Zeroizing;
+
+/// Environment variable holding the key passphrase.
+pub const PASSPHRASE_ENV: &str = "CCLINK_PASSPHRASE";
+
+/// Environment variable holding the PIN of PIN-protected handoffs.
+pub const PIN_ENV: &str = "CCLINK_PIN";
+
+static PASSPHRASE_FILE: OnceLock<PathBuf> = OnceLock::new();
+static PIN_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Read the key passphrase from `path` for the rest of the process.
+pub fn set_passphrase_file(path: PathBuf) -> anyhow::Result<()> {
+    PASSPHRASE_FILE
+        .set(path)
+        .map_err(|_| anyhow::anyhow!("a passphrase file is already set"))
+}
+
+/// Read handoff PINs from `path` for the rest of the process.
+pub fn set_pin_file(path: PathBuf) -> anyhow::Result<()> {
+    PIN_FILE
+        .set(path)
+        .map_err(|_| anyhow::anyhow!("a PIN file is already set"))
+}
+
+/// The key passphrase from `--passphrase-file` or `CCLINK_PASSPHRASE`, if either is set.
+pub fn passphrase() -> anyhow::Result<Option<Zeroizing<String>>> {
+    resolve(PASSPHRASE_FILE.get().map(PathBuf::as_path), PASSPHRASE_ENV)
+}
+
+/// The handoff PIN from `--pin-file` or `CCLINK_PIN`, if either is set.
+pub fn pin() -> anyhow::Result<Option<Zeroizing<String>>> {
+    resolve(PIN_FILE.get().map(PathBuf::as_path), PIN_ENV)
+}
+
+fn resolve(file: Option<&Path>, env: &str) -> anyhow::Result<Option<Zeroizing<String>>> {
+    if let Some(path) = file {
+        return read_secret_file(path).map(Some);
+    }
+    Ok(std::env::var(env)
+        .ok()
+        .filter(|v| !v.is_empty())
+        .map(Zeroizing::new))
+}
+
+fn read_secret_file(path: &Path) -> anyhow::Result<Zeroizing<String>> {
+    let mut text = Zeroizing::new(
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read secret from {}", path.display()))?,
+    );
+    if text.ends_with('\n') {
+        text.pop();
+        if text.ends_with('\r') {
+            text.pop();
+        }
+    }
+    if text.is_empty() {
+        anyhow::bail!("{} is empty", path.display());
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_strips_one_trailing_newline() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("secret");
+        std::fs::write(&path, "correct horse \r\n").unwrap();
+        assert_eq!(read_secret_file(&path).unwrap().as_str(), "correct horse ");
+        std::fs::write(&path, "pin\n\n").unwrap();
+        assert_eq!(read_secret_file(&path).unwrap().as_str(), "pin\n");
+        std::fs::write(&path, "\n").unwrap();
+        assert!(read_secret_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_file_wins_over_unset_env_and_missing_file_errors() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("secret");
+        std::fs::write(&path, "from-file\n").unwrap();
+        let unset = "CCLINK_TEST_UNSET_SECRET_VAR";
+        assert_eq!(
+            resolve(Some(&path), unset).unwrap().unwrap().as_str(),
+            "from-file"
+        );
+        assert!(resolve(None, unset).unwrap().is_none());
+        assert!(resolve(Some(&dir.path().join("missing")), unset).is_err());
+    }
+}
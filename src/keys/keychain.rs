@@ -0,0 +1,50 @@
+//! Key passphrase storage in the OS keyring: the macOS Keychain, Windows Credential
+//! Manager, or the Secret Service (GNOME Keyring, KWallet) on Linux.
+//!
+//! `cclink key remember` stores the passphrase of an encrypted key file, and loading
+//! that key file then tries the stored passphrase before prompting. Entries are keyed
+//! by the key file's path, so every profile (and a rotated-out key) has its own. A
+//! keyring that is locked, missing, or unreachable behaves like an empty one.
+
+use std::path::Path;
+
+use zeroize::Zeroizing;
+
+/// Service name of cclink's keyring entries.
+const SERVICE: &str = "cclink";
+
+fn entry(key_path: &Path) -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(SERVICE, &key_path.display().to_string())
+}
+
+/// The passphrase stored for the key file at `key_path`, if any.
+pub fn remembered(key_path: &Path) -> Option<Zeroizing<String>> {
+    match entry(key_path).and_then(|e| e.get_password()) {
+        Ok(passphrase) => Some(Zeroizing::new(passphrase)),
+        Err(keyring::Error::NoEntry) => None,
+        Err(e) => {
+            tracing::debug!(error = %e, "keyring lookup failed");
+            None
+        }
+    }
+}
+
+/// Store `passphrase` for the key file at `key_path`, replacing any stored one.
+pub fn remember(key_path: &Path, passphrase: &str) -> anyhow::Result<()> {
+    entry(key_path)
+        .and_then(|e| e.set_password(passphrase))
+        .map_err(|e| anyhow::anyhow!("Could not store the passphrase in the OS keyring: {}", e))
+}
+
+/// Delete the passphrase stored for the key file at `key_path`. Returns whether one
+/// was stored.
+pub fn forget(key_path: &Path) -> anyhow::Result<bool> {
+    match entry(key_path).and_then(|e| e.delete_credential()) {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(anyhow::anyhow!(
+            "Could not remove the passphrase from the OS keyring: {}",
+            e
+        )),
+    }
+}
@@ -0,0 +1,87 @@
+//! BIP39 mnemonic encoding of the secret key seed.
+//!
+//! The 32-byte Ed25519 seed is used directly as BIP39 entropy, giving 24 English
+//! words whose last one carries a checksum. This is only a transcription of the seed:
+//! the BIP39 passphrase-stretching step is not applied, so the phrase is not a wallet
+//! seed and a wallet will not derive the same key from it.
+
+use zeroize::Zeroizing;
+
+/// Number of words in a phrase for a 32-byte seed.
+pub const WORD_COUNT: usize = 24;
+
+/// Encode `seed` as a 24-word phrase, words separated by single spaces.
+pub fn to_phrase(seed: &[u8; 32]) -> Zeroizing<String> {
+    let mnemonic =
+        bip39::Mnemonic::from_entropy(seed).expect("32 bytes is a valid BIP39 entropy length");
+    Zeroizing::new(mnemonic.words().collect::<Vec<_>>().join(" "))
+}
+
+/// Decode a 24-word phrase back into the seed.
+///
+/// Case and whitespace are ignored. Fails on an unknown word, the wrong number of
+/// words, or a checksum mismatch (a mistyped or swapped word).
+pub fn from_phrase(phrase: &str) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+    let normalized = Zeroizing::new(
+        phrase
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    let words = normalized.split(' ').filter(|w| !w.is_empty()).count();
+    if words != WORD_COUNT {
+        anyhow::bail!(
+            "expected a {}-word recovery phrase, got {} words",
+            WORD_COUNT,
+            words
+        );
+    }
+    let mnemonic = bip39::Mnemonic::parse_in_normalized(bip39::Language::English, &normalized)
+        .map_err(|e| anyhow::anyhow!("invalid recovery phrase: {}", e))?;
+    let (entropy, len) = mnemonic.to_entropy_array();
+    let entropy = Zeroizing::new(entropy);
+    let mut seed = Zeroizing::new([0u8; 32]);
+    if len != seed.len() {
+        anyhow::bail!("recovery phrase does not hold a 32-byte key");
+    }
+    seed.copy_from_slice(&entropy[..len]);
+    Ok(seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_vector() {
+        // BIP39 reference vector for 256 bits of zero entropy.
+        let phrase = to_phrase(&[0u8; 32]);
+        assert_eq!(
+            phrase.as_str(),
+            format!("{} art", ["abandon"; 23].join(" "))
+        );
+    }
+
+    #[test]
+    fn test_round_trip_ignores_case_and_spacing() {
+        let seed = [0x5au8; 32];
+        let phrase = to_phrase(&seed);
+        let messy = format!("  {}\n", phrase.to_uppercase().replace(' ', "   "));
+        assert_eq!(*from_phrase(&messy).unwrap(), seed);
+    }
+
+    #[test]
+    fn test_rejects_wrong_length_and_bad_checksum() {
+        let phrase = to_phrase(&[7u8; 32]);
+        let short: Vec<&str> = phrase.split(' ').take(12).collect();
+        assert!(from_phrase(&short.join(" ")).is_err());
+
+        // Swapping two words keeps the length but breaks the checksum.
+        let mut words: Vec<&str> = phrase.split(' ').collect();
+        words.swap(0, 1);
+        if words[0] != words[1] {
+            assert!(from_phrase(&words.join(" ")).is_err());
+        }
+    }
+}
@@ -1,2 +1,10 @@
+pub mod age_identity;
+pub mod agent;
+pub mod device;
 pub mod fingerprint;
+pub mod input;
+pub mod keychain;
+pub mod mnemonic;
+pub mod paper;
+pub mod ssh;
 pub mod store;
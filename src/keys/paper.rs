@@ -0,0 +1,97 @@
+//! Paper backup of the secret key as a QR code.
+//!
+//! The QR code holds the passphrase-encrypted CCLINKEK key envelope as one line of
+//! text, `cclink-key1:<base64>`, so a printout is useless without the passphrase. It
+//! reads back from a scanned image (decoded with `zbarimg` from zbar-tools) or from
+//! the text a phone's QR scanner shows, pasted in.
+
+use std::path::Path;
+use std::process::Command;
+
+use base64::Engine;
+
+/// Prefix of the encoded backup text; the digit versions the layout.
+const PREFIX: &str = "cclink-key1:";
+
+/// Encode a CCLINKEK envelope as backup text.
+pub fn encode(envelope: &[u8]) -> String {
+    format!(
+        "{}{}",
+        PREFIX,
+        base64::engine::general_purpose::STANDARD.encode(envelope)
+    )
+}
+
+/// Decode backup text into the CCLINKEK envelope it carries. Surrounding whitespace
+/// and line breaks inserted by the scanner or the paste are ignored.
+pub fn decode(text: &str) -> anyhow::Result<Vec<u8>> {
+    let compact: String = text.split_whitespace().collect();
+    let encoded = compact
+        .strip_prefix(PREFIX)
+        .ok_or_else(|| anyhow::anyhow!("not a cclink key backup (expected '{}...')", PREFIX))?;
+    let envelope = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| anyhow::anyhow!("key backup is corrupted: {}", e))?;
+    if !envelope.starts_with(b"CCLINKEK") {
+        anyhow::bail!("key backup does not hold an encrypted key");
+    }
+    Ok(envelope)
+}
+
+/// Render backup text as a standalone SVG QR code, for printing.
+pub fn svg(text: &str) -> anyhow::Result<String> {
+    let code = qrcode::QrCode::new(text.as_bytes())
+        .map_err(|e| anyhow::anyhow!("QR code render failed: {}", e))?;
+    Ok(code
+        .render::<qrcode::render::svg::Color>()
+        .min_dimensions(400, 400)
+        .build())
+}
+
+/// Read the text of the QR code in the image at `path` with `zbarimg`.
+pub fn scan_image(path: &Path) -> anyhow::Result<String> {
+    let output = Command::new("zbarimg")
+        .args(["--raw", "--quiet"])
+        .arg(path)
+        .output()
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "reading a QR image needs zbarimg (zbar-tools): {}. \
+                 Or pass - instead and paste the code's text",
+                e
+            )
+        })?;
+    if !output.status.success() {
+        anyhow::bail!("no QR code found in {}", path.display());
+    }
+    String::from_utf8(output.stdout).map_err(|_| anyhow::anyhow!("QR code is not text"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_through_a_wrapped_paste() {
+        let envelope = [b"CCLINKEK".as_slice(), &[1u8; 100]].concat();
+        let text = encode(&envelope);
+        let (head, tail) = text.split_at(40);
+        assert_eq!(
+            decode(&format!("  {}\n{}\n", head, tail)).unwrap(),
+            envelope
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_foreign_text() {
+        assert!(decode("https://example.com").is_err());
+        assert!(decode(&encode(b"not an envelope")).is_err());
+    }
+
+    #[test]
+    fn test_svg_renders() {
+        let svg = svg(&encode(b"CCLINKEK")).unwrap();
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains("<svg"));
+    }
+}
@@ -1,19 +1,93 @@
 use anyhow::Context;
 use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use zeroize::Zeroizing;
 
 use crate::error::CclinkError;
 
+/// Profile selected for this process with [`set_profile`].
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Select the named profile for the rest of the process. Its key file, homeserver
+/// setting, and every cclink data file live in `~/.pubky/profiles/<name>/` instead
+/// of `~/.pubky/`. Can be called once, before anything reads the key directory.
+pub fn set_profile(name: &str) -> anyhow::Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!(
+            "invalid profile name '{}': use letters, digits, '-' and '_'",
+            name
+        );
+    }
+    PROFILE
+        .set(name.to_string())
+        .map_err(|_| anyhow::anyhow!("a profile is already selected"))
+}
+
+/// The profile selected with [`set_profile`], or `None` for the default one.
+pub fn profile() -> Option<&'static str> {
+    PROFILE.get().map(String::as_str)
+}
+
 pub fn key_dir() -> anyhow::Result<PathBuf> {
     let home = dirs::home_dir().ok_or(CclinkError::HomeDirNotFound)?;
-    Ok(home.join(".pubky"))
+    Ok(profile_dir(&home.join(".pubky"), profile()))
+}
+
+/// Directory of `profile` under the base key directory; the base itself by default.
+pub(crate) fn profile_dir(base: &Path, profile: Option<&str>) -> PathBuf {
+    match profile {
+        Some(name) => base.join("profiles").join(name),
+        None => base.to_path_buf(),
+    }
 }
 
 pub fn secret_key_path() -> anyhow::Result<PathBuf> {
     Ok(key_dir()?.join("secret_key"))
 }
 
+/// Key file of the key retired by the last `cclink key rotate`, kept for its grace period.
+pub fn previous_key_path() -> anyhow::Result<PathBuf> {
+    Ok(key_dir()?.join("secret_key.previous"))
+}
+
+/// The transition published by the last `cclink key rotate`.
+pub fn rotation_path() -> anyhow::Result<PathBuf> {
+    Ok(key_dir()?.join("cclink_rotation.json"))
+}
+
+/// The retired keypair, while its grace period lasts.
+///
+/// Once the grace period recorded in `cclink_rotation.json` is over, the retired key
+/// file and the rotation record are deleted and `None` is returned.
+pub fn load_previous_keypair() -> anyhow::Result<Option<pkarr::Keypair>> {
+    let key_path = previous_key_path()?;
+    let rotation_path = rotation_path()?;
+    if !key_path.exists() || !rotation_path.exists() {
+        return Ok(None);
+    }
+    let raw = std::fs::read_to_string(&rotation_path)
+        .with_context(|| format!("Failed to read {}", rotation_path.display()))?;
+    let transition: crate::record::transition::Transition = serde_json::from_str(&raw)
+        .with_context(|| format!("Failed to parse {}", rotation_path.display()))?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)?
+        .as_secs();
+    if now >= transition.grace_until {
+        tracing::debug!(old = %transition.old_pubkey, "grace period over, deleting retired key");
+        std::fs::remove_file(&key_path)
+            .with_context(|| format!("Failed to delete {}", key_path.display()))?;
+        std::fs::remove_file(&rotation_path)
+            .with_context(|| format!("Failed to delete {}", rotation_path.display()))?;
+        return Ok(None);
+    }
+    load_keypair_from(&key_path).map(Some)
+}
+
 pub fn homeserver_path() -> anyhow::Result<PathBuf> {
     Ok(key_dir()?.join("cclink_homeserver"))
 }
@@ -60,7 +134,6 @@ pub fn write_keypair_atomic(keypair: &pkarr::Keypair, dest: &Path) -> anyhow::Re
     Ok(())
 }
 
-#[allow(dead_code)]
 pub fn write_homeserver(homeserver: &str) -> anyhow::Result<()> {
     let path = homeserver_path()?;
     std::fs::write(&path, homeserver)
@@ -68,7 +141,6 @@ pub fn write_homeserver(homeserver: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
-#[allow(dead_code)]
 pub fn read_homeserver() -> anyhow::Result<String> {
     let default_pk = "ufibwbmed6jeq9k4p583go95wofakh9fwpp4k734trq79pd9u1uy";
     let path = homeserver_path()?;
@@ -137,25 +209,32 @@ pub fn write_encrypted_keypair_atomic(envelope: &[u8], dest: &Path) -> anyhow::R
 /// delegated to pkarr.
 ///
 /// Transparently detects the file format:
-/// - CCLINKEK magic bytes → encrypted envelope → prompts for passphrase (interactive)
+/// - CCLINKEK magic bytes → encrypted envelope → seed from a running `cclink agent`,
+///   else passphrase from the OS keyring (`cclink key remember`), else prompts for
+///   it (interactive)
 /// - Otherwise → plaintext hex key → decoded directly with no passphrase prompt
 ///
 /// This provides backward compatibility: existing hex key files load without any
 /// change in behavior.
 pub fn load_keypair() -> anyhow::Result<pkarr::Keypair> {
-    let path = secret_key_path()?;
+    load_keypair_from(&secret_key_path()?)
+}
+
+/// Load the keypair from the key file at `path`, as [`load_keypair`] does for the
+/// current key.
+pub fn load_keypair_from(path: &Path) -> anyhow::Result<pkarr::Keypair> {
     if !path.exists() {
         return Err(CclinkError::NoKeypairFound.into());
     }
     // Enforce 0600 permissions before reading key material (SEC-02).
-    check_key_permissions(&path)?;
+    check_key_permissions(path)?;
 
     // Read as raw bytes — CCLINKEK envelopes are binary, not valid UTF-8.
-    let raw = std::fs::read(&path)
+    let raw = std::fs::read(path)
         .with_context(|| format!("Failed to read key file: {}", path.display()))?;
 
     if raw.starts_with(b"CCLINKEK") {
-        load_encrypted_keypair(&raw)
+        load_encrypted_keypair(path, &raw)
     } else {
         load_plaintext_keypair(&raw)
     }
@@ -192,14 +271,35 @@ fn load_plaintext_keypair(raw: &[u8]) -> anyhow::Result<pkarr::Keypair> {
     Ok(pkarr::Keypair::from_secret_key(&seed))
 }
 
-/// Prompt for a passphrase interactively and decrypt a CCLINKEK envelope.
+/// Unlock the CCLINKEK envelope read from `path` with the passphrase supplied for this
+/// run (`keys::input`), through a running agent, or with the passphrase remembered in
+/// the OS keyring, or else one prompted for.
 ///
-/// Requires an interactive terminal — rejects piped/redirected stdin with a clear
-/// error message. On wrong passphrase, prints a user-facing message and exits(1)
-/// so the caller never receives an incorrect keypair silently.
-fn load_encrypted_keypair(envelope: &[u8]) -> anyhow::Result<pkarr::Keypair> {
+/// The prompt requires an interactive terminal — rejects piped/redirected stdin with a
+/// clear error message. On wrong passphrase, prints a user-facing message and exits(1)
+/// so the caller never receives an incorrect keypair silently. A remembered passphrase
+/// that no longer fits (the key file was replaced) falls back to the prompt.
+fn load_encrypted_keypair(path: &Path, envelope: &[u8]) -> anyhow::Result<pkarr::Keypair> {
+    // A passphrase supplied for this run is what the user asked for: no fallbacks.
+    if let Some(passphrase) = crate::keys::input::passphrase()? {
+        return load_encrypted_keypair_with_passphrase(envelope, &passphrase).map_err(|_| {
+            anyhow::anyhow!("Wrong passphrase (from --passphrase-file or CCLINK_PASSPHRASE)")
+        });
+    }
+    if let Some(seed) = crate::keys::agent::request_seed(envelope) {
+        return Ok(pkarr::Keypair::from_secret_key(&seed));
+    }
+    if let Some(passphrase) = crate::keys::keychain::remembered(path) {
+        match load_encrypted_keypair_with_passphrase(envelope, &passphrase) {
+            Ok(kp) => return Ok(kp),
+            Err(_) => tracing::debug!("remembered passphrase no longer fits the key file"),
+        }
+    }
     if !std::io::stdin().is_terminal() {
-        anyhow::bail!("Encrypted keypair requires interactive terminal for passphrase entry");
+        anyhow::bail!(
+            "Encrypted keypair requires interactive terminal for passphrase entry \
+             (or set CCLINK_PASSPHRASE / --passphrase-file)"
+        );
     }
     let passphrase = Zeroizing::new(
         dialoguer::Password::new()
@@ -221,7 +321,7 @@ fn load_encrypted_keypair(envelope: &[u8]) -> anyhow::Result<pkarr::Keypair> {
 /// This is the testable core: no I/O, no terminal dependency. Returns `Ok(Keypair)`
 /// on success or propagates the `Err` from `decrypt_key_envelope` on failure.
 /// The interactive wrapper (`load_encrypted_keypair`) converts the `Err` to an exit(1).
-fn load_encrypted_keypair_with_passphrase(
+pub fn load_encrypted_keypair_with_passphrase(
     envelope: &[u8],
     passphrase: &str,
 ) -> anyhow::Result<pkarr::Keypair> {
@@ -268,6 +368,16 @@ mod tests {
     use super::*;
     use crate::crypto::encrypt_key_envelope;
 
+    #[test]
+    fn test_profile_dir() {
+        let base = Path::new("/home/me/.pubky");
+        assert_eq!(profile_dir(base, None), base);
+        assert_eq!(
+            profile_dir(base, Some("work")),
+            Path::new("/home/me/.pubky/profiles/work")
+        );
+    }
+
     // ── Encrypted key store tests (Phase 16) ────────────────────────────────
 
     #[test]
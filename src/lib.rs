@@ -1,10 +1,258 @@
-/// cclink library crate — exposes internal modules for integration tests.
-///
-/// All modules are re-exported publicly so that `tests/` integration tests
-/// can access crypto, record, and transport functions via `use cclink::crypto::*`.
+//! cclink library crate — everything but the command line.
+//!
+//! The `cclink` binary is argument parsing and the commands on top of these modules,
+//! which are public so that `tests/` integration tests and other tools can use them
+//! directly, e.g. `use cclink::crypto::*` or `cclink::session::discover_sessions`.
+//!
+//! Tools that embed cclink can use [`Cclink`] instead: one identity and its DHT
+//! client, with the publish, pickup, list and revoke steps of the commands.
+
+pub mod cache;
+pub mod clipboard;
+pub mod config;
+pub mod contacts;
 pub mod crypto;
 pub mod error;
+pub mod git;
+pub mod handoff;
+pub mod history;
+pub mod hooks;
 pub mod keys;
+pub mod notify;
+pub mod platform;
 pub mod record;
+pub mod session;
+pub mod state;
+pub mod tmux;
 pub mod transport;
+pub mod trust;
+pub mod ui;
 pub mod util;
+
+use std::time::SystemTime;
+
+use crate::error::CclinkError;
+use crate::record::{HandoffRecord, Payload, SealedHandoff};
+
+/// One cclink identity and its DHT client.
+///
+/// Each method does what the command of the same name does at its core. Nothing is
+/// printed or prompted for, except a key passphrase in [`Cclink::open`], and the
+/// CLI's local state is left alone: no hooks, history, trusted keys or handoff cache.
+///
+/// ```no_run
+/// let cclink = cclink::Cclink::open()?;
+/// for record in cclink.list_handoffs(&[])? {
+///     println!("{} expires in {}s", record.pubkey, record.ttl);
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct Cclink {
+    keypair: pkarr::Keypair,
+    client: transport::DhtClient,
+}
+
+impl Cclink {
+    /// Use the key in `~/.pubky`, as the CLI does. An encrypted key file takes its
+    /// passphrase from `CCLINK_PASSPHRASE` or the OS keyring, or prompts for it.
+    pub fn open() -> anyhow::Result<Self> {
+        Self::with_keypair(keys::store::load_keypair()?)
+    }
+
+    /// Use `keypair` as the identity.
+    pub fn with_keypair(keypair: pkarr::Keypair) -> anyhow::Result<Self> {
+        Ok(Self {
+            keypair,
+            client: transport::DhtClient::new()?,
+        })
+    }
+
+    pub fn public_key(&self) -> pkarr::PublicKey {
+        self.keypair.public_key()
+    }
+
+    /// Publish `session` as this identity's handoff for `ttl` seconds, replacing the
+    /// previous one. It is encrypted to `recipient`, a z32 public key, or without one
+    /// to this key.
+    pub fn publish_session(
+        &self,
+        session: &session::SessionInfo,
+        ttl: u64,
+        recipient: Option<&str>,
+    ) -> anyhow::Result<HandoffRecord> {
+        let sealed = seal_session(&self.keypair, session, ttl, recipient, now()?)?;
+        self.client.publish_sealed(&self.keypair, &sealed)?;
+        Ok(sealed.record)
+    }
+
+    /// The handoff of `publisher` (default: this identity), decrypted.
+    ///
+    /// Fails with [`CclinkError::HandoffExpired`] once its TTL has passed, and when
+    /// it is PIN-protected or encrypted for someone else. A burn-after-read handoff
+    /// is not burned.
+    pub fn pickup_latest(
+        &self,
+        publisher: Option<&pkarr::PublicKey>,
+    ) -> anyhow::Result<(HandoffRecord, Payload)> {
+        let publisher = publisher.cloned().unwrap_or_else(|| self.public_key());
+        let record = self.client.resolve_record(&publisher.to_z32())?;
+        let now = now()?;
+        let expires_at = record.created_at.saturating_add(record.ttl);
+        if now >= expires_at {
+            return Err(CclinkError::HandoffExpired(util::human_duration(now - expires_at)).into());
+        }
+        let ciphertext = handoff::ciphertext(&self.client, &publisher, &publisher, &record)?;
+        let payload = open_payload(&self.keypair, &record, &ciphertext)?;
+        Ok((record, payload))
+    }
+
+    /// The active handoffs of `publishers`, or of this identity when empty.
+    /// Publishers with nothing published, or only an expired handoff, are left out.
+    pub fn list_handoffs(
+        &self,
+        publishers: &[pkarr::PublicKey],
+    ) -> anyhow::Result<Vec<HandoffRecord>> {
+        let own = [self.public_key()];
+        let publishers = if publishers.is_empty() {
+            &own[..]
+        } else {
+            publishers
+        };
+        let now = now()?;
+        let mut records = Vec::new();
+        for publisher in publishers {
+            match self.client.resolve_record(&publisher.to_z32()) {
+                Ok(record) if now < record.created_at.saturating_add(record.ttl) => {
+                    records.push(record)
+                }
+                Ok(_) => {}
+                Err(e)
+                    if matches!(
+                        e.downcast_ref::<CclinkError>(),
+                        Some(CclinkError::RecordNotFound)
+                    ) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(records)
+    }
+
+    /// Revoke this identity's handoff, like `cclink revoke --yes`.
+    pub fn revoke(&self) -> anyhow::Result<()> {
+        self.client.revoke(&self.keypair)
+    }
+}
+
+fn now() -> anyhow::Result<u64> {
+    Ok(SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs())
+}
+
+/// Encrypt and sign `session` as a handoff of `keypair`, in chunks if it does not
+/// fit one packet.
+fn seal_session(
+    keypair: &pkarr::Keypair,
+    session: &session::SessionInfo,
+    ttl: u64,
+    recipient: Option<&str>,
+    created_at: u64,
+) -> anyhow::Result<SealedHandoff> {
+    let (plaintext, compressed) = handoff::encode_payload(&handoff::session_payload(session))?;
+    let ciphertext =
+        crypto::age_encrypt(&plaintext, handoff::recipient(keypair, recipient)?.as_ref())?;
+    HandoffRecord::builder(ciphertext, ttl)
+        .compressed(compressed)
+        .created_at(created_at)
+        .recipient(recipient.map(str::to_string))
+        .seal(keypair)
+}
+
+/// Decrypt the payload of `record` with `keypair`, or with this machine's device key
+/// when the handoff was sent to it with `--to`.
+fn open_payload(
+    keypair: &pkarr::Keypair,
+    record: &HandoffRecord,
+    ciphertext: &[u8],
+) -> anyhow::Result<Payload> {
+    if record.pin_salt.is_some() {
+        anyhow::bail!("this handoff is PIN-protected; pick it up with `cclink pickup`");
+    }
+    match record.recipient.as_deref() {
+        Some(recipient) if recipient != keypair.public_key().to_z32() => {
+            let device = handoff::device_for(record)?.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "this handoff was shared with {}; only the recipient can decrypt it",
+                    recipient
+                )
+            })?;
+            handoff::open(ciphertext, &device.identity, record)
+        }
+        _ => handoff::open(
+            ciphertext,
+            &crypto::age_identity(&crypto::ed25519_to_x25519_secret(keypair)),
+            record,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+
+    fn session() -> session::SessionInfo {
+        session::SessionInfo {
+            agent: session::Agent::Claude,
+            session_id: "abc-123".to_string(),
+            project: "/home/me/code/app".to_string(),
+            mtime: SystemTime::UNIX_EPOCH,
+        }
+    }
+
+    /// The ciphertext of `sealed`, reassembled from its chunks when it did not fit
+    /// inline: a shared handoff is close enough to the packet budget to go either way.
+    fn ciphertext(sealed: &SealedHandoff) -> Vec<u8> {
+        let Some((frames, manifest)) = &sealed.chunks else {
+            return base64::engine::general_purpose::STANDARD
+                .decode(&sealed.record.blob)
+                .unwrap();
+        };
+        let pubkey = sealed.record.pubkey.as_str().try_into().unwrap();
+        let mut verifier = record::chunked::ChunkVerifier::new(
+            manifest,
+            &pubkey,
+            sealed.record.created_at,
+            Vec::new(),
+        )
+        .unwrap();
+        for frame in frames {
+            verifier.push(frame).unwrap();
+        }
+        verifier.finish().unwrap()
+    }
+
+    #[test]
+    fn test_sealed_session_opens_for_self_and_recipient_only() {
+        let me = pkarr::Keypair::from_secret_key(&[1u8; 32]);
+        let them = pkarr::Keypair::from_secret_key(&[2u8; 32]);
+
+        let own = seal_session(&me, &session(), 3600, None, 1_000).unwrap();
+        let payload = open_payload(&me, &own.record, &ciphertext(&own)).unwrap();
+        assert_eq!(payload.session_id, "abc-123");
+        assert_eq!(payload.project, "/home/me/code/app");
+        assert!(open_payload(&them, &own.record, &ciphertext(&own)).is_err());
+
+        let shared = seal_session(
+            &me,
+            &session(),
+            3600,
+            Some(&them.public_key().to_z32()),
+            1_000,
+        )
+        .unwrap();
+        let payload = open_payload(&them, &shared.record, &ciphertext(&shared)).unwrap();
+        assert_eq!(payload.session_id, "abc-123");
+        record::verify_record(&shared.record, &me.public_key()).unwrap();
+    }
+}
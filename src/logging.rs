@@ -0,0 +1,147 @@
+//! Diagnostic logging: `tracing` events written to stderr.
+//!
+//! Verbosity is controlled by the global `-v` flag (`-v` = debug, `-vv` = trace,
+//! including pkarr internals). When `RUST_LOG` is set it takes precedence and
+//! uses the standard `EnvFilter` directive syntax, e.g. `RUST_LOG=cclink=trace`.
+//!
+//! With `--timing`, every event carrying an `elapsed_ms` field (DHT lookups and
+//! publishes, Argon2 derivations, LAN fetches) is also tallied by message, whatever
+//! the verbosity, and [`print_timing`] summarizes them when the command ends.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::IsTerminal;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tracing::field::{Field, Visit};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+/// Timed operations seen so far, by event message.
+static TIMINGS: Mutex<BTreeMap<String, Timing>> = Mutex::new(BTreeMap::new());
+
+/// Install the global tracing subscriber. Call once, at the top of `main`.
+pub fn init(verbosity: u8, timing: bool) {
+    let filter = match std::env::var("RUST_LOG") {
+        Ok(directives) if !directives.trim().is_empty() => EnvFilter::new(directives),
+        _ => EnvFilter::new(match verbosity {
+            0 => "cclink=warn",
+            1 => "cclink=debug",
+            _ => "cclink=trace,pkarr=debug,mainline=debug",
+        }),
+    };
+
+    let fmt = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal() && !crate::ui::no_color())
+        .with_timer(tracing_subscriber::fmt::time::uptime())
+        .with_target(verbosity > 1)
+        .with_filter(filter);
+    let timing = timing.then(|| TimingLayer.with_filter(EnvFilter::new("cclink=trace")));
+    tracing_subscriber::registry().with(fmt).with(timing).init();
+}
+
+/// Print the operations tallied under `--timing` and the command's `total` run time
+/// to stderr.
+pub fn print_timing(total: Duration) {
+    let timings = TIMINGS.lock().unwrap_or_else(|e| e.into_inner());
+    let width = timings.keys().map(String::len).max().unwrap_or(0).max(5);
+    eprintln!();
+    eprintln!(
+        "{:<width$}  {:>5}  {:>9}  {:>9}",
+        "Timing", "calls", "total", "slowest"
+    );
+    for (message, timing) in timings.iter() {
+        eprintln!(
+            "{:<width$}  {:>5}  {:>6} ms  {:>6} ms",
+            message, timing.count, timing.total_ms, timing.max_ms
+        );
+    }
+    eprintln!(
+        "{:<width$}  {:>5}  {:>6} ms",
+        "total",
+        "",
+        total.as_millis()
+    );
+}
+
+/// Calls, summed and slowest duration of one kind of operation.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Timing {
+    count: u32,
+    total_ms: u64,
+    max_ms: u64,
+}
+
+impl Timing {
+    fn add(&mut self, elapsed_ms: u64) {
+        self.count += 1;
+        self.total_ms += elapsed_ms;
+        self.max_ms = self.max_ms.max(elapsed_ms);
+    }
+}
+
+/// Tallies events that carry an `elapsed_ms` field into [`TIMINGS`].
+struct TimingLayer;
+
+impl<S: tracing::Subscriber> Layer<S> for TimingLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = TimedFields::default();
+        event.record(&mut fields);
+        if let (Some(message), Some(elapsed_ms)) = (fields.message, fields.elapsed_ms) {
+            TIMINGS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(message)
+                .or_default()
+                .add(elapsed_ms);
+        }
+    }
+}
+
+#[derive(Default)]
+struct TimedFields {
+    message: Option<String>,
+    elapsed_ms: Option<u64>,
+}
+
+impl Visit for TimedFields {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "elapsed_ms" {
+            self.elapsed_ms = Some(value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{:?}", value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_layer_tallies_timed_events() {
+        let subscriber = tracing_subscriber::registry().with(TimingLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!(elapsed_ms = 30u64, "test lookup complete");
+            tracing::debug!(elapsed_ms = 10u64, "test lookup complete");
+            tracing::debug!("test event without a duration");
+        });
+        let timings = TIMINGS.lock().unwrap();
+        assert_eq!(
+            timings.get("test lookup complete"),
+            Some(&Timing {
+                count: 2,
+                total_ms: 40,
+                max_ms: 30
+            })
+        );
+        assert!(!timings.contains_key("test event without a duration"));
+    }
+}
@@ -1,25 +1,105 @@
 mod cli;
 mod commands;
-mod crypto;
-mod error;
-mod keys;
-mod record;
-mod session;
-mod transport;
-mod util;
+mod logging;
+mod progress;
 
+// Everything but the command line lives in the library crate. Importing its modules
+// at the root keeps `crate::keys` and the like working throughout the commands.
+use cclink::{
+    cache, clipboard, config, contacts, crypto, error, git, handoff, history, hooks, keys, notify,
+    platform, record, session, state, status, tmux, transport, trust, ui, util,
+};
 use clap::Parser;
 use cli::{Cli, Commands};
 
-fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
+fn main() {
+    let cli = Cli::try_parse().unwrap_or_else(|e| {
+        // Bad arguments exit 1 like any other failure; 2 means "not found".
+        let _ = e.print();
+        std::process::exit(if e.use_stderr() { 1 } else { 0 });
+    });
+    if cli.no_color || ui::no_color_env() {
+        ui::set_no_color();
+    }
+    logging::init(cli.verbose, cli.timing);
+
+    let timing = cli.timing;
+    let quiet = cli.quiet;
+    let started = std::time::Instant::now();
+    let result = run(cli);
+    if timing {
+        logging::print_timing(started.elapsed());
+    }
+    if let Err(e) = result {
+        error::report(&e, quiet);
+        std::process::exit(error::exit_code(&e));
+    }
+}
+
+fn run(cli: Cli) -> anyhow::Result<()> {
+    let profile = cli.profile.clone().or_else(|| {
+        std::env::var("CCLINK_PROFILE")
+            .ok()
+            .filter(|p| !p.is_empty())
+    });
+    if let Some(ref name) = profile {
+        keys::store::set_profile(name)?;
+    }
+    if let Some(path) = cli.passphrase_file.clone() {
+        keys::input::set_passphrase_file(path)?;
+    }
+    if let Some(path) = cli.pin_file.clone() {
+        keys::input::set_pin_file(path)?;
+    }
+    if cli.quiet {
+        ui::set_quiet();
+    }
+    let config = config::load()?;
+    ui::set_theme(config.theme);
+    let mut network = config.network;
+    network.timeout = cli.timeout.or(network.timeout);
+    network.max_retries = cli.max_retries.unwrap_or(network.max_retries);
+    config::set_network(network)?;
 
     match cli.command {
         Some(Commands::Init(args)) => commands::init::run_init(args)?,
         Some(Commands::Whoami) => commands::whoami::run_whoami()?,
         Some(Commands::Pickup(args)) => commands::pickup::run_pickup(args)?,
-        Some(Commands::List) => commands::list::run_list()?,
+        Some(Commands::Copy) => commands::publish::run_copy(&cli)?,
+        Some(Commands::Paste(args)) => commands::pickup::run_paste(args)?,
+        Some(Commands::Export(ref args)) => commands::publish::run_export(&cli, args)?,
+        Some(Commands::Import(args)) => commands::pickup::run_import(args)?,
+        Some(Commands::Serve(ref args)) => commands::publish::run_serve(&cli, args)?,
+        Some(Commands::List(args)) => commands::list::run_list(args)?,
+        Some(Commands::Status) => commands::status::run_status()?,
+        Some(Commands::Inspect(args)) => commands::inspect::run_inspect(args)?,
         Some(Commands::Revoke(args)) => commands::revoke::run_revoke(args)?,
+        Some(Commands::Prune(args)) => commands::prune::run_prune(args)?,
+        Some(Commands::Wipe(args)) => commands::wipe::run_wipe(args)?,
+        Some(Commands::Backup(args)) => commands::backup::run_backup(args)?,
+        Some(Commands::Restore(args)) => commands::backup::run_restore(args)?,
+        Some(Commands::Ui) => commands::ui::run_ui(&cli)?,
+        Some(Commands::Doctor) => commands::doctor::run_doctor()?,
+        Some(Commands::Contacts(args)) => commands::contacts::run_contacts(args)?,
+        Some(Commands::Device(args)) => commands::device::run_device(args)?,
+        Some(Commands::Profile(args)) => commands::profile::run_profile(args)?,
+        Some(Commands::Env(args)) => commands::env::run_env(args)?,
+        Some(Commands::Trust(args)) => commands::trust::run_trust(args)?,
+        Some(Commands::Verify(args)) => commands::verify::run_verify(args)?,
+        Some(Commands::Explain(args)) => commands::explain::run_explain(args)?,
+        Some(Commands::Send(args)) => commands::send::run_send(args)?,
+        Some(Commands::Recv(args)) => commands::recv::run_recv(args)?,
+        Some(Commands::Key(args)) => commands::key::run_key(args)?,
+        Some(Commands::Monitor(args)) => commands::monitor::run_monitor(args)?,
+        Some(Commands::Receipts(args)) => commands::receipts::run_receipts(args)?,
+        Some(Commands::History(args)) => commands::history::run_history(args)?,
+        Some(Commands::Listen(args)) => commands::listen::run_listen(args)?,
+        Some(Commands::Agent(args)) => commands::agent::run_agent(args)?,
+        Some(Commands::Sessions(ref args)) => {
+            if let Some(session) = commands::sessions::run_sessions(args, cli.agent)? {
+                commands::publish::publish_session(&cli, Some(session))?;
+            }
+        }
         None => commands::publish::run_publish(&cli)?,
     }
 
@@ -0,0 +1,86 @@
+//! Desktop notifications through the platform's own notifier: `notify-send` on Linux
+//! and the BSDs, `osascript` on macOS, and a PowerShell tray balloon on Windows.
+//!
+//! Notifications are best-effort. A machine without a notifier (a headless server, a
+//! minimal container) reports an error the caller logs and otherwise ignores.
+
+use std::process::{Command, Stdio};
+
+/// Show a desktop notification with `title` and `body`.
+pub fn notify(title: &str, body: &str) -> anyhow::Result<()> {
+    let mut cmd = command(title, body);
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    let status = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| anyhow::anyhow!("failed to run {}: {}", program, e))?;
+    if !status.success() {
+        anyhow::bail!("{} exited with status {}", program, status);
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn command(title: &str, body: &str) -> Command {
+    let mut cmd = Command::new("osascript");
+    cmd.arg("-e").arg(format!(
+        "display notification {} with title {}",
+        applescript_string(body),
+        applescript_string(title)
+    ));
+    cmd
+}
+
+#[cfg(windows)]
+fn command(title: &str, body: &str) -> Command {
+    let script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         $n = New-Object System.Windows.Forms.NotifyIcon; \
+         $n.Icon = [System.Drawing.SystemIcons]::Information; $n.Visible = $true; \
+         $n.ShowBalloonTip(10000, {}, {}, 'Info'); Start-Sleep -Seconds 10; $n.Dispose()",
+        powershell_string(title),
+        powershell_string(body)
+    );
+    let mut cmd = Command::new("powershell");
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    cmd
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn command(title: &str, body: &str) -> Command {
+    let mut cmd = Command::new("notify-send");
+    cmd.args(["--app-name=cclink", title, body]);
+    cmd
+}
+
+/// `text` as a double-quoted AppleScript string literal.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// `text` as a single-quoted PowerShell string literal.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn powershell_string(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_applescript_string_escapes_quotes_and_backslashes() {
+        assert_eq!(
+            applescript_string(r#"say "hi" \ bye"#),
+            r#""say \"hi\" \\ bye""#
+        );
+    }
+
+    #[test]
+    fn test_powershell_string_doubles_single_quotes() {
+        assert_eq!(powershell_string("alice's laptop"), "'alice''s laptop'");
+    }
+}
@@ -0,0 +1,114 @@
+//! Platform hints: detect the local OS, architecture, and `claude` version, and
+//! compare them with the hints a publisher put in the payload.
+//!
+//! Sessions can reference platform-specific paths and tools, so resuming a macOS
+//! session on Linux, or on an older `claude` than it was recorded with, may misbehave.
+//! Pickup only warns — the user decides whether to continue.
+
+use crate::record::PlatformHints;
+
+/// Hints describing this machine.
+pub fn current() -> PlatformHints {
+    PlatformHints {
+        arch: std::env::consts::ARCH.to_string(),
+        claude_version: claude_version(),
+        os: std::env::consts::OS.to_string(),
+    }
+}
+
+/// Version reported by `claude --version` (first token, e.g. "2.0.14"), if available.
+pub fn claude_version() -> Option<String> {
+    let output = std::process::Command::new("claude")
+        .arg("--version")
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let version = stdout.split_whitespace().next()?.to_string();
+    tracing::debug!(version = %version, "detected claude version");
+    Some(version)
+}
+
+/// Human-readable warnings for every way `local` differs from `published`.
+pub fn mismatches(published: &PlatformHints, local: &PlatformHints) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if published.os != local.os || published.arch != local.arch {
+        warnings.push(format!(
+            "Session was published on {}/{}; this machine is {}/{}. \
+             Paths or tools referenced in the session may not exist here.",
+            published.os, published.arch, local.os, local.arch
+        ));
+    }
+    if let Some(ref theirs) = published.claude_version {
+        match local.claude_version {
+            Some(ref ours) if version_older(ours, theirs) => warnings.push(format!(
+                "Local claude {} is older than the publisher's {}. Consider updating before resuming.",
+                ours, theirs
+            )),
+            Some(_) => {}
+            None => warnings.push(format!(
+                "Could not determine the local claude version (publisher has {}).",
+                theirs
+            )),
+        }
+    }
+    warnings
+}
+
+/// True if dotted version `a` is strictly older than `b`. Non-numeric suffixes
+/// ("-beta") are ignored; unparseable components compare as 0.
+fn version_older(a: &str, b: &str) -> bool {
+    fn parts(v: &str) -> Vec<u64> {
+        v.trim_start_matches('v')
+            .split(['.', '-', '+'])
+            .take(3)
+            .map(|p| p.parse().unwrap_or(0))
+            .collect()
+    }
+    let (mut a, mut b) = (parts(a), parts(b));
+    a.resize(3, 0);
+    b.resize(3, 0);
+    a < b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hints(os: &str, arch: &str, claude: Option<&str>) -> PlatformHints {
+        PlatformHints {
+            arch: arch.to_string(),
+            claude_version: claude.map(str::to_string),
+            os: os.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_version_older() {
+        assert!(version_older("1.0.9", "1.0.10"));
+        assert!(version_older("1.9", "2.0.0"));
+        assert!(!version_older("2.0.0", "2.0"));
+        assert!(!version_older("2.1.0", "2.0.14"));
+        assert!(version_older("v2.0.1-beta", "2.0.2"));
+    }
+
+    #[test]
+    fn test_matching_platform_has_no_warnings() {
+        let h = hints("linux", "x86_64", Some("2.0.14"));
+        assert!(mismatches(&h, &h).is_empty());
+    }
+
+    #[test]
+    fn test_mismatches_reported() {
+        let published = hints("macos", "aarch64", Some("2.0.14"));
+        let local = hints("linux", "x86_64", Some("2.0.1"));
+        assert_eq!(mismatches(&published, &local).len(), 2);
+
+        let newer_local = hints("macos", "aarch64", Some("2.1.0"));
+        assert!(mismatches(&published, &newer_local).is_empty());
+    }
+}
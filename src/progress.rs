@@ -0,0 +1,39 @@
+//! Progress bars for chunked handoffs, drawn on stderr.
+//!
+//! A chunked publish or pickup makes one DHT request per chunk and can take a
+//! minute; [`attach`] shows a bar with throughput and ETA while it runs. Bars only
+//! appear on a terminal and never with `--quiet`.
+
+use std::sync::OnceLock;
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+use crate::transport::DhtClient;
+
+/// Show a bar labelled `label` whenever `client` moves a chunked handoff.
+pub fn attach(client: DhtClient, label: &'static str) -> DhtClient {
+    if crate::ui::is_quiet() {
+        return client;
+    }
+    let bar: OnceLock<ProgressBar> = OnceLock::new();
+    client.with_progress(move |done, total| {
+        let bar = bar.get_or_init(|| new_bar(label, total));
+        bar.set_position(done);
+        if done >= total {
+            bar.finish_and_clear();
+        }
+    })
+}
+
+fn new_bar(label: &'static str, total: u64) -> ProgressBar {
+    let bar = ProgressBar::with_draw_target(Some(total), ProgressDrawTarget::stderr());
+    bar.set_style(
+        ProgressStyle::with_template(
+            "{msg} [{bar:30}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta} left)",
+        )
+        .expect("valid progress template")
+        .progress_chars("=> "),
+    );
+    bar.set_message(label);
+    bar
+}
@@ -0,0 +1,154 @@
+//! ASCII-armored handoff records, for carrying one by email, chat or file.
+//!
+//! `cclink export` writes a record between `BEGIN`/`END` lines as base64 wrapped at 64
+//! columns, followed by a CRC-24 checksum line (the OpenPGP armor layout), and
+//! `cclink import` reads it back. The block may sit inside other text and survive
+//! email quoting. The checksum only catches damage in transit; the record's own
+//! signature is what pickup trusts.
+
+use base64::Engine;
+
+use super::HandoffRecord;
+
+/// First line of an armored record.
+pub const BEGIN: &str = "-----BEGIN CCLINK HANDOFF-----";
+
+/// Last line of an armored record.
+pub const END: &str = "-----END CCLINK HANDOFF-----";
+
+/// Characters per base64 line.
+const LINE_WIDTH: usize = 64;
+
+/// Armor `record`. Chunked records are rejected, as for the compact form.
+pub fn encode(record: &HandoffRecord) -> anyhow::Result<String> {
+    if record.blob.is_empty() {
+        anyhow::bail!("this handoff is too large to carry off the DHT (its payload is chunked)");
+    }
+    let json = serde_json::to_vec(record)
+        .map_err(|e| anyhow::anyhow!("failed to serialize record: {}", e))?;
+    let body = base64::engine::general_purpose::STANDARD.encode(&json);
+
+    let mut armored = format!("{}\n", BEGIN);
+    for line in body.as_bytes().chunks(LINE_WIDTH) {
+        armored.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        armored.push('\n');
+    }
+    armored.push_str(&format!("={}\n{}\n", checksum(&json), END));
+    Ok(armored)
+}
+
+/// Find the armored block in `text` and decode it. Text around the block and email
+/// quote markers (`> `) are ignored. The signature is not checked here.
+pub fn decode(text: &str) -> anyhow::Result<HandoffRecord> {
+    let mut lines = text
+        .lines()
+        .map(|line| line.trim_start_matches(['>', ' ']).trim_end())
+        .skip_while(|line| *line != BEGIN);
+    if lines.next().is_none() {
+        anyhow::bail!("no armored handoff found (expected a '{}' line)", BEGIN);
+    }
+
+    let mut body = String::new();
+    let mut sum = None;
+    let mut ended = false;
+    for line in lines {
+        if line == END {
+            ended = true;
+            break;
+        }
+        match line.strip_prefix('=') {
+            Some(value) => sum = Some(value.to_string()),
+            None => body.push_str(line),
+        }
+    }
+    if !ended {
+        anyhow::bail!("armored handoff is truncated (no '{}' line)", END);
+    }
+
+    let json = base64::engine::general_purpose::STANDARD
+        .decode(&body)
+        .map_err(|e| anyhow::anyhow!("armored handoff is corrupted: {}", e))?;
+    match sum {
+        Some(sum) if sum == checksum(&json) => {}
+        Some(_) => anyhow::bail!("armored handoff is corrupted: checksum mismatch"),
+        None => anyhow::bail!("armored handoff is missing its checksum line"),
+    }
+    let json = String::from_utf8(json)
+        .map_err(|_| anyhow::anyhow!("armored handoff is corrupted: not UTF-8 JSON"))?;
+    super::migrate::parse(&json)
+}
+
+/// The checksum line's value: the CRC-24 of `data`, base64-encoded.
+fn checksum(data: &[u8]) -> String {
+    let crc = crc24(data).to_be_bytes();
+    base64::engine::general_purpose::STANDARD.encode(&crc[1..])
+}
+
+/// CRC-24 as used by OpenPGP armor (RFC 4880, section 6.1).
+fn crc24(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0xB7_04CE;
+    for &byte in data {
+        crc ^= u32::from(byte) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x100_0000 != 0 {
+                crc ^= 0x186_4CFB;
+            }
+        }
+    }
+    crc & 0xFF_FFFF
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(blob: Vec<u8>) -> HandoffRecord {
+        HandoffRecord::builder(blob, 3600)
+            .created_at(1_000)
+            .sign(&pkarr::Keypair::from_secret_key(&[6u8; 32]))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_crc24_check_value() {
+        assert_eq!(crc24(b"123456789"), 0x21_CF02);
+    }
+
+    #[test]
+    fn test_round_trip_through_quoted_email() {
+        let record = record(vec![7u8; 200]);
+        let armored = encode(&record).unwrap();
+        assert!(armored.lines().all(|line| line.len() <= LINE_WIDTH));
+        assert!(armored.starts_with(BEGIN));
+
+        let quoted: String = armored
+            .lines()
+            .map(|line| format!("> {}\n", line))
+            .collect();
+        let email = format!("Here's the handoff:\n\n{}\nThanks!\n", quoted);
+        let decoded = decode(&email).unwrap();
+        assert_eq!(
+            serde_json::to_string(&decoded).unwrap(),
+            serde_json::to_string(&record).unwrap()
+        );
+        let pubkey = pkarr::PublicKey::try_from(decoded.pubkey.as_str()).unwrap();
+        crate::record::verify_record(&decoded, &pubkey).expect("signature survives");
+    }
+
+    #[test]
+    fn test_rejects_damaged_armor() {
+        let armored = encode(&record(vec![7u8; 200])).unwrap();
+        let mut lines: Vec<String> = armored.lines().map(str::to_string).collect();
+
+        let mut flipped = lines.clone();
+        let first = flipped[1].remove(0);
+        flipped[1].insert(0, if first == 'A' { 'B' } else { 'A' });
+        assert!(decode(&flipped.join("\n")).is_err());
+
+        lines.pop();
+        assert!(decode(&lines.join("\n")).is_err());
+        assert!(decode("no armor here").is_err());
+        assert!(encode(&record(Vec::new())).is_err());
+    }
+}
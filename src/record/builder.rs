@@ -0,0 +1,230 @@
+//! Builder for signed [`HandoffRecord`]s.
+//!
+//! Callers supply the ciphertext and the record options; the builder fills in the
+//! publisher's key, signs the canonical form, and returns a record whose signature
+//! covers exactly the fields it carries. A field added to the record only needs to be
+//! threaded through here, not through every place that publishes.
+//!
+//! ```no_run
+//! # fn main() -> anyhow::Result<()> {
+//! # let keypair = pkarr::Keypair::random();
+//! # let ciphertext = vec![0u8; 64];
+//! use cclink::record::HandoffRecord;
+//!
+//! let sealed = HandoffRecord::builder(ciphertext, 3600)
+//!     .burn(true)
+//!     .recipient(Some("publisher-z32-key".to_string()))
+//!     .seal(&keypair)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::SystemTime;
+
+use base64::Engine;
+
+use super::{HandoffRecord, HandoffRecordSignable, SealedHandoff};
+
+/// Options for a [`HandoffRecord`], set fluently and consumed by
+/// [`sign`](Self::sign) or [`seal`](Self::seal). Start one with [`HandoffRecord::builder`].
+#[derive(Debug, Clone)]
+#[must_use = "a builder does nothing until `sign` or `seal` is called"]
+pub struct HandoffRecordBuilder {
+    ciphertext: Vec<u8>,
+    burn: bool,
+    burn_grace: Option<u64>,
+    burn_uses: Option<u32>,
+    compressed: bool,
+    created_at: Option<u64>,
+    pin_salt: Option<[u8; 32]>,
+    recipient: Option<String>,
+    ttl: u64,
+}
+
+impl HandoffRecord {
+    /// Start building a record around `ciphertext` that expires `ttl` seconds after
+    /// its creation time.
+    pub fn builder(ciphertext: impl Into<Vec<u8>>, ttl: u64) -> HandoffRecordBuilder {
+        HandoffRecordBuilder {
+            ciphertext: ciphertext.into(),
+            burn: false,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
+            created_at: None,
+            pin_salt: None,
+            recipient: None,
+            ttl,
+        }
+    }
+}
+
+impl HandoffRecordBuilder {
+    /// Mark the record burn-after-read.
+    pub fn burn(mut self, burn: bool) -> Self {
+        self.burn = burn;
+        self
+    }
+
+    /// Allow `uses` pickups of a burn record, and keep it readable for `grace` seconds
+    /// after the last one. One use and no grace is plain burn-after-read.
+    pub fn burn_limit(mut self, uses: u32, grace: u64) -> Self {
+        self.burn_uses = (uses != 1).then_some(uses);
+        self.burn_grace = (grace != 0).then_some(grace);
+        self
+    }
+
+    /// Flag the payload as zstd-compressed before encryption.
+    pub fn compressed(mut self, compressed: bool) -> Self {
+        self.compressed = compressed;
+        self
+    }
+
+    /// Creation time in Unix seconds. Defaults to the time of signing.
+    pub fn created_at(mut self, unix_secs: u64) -> Self {
+        self.created_at = Some(unix_secs);
+        self
+    }
+
+    /// Salt the ciphertext's PIN key was derived with, for PIN-protected records.
+    pub fn pin_salt(mut self, salt: impl Into<Option<[u8; 32]>>) -> Self {
+        self.pin_salt = salt.into();
+        self
+    }
+
+    /// z32 public key the ciphertext was encrypted for (`None`: self-encrypted).
+    pub fn recipient(mut self, z32: impl Into<Option<String>>) -> Self {
+        self.recipient = z32.into();
+        self
+    }
+
+    /// Sign a record carrying the ciphertext inline.
+    ///
+    /// The result is not checked against the packet budget; use [`seal`](Self::seal)
+    /// for anything that will be published.
+    pub fn sign(self, keypair: &pkarr::Keypair) -> anyhow::Result<HandoffRecord> {
+        let (signable, ciphertext) = self.into_signable(keypair)?;
+        let blob = base64::engine::general_purpose::STANDARD.encode(ciphertext);
+        super::sign_signable(HandoffRecordSignable { blob, ..signable }, keypair)
+    }
+
+    /// Sign a record ready to publish, moving the ciphertext into chunk frames when it
+    /// does not fit in the packet.
+    ///
+    /// Fails with [`crate::error::CclinkError::RecordTooLarge`] if even the chunked form
+    /// is too big.
+    pub fn seal(self, keypair: &pkarr::Keypair) -> anyhow::Result<SealedHandoff> {
+        let (signable, ciphertext) = self.into_signable(keypair)?;
+        super::seal_signable(signable, &ciphertext, keypair)
+    }
+
+    /// The signable fields (blob still empty) and the ciphertext.
+    fn into_signable(
+        self,
+        keypair: &pkarr::Keypair,
+    ) -> anyhow::Result<(HandoffRecordSignable, Vec<u8>)> {
+        let created_at = match self.created_at {
+            Some(t) => t,
+            None => SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)?
+                .as_secs(),
+        };
+        // Outer hostname and project stay empty: since v1.1 that metadata lives only
+        // inside the encrypted payload.
+        let signable = HandoffRecordSignable {
+            blob: String::new(),
+            blob_hash: None,
+            burn: self.burn,
+            burn_grace: self.burn_grace,
+            burn_uses: self.burn_uses,
+            compressed: self.compressed,
+            created_at,
+            hostname: String::new(),
+            pin_salt: self
+                .pin_salt
+                .map(|salt| base64::engine::general_purpose::STANDARD.encode(salt)),
+            project: String::new(),
+            pubkey: keypair.public_key().to_z32(),
+            recipient: self.recipient,
+            ttl: self.ttl,
+            version: super::migrate::CURRENT_VERSION,
+        };
+        Ok((signable, self.ciphertext))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::verify_record;
+
+    fn fixed_keypair() -> pkarr::Keypair {
+        pkarr::Keypair::from_secret_key(&[42u8; 32])
+    }
+
+    #[test]
+    fn test_builder_fills_and_signs_every_field() {
+        let keypair = fixed_keypair();
+        let record = HandoffRecord::builder(vec![1, 2, 3], 600)
+            .burn(true)
+            .compressed(true)
+            .created_at(1_700_000_000)
+            .pin_salt([7u8; 32])
+            .recipient("recipientkey".to_string())
+            .sign(&keypair)
+            .unwrap();
+
+        assert_eq!(record.blob, "AQID");
+        assert!(record.burn && record.compressed);
+        assert_eq!(record.created_at, 1_700_000_000);
+        assert_eq!(
+            record.pin_salt.as_deref(),
+            Some("BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc=")
+        );
+        assert_eq!(record.pubkey, keypair.public_key().to_z32());
+        assert_eq!(record.recipient.as_deref(), Some("recipientkey"));
+        assert_eq!(record.ttl, 600);
+        assert!(record.hostname.is_empty() && record.project.is_empty());
+        verify_record(&record, &keypair.public_key()).unwrap();
+    }
+
+    #[test]
+    fn test_builder_defaults_created_at_to_now() {
+        let before = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let record = HandoffRecord::builder(vec![0], 60)
+            .sign(&fixed_keypair())
+            .unwrap();
+        assert!(record.created_at >= before);
+        assert!(!record.burn && record.pin_salt.is_none() && record.recipient.is_none());
+    }
+
+    #[test]
+    fn test_seal_chunks_oversized_ciphertext() {
+        let keypair = fixed_keypair();
+        let small = HandoffRecord::builder(vec![1u8; 100], 60)
+            .seal(&keypair)
+            .unwrap();
+        assert!(small.chunks.is_none());
+        assert!(!small.record.blob.is_empty());
+
+        let large = HandoffRecord::builder(vec![1u8; 2_000], 60)
+            .seal(&keypair)
+            .unwrap();
+        let (frames, manifest) = large.chunks.expect("2000 bytes must be chunked");
+        assert!(large.record.blob.is_empty());
+        assert_eq!(frames.len(), manifest.chunks as usize);
+        verify_record(&large.record, &keypair.public_key()).unwrap();
+
+        // The chunked record is bound to its ciphertext; the inline one needs no hash.
+        assert!(small.record.blob_hash.is_none());
+        crate::record::verify_blob(&large.record, &[1u8; 2_000]).unwrap();
+        assert!(crate::record::verify_blob(&large.record, &[2u8; 2_000]).is_err());
+        let mut stripped = large.record.clone();
+        stripped.blob_hash = None;
+        assert!(verify_record(&stripped, &keypair.public_key()).is_err());
+        assert!(crate::record::verify_blob(&stripped, &[1u8; 2_000]).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+//! Multi-use burn: the pickups a burn handoff has left.
+//!
+//! A handoff published with `--burn=N` allows N pickups instead of one. The uses left
+//! are kept in a [`BurnCounter`] in its own DHT slot, derived from the publisher's
+//! seed and the handoff's `created_at` (`crypto::burn_keypair`), so only machines
+//! holding the publisher's key can find or spend it. Each pickup writes the counter
+//! back with compare-and-swap on the slot's packet timestamp: of two concurrent
+//! pickups only one spends a given use, and the other re-reads and tries again.
+//!
+//! The pickup that spends the last use deletes the handoff. With a grace window
+//! (`--burn-grace`), it instead marks the counter exhausted and pickups keep working
+//! until the window closes; the first one after that deletes the handoff and is refused.
+
+use serde::{Deserialize, Serialize};
+
+use super::HandoffRecord;
+
+/// Uses left on a multi-use burn handoff.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BurnCounter {
+    /// Unix timestamp (seconds) the last use was spent; only set with a grace window.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exhausted_at: Option<u64>,
+    /// `created_at` of the handoff the counter belongs to.
+    pub handoff: u64,
+    /// Pickups left.
+    pub remaining: u32,
+}
+
+/// What a pickup does to a burn handoff, given the counter it read.
+#[derive(Debug, PartialEq)]
+pub enum BurnStep {
+    /// Go ahead and store the updated counter.
+    Spend(BurnCounter),
+    /// Go ahead and delete the handoff: this was its last use.
+    Delete,
+    /// Go ahead without touching the counter: the uses are spent but the grace
+    /// window is still open.
+    Grace,
+    /// Refuse and delete the handoff: uses and grace window are both spent.
+    Exhausted,
+}
+
+/// Whether `record` needs a counter, i.e. allows more than one pickup or keeps a
+/// grace window. Plain burn-after-read records are deleted on first pickup.
+pub fn is_counted(record: &HandoffRecord) -> bool {
+    record.burn && (record.burn_uses.is_some_and(|uses| uses > 1) || record.burn_grace.is_some())
+}
+
+/// Decide what a pickup at `now` does to `record`, whose counter slot holds `counter`.
+///
+/// No counter yet (or one left over from another handoff) means no use was spent.
+pub fn next_step(record: &HandoffRecord, counter: Option<&BurnCounter>, now: u64) -> BurnStep {
+    let grace = record.burn_grace.unwrap_or(0);
+    let counter = match counter {
+        Some(c) if c.handoff == record.created_at => c.clone(),
+        _ => BurnCounter {
+            exhausted_at: None,
+            handoff: record.created_at,
+            remaining: record.burn_uses.unwrap_or(1),
+        },
+    };
+    match (counter.remaining, counter.exhausted_at) {
+        (0, Some(at)) if now < at.saturating_add(grace) => BurnStep::Grace,
+        (0, _) => BurnStep::Exhausted,
+        (1, _) if grace == 0 => BurnStep::Delete,
+        (remaining, _) => BurnStep::Spend(BurnCounter {
+            exhausted_at: (remaining == 1).then_some(now),
+            remaining: remaining - 1,
+            ..counter
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(uses: u32, grace: u64) -> HandoffRecord {
+        HandoffRecord::builder(vec![0], 600)
+            .burn(true)
+            .burn_limit(uses, grace)
+            .created_at(1_000)
+            .sign(&pkarr::Keypair::from_secret_key(&[42u8; 32]))
+            .unwrap()
+    }
+
+    fn apply(record: &HandoffRecord, counter: &mut Option<BurnCounter>, now: u64) -> BurnStep {
+        let step = next_step(record, counter.as_ref(), now);
+        if let BurnStep::Spend(ref next) = step {
+            *counter = Some(next.clone());
+        }
+        step
+    }
+
+    #[test]
+    fn test_plain_burn_is_not_counted() {
+        assert!(!is_counted(&record(1, 0)));
+        assert!(is_counted(&record(3, 0)));
+        assert!(is_counted(&record(1, 60)));
+        assert_eq!(next_step(&record(1, 0), None, 1_000), BurnStep::Delete);
+    }
+
+    #[test]
+    fn test_three_uses_then_delete() {
+        let record = record(3, 0);
+        let mut counter = None;
+        assert!(matches!(
+            apply(&record, &mut counter, 1_010),
+            BurnStep::Spend(BurnCounter { remaining: 2, .. })
+        ));
+        assert!(matches!(
+            apply(&record, &mut counter, 1_020),
+            BurnStep::Spend(BurnCounter { remaining: 1, .. })
+        ));
+        assert_eq!(apply(&record, &mut counter, 1_030), BurnStep::Delete);
+    }
+
+    #[test]
+    fn test_grace_window_after_last_use() {
+        let record = record(2, 60);
+        let mut counter = None;
+        apply(&record, &mut counter, 1_010);
+        assert_eq!(
+            apply(&record, &mut counter, 1_020),
+            BurnStep::Spend(BurnCounter {
+                exhausted_at: Some(1_020),
+                handoff: 1_000,
+                remaining: 0,
+            })
+        );
+        assert_eq!(apply(&record, &mut counter, 1_079), BurnStep::Grace);
+        assert_eq!(apply(&record, &mut counter, 1_080), BurnStep::Exhausted);
+    }
+
+    #[test]
+    fn test_counter_of_another_handoff_is_ignored() {
+        let stale = BurnCounter {
+            exhausted_at: None,
+            handoff: 999,
+            remaining: 0,
+        };
+        assert!(matches!(
+            next_step(&record(3, 0), Some(&stale), 1_010),
+            BurnStep::Spend(BurnCounter { remaining: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,536 @@
+//! Chunked transfers: a signed manifest plus a hash-chained sequence of chunk frames.
+//!
+//! Large payloads are split into fixed-size chunks. Each chunk is wrapped in a frame
+//! that starts with the SHA-256 of the *next* frame, so the signed manifest only has
+//! to carry the hash of the first frame and stays the same size however large the
+//! transfer is. A receiver verifies the manifest signature before fetching anything,
+//! then verifies each frame as it arrives with [`ChunkVerifier`] — a tampered transfer
+//! aborts at the first bad chunk instead of after everything has been downloaded. The
+//! manifest signs the `created_at` of the record it belongs to, so a manifest left
+//! over from one handoff cannot be served with another.
+//!
+//! On the DHT, the manifest travels next to the handoff record (whose blob is then
+//! empty) and frame `i` is published under the slot keypair
+//! [`crate::crypto::chunk_keypair`]`(first_hash, i)`.
+//!
+//! ```text
+//! Frame layout
+//! Offset  Size  Field
+//! 0       32    SHA-256 of the next frame (all zeros in the last frame)
+//! 32      N     Chunk data
+//! ```
+
+use std::io::Write;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::CclinkError;
+
+/// Length of the next-frame hash that prefixes every frame.
+pub const FRAME_HASH_LEN: usize = 32;
+
+/// Data bytes per frame when chunking a handoff blob. A full frame, base64-encoded,
+/// fits in one TXT record of a 1000-byte SignedPacket.
+pub const CHUNK_DATA_SIZE: usize = 600;
+
+/// Most chunks a handoff may be split into. Each chunk is a separate DHT publish,
+/// so this bounds both the payload size and the time publishing takes.
+pub const MAX_CHUNKS: usize = 64;
+
+/// Largest ciphertext a handoff can carry once chunked: 38,400 bytes (37.5 KiB).
+pub const MAX_BLOB_BYTES: usize = CHUNK_DATA_SIZE * MAX_CHUNKS;
+
+/// Signed description of a chunked transfer.
+///
+/// The signature covers the JCS form of [`ChunkManifestSignable`] (see
+/// [`super::jcs`]), which sorts members by name, so the order fields are declared in
+/// does not affect it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChunkManifest {
+    /// Number of frames in the transfer (at least 1; an empty payload is one empty frame).
+    pub chunks: u32,
+    /// `created_at` of the record whose ciphertext this is. Signed, so the manifest
+    /// only serves that one handoff.
+    pub created_at: u64,
+    /// Base64-encoded SHA-256 of the first frame — the root of the hash chain.
+    pub first_hash: String,
+    /// Creator's z32-encoded Ed25519 public key.
+    pub pubkey: String,
+    /// Base64-encoded Ed25519 signature over canonical JSON of the signable fields.
+    pub signature: String,
+    /// Total payload length in bytes (chunk data only, excluding frame hashes).
+    pub total_size: u64,
+}
+
+/// The signable subset of [`ChunkManifest`] (everything except `signature`).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChunkManifestSignable {
+    pub chunks: u32,
+    pub created_at: u64,
+    pub first_hash: String,
+    pub pubkey: String,
+    pub total_size: u64,
+}
+
+impl From<&ChunkManifest> for ChunkManifestSignable {
+    fn from(manifest: &ChunkManifest) -> Self {
+        ChunkManifestSignable {
+            chunks: manifest.chunks,
+            created_at: manifest.created_at,
+            first_hash: manifest.first_hash.clone(),
+            pubkey: manifest.pubkey.clone(),
+            total_size: manifest.total_size,
+        }
+    }
+}
+
+/// Split `data` into hash-chained frames carrying at most `chunk_size` data bytes each.
+///
+/// Frames are built back to front so each one can embed the hash of its successor.
+/// Returns the frames in transfer order together with the unsigned manifest fields;
+/// sign the result with [`sign_manifest`].
+pub fn build_frames(
+    data: &[u8],
+    chunk_size: usize,
+    pubkey: &str,
+    created_at: u64,
+) -> (Vec<Vec<u8>>, ChunkManifestSignable) {
+    assert!(chunk_size > 0, "chunk_size must be non-zero");
+
+    let mut pieces: Vec<&[u8]> = data.chunks(chunk_size).collect();
+    if pieces.is_empty() {
+        pieces.push(&[]);
+    }
+
+    let mut frames: Vec<Vec<u8>> = Vec::with_capacity(pieces.len());
+    let mut next_hash = [0u8; FRAME_HASH_LEN];
+    for piece in pieces.iter().rev() {
+        let mut frame = Vec::with_capacity(FRAME_HASH_LEN + piece.len());
+        frame.extend_from_slice(&next_hash);
+        frame.extend_from_slice(piece);
+        next_hash = Sha256::digest(&frame).into();
+        frames.push(frame);
+    }
+    frames.reverse();
+
+    let signable = ChunkManifestSignable {
+        chunks: frames.len() as u32,
+        created_at,
+        first_hash: base64::engine::general_purpose::STANDARD.encode(next_hash),
+        pubkey: pubkey.to_string(),
+        total_size: data.len() as u64,
+    };
+    (frames, signable)
+}
+
+/// Sign a manifest, returning the sealed [`ChunkManifest`].
+pub fn sign_manifest(
+    signable: ChunkManifestSignable,
+    keypair: &pkarr::Keypair,
+) -> anyhow::Result<ChunkManifest> {
+    let json = super::jcs::to_string(&signable)?;
+    let signature = super::sign_bytes(json.as_bytes(), keypair);
+    Ok(ChunkManifest {
+        chunks: signable.chunks,
+        created_at: signable.created_at,
+        first_hash: signable.first_hash,
+        pubkey: signable.pubkey,
+        signature,
+        total_size: signable.total_size,
+    })
+}
+
+/// Split a handoff's ciphertext into DHT-sized frames and sign the manifest.
+///
+/// Fails with [`CclinkError::RecordTooLarge`] if `ciphertext` is over
+/// [`MAX_BLOB_BYTES`].
+pub fn split_blob(
+    ciphertext: &[u8],
+    keypair: &pkarr::Keypair,
+    created_at: u64,
+) -> anyhow::Result<(Vec<Vec<u8>>, ChunkManifest)> {
+    if ciphertext.len() > MAX_BLOB_BYTES {
+        return Err(CclinkError::RecordTooLarge {
+            size: ciphertext.len(),
+            limit: MAX_BLOB_BYTES,
+        }
+        .into());
+    }
+    let (frames, signable) = build_frames(
+        ciphertext,
+        CHUNK_DATA_SIZE,
+        &keypair.public_key().to_z32(),
+        created_at,
+    );
+    Ok((frames, sign_manifest(signable, keypair)?))
+}
+
+/// Decode a manifest's `first_hash` — the root of the hash chain and the seed of
+/// the chunk slots.
+pub fn first_hash_bytes(manifest: &ChunkManifest) -> anyhow::Result<[u8; FRAME_HASH_LEN]> {
+    base64::engine::general_purpose::STANDARD
+        .decode(&manifest.first_hash)
+        .map_err(|e| anyhow::anyhow!("invalid manifest first_hash base64: {}", e))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("manifest first_hash must be exactly 32 bytes"))
+}
+
+/// Verify the Ed25519 signature on a manifest using the given public key.
+pub fn verify_manifest(manifest: &ChunkManifest, pubkey: &pkarr::PublicKey) -> anyhow::Result<()> {
+    let json = super::jcs::to_string(&ChunkManifestSignable::from(manifest))?;
+    super::verify_bytes(json.as_bytes(), &manifest.signature, pubkey)
+}
+
+/// In-memory sink for the ciphertext of a handoff, capped at [`MAX_BLOB_BYTES`].
+///
+/// The write that would take it past the budget fails, so encrypting an input that
+/// cannot fit stops there instead of running to the end first.
+#[derive(Debug, Default)]
+pub struct BlobWriter {
+    buf: Vec<u8>,
+    overflowed: bool,
+}
+
+impl BlobWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a write was refused for going over the budget.
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+impl Write for BlobWriter {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        if self.buf.len() + data.len() > MAX_BLOB_BYTES {
+            self.overflowed = true;
+            return Err(std::io::Error::other(format!(
+                "ciphertext exceeds the {} byte handoff budget",
+                MAX_BLOB_BYTES
+            )));
+        }
+        self.buf.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Incremental verifier for a chunked transfer.
+///
+/// Construction verifies the manifest signature; each [`push`](Self::push) verifies one
+/// frame against the hash chain and writes its data to the sink, so nothing unverified
+/// ever reaches the sink. [`finish`](Self::finish) checks that the transfer is complete.
+pub struct ChunkVerifier<W: Write> {
+    expected: [u8; FRAME_HASH_LEN],
+    index: u32,
+    chunks: u32,
+    written: u64,
+    total_size: u64,
+    sink: W,
+}
+
+impl<W: Write> ChunkVerifier<W> {
+    /// Verify the manifest signature and that it belongs to the record created at
+    /// `created_at`, and prepare to receive frames in order.
+    pub fn new(
+        manifest: &ChunkManifest,
+        pubkey: &pkarr::PublicKey,
+        created_at: u64,
+        sink: W,
+    ) -> anyhow::Result<Self> {
+        verify_manifest(manifest, pubkey)?;
+        if manifest.created_at != created_at {
+            return Err(CclinkError::SignatureVerificationFailed(
+                "chunk manifest belongs to a different handoff".to_string(),
+            )
+            .into());
+        }
+        if manifest.chunks == 0 {
+            anyhow::bail!("invalid manifest: zero chunks");
+        }
+        let limit = MAX_BLOB_BYTES as u64;
+        if manifest.chunks as usize > MAX_CHUNKS || manifest.total_size > limit {
+            return Err(CclinkError::ResponseTooLarge {
+                size: manifest.total_size,
+                limit,
+            }
+            .into());
+        }
+        let expected = first_hash_bytes(manifest)?;
+        Ok(Self {
+            expected,
+            index: 0,
+            chunks: manifest.chunks,
+            written: 0,
+            total_size: manifest.total_size,
+            sink,
+        })
+    }
+
+    /// Index of the next frame this verifier expects.
+    pub fn next_index(&self) -> u32 {
+        self.index
+    }
+
+    /// Verify the next frame and write its data to the sink.
+    ///
+    /// Returns [`CclinkError::ChunkVerificationFailed`] with the frame index if the frame
+    /// does not hash to the expected value, or if it would overrun the declared size.
+    pub fn push(&mut self, frame: &[u8]) -> anyhow::Result<()> {
+        if self.index >= self.chunks {
+            anyhow::bail!(
+                "unexpected chunk {}: transfer has {} chunks",
+                self.index,
+                self.chunks
+            );
+        }
+        let fail = || CclinkError::ChunkVerificationFailed(self.index);
+
+        if frame.len() < FRAME_HASH_LEN {
+            return Err(fail().into());
+        }
+        let hash: [u8; FRAME_HASH_LEN] = Sha256::digest(frame).into();
+        if hash != self.expected {
+            return Err(fail().into());
+        }
+
+        let (next, data) = frame.split_at(FRAME_HASH_LEN);
+        let is_last = self.index + 1 == self.chunks;
+        if is_last && next.iter().any(|&b| b != 0) {
+            return Err(fail().into());
+        }
+        let written = self.written + data.len() as u64;
+        if written > self.total_size {
+            return Err(fail().into());
+        }
+
+        self.sink.write_all(data)?;
+        self.written = written;
+        self.expected.copy_from_slice(next);
+        self.index += 1;
+        Ok(())
+    }
+
+    /// Confirm every frame was received and return the sink.
+    pub fn finish(mut self) -> anyhow::Result<W> {
+        if self.index != self.chunks {
+            anyhow::bail!(
+                "incomplete transfer: received {} of {} chunks",
+                self.index,
+                self.chunks
+            );
+        }
+        if self.written != self.total_size {
+            anyhow::bail!(
+                "transfer size mismatch: received {} bytes, manifest declares {}",
+                self.written,
+                self.total_size
+            );
+        }
+        self.sink.flush()?;
+        Ok(self.sink)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CREATED_AT: u64 = 1_700_000_000;
+
+    fn fixed_keypair() -> pkarr::Keypair {
+        pkarr::Keypair::from_secret_key(&[42u8; 32])
+    }
+
+    fn signed_transfer(data: &[u8], chunk_size: usize) -> (Vec<Vec<u8>>, ChunkManifest) {
+        let keypair = fixed_keypair();
+        let (frames, signable) =
+            build_frames(data, chunk_size, &keypair.public_key().to_z32(), CREATED_AT);
+        let manifest = sign_manifest(signable, &keypair).expect("sign_manifest should succeed");
+        (frames, manifest)
+    }
+
+    #[test]
+    fn test_chunked_round_trip() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        let (frames, manifest) = signed_transfer(&data, 700);
+        assert_eq!(manifest.chunks, 15);
+        assert_eq!(manifest.total_size, 10_000);
+
+        let mut verifier = ChunkVerifier::new(
+            &manifest,
+            &fixed_keypair().public_key(),
+            CREATED_AT,
+            Vec::new(),
+        )
+        .expect("manifest should verify");
+        for frame in &frames {
+            verifier.push(frame).expect("frame should verify");
+        }
+        let out = verifier.finish().expect("transfer should be complete");
+        assert_eq!(out, data);
+    }
+
+    #[test]
+    fn test_blob_writer_stops_at_budget() {
+        let mut writer = BlobWriter::new();
+        writer.write_all(&vec![0u8; MAX_BLOB_BYTES]).expect("fits");
+        assert!(!writer.overflowed());
+        assert!(writer.write_all(&[0u8]).is_err());
+        assert!(writer.overflowed());
+        assert_eq!(writer.into_inner().len(), MAX_BLOB_BYTES);
+    }
+
+    #[test]
+    fn test_split_blob_limits() {
+        let keypair = fixed_keypair();
+        let (frames, manifest) =
+            split_blob(&vec![1u8; CHUNK_DATA_SIZE * 2 + 1], &keypair, 1).expect("fits");
+        assert_eq!(frames.len(), 3);
+        assert_eq!(manifest.chunks, 3);
+
+        let err = split_blob(&vec![1u8; CHUNK_DATA_SIZE * MAX_CHUNKS + 1], &keypair, 1)
+            .expect_err("too many chunks");
+        assert!(matches!(
+            err.downcast_ref::<CclinkError>(),
+            Some(CclinkError::RecordTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_empty_payload_is_single_frame() {
+        let (frames, manifest) = signed_transfer(&[], 700);
+        assert_eq!(frames.len(), 1);
+        let mut verifier = ChunkVerifier::new(
+            &manifest,
+            &fixed_keypair().public_key(),
+            CREATED_AT,
+            Vec::new(),
+        )
+        .expect("manifest should verify");
+        verifier.push(&frames[0]).expect("frame should verify");
+        assert!(verifier.finish().expect("complete").is_empty());
+    }
+
+    #[test]
+    fn test_tampered_chunk_aborts_at_that_chunk() {
+        let data = vec![7u8; 5_000];
+        let (mut frames, manifest) = signed_transfer(&data, 1_000);
+        frames[2][40] ^= 0xff;
+
+        let mut verifier = ChunkVerifier::new(
+            &manifest,
+            &fixed_keypair().public_key(),
+            CREATED_AT,
+            Vec::new(),
+        )
+        .expect("manifest should verify");
+        verifier.push(&frames[0]).expect("chunk 0 is intact");
+        verifier.push(&frames[1]).expect("chunk 1 is intact");
+        let err = verifier.push(&frames[2]).expect_err("chunk 2 was tampered");
+        assert!(
+            matches!(
+                err.downcast_ref::<CclinkError>(),
+                Some(CclinkError::ChunkVerificationFailed(2))
+            ),
+            "expected ChunkVerificationFailed(2), got: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_out_of_order_chunk_rejected() {
+        let (frames, manifest) = signed_transfer(&[1u8; 3_000], 1_000);
+        let mut verifier = ChunkVerifier::new(
+            &manifest,
+            &fixed_keypair().public_key(),
+            CREATED_AT,
+            Vec::new(),
+        )
+        .expect("manifest should verify");
+        assert!(verifier.push(&frames[1]).is_err());
+    }
+
+    #[test]
+    fn test_tampered_manifest_rejected_before_any_chunk() {
+        let (_frames, mut manifest) = signed_transfer(&[1u8; 3_000], 1_000);
+        manifest.total_size += 1;
+        let result = ChunkVerifier::new(
+            &manifest,
+            &fixed_keypair().public_key(),
+            CREATED_AT,
+            Vec::new(),
+        );
+        assert!(result.is_err(), "tampered manifest must fail verification");
+    }
+
+    #[test]
+    fn test_manifest_of_another_handoff_rejected() {
+        let (_frames, manifest) = signed_transfer(&[1u8; 100], 1_000);
+        let result = ChunkVerifier::new(
+            &manifest,
+            &fixed_keypair().public_key(),
+            CREATED_AT + 1,
+            Vec::new(),
+        );
+        assert!(matches!(
+            result.err().and_then(|e| e.downcast::<CclinkError>().ok()),
+            Some(CclinkError::SignatureVerificationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_manifest_wrong_pubkey_rejected() {
+        let (_frames, manifest) = signed_transfer(&[1u8; 100], 1_000);
+        let other = pkarr::Keypair::from_secret_key(&[99u8; 32]);
+        assert!(
+            ChunkVerifier::new(&manifest, &other.public_key(), CREATED_AT, Vec::new()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_oversized_manifest_rejected_before_any_chunk() {
+        let (_frames, manifest) = signed_transfer(&[1u8; 650], 10);
+        assert_eq!(manifest.chunks as usize, MAX_CHUNKS + 1);
+        let err = ChunkVerifier::new(
+            &manifest,
+            &fixed_keypair().public_key(),
+            CREATED_AT,
+            Vec::new(),
+        )
+        .err()
+        .expect("too many chunks");
+        assert!(matches!(
+            err.downcast_ref::<CclinkError>(),
+            Some(CclinkError::ResponseTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_missing_chunks_fail_finish() {
+        let (frames, manifest) = signed_transfer(&[1u8; 3_000], 1_000);
+        let mut verifier = ChunkVerifier::new(
+            &manifest,
+            &fixed_keypair().public_key(),
+            CREATED_AT,
+            Vec::new(),
+        )
+        .expect("manifest should verify");
+        verifier.push(&frames[0]).expect("chunk 0 is intact");
+        assert!(
+            verifier.finish().is_err(),
+            "finish must fail on an incomplete transfer"
+        );
+    }
+}
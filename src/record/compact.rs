@@ -0,0 +1,84 @@
+//! One-line text form of a signed handoff record, for carrying it off the DHT.
+//!
+//! `cclink --qr-payload` renders a record as `cclink-handoff1:<base64 JSON>` in a QR
+//! code, and `cclink pickup --from-qr` reads it back, so a machine with no network
+//! path to the DHT can still pick up. The record keeps its inner signature, which is
+//! checked on the way in; the text adds no protection of its own.
+
+use base64::Engine;
+
+use super::HandoffRecord;
+
+/// Prefix of the encoded record; the digit versions the layout.
+pub const PREFIX: &str = "cclink-handoff1:";
+
+/// Encode `record` as one line of text.
+///
+/// Only records that carry their blob inline fit: a chunked record's ciphertext lives
+/// in separate DHT slots.
+pub fn encode(record: &HandoffRecord) -> anyhow::Result<String> {
+    if record.blob.is_empty() {
+        anyhow::bail!("this handoff is too large to carry off the DHT (its payload is chunked)");
+    }
+    let json = serde_json::to_vec(record)
+        .map_err(|e| anyhow::anyhow!("failed to serialize record: {}", e))?;
+    Ok(format!(
+        "{}{}",
+        PREFIX,
+        base64::engine::general_purpose::STANDARD.encode(json)
+    ))
+}
+
+/// Decode text made by [`encode`]. Whitespace and line breaks inserted by a scanner or
+/// a paste are ignored. The signature is not checked here.
+pub fn decode(text: &str) -> anyhow::Result<HandoffRecord> {
+    let compact: String = text.split_whitespace().collect();
+    let encoded = compact
+        .strip_prefix(PREFIX)
+        .ok_or_else(|| anyhow::anyhow!("not a cclink handoff (expected '{}...')", PREFIX))?;
+    let json = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| anyhow::anyhow!("handoff text is corrupted: {}", e))?;
+    let json = String::from_utf8(json)
+        .map_err(|_| anyhow::anyhow!("handoff text is corrupted: not UTF-8 JSON"))?;
+    super::migrate::parse(&json)
+}
+
+/// Whether `text` looks like an encoded record rather than, say, a file path.
+pub fn is_encoded(text: &str) -> bool {
+    text.trim_start().starts_with(PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(blob: Vec<u8>) -> HandoffRecord {
+        HandoffRecord::builder(blob, 3600)
+            .created_at(1_000)
+            .sign(&pkarr::Keypair::from_secret_key(&[5u8; 32]))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_keeps_signature_valid() {
+        let record = record(b"ciphertext".to_vec());
+        let text = encode(&record).unwrap();
+        assert!(is_encoded(&text));
+        let (head, tail) = text.split_at(30);
+        let decoded = decode(&format!("{}\n  {}\n", head, tail)).unwrap();
+        assert_eq!(
+            serde_json::to_string(&decoded).unwrap(),
+            serde_json::to_string(&record).unwrap()
+        );
+        let pubkey = pkarr::PublicKey::try_from(decoded.pubkey.as_str()).unwrap();
+        crate::record::verify_record(&decoded, &pubkey).expect("signature survives");
+    }
+
+    #[test]
+    fn test_rejects_chunked_and_foreign_text() {
+        assert!(encode(&record(Vec::new())).is_err());
+        assert!(decode("cclink-key1:AAAA").is_err());
+        assert!(!is_encoded("/tmp/scan.png"));
+    }
+}
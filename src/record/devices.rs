@@ -0,0 +1,111 @@
+//! Device registry: the machines sharing one cclink key, by name.
+//!
+//! `cclink device register <name>` gives a machine its own age key (see
+//! [`crate::keys::device`]) and adds its name and age recipient to the registry, kept
+//! under `_cclink-devices` in a slot only holders of the cclink key can derive
+//! (`crypto::devices_keypair`). `cclink --to <name>` looks the name up and encrypts
+//! the handoff for that machine alone.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Registered devices: name → age recipient (`age1…`), kept sorted by name.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct DeviceRegistry {
+    devices: BTreeMap<String, String>,
+}
+
+impl DeviceRegistry {
+    /// Add or replace a device. Returns the previous recipient if the name existed.
+    pub fn add(&mut self, name: &str, recipient: &str) -> anyhow::Result<Option<String>> {
+        validate_name(name)?;
+        recipient
+            .parse::<age::x25519::Recipient>()
+            .map_err(|e| anyhow::anyhow!("invalid device recipient '{}': {}", recipient, e))?;
+        Ok(self.devices.insert(name.to_string(), recipient.to_string()))
+    }
+
+    /// Remove a device. Returns its recipient, or `None` if the name was unknown.
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        self.devices.remove(name)
+    }
+
+    /// Look up a device's age recipient by name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.devices.get(name).map(String::as_str)
+    }
+
+    /// The name registered for `recipient`, if any.
+    pub fn name_of(&self, recipient: &str) -> Option<&str> {
+        self.devices
+            .iter()
+            .find(|(_, r)| *r == recipient)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Iterate over `(name, recipient)` pairs in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.devices.iter().map(|(n, r)| (n.as_str(), r.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+}
+
+/// Device names must be non-empty and short, and use letters, digits, '-' and '_'.
+fn validate_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty()
+        || name.len() > 32
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!(
+            "invalid device name '{}': use up to 32 letters, digits, '-' and '_'",
+            name
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipient() -> String {
+        age::x25519::Identity::generate().to_public().to_string()
+    }
+
+    #[test]
+    fn test_registry_add_lookup_remove() {
+        let mut registry = DeviceRegistry::default();
+        let laptop = recipient();
+        assert_eq!(registry.add("laptop", &laptop).unwrap(), None);
+        assert_eq!(registry.get("laptop"), Some(laptop.as_str()));
+        assert_eq!(registry.name_of(&laptop), Some("laptop"));
+
+        let replaced = recipient();
+        assert_eq!(
+            registry.add("laptop", &replaced).unwrap(),
+            Some(laptop.clone())
+        );
+        assert_eq!(registry.name_of(&laptop), None);
+
+        let json = serde_json::to_string(&registry).unwrap();
+        let parsed: DeviceRegistry = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, registry);
+
+        assert_eq!(registry.remove("laptop"), Some(replaced));
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_registry_rejects_bad_input() {
+        let mut registry = DeviceRegistry::default();
+        assert!(registry.add("laptop", "not-a-recipient").is_err());
+        assert!(registry.add("", &recipient()).is_err());
+        assert!(registry.add("two words", &recipient()).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+//! File payloads for `cclink send` / `cclink recv`.
+//!
+//! The plaintext encrypted into a file handoff's blob is the file name followed by
+//! the raw file contents:
+//!
+//! ```text
+//! Offset  Size  Field
+//! 0       2     Name length N (big-endian u16)
+//! 2       N     File name (UTF-8, no directory components)
+//! 2+N     ...   File contents
+//! ```
+
+use std::io::Read;
+
+/// Longest file name carried in a payload.
+pub const MAX_NAME_LEN: usize = 255;
+
+/// Frame a file as a payload: the header for `name` followed by everything read from
+/// `contents`. Only the final path component of `name` is kept.
+pub fn reader<R: Read>(name: &str, contents: R) -> anyhow::Result<impl Read> {
+    Ok(std::io::Cursor::new(header(name)?).chain(contents))
+}
+
+/// Consume the header from `reader` and return the file name, leaving `reader` at the
+/// start of the file contents.
+///
+/// The name is re-checked, so a hostile sender cannot smuggle in a path.
+pub fn read_header<R: Read>(reader: &mut R) -> anyhow::Result<String> {
+    let mut len = [0u8; 2];
+    read_exact(reader, &mut len)?;
+    let mut name = vec![0u8; u16::from_be_bytes(len) as usize];
+    read_exact(reader, &mut name)?;
+    let name = String::from_utf8(name)
+        .map_err(|e| anyhow::anyhow!("file name is not valid UTF-8: {}", e))?;
+    if name.contains(['/', '\\']) {
+        anyhow::bail!("file name must not contain directory components");
+    }
+    sanitize_name(&name)
+}
+
+fn header(name: &str) -> anyhow::Result<Vec<u8>> {
+    let name = sanitize_name(name)?;
+    let mut out = Vec::with_capacity(2 + name.len());
+    out.extend_from_slice(&(name.len() as u16).to_be_bytes());
+    out.extend_from_slice(name.as_bytes());
+    Ok(out)
+}
+
+fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8]) -> anyhow::Result<()> {
+    reader.read_exact(buf).map_err(|e| match e.kind() {
+        std::io::ErrorKind::UnexpectedEof => anyhow::anyhow!("file payload is truncated"),
+        _ => anyhow::anyhow!("failed to read file payload: {}", e),
+    })
+}
+
+/// The final component of `name`, rejecting empty, `.`/`..` and over-long names.
+fn sanitize_name(name: &str) -> anyhow::Result<String> {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    if base.is_empty() || base == "." || base == ".." {
+        anyhow::bail!("invalid file name '{}'", name);
+    }
+    if base.len() > MAX_NAME_LEN {
+        anyhow::bail!("file name is longer than {} bytes", MAX_NAME_LEN);
+    }
+    Ok(base.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_keeps_basename() {
+        let mut payload = Vec::new();
+        reader("/tmp/notes/todo.md", &b"- ship it"[..])
+            .unwrap()
+            .read_to_end(&mut payload)
+            .unwrap();
+        assert_eq!(&payload[..9], b"\x00\x07todo.md");
+
+        let mut rest = &payload[..];
+        assert_eq!(read_header(&mut rest).unwrap(), "todo.md");
+        assert_eq!(rest, b"- ship it");
+    }
+
+    #[test]
+    fn test_read_header_rejects_paths() {
+        let mut payload: &[u8] = b"\x00\x0a../.bashrc";
+        assert!(read_header(&mut payload).is_err());
+
+        let mut payload: &[u8] = b"\x00\x02..";
+        assert!(read_header(&mut payload).is_err());
+    }
+
+    #[test]
+    fn test_read_header_rejects_truncated() {
+        assert!(read_header(&mut &[0u8][..]).is_err());
+        assert!(read_header(&mut &[0u8, 5, b'a'][..]).is_err());
+    }
+}
@@ -0,0 +1,165 @@
+//! JSON Canonicalization Scheme (RFC 8785) for signed JSON.
+//!
+//! Every signature cclink makes covers the JCS form of the signable fields: object
+//! members sorted by the UTF-16 code units of their names, no whitespace, strings
+//! with only the escapes JSON requires, and numbers in their shortest form. The bytes
+//! depend only on the data, so reordering struct fields or producing the record in
+//! another language leaves signatures valid.
+//!
+//! For the ASCII field names and integer values cclink signs, JCS output is identical
+//! to the compact JSON of a struct with alphabetically ordered fields — what earlier
+//! releases signed — so existing records still verify. Numbers are limited to
+//! integers within ±2^53, which every JSON implementation reads exactly.
+
+use std::fmt::Write;
+
+use serde::Serialize;
+
+/// Largest integer magnitude that IEEE 754 doubles (and so every JSON reader) hold exactly.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Serialize `value` to its RFC 8785 canonical JSON text.
+pub fn to_string<T: Serialize>(value: &T) -> anyhow::Result<String> {
+    let value = serde_json::to_value(value)?;
+    let mut out = String::new();
+    write_value(&mut out, &value)?;
+    Ok(out)
+}
+
+fn write_value(out: &mut String, value: &serde_json::Value) -> anyhow::Result<()> {
+    use serde_json::Value;
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => write_number(out, n)?,
+        Value::String(s) => write_string(out, s),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, item)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut members: Vec<(&String, &Value)> = map.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (name, member)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(out, name);
+                out.push(':');
+                write_value(out, member)?;
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+fn write_number(out: &mut String, n: &serde_json::Number) -> anyhow::Result<()> {
+    if let Some(u) = n.as_u64().filter(|u| *u <= MAX_SAFE_INTEGER) {
+        write!(out, "{}", u).expect("writing to a String cannot fail");
+    } else if let Some(i) = n.as_i64().filter(|i| i.unsigned_abs() <= MAX_SAFE_INTEGER) {
+        write!(out, "{}", i).expect("writing to a String cannot fail");
+    } else {
+        anyhow::bail!(
+            "cannot canonicalize {}: only integers within ±2^53 are signed",
+            n
+        );
+    }
+    Ok(())
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => {
+                write!(out, "\\u{:04x}", c as u32).expect("writing to a String cannot fail")
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_member_order_ignores_declaration_order() {
+        #[derive(Serialize)]
+        struct Shuffled {
+            ttl: u64,
+            blob: &'static str,
+            created_at: u64,
+        }
+        let json = to_string(&Shuffled {
+            ttl: 1,
+            blob: "b",
+            created_at: 2,
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"blob":"b","created_at":2,"ttl":1}"#);
+    }
+
+    #[test]
+    fn test_matches_compact_json_of_alphabetical_structs() {
+        let signable = crate::record::HandoffRecordSignable {
+            blob: "YWJj".to_string(),
+            blob_hash: None,
+            burn: true,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
+            created_at: 1_700_000_000,
+            hostname: String::new(),
+            pin_salt: None,
+            project: "caf\u{e9} \"q\"".to_string(),
+            pubkey: "pk".to_string(),
+            recipient: None,
+            ttl: 3600,
+            version: crate::record::migrate::CURRENT_VERSION,
+        };
+        assert_eq!(
+            to_string(&signable).unwrap(),
+            serde_json::to_string(&signable).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rfc8785_names_sort_by_utf16_and_strings_escape_minimally() {
+        // U+1F600 is a surrogate pair (D83D DE00), so it sorts before U+E000 in
+        // UTF-16 even though its UTF-8 encoding sorts after.
+        let value = serde_json::json!({
+            "\u{e000}": 1,
+            "\u{1f600}": 2,
+            "a": "line\nbreak\u{1}/\u{e9}",
+            "n": [-5, 0, null, false],
+        });
+        assert_eq!(
+            to_string(&value).unwrap(),
+            "{\"a\":\"line\\nbreak\\u0001/\u{e9}\",\"n\":[-5,0,null,false],\"\u{1f600}\":2,\"\u{e000}\":1}"
+        );
+    }
+
+    #[test]
+    fn test_rejects_numbers_outside_the_exact_range() {
+        assert!(to_string(&(1u64 << 53)).is_err());
+        assert!(to_string(&1.5f64).is_err());
+        assert_eq!(to_string(&MAX_SAFE_INTEGER).unwrap(), "9007199254740991");
+    }
+}
@@ -0,0 +1,127 @@
+//! Record schema versions and parsing of records from older releases.
+//!
+//! Schema history:
+//! - 1: the v1.1 format, with `burn` and `recipient` signed; no `version` field.
+//! - 2: an explicit, signed `version`. Chunked records sign `blob_hash`, the SHA-256
+//!   of their ciphertext, and multi-use burn records sign `burn_uses` and `burn_grace`.
+//!
+//! Records carry an explicit `version` since schema 2. A record without one is a v1.1
+//! record (the format since `burn` and `recipient` were signed) and is read as
+//! version 1: its signature never covered a version, so the field is left out of the
+//! signed JSON for it, exactly as it was signed. A record from a newer schema than
+//! this build knows fails with [`CclinkError::UnsupportedRecordVersion`] instead of a
+//! confusing signature or parse error, so a format change never again breaks old
+//! clients silently.
+
+use crate::error::CclinkError;
+
+use super::HandoffRecord;
+
+/// Schema version written by this build.
+pub const CURRENT_VERSION: u32 = 2;
+
+/// Version of records without a `version` field: the v1.1 format.
+pub fn v1() -> u32 {
+    1
+}
+
+/// Version 1 is implied by a missing field, so it is never written out.
+pub fn is_v1(version: &u32) -> bool {
+    *version == 1
+}
+
+/// Parse record JSON of any supported schema version.
+pub fn parse(json: &str) -> anyhow::Result<HandoffRecord> {
+    let value: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| anyhow::anyhow!("failed to deserialize record: {}", e))?;
+    let version = match value.get("version") {
+        None => v1(),
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| anyhow::anyhow!("record version is not a number: {}", v))?,
+    };
+    match version {
+        1 | CURRENT_VERSION => serde_json::from_value(value)
+            .map_err(|e| anyhow::anyhow!("failed to deserialize v{} record: {}", version, e)),
+        found if found > CURRENT_VERSION => Err(CclinkError::UnsupportedRecordVersion {
+            found,
+            supported: CURRENT_VERSION,
+        }
+        .into()),
+        found => anyhow::bail!("invalid record version {}", found),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keypair() -> pkarr::Keypair {
+        pkarr::Keypair::from_secret_key(&[42u8; 32])
+    }
+
+    #[test]
+    fn test_current_records_round_trip_and_verify() {
+        let record = HandoffRecord::builder(b"blob".to_vec(), 3600)
+            .created_at(1_700_000_000)
+            .sign(&keypair())
+            .unwrap();
+        assert_eq!(record.version, CURRENT_VERSION);
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(json.contains(&format!("\"version\":{}", CURRENT_VERSION)));
+        let parsed = parse(&json).unwrap();
+        crate::record::verify_record(&parsed, &keypair().public_key()).unwrap();
+
+        // The version is signed: downgrading it breaks the signature.
+        let mut downgraded = parsed.clone();
+        downgraded.version = 1;
+        assert!(crate::record::verify_record(&downgraded, &keypair().public_key()).is_err());
+    }
+
+    #[test]
+    fn test_v1_1_record_still_verifies() {
+        // Signed by a release without the version field: the canonical JSON has none.
+        let signable = serde_json::json!({
+            "blob": "YmxvYg==",
+            "burn": false,
+            "created_at": 1_700_000_000u64,
+            "hostname": "",
+            "pin_salt": null,
+            "project": "",
+            "pubkey": keypair().public_key().to_z32(),
+            "recipient": null,
+            "ttl": 3600,
+        });
+        let signature = crate::record::sign_bytes(signable.to_string().as_bytes(), &keypair());
+        let mut stored = signable.clone();
+        stored["signature"] = signature.into();
+
+        let record = parse(&stored.to_string()).unwrap();
+        assert_eq!(record.version, 1);
+        crate::record::verify_record(&record, &keypair().public_key()).unwrap();
+        assert!(!serde_json::to_string(&record).unwrap().contains("version"));
+    }
+
+    #[test]
+    fn test_newer_version_is_a_clear_error() {
+        let err =
+            parse(r#"{"version":99,"blob":"","created_at":1,"pubkey":"x","signature":"","ttl":1}"#)
+                .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CclinkError>(),
+            Some(CclinkError::UnsupportedRecordVersion { found: 99, .. })
+        ));
+        // The first version past CURRENT_VERSION is rejected too, naming both versions.
+        let err = parse(r#"{"version":3}"#).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CclinkError>(),
+            Some(CclinkError::UnsupportedRecordVersion {
+                found: 3,
+                supported: 2
+            })
+        ));
+        assert!(parse(r#"{"version":0}"#).is_err());
+        assert!(parse(r#"{"version":"two"}"#).is_err());
+    }
+}
@@ -2,22 +2,45 @@
 //!
 //! HandoffRecord stores all metadata for a session handoff: hostname, project path,
 //! timestamp, TTL, encrypted session blob, and creator pubkey. Signing is performed
-//! over a canonical (deterministic, alphabetically-sorted, compact) JSON representation
-//! of the signable fields, excluding the signature itself.
+//! over the RFC 8785 canonical JSON ([`jcs`]) of the signable fields, excluding the
+//! signature itself.
+
+pub mod armor;
+pub mod builder;
+pub mod burn;
+pub mod chunked;
+pub mod compact;
+pub mod devices;
+pub mod file;
+pub mod jcs;
+pub mod migrate;
+pub mod profile;
+pub mod receipt;
+pub mod revocation;
+pub mod sas;
+pub mod transition;
+pub mod wormhole;
+
+use std::io::Read;
 
 use base64::Engine;
 use serde::{Deserialize, Serialize};
 
+/// Largest HandoffRecord JSON that fits in a `_cclink` TXT record inside a
+/// 1000-byte PKARR SignedPacket (DNS overhead is ~88 bytes). Empirically determined.
+pub const MAX_RECORD_JSON: usize = 912;
+
 /// A complete handoff record including the Ed25519 signature.
 ///
-/// Fields are in alphabetical order. This is critical: serde serializes struct fields
-/// in declaration order, so alphabetical order ensures deterministic JSON output
-/// without enabling the `preserve_order` serde_json feature.
+/// Fields are in alphabetical order, matching the member order of the canonical JSON
+/// that is signed ([`canonical_json`] sorts members itself, so the order is not
+/// load-bearing).
 ///
 /// As of v1.1, `burn` and `recipient` are included in the signed envelope
 /// (HandoffRecordSignable), so tampering with either field causes signature
 /// verification failure. v1.0 records (signed without these fields) are not
-/// supported — they expire via TTL (clean break).
+/// supported — they expire via TTL (clean break). Since schema 2 the record names its
+/// version; parse stored JSON with [`migrate::parse`].
 ///
 /// Fields with default values use `skip_serializing_if` to reduce JSON size in the
 /// DHT (max 912 bytes of JSON in a SignedPacket). Signatures are computed over
@@ -27,10 +50,24 @@ use serde::{Deserialize, Serialize};
 pub struct HandoffRecord {
     /// Base64-encoded age ciphertext containing the encrypted session payload.
     pub blob: String,
+    /// Base64-encoded SHA-256 of the ciphertext when it travels in chunks (the blob is
+    /// then empty), binding the signed record to exactly that ciphertext. Signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_hash: Option<String>,
     /// Burn-after-read flag: if true, the record should be deleted after first successful pickup.
     /// Signed as part of the v1.1 envelope — tampering causes verification failure.
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub burn: bool,
+    /// Seconds a multi-use burn handoff stays readable after its last use. Signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burn_grace: Option<u64>,
+    /// Pickups a burn handoff allows before it is deleted; absent means one. Signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burn_uses: Option<u32>,
+    /// The payload was zstd-compressed before encryption; pickup decompresses after
+    /// decrypting. Signed — see [`HandoffRecordSignable::compressed`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub compressed: bool,
     /// Unix timestamp (seconds) when the record was created.
     pub created_at: u64,
     /// Hostname of the machine that created this record (empty since v1.1 — encrypted in blob).
@@ -53,24 +90,43 @@ pub struct HandoffRecord {
     pub signature: String,
     /// Record time-to-live in seconds.
     pub ttl: u64,
+    /// Schema version ([`migrate::CURRENT_VERSION`] for new records). Absent on v1.1
+    /// records, which read as version 1.
+    #[serde(default = "migrate::v1", skip_serializing_if = "migrate::is_v1")]
+    pub version: u32,
 }
 
 /// The signable subset of HandoffRecord fields (excludes `signature` to avoid circular dependency).
 ///
-/// Fields are in alphabetical order — matching HandoffRecord ordering — for deterministic
-/// canonical JSON serialization.
+/// Fields are in alphabetical order — matching HandoffRecord ordering and the member
+/// order of the canonical JSON.
 ///
-/// Field order (alphabetical): blob, burn, created_at, hostname, pin_salt, project, pubkey, recipient, ttl
+/// Field order (alphabetical): blob, blob_hash, burn, burn_grace, burn_uses, compressed, created_at, hostname, pin_salt, project, pubkey, recipient, ttl, version
 ///
 /// v1.1 change: `burn` and `recipient` are now included in the signed envelope.
 /// This is a clean break from v1.0 — v1.0 records (signed without burn/recipient) are
-/// not supported; they expire via TTL. There is no version negotiation.
+/// not supported; they expire via TTL. Schema 2 signs an explicit `version`, so later
+/// changes can be told apart instead of failing verification (see [`migrate`]).
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct HandoffRecordSignable {
     /// Base64-encoded age ciphertext.
     pub blob: String,
+    /// Hash of chunked ciphertext. Omitted when absent, so inline and v1.1 records
+    /// verify as they were signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_hash: Option<String>,
     /// Burn-after-read flag: signed into the envelope so tampering is detectable.
     pub burn: bool,
+    /// Grace window after the last burn use. Omitted when absent, like `blob_hash`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burn_grace: Option<u64>,
+    /// Pickups allowed before the burn. Omitted when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burn_uses: Option<u32>,
+    /// Compression flag. Unlike the other fields it is omitted from the canonical
+    /// JSON when false, so records from before the flag existed still verify.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub compressed: bool,
     /// Unix timestamp (seconds) when the record was created.
     pub created_at: u64,
     /// Hostname of the machine that created this record.
@@ -86,6 +142,10 @@ pub struct HandoffRecordSignable {
     pub recipient: Option<String>,
     /// Record time-to-live in seconds.
     pub ttl: u64,
+    /// Schema version. Omitted from the canonical JSON for version 1, so v1.1 records
+    /// still verify; signed from version 2 on, so it cannot be downgraded.
+    #[serde(default = "migrate::v1", skip_serializing_if = "migrate::is_v1")]
+    pub version: u32,
 }
 
 /// Encrypted payload containing sensitive session metadata.
@@ -97,14 +157,148 @@ pub struct HandoffRecordSignable {
 /// Fields use short serde names ("h", "p", "s") to minimize the encrypted
 /// payload size — the Payload JSON is encrypted inside the blob and must fit
 /// within the 1000-byte SignedPacket budget alongside other record fields.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Payload {
+    /// Coding agent the session belongs to (`session::Agent` name); absent for Claude Code.
+    #[serde(rename = "a", default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    /// Registered device name of the publishing machine (`cclink device register`),
+    /// shown in place of its hostname.
+    #[serde(rename = "d", default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    /// State of the publisher's git checkout, so pickup can warn when the local one differs.
+    #[serde(rename = "g", default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitState>,
     #[serde(rename = "h")]
     pub hostname: String,
     #[serde(rename = "p")]
     pub project: String,
+    /// Base64-encoded secret of the handoff's receipt slot (`crypto::receipt_keypair`).
+    /// Present when the publisher asked for a pickup receipt.
+    #[serde(rename = "r", default, skip_serializing_if = "Option::is_none")]
+    pub receipt_key: Option<String>,
     #[serde(rename = "s")]
     pub session_id: String,
+    /// Full session JSONL, when published with `--with-transcript`, so pickup can
+    /// install it on a machine that never had the session.
+    #[serde(rename = "t", default, skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<String>,
+    /// Publisher's platform, so pickup can warn about OS/arch or claude version mismatches.
+    #[serde(rename = "x", default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<PlatformHints>,
+}
+
+/// Platform the session was published from. Short serde names, like [`Payload`].
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PlatformHints {
+    /// CPU architecture (`std::env::consts::ARCH`, e.g. "aarch64").
+    #[serde(rename = "a")]
+    pub arch: String,
+    /// `claude --version` of the publisher, if it could be detected.
+    #[serde(rename = "c", default, skip_serializing_if = "Option::is_none")]
+    pub claude_version: Option<String>,
+    /// Operating system (`std::env::consts::OS`, e.g. "macos").
+    #[serde(rename = "o")]
+    pub os: String,
+}
+
+/// Git checkout the session was published from. Short serde names, like [`Payload`].
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GitState {
+    /// Checked-out branch; `None` on a detached HEAD.
+    #[serde(rename = "b", default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Number of modified or untracked files (`git status --porcelain` lines).
+    #[serde(rename = "d", default)]
+    pub dirty: u32,
+    /// Full HEAD commit hash.
+    #[serde(rename = "h")]
+    pub head: String,
+    /// URL of the `origin` remote with any credentials removed.
+    #[serde(rename = "r", default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+}
+
+/// Largest decompressed payload accepted, so a hostile record cannot make
+/// `decompress_payload` allocate without bound. Also caps `cclink send` input.
+pub const MAX_PAYLOAD_BYTES: usize = 1024 * 1024;
+
+/// zstd level for payload compression. Payloads are tiny; the maximum level costs nothing.
+const PAYLOAD_ZSTD_LEVEL: i32 = 19;
+
+/// zstd-compress serialized Payload JSON before encryption.
+///
+/// Returns `None` when compression would not make the payload smaller — short JSON
+/// can grow by the zstd frame overhead — in which case the record is left unflagged.
+pub fn compress_payload(json: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+    let compressed = zstd::bulk::compress(json, PAYLOAD_ZSTD_LEVEL)
+        .map_err(|e| anyhow::anyhow!("failed to compress payload: {}", e))?;
+    Ok((compressed.len() < json.len()).then_some(compressed))
+}
+
+/// Reverse [`compress_payload`] for a record with the `compressed` flag set.
+pub fn decompress_payload(bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+    zstd::bulk::decompress(bytes, MAX_PAYLOAD_BYTES)
+        .map_err(|e| anyhow::anyhow!("failed to decompress payload: {}", e))
+}
+
+/// Decode a record's decrypted blob as [`Payload`] JSON, decompressing first if the
+/// record is flagged `compressed`. Records older than `Payload` carry a bare session ID,
+/// with the hostname and project in the record itself.
+pub fn decode_payload(plaintext: Vec<u8>, record: &HandoffRecord) -> anyhow::Result<Payload> {
+    let plaintext = if record.compressed {
+        decompress_payload(&plaintext)?
+    } else {
+        plaintext
+    };
+    if let Ok(payload) = serde_json::from_slice::<Payload>(&plaintext) {
+        Ok(payload)
+    } else {
+        // Old format: raw session_id string, metadata in outer record
+        let session_id = String::from_utf8(plaintext)
+            .map_err(|e| anyhow::anyhow!("session ID is not valid UTF-8: {}", e))?;
+        Ok(Payload {
+            hostname: record.hostname.clone(),
+            project: record.project.clone(),
+            session_id,
+            ..Payload::default()
+        })
+    }
+}
+
+/// Streaming counterpart of [`compress_payload`] for payloads read from a file.
+///
+/// Always compresses, since whether the output shrinks is only known once the input
+/// has been consumed; on incompressible data the zstd overhead is a few bytes per block.
+pub fn compress_reader<R: Read>(reader: R) -> anyhow::Result<impl Read> {
+    zstd::stream::read::Encoder::new(reader, PAYLOAD_ZSTD_LEVEL)
+        .map_err(|e| anyhow::anyhow!("failed to compress payload: {}", e))
+}
+
+/// Streaming counterpart of [`decompress_payload`].
+///
+/// The output is not capped here; callers bound how much they read (see `cclink recv`).
+pub fn decompress_reader<R: Read>(reader: R) -> anyhow::Result<impl Read> {
+    zstd::stream::read::Decoder::new(reader)
+        .map_err(|e| anyhow::anyhow!("failed to decompress payload: {}", e))
+}
+
+/// Check that a signed record fits the DHT packet budget before publishing.
+///
+/// Fails with [`crate::error::CclinkError::RecordTooLarge`] naming the actual size, so the user
+/// gets a clear error instead of an opaque packet-building failure.
+pub fn validate_size(record: &HandoffRecord) -> anyhow::Result<()> {
+    let size = serde_json::to_string(record)
+        .map_err(|e| anyhow::anyhow!("failed to serialize record: {}", e))?
+        .len();
+    if size > MAX_RECORD_JSON {
+        return Err(crate::error::CclinkError::RecordTooLarge {
+            size,
+            limit: MAX_RECORD_JSON,
+        }
+        .into());
+    }
+    Ok(())
 }
 
 impl From<&HandoffRecord> for HandoffRecordSignable {
@@ -113,7 +307,11 @@ impl From<&HandoffRecord> for HandoffRecordSignable {
     fn from(record: &HandoffRecord) -> Self {
         HandoffRecordSignable {
             blob: record.blob.clone(),
+            blob_hash: record.blob_hash.clone(),
             burn: record.burn,
+            burn_grace: record.burn_grace,
+            burn_uses: record.burn_uses,
+            compressed: record.compressed,
             created_at: record.created_at,
             hostname: record.hostname.clone(),
             pin_salt: record.pin_salt.clone(),
@@ -121,31 +319,148 @@ impl From<&HandoffRecord> for HandoffRecordSignable {
             pubkey: record.pubkey.clone(),
             recipient: record.recipient.clone(),
             ttl: record.ttl,
+            version: record.version,
         }
     }
 }
 
-/// Produce canonical JSON for signing: compact (no whitespace), fields in alphabetical order.
-///
-/// Because HandoffRecordSignable fields are declared in alphabetical order and serde_json
-/// serializes struct fields in declaration order, this produces deterministic output.
-/// Do NOT enable the `preserve_order` feature on serde_json.
+/// Produce canonical JSON for signing: RFC 8785 (JCS), so the bytes depend only on the
+/// field values, not on declaration order or the implementation that wrote them.
 pub fn canonical_json(signable: &HandoffRecordSignable) -> anyhow::Result<String> {
-    Ok(serde_json::to_string(signable)?)
+    jcs::to_string(signable)
 }
 
 /// Sign a HandoffRecordSignable with a pkarr Keypair, returning a base64-encoded signature.
 ///
 /// Signs the canonical JSON bytes with the Ed25519 private key. The returned string is
 /// suitable for storage in HandoffRecord.signature.
+#[deprecated(note = "build records with `HandoffRecord::builder`, which signs internally")]
 pub fn sign_record(
     signable: &HandoffRecordSignable,
     keypair: &pkarr::Keypair,
+) -> anyhow::Result<String> {
+    signature_for(signable, keypair)
+}
+
+/// Sign a HandoffRecordSignable and assemble the complete [`HandoffRecord`].
+#[deprecated(note = "use `HandoffRecord::builder(..).sign(keypair)`")]
+pub fn seal_record(
+    signable: HandoffRecordSignable,
+    keypair: &pkarr::Keypair,
+) -> anyhow::Result<HandoffRecord> {
+    sign_signable(signable, keypair)
+}
+
+/// A sealed record, plus the chunks carrying its ciphertext when the blob did not
+/// fit in the record itself.
+pub struct SealedHandoff {
+    pub record: HandoffRecord,
+    pub chunks: Option<(Vec<Vec<u8>>, chunked::ChunkManifest)>,
+}
+
+/// Seal a record around `ciphertext`, chunking it if the record would exceed the
+/// packet budget.
+#[deprecated(note = "use `HandoffRecord::builder(..).seal(keypair)`")]
+pub fn seal_with_chunking(
+    signable: HandoffRecordSignable,
+    ciphertext: &[u8],
+    keypair: &pkarr::Keypair,
+) -> anyhow::Result<SealedHandoff> {
+    seal_signable(signable, ciphertext, keypair)
+}
+
+fn signature_for(
+    signable: &HandoffRecordSignable,
+    keypair: &pkarr::Keypair,
 ) -> anyhow::Result<String> {
     let json = canonical_json(signable)?;
-    let sig = keypair.sign(json.as_bytes());
-    let encoded = base64::engine::general_purpose::STANDARD.encode(sig.to_bytes());
-    Ok(encoded)
+    Ok(sign_bytes(json.as_bytes(), keypair))
+}
+
+/// Sign `signable` and move its fields into a [`HandoffRecord`].
+fn sign_signable(
+    signable: HandoffRecordSignable,
+    keypair: &pkarr::Keypair,
+) -> anyhow::Result<HandoffRecord> {
+    let signature = signature_for(&signable, keypair)?;
+    Ok(HandoffRecord {
+        blob: signable.blob,
+        blob_hash: signable.blob_hash,
+        burn: signable.burn,
+        burn_grace: signable.burn_grace,
+        burn_uses: signable.burn_uses,
+        compressed: signable.compressed,
+        created_at: signable.created_at,
+        hostname: signable.hostname,
+        pin_salt: signable.pin_salt,
+        project: signable.project,
+        pubkey: signable.pubkey,
+        recipient: signable.recipient,
+        signature,
+        ttl: signable.ttl,
+        version: signable.version,
+    })
+}
+
+/// Inline, `signable.blob` is the base64 ciphertext. Chunked, the blob is empty and
+/// the ciphertext travels in frames committed to by a signed manifest. Fails with
+/// [`crate::error::CclinkError::RecordTooLarge`] if even the chunked form is too big.
+fn seal_signable(
+    mut signable: HandoffRecordSignable,
+    ciphertext: &[u8],
+    keypair: &pkarr::Keypair,
+) -> anyhow::Result<SealedHandoff> {
+    signable.blob = base64::engine::general_purpose::STANDARD.encode(ciphertext);
+    let record = sign_signable(signable.clone(), keypair)?;
+    if validate_size(&record).is_ok() {
+        return Ok(SealedHandoff {
+            record,
+            chunks: None,
+        });
+    }
+
+    let (frames, manifest) = chunked::split_blob(ciphertext, keypair, signable.created_at)?;
+    tracing::debug!(
+        chunks = frames.len(),
+        "blob exceeds packet budget, chunking"
+    );
+    signable.blob = String::new();
+    signable.blob_hash = Some(blob_hash(ciphertext));
+    let record = sign_signable(signable, keypair)?;
+    validate_size(&record)?;
+    Ok(SealedHandoff {
+        record,
+        chunks: Some((frames, manifest)),
+    })
+}
+
+/// Base64-encoded SHA-256 of `ciphertext`, as stored in [`HandoffRecord::blob_hash`].
+pub fn blob_hash(ciphertext: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    base64::engine::general_purpose::STANDARD.encode(Sha256::digest(ciphertext))
+}
+
+/// Check that `ciphertext` fetched for a (verified) `record` is the one it signed.
+///
+/// Chunked ciphertext must match the record's `blob_hash`, so chunks left over from
+/// another handoff, or swapped in, are refused before decryption; a chunked record
+/// without one is refused outright. Inline blobs are covered by the record signature
+/// itself.
+pub fn verify_blob(record: &HandoffRecord, ciphertext: &[u8]) -> anyhow::Result<()> {
+    let reason = match &record.blob_hash {
+        Some(expected) if *expected != blob_hash(ciphertext) => {
+            "ciphertext does not match the record's signed blob_hash"
+        }
+        None if record.blob.is_empty() => "chunked record carries no signed blob_hash",
+        _ => return Ok(()),
+    };
+    Err(crate::error::CclinkError::SignatureVerificationFailed(reason.to_string()).into())
+}
+
+/// Sign arbitrary bytes and return the base64-encoded Ed25519 signature.
+pub(crate) fn sign_bytes(message: &[u8], keypair: &pkarr::Keypair) -> String {
+    let sig = keypair.sign(message);
+    base64::engine::general_purpose::STANDARD.encode(sig.to_bytes())
 }
 
 /// Verify the Ed25519 signature on a HandoffRecord using the given public key.
@@ -156,13 +471,21 @@ pub fn sign_record(
 /// Returns an error if the signature is invalid, the base64 is malformed, or the
 /// signature bytes cannot be interpreted as a valid Ed25519 signature.
 pub fn verify_record(record: &HandoffRecord, pubkey: &pkarr::PublicKey) -> anyhow::Result<()> {
-    use crate::error::CclinkError;
-
     let signable = HandoffRecordSignable::from(record);
     let json = canonical_json(&signable)?;
+    verify_bytes(json.as_bytes(), &record.signature, pubkey)
+}
+
+/// Verify a base64-encoded Ed25519 signature over `message`.
+pub(crate) fn verify_bytes(
+    message: &[u8],
+    signature: &str,
+    pubkey: &pkarr::PublicKey,
+) -> anyhow::Result<()> {
+    use crate::error::CclinkError;
 
     let sig_bytes = base64::engine::general_purpose::STANDARD
-        .decode(&record.signature)
+        .decode(signature)
         .map_err(|e| anyhow::anyhow!("invalid base64 signature: {}", e))?;
 
     let sig_array: [u8; 64] = sig_bytes
@@ -171,9 +494,11 @@ pub fn verify_record(record: &HandoffRecord, pubkey: &pkarr::PublicKey) -> anyho
 
     let sig = ed25519_dalek::Signature::from_bytes(&sig_array);
 
-    pubkey
-        .verify(json.as_bytes(), &sig)
-        .map_err(|e| CclinkError::SignatureVerificationFailed(e.to_string()))?;
+    if let Err(e) = pubkey.verify(message, &sig) {
+        tracing::debug!(pubkey = %pubkey.to_z32(), error = %e, "record signature invalid");
+        return Err(CclinkError::SignatureVerificationFailed(e.to_string()).into());
+    }
+    tracing::debug!(pubkey = %pubkey.to_z32(), "record signature verified");
 
     Ok(())
 }
@@ -189,7 +514,11 @@ mod tests {
     fn sample_signable() -> HandoffRecordSignable {
         HandoffRecordSignable {
             blob: "dGVzdGJsb2I=".to_string(),
+            blob_hash: None,
             burn: false,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
             created_at: 1_700_000_000,
             hostname: "testhost".to_string(),
             pin_salt: None,
@@ -197,6 +526,7 @@ mod tests {
             pubkey: "testpubkey".to_string(),
             recipient: None,
             ttl: 3600,
+            version: migrate::CURRENT_VERSION,
         }
     }
 
@@ -205,7 +535,11 @@ mod tests {
         // Use a signable with recipient set so its position is testable
         let signable = HandoffRecordSignable {
             blob: "dGVzdGJsb2I=".to_string(),
+            blob_hash: None,
             burn: false,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
             created_at: 1_700_000_000,
             hostname: "testhost".to_string(),
             pin_salt: None,
@@ -213,6 +547,7 @@ mod tests {
             pubkey: "testpubkey".to_string(),
             recipient: Some("recipientkey".to_string()),
             ttl: 3600,
+            version: migrate::CURRENT_VERSION,
         };
         let json = canonical_json(&signable).expect("canonical_json should succeed");
 
@@ -289,6 +624,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_sign_and_verify_round_trip() {
         let keypair = fixed_keypair();
         let signable = sample_signable();
@@ -296,7 +632,11 @@ mod tests {
 
         let record = HandoffRecord {
             blob: signable.blob.clone(),
+            blob_hash: None,
             burn: false,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
             created_at: signable.created_at,
             hostname: signable.hostname.clone(),
             pin_salt: None,
@@ -305,6 +645,7 @@ mod tests {
             recipient: None,
             signature,
             ttl: signable.ttl,
+            version: signable.version,
         };
 
         verify_record(&record, &keypair.public_key())
@@ -312,6 +653,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_verify_fails_wrong_pubkey() {
         let keypair_a = fixed_keypair();
         let keypair_b = pkarr::Keypair::from_secret_key(&[99u8; 32]);
@@ -321,7 +663,11 @@ mod tests {
 
         let record = HandoffRecord {
             blob: signable.blob.clone(),
+            blob_hash: None,
             burn: false,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
             created_at: signable.created_at,
             hostname: signable.hostname.clone(),
             pin_salt: None,
@@ -330,6 +676,7 @@ mod tests {
             recipient: None,
             signature,
             ttl: signable.ttl,
+            version: signable.version,
         };
 
         let result = verify_record(&record, &keypair_b.public_key());
@@ -347,6 +694,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_verify_fails_tampered_json() {
         let keypair = fixed_keypair();
         let signable = sample_signable();
@@ -355,7 +703,11 @@ mod tests {
         // Tamper with the TTL field
         let tampered = HandoffRecord {
             blob: signable.blob.clone(),
+            blob_hash: None,
             burn: false,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
             created_at: signable.created_at,
             hostname: signable.hostname.clone(),
             pin_salt: None,
@@ -364,6 +716,7 @@ mod tests {
             recipient: None,
             signature,
             ttl: signable.ttl + 9999, // tampered!
+            version: signable.version,
         };
 
         let result = verify_record(&tampered, &keypair.public_key());
@@ -377,7 +730,11 @@ mod tests {
     fn test_signable_includes_burn_field() {
         let signable = HandoffRecordSignable {
             blob: "dGVzdGJsb2I=".to_string(),
+            blob_hash: None,
             burn: true,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
             created_at: 1_700_000_000,
             hostname: "testhost".to_string(),
             pin_salt: None,
@@ -385,6 +742,7 @@ mod tests {
             pubkey: "testpubkey".to_string(),
             recipient: None,
             ttl: 3600,
+            version: migrate::CURRENT_VERSION,
         };
         let json = canonical_json(&signable).expect("canonical_json should succeed");
         assert!(
@@ -398,7 +756,11 @@ mod tests {
     fn test_signable_includes_recipient_field() {
         let signable = HandoffRecordSignable {
             blob: "dGVzdGJsb2I=".to_string(),
+            blob_hash: None,
             burn: false,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
             created_at: 1_700_000_000,
             hostname: "testhost".to_string(),
             pin_salt: None,
@@ -406,6 +768,7 @@ mod tests {
             pubkey: "testpubkey".to_string(),
             recipient: Some("abc123".to_string()),
             ttl: 3600,
+            version: migrate::CURRENT_VERSION,
         };
         let json = canonical_json(&signable).expect("canonical_json should succeed");
         assert!(
@@ -416,26 +779,21 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_tampered_burn_fails_verification() {
         let keypair = fixed_keypair();
         // Sign with burn: false
-        let signable = HandoffRecordSignable {
-            blob: "dGVzdGJsb2I=".to_string(),
-            burn: false,
-            created_at: 1_700_000_000,
-            hostname: "testhost".to_string(),
-            pin_salt: None,
-            project: "/home/user/project".to_string(),
-            pubkey: "testpubkey".to_string(),
-            recipient: None,
-            ttl: 3600,
-        };
+        let signable = sample_signable();
         let signature = sign_record(&signable, &keypair).expect("sign_record should succeed");
 
         // Tamper: construct record with burn: true (different from what was signed)
         let tampered = HandoffRecord {
             blob: signable.blob.clone(),
+            blob_hash: None,
             burn: true, // tampered!
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
             created_at: signable.created_at,
             hostname: signable.hostname.clone(),
             pin_salt: signable.pin_salt.clone(),
@@ -444,6 +802,7 @@ mod tests {
             recipient: signable.recipient.clone(),
             signature,
             ttl: signable.ttl,
+            version: signable.version,
         };
 
         let result = verify_record(&tampered, &keypair.public_key());
@@ -452,6 +811,158 @@ mod tests {
             "verify_record must fail when burn field is tampered after signing"
         );
     }
+
+    #[test]
+    fn test_validate_size_rejects_oversized_record() {
+        let keypair = fixed_keypair();
+        let mut record = HandoffRecord {
+            blob: "A".repeat(600),
+            blob_hash: None,
+            burn: false,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
+            created_at: 1_700_000_000,
+            hostname: String::new(),
+            pin_salt: None,
+            project: String::new(),
+            pubkey: keypair.public_key().to_z32(),
+            recipient: None,
+            signature: "A".repeat(88),
+            ttl: 86400,
+            version: migrate::CURRENT_VERSION,
+        };
+        assert!(validate_size(&record).is_ok());
+
+        record.blob = "A".repeat(MAX_RECORD_JSON);
+        let err = validate_size(&record).expect_err("oversized record must fail");
+        assert!(matches!(
+            err.downcast_ref::<crate::error::CclinkError>(),
+            Some(crate::error::CclinkError::RecordTooLarge {
+                limit: MAX_RECORD_JSON,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_payload_platform_hints_optional() {
+        // Payloads from older publishers have no "x" field.
+        let old: Payload =
+            serde_json::from_str(r#"{"h":"host","p":"/proj","s":"sid"}"#).expect("old payload");
+        assert!(old.platform.is_none());
+
+        let payload = Payload {
+            session_id: "sid".to_string(),
+            platform: Some(PlatformHints {
+                arch: "aarch64".to_string(),
+                claude_version: Some("2.0.14".to_string()),
+                os: "macos".to_string(),
+            }),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&payload).expect("serialize");
+        assert!(json.contains(r#""x":{"a":"aarch64","c":"2.0.14","o":"macos"}"#));
+        let back: Payload = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(back.platform, payload.platform);
+    }
+
+    #[test]
+    fn test_payload_git_state_short_names() {
+        let old: Payload =
+            serde_json::from_str(r#"{"h":"host","p":"/proj","s":"sid"}"#).expect("old payload");
+        assert!(old.git.is_none());
+
+        let payload = Payload {
+            git: Some(GitState {
+                branch: None,
+                dirty: 2,
+                head: "abc".to_string(),
+                remote: None,
+            }),
+            session_id: "sid".to_string(),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&payload).expect("serialize");
+        assert!(json.starts_with(r#"{"g":{"d":2,"h":"abc"},"#), "{}", json);
+        let back: Payload = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(back.git, payload.git);
+    }
+
+    #[test]
+    fn test_payload_compression_round_trip() {
+        let payload = Payload {
+            hostname: "build-server-01.internal.example.com".to_string(),
+            project: "/home/developer/workspace/projects/example/example-service".to_string(),
+            session_id: "3f2a1b4c-5d6e-7f80-9a1b-2c3d4e5f6a7b".to_string(),
+            ..Default::default()
+        };
+        let json = serde_json::to_vec(&payload).unwrap();
+        let compressed = compress_payload(&json)
+            .unwrap()
+            .expect("repetitive paths should compress");
+        assert!(compressed.len() < json.len());
+        assert_eq!(decompress_payload(&compressed).unwrap(), json);
+
+        assert!(
+            compress_payload(b"{}").unwrap().is_none(),
+            "tiny input is left uncompressed"
+        );
+    }
+
+    #[test]
+    fn test_streaming_compression_round_trip() {
+        let data = vec![b'a'; 4096];
+        let mut compressed = Vec::new();
+        compress_reader(&data[..])
+            .unwrap()
+            .read_to_end(&mut compressed)
+            .unwrap();
+        assert!(compressed.len() < data.len());
+        assert_eq!(decompress_payload(&compressed).unwrap(), data);
+
+        let mut out = Vec::new();
+        decompress_reader(&compressed[..])
+            .unwrap()
+            .read_to_end(&mut out)
+            .unwrap();
+        assert_eq!(out, data);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_seal_with_chunking_inline_and_chunked() {
+        let keypair = fixed_keypair();
+        let small = seal_with_chunking(sample_signable(), &[1u8; 100], &keypair).unwrap();
+        assert!(small.chunks.is_none());
+        assert!(!small.record.blob.is_empty());
+
+        let large = seal_with_chunking(sample_signable(), &[1u8; 2_000], &keypair).unwrap();
+        let (frames, manifest) = large.chunks.expect("2000 bytes must be chunked");
+        assert!(large.record.blob.is_empty());
+        assert_eq!(frames.len(), manifest.chunks as usize);
+        assert!(verify_record(&large.record, &keypair.public_key()).is_ok());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_compressed_flag_is_signed_but_absent_when_false() {
+        let signable = sample_signable();
+        assert!(!canonical_json(&signable).unwrap().contains("compressed"));
+
+        let keypair = fixed_keypair();
+        let mut record = seal_record(
+            HandoffRecordSignable {
+                compressed: true,
+                ..sample_signable()
+            },
+            &keypair,
+        )
+        .unwrap();
+        assert!(verify_record(&record, &keypair.public_key()).is_ok());
+        record.compressed = false;
+        assert!(verify_record(&record, &keypair.public_key()).is_err());
+    }
 }
 
 #[cfg(test)]
@@ -465,9 +976,7 @@ mod size_analysis {
     /// varies by ~120 bytes per run due to randomized encryption).
     #[test]
     fn analyze_record_sizes_with_encrypted_payload() {
-        // Empirically determined: max JSON fitting in a `_cclink` TXT record
-        // inside a 1000-byte SignedPacket is 912 bytes (DNS overhead ~88 bytes).
-        const MAX_JSON: usize = 912;
+        const MAX_JSON: usize = MAX_RECORD_JSON;
 
         // Fixed blob: 600 chars = base64 of ~450-byte ciphertext.
         // Observed age ciphertext range for 99-byte payload: 324-447 bytes.
@@ -477,7 +986,11 @@ mod size_analysis {
         let typical_sig_b64 = "YXNkZnNhZGZzYWRmc2FkZnNhZGZzYWRmc2FkZnNhZGZzYWRmc2FkZnNhZGZzYWRmc2FkZnNhZGZzYWRmc2FkZg==";
         let record = HandoffRecord {
             blob: blob.clone(),
+            blob_hash: None,
             burn: false,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
             created_at: 1740000000u64,
             hostname: String::new(),
             pin_salt: None,
@@ -486,6 +999,7 @@ mod size_analysis {
             recipient: None,
             signature: typical_sig_b64.to_string(),
             ttl: 86400,
+            version: migrate::CURRENT_VERSION,
         };
 
         let record_json = serde_json::to_string(&record).expect("serialize record");
@@ -551,6 +1065,7 @@ mod size_analysis {
             hostname: "macbook-pro-m3".to_string(),
             project: "/Users/john/projects/my-app".to_string(),
             session_id: "3c0a3f7a-1234-5678-abcd-ef1234567890".to_string(),
+            ..Default::default()
         };
         let payload_json = serde_json::to_vec(&payload).expect("serialize payload");
 
@@ -563,7 +1078,11 @@ mod size_analysis {
         let typical_sig_b64 = "YXNkZnNhZGZzYWRmc2FkZnNhZGZzYWRmc2FkZnNhZGZzYWRmc2FkZnNhZGZzYWRmc2FkZnNhZGZzYWRmc2FkZg==";
         let record = HandoffRecord {
             blob,
+            blob_hash: None,
             burn: false,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
             created_at: 1740000000u64,
             hostname: String::new(),
             pin_salt: None,
@@ -572,6 +1091,7 @@ mod size_analysis {
             recipient: None,
             signature: typical_sig_b64.to_string(),
             ttl: 86400,
+            version: migrate::CURRENT_VERSION,
         };
         let record_json = serde_json::to_string(&record).expect("serialize record");
 
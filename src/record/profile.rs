@@ -0,0 +1,129 @@
+//! Profile records: a self-chosen display name for a public key.
+//!
+//! `cclink profile set-name <name>` signs a [`Profile`] with the cclink key and
+//! publishes it under `_cclink-profile`, in a slot derived from the public key
+//! (`crypto::profile_keypair`) so anyone holding the pubkey can look it up. `list`,
+//! `pickup` and `inspect` show the name next to the pubkey.
+//!
+//! The name is claimed by the key's owner, not vouched for by anyone: it is shown
+//! quoted, and a local contact name always takes precedence.
+
+use serde::{Deserialize, Serialize};
+
+/// Longest accepted display name, in characters.
+pub const MAX_NAME_CHARS: usize = 64;
+
+/// A signed profile. Fields are in alphabetical order for canonical JSON.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Profile {
+    /// Unix timestamp (seconds) when the profile was signed.
+    pub created_at: u64,
+    /// Display name, e.g. "Alice (work laptop)".
+    pub name: String,
+    /// z32-encoded public key the profile describes.
+    pub pubkey: String,
+    /// Base64-encoded Ed25519 signature (profile key) over the signable fields.
+    pub signature: String,
+}
+
+/// The signable subset of [`Profile`] (everything except `signature`).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProfileSignable {
+    pub created_at: u64,
+    pub name: String,
+    pub pubkey: String,
+}
+
+impl From<&Profile> for ProfileSignable {
+    fn from(profile: &Profile) -> Self {
+        ProfileSignable {
+            created_at: profile.created_at,
+            name: profile.name.clone(),
+            pubkey: profile.pubkey.clone(),
+        }
+    }
+}
+
+/// Build a profile naming `keypair`, signed by it.
+pub fn seal_profile(
+    keypair: &pkarr::Keypair,
+    created_at: u64,
+    name: &str,
+) -> anyhow::Result<Profile> {
+    validate_name(name)?;
+    let signable = ProfileSignable {
+        created_at,
+        name: name.to_string(),
+        pubkey: keypair.public_key().to_z32(),
+    };
+    let json = super::jcs::to_string(&signable)?;
+    let signature = super::sign_bytes(json.as_bytes(), keypair);
+    Ok(Profile {
+        created_at: signable.created_at,
+        name: signable.name,
+        pubkey: signable.pubkey,
+        signature,
+    })
+}
+
+/// Verify that `profile` describes `pubkey`, was signed by it, and carries a valid name.
+pub fn verify_profile(profile: &Profile, pubkey: &pkarr::PublicKey) -> anyhow::Result<()> {
+    if profile.pubkey != pubkey.to_z32() {
+        anyhow::bail!("profile is for {}, not {}", profile.pubkey, pubkey.to_z32());
+    }
+    validate_name(&profile.name)?;
+    let json = super::jcs::to_string(&ProfileSignable::from(profile))?;
+    super::verify_bytes(json.as_bytes(), &profile.signature, pubkey)
+}
+
+/// Names must be non-blank, at most [`MAX_NAME_CHARS`] characters, and free of control
+/// characters, so a published name cannot rewrite the terminal it is printed to.
+fn validate_name(name: &str) -> anyhow::Result<()> {
+    if name.trim().is_empty() {
+        anyhow::bail!("display name must not be empty");
+    }
+    if name.chars().count() > MAX_NAME_CHARS {
+        anyhow::bail!("display name is longer than {} characters", MAX_NAME_CHARS);
+    }
+    if name.chars().any(char::is_control) {
+        anyhow::bail!("display name must not contain control characters");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keypair() -> pkarr::Keypair {
+        pkarr::Keypair::from_secret_key(&[42u8; 32])
+    }
+
+    #[test]
+    fn test_profile_round_trip() {
+        let profile = seal_profile(&keypair(), 1_700_000_000, "Alice").unwrap();
+        let json = serde_json::to_string(&profile).unwrap();
+        let parsed: Profile = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, profile);
+        verify_profile(&parsed, &keypair().public_key()).expect("should verify");
+    }
+
+    #[test]
+    fn test_profile_rejects_other_key_and_tampering() {
+        let profile = seal_profile(&keypair(), 1_700_000_000, "Alice").unwrap();
+        let other = pkarr::Keypair::from_secret_key(&[43u8; 32]).public_key();
+        assert!(verify_profile(&profile, &other).is_err());
+
+        let mut tampered = profile.clone();
+        tampered.name = "Mallory".to_string();
+        assert!(verify_profile(&tampered, &keypair().public_key()).is_err());
+    }
+
+    #[test]
+    fn test_profile_rejects_bad_names() {
+        assert!(seal_profile(&keypair(), 0, "  ").is_err());
+        assert!(seal_profile(&keypair(), 0, "evil\x1b[2J").is_err());
+        assert!(seal_profile(&keypair(), 0, &"a".repeat(MAX_NAME_CHARS + 1)).is_err());
+        assert!(seal_profile(&keypair(), 0, "Zoë 🦀").is_ok());
+    }
+}
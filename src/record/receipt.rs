@@ -0,0 +1,161 @@
+//! Pickup receipts: small signed records a picker publishes back to the publisher.
+//!
+//! When a handoff's payload carries a receipt key, the picker publishes a [`Receipt`]
+//! into the DHT slot owned by that key after a successful pickup. The slot keypair is
+//! derived by the publisher from its own seed and the handoff's `created_at`
+//! (`crypto::receipt_keypair`), so only the publisher and whoever could decrypt the
+//! handoff can locate or write the slot. The receipt is signed with the picker's own
+//! key, and the picker's hostname is age-encrypted to the publisher.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// A signed pickup receipt. Fields are in alphabetical order for canonical JSON.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Receipt {
+    /// Base64-encoded age ciphertext of [`ReceiptDetails`], encrypted to the publisher.
+    pub blob: String,
+    /// Unix timestamp (seconds) when the pickup happened.
+    pub created_at: u64,
+    /// `created_at` of the handoff that was picked up — identifies the handoff.
+    pub handoff: u64,
+    /// Picker's z32-encoded Ed25519 public key.
+    pub pubkey: String,
+    /// Base64-encoded Ed25519 signature (picker's key) over the signable fields.
+    pub signature: String,
+}
+
+/// The signable subset of [`Receipt`] (everything except `signature`).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReceiptSignable {
+    pub blob: String,
+    pub created_at: u64,
+    pub handoff: u64,
+    pub pubkey: String,
+}
+
+/// Receipt contents only the publisher can read.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReceiptDetails {
+    /// Hostname of the machine that picked up the handoff.
+    #[serde(rename = "h")]
+    pub hostname: String,
+}
+
+impl From<&Receipt> for ReceiptSignable {
+    fn from(receipt: &Receipt) -> Self {
+        ReceiptSignable {
+            blob: receipt.blob.clone(),
+            created_at: receipt.created_at,
+            handoff: receipt.handoff,
+            pubkey: receipt.pubkey.clone(),
+        }
+    }
+}
+
+/// Build and sign a receipt for the handoff published by `publisher_z32`.
+pub fn seal_receipt(
+    handoff: u64,
+    publisher_z32: &str,
+    picker: &pkarr::Keypair,
+    details: &ReceiptDetails,
+    created_at: u64,
+) -> anyhow::Result<Receipt> {
+    let recipient = crate::crypto::recipient_from_z32(publisher_z32)?;
+    let plaintext = serde_json::to_vec(details)
+        .map_err(|e| anyhow::anyhow!("failed to serialize receipt details: {}", e))?;
+    let ciphertext = crate::crypto::age_encrypt(&plaintext, &recipient)?;
+
+    let signable = ReceiptSignable {
+        blob: base64::engine::general_purpose::STANDARD.encode(&ciphertext),
+        created_at,
+        handoff,
+        pubkey: picker.public_key().to_z32(),
+    };
+    let json = super::jcs::to_string(&signable)?;
+    let signature = super::sign_bytes(json.as_bytes(), picker);
+    Ok(Receipt {
+        blob: signable.blob,
+        created_at: signable.created_at,
+        handoff: signable.handoff,
+        pubkey: signable.pubkey,
+        signature,
+    })
+}
+
+/// Verify a receipt's signature against the picker key it claims.
+///
+/// Returns the picker's public key on success. Callers that know who the picker
+/// must be (e.g. the `--share` recipient) should compare it.
+pub fn verify_receipt(receipt: &Receipt) -> anyhow::Result<pkarr::PublicKey> {
+    let picker = pkarr::PublicKey::try_from(receipt.pubkey.as_str())
+        .map_err(|e| anyhow::anyhow!("invalid receipt pubkey: {}", e))?;
+    let json = super::jcs::to_string(&ReceiptSignable::from(receipt))?;
+    super::verify_bytes(json.as_bytes(), &receipt.signature, &picker)?;
+    Ok(picker)
+}
+
+/// Decrypt the receipt details with the publisher's keypair.
+pub fn open_receipt(
+    receipt: &Receipt,
+    publisher: &pkarr::Keypair,
+) -> anyhow::Result<ReceiptDetails> {
+    let ciphertext = base64::engine::general_purpose::STANDARD
+        .decode(&receipt.blob)
+        .map_err(|e| anyhow::anyhow!("failed to decode receipt blob: {}", e))?;
+    let x25519_secret = crate::crypto::ed25519_to_x25519_secret(publisher);
+    let identity = crate::crypto::age_identity(&x25519_secret);
+    let plaintext = crate::crypto::age_decrypt(&ciphertext, &identity)?;
+    serde_json::from_slice(&plaintext)
+        .map_err(|e| anyhow::anyhow!("failed to parse receipt details: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn publisher() -> pkarr::Keypair {
+        pkarr::Keypair::from_secret_key(&[42u8; 32])
+    }
+
+    fn picker() -> pkarr::Keypair {
+        pkarr::Keypair::from_secret_key(&[99u8; 32])
+    }
+
+    fn sample_receipt() -> Receipt {
+        let details = ReceiptDetails {
+            hostname: "laptop".to_string(),
+        };
+        seal_receipt(
+            1_700_000_000,
+            &publisher().public_key().to_z32(),
+            &picker(),
+            &details,
+            1_700_000_100,
+        )
+        .expect("seal_receipt should succeed")
+    }
+
+    #[test]
+    fn test_receipt_round_trip() {
+        let receipt = sample_receipt();
+        let signer = verify_receipt(&receipt).expect("receipt should verify");
+        assert_eq!(signer, picker().public_key());
+        let details = open_receipt(&receipt, &publisher()).expect("publisher should decrypt");
+        assert_eq!(details.hostname, "laptop");
+    }
+
+    #[test]
+    fn test_receipt_tampered_handoff_fails_verification() {
+        let mut receipt = sample_receipt();
+        receipt.handoff += 1;
+        assert!(verify_receipt(&receipt).is_err());
+    }
+
+    #[test]
+    fn test_receipt_details_hidden_from_picker() {
+        let receipt = sample_receipt();
+        assert!(!receipt.blob.contains("laptop"));
+        assert!(open_receipt(&receipt, &picker()).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+//! Revocation certificates: signed statements that a key must no longer be trusted.
+//!
+//! `cclink key revoke-cert` signs a [`Revocation`] with the current key while it is
+//! still in hand; the user stores it offline. If the key is later lost or stolen,
+//! `cclink key publish-revocation` puts the certificate into a slot derived from the
+//! public key (`crypto::revocation_keypair`) — no secret needed — and pickups from
+//! that key refuse to continue. With the key still at hand, the certificate also goes
+//! into the key's own packet. A picker that has seen a certificate remembers it in
+//! its state database, so the key stays refused there if the slot is overwritten.
+
+use serde::{Deserialize, Serialize};
+
+/// A signed revocation certificate. Fields are in alphabetical order for canonical JSON.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Revocation {
+    /// Unix timestamp (seconds) when the certificate was made.
+    pub created_at: u64,
+    /// z32-encoded public key being revoked.
+    pub pubkey: String,
+    /// Free-form reason shown to pickers, e.g. "laptop stolen".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Base64-encoded Ed25519 signature (revoked key) over the signable fields.
+    pub signature: String,
+}
+
+/// The signable subset of [`Revocation`] (everything except `signature`).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RevocationSignable {
+    pub created_at: u64,
+    pub pubkey: String,
+    pub reason: Option<String>,
+}
+
+impl From<&Revocation> for RevocationSignable {
+    fn from(revocation: &Revocation) -> Self {
+        RevocationSignable {
+            created_at: revocation.created_at,
+            pubkey: revocation.pubkey.clone(),
+            reason: revocation.reason.clone(),
+        }
+    }
+}
+
+/// Build a revocation certificate for `keypair`, signed by it.
+pub fn seal_revocation(
+    keypair: &pkarr::Keypair,
+    created_at: u64,
+    reason: Option<String>,
+) -> anyhow::Result<Revocation> {
+    let signable = RevocationSignable {
+        created_at,
+        pubkey: keypair.public_key().to_z32(),
+        reason,
+    };
+    let json = super::jcs::to_string(&signable)?;
+    let signature = super::sign_bytes(json.as_bytes(), keypair);
+    Ok(Revocation {
+        created_at: signable.created_at,
+        pubkey: signable.pubkey,
+        reason: signable.reason,
+        signature,
+    })
+}
+
+/// Verify that `revocation` revokes `pubkey` and was signed by it.
+pub fn verify_revocation(revocation: &Revocation, pubkey: &pkarr::PublicKey) -> anyhow::Result<()> {
+    if revocation.pubkey != pubkey.to_z32() {
+        anyhow::bail!(
+            "revocation is for {}, not {}",
+            revocation.pubkey,
+            pubkey.to_z32()
+        );
+    }
+    let json = super::jcs::to_string(&RevocationSignable::from(revocation))?;
+    super::verify_bytes(json.as_bytes(), &revocation.signature, pubkey)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keypair() -> pkarr::Keypair {
+        pkarr::Keypair::from_secret_key(&[42u8; 32])
+    }
+
+    #[test]
+    fn test_revocation_round_trip() {
+        let revocation =
+            seal_revocation(&keypair(), 1_700_000_000, Some("laptop stolen".to_string())).unwrap();
+        let json = serde_json::to_string(&revocation).unwrap();
+        let parsed: Revocation = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, revocation);
+        verify_revocation(&parsed, &keypair().public_key()).expect("should verify");
+    }
+
+    #[test]
+    fn test_revocation_rejects_other_key_and_tampering() {
+        let revocation = seal_revocation(&keypair(), 1_700_000_000, None).unwrap();
+        let other = pkarr::Keypair::from_secret_key(&[43u8; 32]).public_key();
+        assert!(verify_revocation(&revocation, &other).is_err());
+
+        let mut tampered = revocation.clone();
+        tampered.reason = Some("forged".to_string());
+        assert!(verify_revocation(&tampered, &keypair().public_key()).is_err());
+    }
+}
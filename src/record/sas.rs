@@ -0,0 +1,210 @@
+//! Short-authentication-string (SAS) key verification between two cclink users.
+//!
+//! Both parties run `cclink verify <peer>`. Each picks a fresh random nonce and
+//! publishes a signed [`VerifyOffer`] for the other in a slot both of them can derive
+//! (`crypto::verify_slot_keypair`): first only a commitment to the nonce, then — once
+//! the peer's commitment has been seen — the nonce itself. The code is a hash of both
+//! keys and both nonces, shown as 6 digits and 4 emoji. Committing before revealing
+//! means neither side (nor anyone in the middle) can pick a nonce that steers the
+//! code after seeing the other's, so matching codes read aloud prove both sides hold
+//! each other's real key.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Domain separation for the code hash.
+const SAS_DOMAIN: &[u8] = b"cclink-sas-v1";
+
+/// One side of a verification. Fields are in alphabetical order for canonical JSON.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VerifyOffer {
+    /// Base64 SHA-256 of the nonce.
+    pub commitment: String,
+    /// Unix timestamp (seconds) when this verification run started.
+    pub created_at: u64,
+    /// Base64-encoded 32-byte nonce; absent until the peer's commitment was seen.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// z32-encoded public key being verified.
+    pub peer: String,
+    /// z32-encoded public key of the side that made the offer.
+    pub pubkey: String,
+    /// Base64-encoded Ed25519 signature (offering key) over the signable fields.
+    pub signature: String,
+}
+
+/// The signable subset of [`VerifyOffer`] (everything except `signature`).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VerifyOfferSignable {
+    pub commitment: String,
+    pub created_at: u64,
+    pub nonce: Option<String>,
+    pub peer: String,
+    pub pubkey: String,
+}
+
+impl From<&VerifyOffer> for VerifyOfferSignable {
+    fn from(offer: &VerifyOffer) -> Self {
+        VerifyOfferSignable {
+            commitment: offer.commitment.clone(),
+            created_at: offer.created_at,
+            nonce: offer.nonce.clone(),
+            peer: offer.peer.clone(),
+            pubkey: offer.pubkey.clone(),
+        }
+    }
+}
+
+/// The code both sides compare.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sas {
+    /// Six decimal digits.
+    pub digits: String,
+    /// Four indices into [`crate::keys::fingerprint::EMOJI`].
+    pub emoji: [usize; 4],
+}
+
+/// Base64 SHA-256 commitment to `nonce`.
+pub fn commitment(nonce: &[u8; 32]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(Sha256::digest(nonce))
+}
+
+/// Build an offer from `keypair` to `peer`, signed by `keypair`. With `reveal`, the
+/// nonce goes in alongside its commitment.
+pub fn seal_offer(
+    keypair: &pkarr::Keypair,
+    peer: &pkarr::PublicKey,
+    created_at: u64,
+    nonce: &[u8; 32],
+    reveal: bool,
+) -> anyhow::Result<VerifyOffer> {
+    let signable = VerifyOfferSignable {
+        commitment: commitment(nonce),
+        created_at,
+        nonce: reveal.then(|| base64::engine::general_purpose::STANDARD.encode(nonce)),
+        peer: peer.to_z32(),
+        pubkey: keypair.public_key().to_z32(),
+    };
+    let json = super::jcs::to_string(&signable)?;
+    let signature = super::sign_bytes(json.as_bytes(), keypair);
+    Ok(VerifyOffer {
+        commitment: signable.commitment,
+        created_at: signable.created_at,
+        nonce: signable.nonce,
+        peer: signable.peer,
+        pubkey: signable.pubkey,
+        signature,
+    })
+}
+
+/// Verify that `offer` was made by `from` for `to`, and that a revealed nonce matches
+/// the commitment. Returns the nonce once revealed.
+pub fn verify_offer(
+    offer: &VerifyOffer,
+    from: &pkarr::PublicKey,
+    to: &pkarr::PublicKey,
+) -> anyhow::Result<Option<[u8; 32]>> {
+    if offer.pubkey != from.to_z32() || offer.peer != to.to_z32() {
+        anyhow::bail!(
+            "verification offer is from {} to {}, not {} to {}",
+            offer.pubkey,
+            offer.peer,
+            from.to_z32(),
+            to.to_z32()
+        );
+    }
+    let json = super::jcs::to_string(&VerifyOfferSignable::from(offer))?;
+    super::verify_bytes(json.as_bytes(), &offer.signature, from)?;
+    let Some(ref encoded) = offer.nonce else {
+        return Ok(None);
+    };
+    let nonce: [u8; 32] = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| anyhow::anyhow!("invalid nonce base64: {}", e))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("nonce must be exactly 32 bytes"))?;
+    if commitment(&nonce) != offer.commitment {
+        anyhow::bail!("revealed nonce does not match its commitment");
+    }
+    Ok(Some(nonce))
+}
+
+/// The code for keys `a` and `b` with their nonces. Both sides get the same code
+/// whichever of them is `a`.
+pub fn sas(
+    a: &pkarr::PublicKey,
+    nonce_a: &[u8; 32],
+    b: &pkarr::PublicKey,
+    nonce_b: &[u8; 32],
+) -> Sas {
+    let (first, second) = if a.as_bytes() <= b.as_bytes() {
+        ((a, nonce_a), (b, nonce_b))
+    } else {
+        ((b, nonce_b), (a, nonce_a))
+    };
+    let mut hasher = Sha256::new();
+    hasher.update(SAS_DOMAIN);
+    hasher.update(first.0.as_bytes());
+    hasher.update(first.1);
+    hasher.update(second.0.as_bytes());
+    hasher.update(second.1);
+    let digest = hasher.finalize();
+
+    let number = u32::from_be_bytes([0, digest[0], digest[1], digest[2]]) % 1_000_000;
+    let indices = crate::keys::fingerprint::emoji_indices(&digest[3..6]);
+    Sas {
+        digits: format!("{:06}", number),
+        emoji: [indices[0], indices[1], indices[2], indices[3]],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keypair(seed: u8) -> pkarr::Keypair {
+        pkarr::Keypair::from_secret_key(&[seed; 32])
+    }
+
+    #[test]
+    fn test_offer_commit_then_reveal() {
+        let (alice, bob) = (keypair(1), keypair(2));
+        let nonce = [7u8; 32];
+        let committed = seal_offer(&alice, &bob.public_key(), 100, &nonce, false).unwrap();
+        assert_eq!(
+            verify_offer(&committed, &alice.public_key(), &bob.public_key()).unwrap(),
+            None
+        );
+        let revealed = seal_offer(&alice, &bob.public_key(), 100, &nonce, true).unwrap();
+        assert_eq!(revealed.commitment, committed.commitment);
+        assert_eq!(
+            verify_offer(&revealed, &alice.public_key(), &bob.public_key()).unwrap(),
+            Some(nonce)
+        );
+    }
+
+    #[test]
+    fn test_offer_rejects_wrong_parties_and_swapped_nonce() {
+        let (alice, bob) = (keypair(1), keypair(2));
+        let offer = seal_offer(&alice, &bob.public_key(), 100, &[7u8; 32], true).unwrap();
+        assert!(verify_offer(&offer, &bob.public_key(), &alice.public_key()).is_err());
+
+        // A nonce that does not match the commitment, even when properly signed.
+        let mut forged = seal_offer(&alice, &bob.public_key(), 100, &[8u8; 32], true).unwrap();
+        forged.commitment = offer.commitment.clone();
+        let json = crate::record::jcs::to_string(&VerifyOfferSignable::from(&forged)).unwrap();
+        forged.signature = crate::record::sign_bytes(json.as_bytes(), &alice);
+        assert!(verify_offer(&forged, &alice.public_key(), &bob.public_key()).is_err());
+    }
+
+    #[test]
+    fn test_sas_is_symmetric_and_depends_on_nonces() {
+        let (a, b) = (keypair(1).public_key(), keypair(2).public_key());
+        let code = sas(&a, &[1u8; 32], &b, &[2u8; 32]);
+        assert_eq!(code, sas(&b, &[2u8; 32], &a, &[1u8; 32]));
+        assert_eq!(code.digits.len(), 6);
+        assert!(code.emoji.iter().all(|&i| i < 64));
+        assert_ne!(code, sas(&a, &[1u8; 32], &b, &[3u8; 32]));
+    }
+}
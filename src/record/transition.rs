@@ -0,0 +1,142 @@
+//! Key transitions: cross-signed statements that a retired key was replaced by a new one.
+//!
+//! `cclink key rotate` publishes a [`Transition`] into a slot derived from the old
+//! public key (`crypto::transition_keypair`), so anyone who knew the old key can find
+//! its successor. The statement is signed by the old key — proving its holder retired
+//! it — and by the new key — proving the successor accepted it — so neither a thief of
+//! the new key nor a writer of the public slot can redirect the old key elsewhere.
+//! Pickup pins the first transition it follows in its state database, so erasing the
+//! slot later does not hide the rotation, and a second transition signed by a stolen
+//! old key is refused.
+
+use serde::{Deserialize, Serialize};
+
+/// A signed key transition. Fields are in alphabetical order for canonical JSON.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Transition {
+    /// Unix timestamp (seconds) of the rotation.
+    pub created_at: u64,
+    /// Unix timestamp until which the old key keeps serving its last handoff.
+    pub grace_until: u64,
+    /// z32-encoded public key that replaces `old_pubkey`.
+    pub new_pubkey: String,
+    /// Base64-encoded Ed25519 signature (new key) over the signable fields.
+    pub new_signature: String,
+    /// z32-encoded public key being retired.
+    pub old_pubkey: String,
+    /// Base64-encoded Ed25519 signature (old key) over the signable fields.
+    pub old_signature: String,
+}
+
+/// The signable subset of [`Transition`] (everything except the signatures).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransitionSignable {
+    pub created_at: u64,
+    pub grace_until: u64,
+    pub new_pubkey: String,
+    pub old_pubkey: String,
+}
+
+impl From<&Transition> for TransitionSignable {
+    fn from(transition: &Transition) -> Self {
+        TransitionSignable {
+            created_at: transition.created_at,
+            grace_until: transition.grace_until,
+            new_pubkey: transition.new_pubkey.clone(),
+            old_pubkey: transition.old_pubkey.clone(),
+        }
+    }
+}
+
+/// Build a transition from `old` to `new`, signed by both keys.
+pub fn seal_transition(
+    old: &pkarr::Keypair,
+    new: &pkarr::Keypair,
+    created_at: u64,
+    grace_until: u64,
+) -> anyhow::Result<Transition> {
+    let signable = TransitionSignable {
+        created_at,
+        grace_until,
+        new_pubkey: new.public_key().to_z32(),
+        old_pubkey: old.public_key().to_z32(),
+    };
+    let json = super::jcs::to_string(&signable)?;
+    Ok(Transition {
+        created_at: signable.created_at,
+        grace_until: signable.grace_until,
+        new_signature: super::sign_bytes(json.as_bytes(), new),
+        old_signature: super::sign_bytes(json.as_bytes(), old),
+        new_pubkey: signable.new_pubkey,
+        old_pubkey: signable.old_pubkey,
+    })
+}
+
+/// Verify that `transition` retires `old` and carries both signatures.
+///
+/// Returns the new public key on success.
+pub fn verify_transition(
+    transition: &Transition,
+    old: &pkarr::PublicKey,
+) -> anyhow::Result<pkarr::PublicKey> {
+    if transition.old_pubkey != old.to_z32() {
+        anyhow::bail!(
+            "transition is for {}, not {}",
+            transition.old_pubkey,
+            old.to_z32()
+        );
+    }
+    let new = pkarr::PublicKey::try_from(transition.new_pubkey.as_str())
+        .map_err(|e| anyhow::anyhow!("invalid transition pubkey: {}", e))?;
+    let json = super::jcs::to_string(&TransitionSignable::from(transition))?;
+    super::verify_bytes(json.as_bytes(), &transition.old_signature, old)?;
+    super::verify_bytes(json.as_bytes(), &transition.new_signature, &new)?;
+    Ok(new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn old() -> pkarr::Keypair {
+        pkarr::Keypair::from_secret_key(&[42u8; 32])
+    }
+
+    fn new() -> pkarr::Keypair {
+        pkarr::Keypair::from_secret_key(&[43u8; 32])
+    }
+
+    fn sample_transition() -> Transition {
+        seal_transition(&old(), &new(), 1_700_000_000, 1_700_604_800)
+            .expect("seal_transition should succeed")
+    }
+
+    #[test]
+    fn test_transition_round_trip() {
+        let transition = sample_transition();
+        let successor =
+            verify_transition(&transition, &old().public_key()).expect("transition should verify");
+        assert_eq!(successor, new().public_key());
+    }
+
+    #[test]
+    fn test_transition_for_another_key_is_rejected() {
+        let transition = sample_transition();
+        assert!(verify_transition(&transition, &new().public_key()).is_err());
+    }
+
+    #[test]
+    fn test_transition_needs_both_signatures() {
+        // Redirecting to a third key breaks the old key's signature.
+        let mut redirected = sample_transition();
+        redirected.new_pubkey = pkarr::Keypair::from_secret_key(&[44u8; 32])
+            .public_key()
+            .to_z32();
+        assert!(verify_transition(&redirected, &old().public_key()).is_err());
+
+        // A statement the new key never signed is rejected too.
+        let mut unaccepted = sample_transition();
+        unaccepted.new_signature = unaccepted.old_signature.clone();
+        assert!(verify_transition(&unaccepted, &old().public_key()).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+//! One-time-code handoffs: share with someone whose key you do not have.
+//!
+//! `cclink --code` prints a short code such as `7-guitarist-revenge` (a number and
+//! two BIP39 words) and `cclink pickup --code <code>` types it in. Both sides run
+//! SPAKE2 with the code as the password, exchanging its messages through two slots
+//! derived from the code (`crypto::wormhole_keypair`), and turn the shared key into
+//! an age identity. The publisher encrypts the handoff to that identity's recipient
+//! and then tells the picker its public key, encrypted the same way.
+//!
+//! SPAKE2 gives anyone without the code one guess per run, and nothing observed on
+//! the DHT helps guess offline, so a short code is enough for a one-off share.
+
+use base64::Engine;
+use hkdf::Hkdf;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use spake2::{Ed25519Group, Identity, Password, Spake2};
+use zeroize::Zeroizing;
+
+/// SPAKE2 identity: both sides use the symmetric mode under this name.
+const PAKE_ID: &[u8] = b"cclink-wormhole-v1";
+
+/// HKDF info string turning the SPAKE2 key into an age identity.
+const KEY_HKDF_INFO: &[u8] = b"cclink-wormhole-key-v1";
+
+/// One side's message in its rendezvous slot.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WormholeMessage {
+    /// Base64-encoded SPAKE2 message.
+    pub pake: String,
+    /// Base64-encoded age ciphertext of the publisher's z32 key, to the agreed key.
+    /// Added by the publisher once the handoff is published.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+}
+
+/// A fresh code: `<1-99>-<word>-<word>`.
+pub fn generate_code() -> String {
+    let words = bip39::Language::English.word_list();
+    let mut rng = rand::thread_rng();
+    format!(
+        "{}-{}-{}",
+        rng.gen_range(1..=99),
+        words[rng.gen_range(0..words.len())],
+        words[rng.gen_range(0..words.len())]
+    )
+}
+
+/// Check a typed code and put it in canonical form (trimmed, lowercase).
+pub fn normalize_code(input: &str) -> anyhow::Result<String> {
+    let code = input.trim().to_lowercase();
+    let parts: Vec<&str> = code.split('-').collect();
+    let valid = match parts.as_slice() {
+        [number, first, second] => {
+            number.parse::<u8>().is_ok_and(|n| (1..=99).contains(&n))
+                && [first, second]
+                    .iter()
+                    .all(|w| bip39::Language::English.find_word(w).is_some())
+        }
+        _ => false,
+    };
+    if !valid {
+        anyhow::bail!(
+            "'{}' is not a cclink code (expected something like 7-guitarist-revenge)",
+            input.trim()
+        );
+    }
+    Ok(code)
+}
+
+/// Start SPAKE2 for `code`. Returns the state to finish with and the message to send.
+pub fn start(code: &str) -> (Spake2<Ed25519Group>, Vec<u8>) {
+    Spake2::<Ed25519Group>::start_symmetric(
+        &Password::new(code.as_bytes()),
+        &Identity::new(PAKE_ID),
+    )
+}
+
+/// Finish SPAKE2 with the other side's message and derive the age identity both sides
+/// share when they typed the same code.
+pub fn finish(
+    state: Spake2<Ed25519Group>,
+    their_message: &str,
+) -> anyhow::Result<age::x25519::Identity> {
+    let message = base64::engine::general_purpose::STANDARD
+        .decode(their_message)
+        .map_err(|e| anyhow::anyhow!("invalid key agreement message: {}", e))?;
+    let key = Zeroizing::new(
+        state
+            .finish(&message)
+            .map_err(|e| anyhow::anyhow!("key agreement failed: {:?}", e))?,
+    );
+    let hkdf = Hkdf::<Sha256>::new(None, &key);
+    let mut okm = Zeroizing::new([0u8; 32]);
+    hkdf.expand(KEY_HKDF_INFO, okm.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    Ok(crate::crypto::age_identity(&okm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(message: &[u8]) -> String {
+        base64::engine::general_purpose::STANDARD.encode(message)
+    }
+
+    #[test]
+    fn test_generated_codes_are_valid() {
+        for _ in 0..20 {
+            let code = generate_code();
+            assert_eq!(normalize_code(&code).unwrap(), code);
+        }
+    }
+
+    #[test]
+    fn test_normalize_code() {
+        assert_eq!(
+            normalize_code("  7-Guitar-Abandon ").unwrap(),
+            "7-guitar-abandon"
+        );
+        assert!(normalize_code("7-guitar").is_err());
+        assert!(normalize_code("0-guitar-abandon").is_err());
+        assert!(normalize_code("7-guitar-notaword").is_err());
+    }
+
+    #[test]
+    fn test_same_code_agrees_on_identity() {
+        let (a, a_msg) = start("7-guitar-abandon");
+        let (b, b_msg) = start("7-guitar-abandon");
+        let a_id = finish(a, &encode(&b_msg)).unwrap();
+        let b_id = finish(b, &encode(&a_msg)).unwrap();
+        assert_eq!(a_id.to_public().to_string(), b_id.to_public().to_string());
+
+        let (c, c_msg) = start("7-guitar-abandon");
+        let (d, d_msg) = start("8-guitar-abandon");
+        let c_id = finish(c, &encode(&d_msg)).unwrap();
+        let d_id = finish(d, &encode(&c_msg)).unwrap();
+        assert_ne!(c_id.to_public().to_string(), d_id.to_public().to_string());
+    }
+}
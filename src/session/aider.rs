@@ -0,0 +1,215 @@
+//! Aider sessions: Aider keeps one chat history per project, in
+//! `.aider.chat.history.md` at the repository root, and has no session IDs. A
+//! project's history is its session; the ID cclink shows is derived from the path.
+
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use super::{Agent, Message, Role, SessionInfo, SessionProvider};
+
+const HISTORY_FILE: &str = ".aider.chat.history.md";
+
+/// How much of the end of a history file [`Aider::last_assistant_message`] reads.
+const TAIL_BYTES: u64 = 64 * 1024;
+
+pub struct Aider;
+
+impl SessionProvider for Aider {
+    /// The history of the project containing `cwd_filter` (or the current directory),
+    /// if it was written to within the last 24 hours.
+    fn discover(&self, cwd_filter: Option<&Path>) -> anyhow::Result<Vec<SessionInfo>> {
+        let start = match cwd_filter {
+            Some(dir) => dir.to_path_buf(),
+            None => std::env::current_dir()?,
+        };
+        let Some(path) = find_history(&start) else {
+            return Ok(vec![]);
+        };
+        let mtime = std::fs::metadata(&path)?.modified()?;
+        if mtime < super::active_cutoff() {
+            return Ok(vec![]);
+        }
+        let project = path
+            .parent()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        Ok(vec![SessionInfo {
+            agent: Agent::Aider,
+            session_id: session_id(&project),
+            project,
+            mtime,
+        }])
+    }
+
+    /// The history of the project containing the current directory. Aider has one
+    /// history per project, so the ID is not needed to find it.
+    fn find_session_file(&self, _session_id: &str) -> Option<PathBuf> {
+        find_history(&std::env::current_dir().ok()?)
+    }
+
+    fn last_assistant_message(&self, path: &Path, max_chars: usize) -> Option<String> {
+        let lines = super::tail_lines(path, TAIL_BYTES).ok()?;
+        let text = last_reply(&lines)?;
+        Some(super::summarize(&text, max_chars))
+    }
+
+    fn recent_messages(&self, path: &Path, count: usize, max_chars: usize) -> Vec<Message> {
+        let lines = super::tail_lines(path, TAIL_BYTES).unwrap_or_default();
+        super::last_messages(messages(&lines).into_iter(), count, max_chars)
+    }
+
+    fn transcripts(&self) -> bool {
+        true
+    }
+
+    /// Write the history into the project directory, which must already exist.
+    fn install_transcript(
+        &self,
+        _session_id: &str,
+        project: &str,
+        transcript: &str,
+    ) -> anyhow::Result<PathBuf> {
+        let dir = Path::new(project);
+        if !dir.is_dir() {
+            anyhow::bail!("project directory {} does not exist", dir.display());
+        }
+        super::write_new(&dir.join(HISTORY_FILE), transcript)
+    }
+
+    fn resume_command(&self, _session_id: &str) -> std::process::Command {
+        let mut cmd = std::process::Command::new("aider");
+        cmd.arg("--restore-chat-history");
+        cmd
+    }
+}
+
+/// Stable ID for a project's history: 12 hex digits of SHA-256 of its path.
+fn session_id(project: &str) -> String {
+    crate::util::to_hex(&Sha256::digest(project.as_bytes())[..6])
+}
+
+/// Nearest history file from `start` up to the repository root (the first
+/// directory containing `.git`), where Aider writes it.
+fn find_history(start: &Path) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        let candidate = dir.join(HISTORY_FILE);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+/// Text after the last `#### ` user prompt, without tool output (`> ` lines).
+fn last_reply(lines: &[String]) -> Option<String> {
+    match messages(lines).pop()? {
+        (Role::Assistant, text) => Some(text),
+        (Role::User, _) => None,
+    }
+}
+
+/// The conversation in a history: `#### ` lines are the user's prompt (one message per
+/// run of them), and the text up to the next prompt is the reply, without tool output
+/// (`> ` lines) or headings. Text before the first prompt is skipped.
+fn messages(lines: &[String]) -> Vec<(Role, String)> {
+    let mut messages: Vec<(Role, String)> = Vec::new();
+    let mut seen_prompt = false;
+    for line in lines {
+        let (role, text) = if let Some(prompt) = line.strip_prefix("#### ") {
+            seen_prompt = true;
+            (Role::User, prompt)
+        } else if seen_prompt && !line.starts_with('>') && !line.starts_with('#') {
+            (Role::Assistant, line.as_str())
+        } else {
+            continue;
+        };
+        match messages.last_mut() {
+            Some((last, joined)) if *last == role => {
+                joined.push(' ');
+                joined.push_str(text);
+            }
+            _ => messages.push((role, text.to_string())),
+        }
+    }
+    messages.retain(|(_, text)| !text.trim().is_empty());
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_reply_skips_prompt_and_tool_output() {
+        let lines: Vec<String> = [
+            "# aider chat started at 2025-09-01 10:00:00",
+            "",
+            "#### fix the parser",
+            "",
+            "The parser now handles",
+            "empty input.",
+            "",
+            "> Applied edit to parser.py",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(
+            last_reply(&lines).map(|t| crate::session::summarize(&t, 80)),
+            Some("The parser now handles empty input.".to_string())
+        );
+        assert_eq!(last_reply(&lines[..2]), None);
+    }
+
+    #[test]
+    fn messages_alternate_prompts_and_replies() {
+        let lines: Vec<String> = [
+            "# aider chat started at 2025-09-01 10:00:00",
+            "#### fix the parser",
+            "#### and add a test",
+            "Done.",
+            "> Applied edit to parser.py",
+            "#### thanks",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(
+            messages(&lines),
+            [
+                (Role::User, "fix the parser and add a test".to_string()),
+                (Role::Assistant, "Done.".to_string()),
+                (Role::User, "thanks".to_string()),
+            ]
+        );
+        assert_eq!(last_reply(&lines), None);
+    }
+
+    #[test]
+    fn find_history_stops_at_repository_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        let sub = repo.join("src/deep");
+        std::fs::create_dir_all(&sub).unwrap();
+        std::fs::create_dir(repo.join(".git")).unwrap();
+        // A history above the repository belongs to some other project.
+        std::fs::write(dir.path().join(HISTORY_FILE), "").unwrap();
+        assert_eq!(find_history(&sub), None);
+
+        std::fs::write(repo.join(HISTORY_FILE), "").unwrap();
+        assert_eq!(find_history(&sub), Some(repo.join(HISTORY_FILE)));
+    }
+
+    #[test]
+    fn install_transcript_writes_into_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().display().to_string();
+        let path = Aider
+            .install_transcript("x", &project, "#### hi\n")
+            .unwrap();
+        assert_eq!(path, dir.path().join(HISTORY_FILE));
+        assert!(Aider.install_transcript("x", &project, "again").is_err());
+    }
+}
@@ -0,0 +1,346 @@
+//! Claude Code sessions: one JSONL file per session under
+//! `~/.claude/projects/<project_dir_name>/<session_id>.jsonl`.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use super::{Agent, Message, ProjectScope, Role, SessionInfo, SessionProvider};
+
+/// How much of the end of a session file [`ClaudeCode::last_assistant_message`] reads.
+const TAIL_BYTES: u64 = 256 * 1024;
+
+pub struct ClaudeCode;
+
+impl SessionProvider for ClaudeCode {
+    /// Scan `~/.claude/projects/` for sessions modified within the last 24 hours.
+    fn discover(&self, cwd_filter: Option<&Path>) -> anyhow::Result<Vec<SessionInfo>> {
+        let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
+        let projects_dir = home.join(".claude/projects");
+
+        if !projects_dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let cutoff = super::active_cutoff();
+
+        // Resolve the filter scope once before the loop
+        let scope = cwd_filter.map(ProjectScope::new);
+
+        let mut sessions: Vec<SessionInfo> = Vec::new();
+
+        for project_dir_entry in std::fs::read_dir(&projects_dir)? {
+            let project_dir = project_dir_entry?.path();
+            if !project_dir.is_dir() {
+                continue;
+            }
+
+            for entry in std::fs::read_dir(&project_dir)? {
+                let entry = entry?;
+                let path = entry.path();
+
+                // Only process .jsonl files
+                if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                    continue;
+                }
+
+                let mtime = entry.metadata()?.modified()?;
+
+                // Skip sessions older than 24 hours
+                if mtime < cutoff {
+                    continue;
+                }
+
+                // Session ID is the filename stem (the UUID)
+                let session_id = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("")
+                    .to_string();
+
+                if session_id.is_empty() {
+                    continue;
+                }
+
+                // Read cwd from JSONL progress record
+                if let Ok(project) = read_session_cwd(&path) {
+                    // Filter by cwd if provided
+                    if let Some(ref scope) = scope {
+                        if !scope.matches(Path::new(&project)) {
+                            continue;
+                        }
+                    }
+
+                    sessions.push(SessionInfo {
+                        agent: Agent::Claude,
+                        session_id,
+                        project,
+                        mtime,
+                    });
+                }
+            }
+        }
+
+        // Sort by mtime descending (most recent first)
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.mtime));
+        Ok(sessions)
+    }
+
+    /// Locate a session's JSONL file under `~/.claude/projects/<project_hash>/`.
+    ///
+    /// The project hash is not known from the session ID alone, so every project
+    /// directory is checked.
+    fn find_session_file(&self, session_id: &str) -> Option<PathBuf> {
+        let projects_dir = dirs::home_dir()?.join(".claude/projects");
+        let target = format!("{}.jsonl", session_id);
+        std::fs::read_dir(&projects_dir)
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path().join(&target))
+            .find(|path| path.is_file())
+    }
+
+    /// Only the final [`TAIL_BYTES`] of the file are read, so this stays cheap on long
+    /// sessions. Assistant entries that contain only tool calls are skipped.
+    fn last_assistant_message(&self, path: &Path, max_chars: usize) -> Option<String> {
+        let text = super::tail_lines(path, TAIL_BYTES)
+            .ok()?
+            .iter()
+            .rev()
+            .find_map(|line| match message_text(line)? {
+                (Role::Assistant, text) => Some(text),
+                (Role::User, _) => None,
+            })?;
+        Some(super::summarize(&text, max_chars))
+    }
+
+    fn recent_messages(&self, path: &Path, count: usize, max_chars: usize) -> Vec<Message> {
+        let lines = super::tail_lines(path, TAIL_BYTES).unwrap_or_default();
+        super::last_messages(
+            lines.iter().filter_map(|line| message_text(line)),
+            count,
+            max_chars,
+        )
+    }
+
+    fn transcripts(&self) -> bool {
+        true
+    }
+
+    /// Write the transcript to `~/.claude/projects/<project_dir_name>/<session_id>.jsonl`.
+    fn install_transcript(
+        &self,
+        session_id: &str,
+        project: &str,
+        transcript: &str,
+    ) -> anyhow::Result<PathBuf> {
+        let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
+        write_transcript(
+            &home.join(".claude/projects"),
+            session_id,
+            project,
+            transcript,
+        )
+    }
+
+    fn resume_command(&self, session_id: &str) -> std::process::Command {
+        let mut cmd = std::process::Command::new("claude");
+        cmd.arg("--resume").arg(session_id);
+        cmd
+    }
+}
+
+/// Name of the `~/.claude/projects/` subdirectory Claude Code uses for `project`:
+/// the path with every character other than an ASCII letter or digit replaced by `-`.
+fn project_dir_name(project: &str) -> String {
+    project
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+fn write_transcript(
+    projects_dir: &Path,
+    session_id: &str,
+    project: &str,
+    transcript: &str,
+) -> anyhow::Result<PathBuf> {
+    // The ID comes from the (verified, decrypted) payload, but it still names a file.
+    if session_id.is_empty()
+        || !session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        anyhow::bail!("invalid session ID '{}'", session_id);
+    }
+    let dir = projects_dir.join(project_dir_name(project));
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    super::write_new(&dir.join(format!("{}.jsonl", session_id)), transcript)
+}
+
+/// Role and joined text blocks of a user or assistant JSONL entry, or `None` for any
+/// other line. Tool results, and the command and hook output Claude Code records as
+/// user entries (`isMeta`, or text in `<tags>`), are not messages.
+fn message_text(line: &str) -> Option<(Role, String)> {
+    let obj: serde_json::Value = serde_json::from_str(line).ok()?;
+    let role = match obj.get("type")?.as_str()? {
+        "user" => Role::User,
+        "assistant" => Role::Assistant,
+        _ => return None,
+    };
+    if obj.get("isMeta").and_then(|m| m.as_bool()) == Some(true) {
+        return None;
+    }
+    let content = obj.get("message")?.get("content")?;
+    let text = match content {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+            .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => return None,
+    };
+    if role == Role::User && text.trim_start().starts_with('<') {
+        return None;
+    }
+    (!text.trim().is_empty()).then_some((role, text))
+}
+
+/// Read the `cwd` field from a JSONL session file.
+///
+/// Reads up to 20 lines and looks for the first line with a non-empty `cwd`
+/// string. The second line is typically a `type=progress` record containing
+/// both `cwd` and `sessionId`. Caps at 20 lines to avoid reading large files.
+fn read_session_cwd(path: &Path) -> anyhow::Result<String> {
+    use std::io::{BufRead, BufReader};
+    let file = std::fs::File::open(path)?;
+    let reader = BufReader::new(file);
+
+    for line in reader.lines().take(20) {
+        let line = line?;
+        if let Ok(obj) = serde_json::from_str::<serde_json::Value>(&line) {
+            if let Some(cwd) = obj.get("cwd").and_then(|v| v.as_str()) {
+                if !cwd.is_empty() {
+                    return Ok(cwd.to_string());
+                }
+            }
+        }
+    }
+
+    anyhow::bail!("no cwd found in session file: {}", path.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::tests::write_jsonl;
+
+    #[test]
+    fn project_dir_name_matches_claude_layout() {
+        assert_eq!(
+            project_dir_name("/home/dev/my.app_v2"),
+            "-home-dev-my-app-v2"
+        );
+    }
+
+    #[test]
+    fn write_transcript_creates_project_dir_and_refuses_overwrite() {
+        let projects = tempfile::tempdir().unwrap();
+        let path = write_transcript(projects.path(), "abc-123", "/work/app", "{}\n").unwrap();
+        assert_eq!(path, projects.path().join("-work-app/abc-123.jsonl"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{}\n");
+
+        assert!(write_transcript(projects.path(), "abc-123", "/work/app", "{}\n").is_err());
+        assert!(write_transcript(projects.path(), "../evil", "/work/app", "{}\n").is_err());
+    }
+
+    #[test]
+    fn last_assistant_message_skips_tool_only_entries() {
+        let file = write_jsonl(&[
+            r#"{"type":"user","message":{"role":"user","content":"hi"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Fixed the   parser\nbug."}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Bash"}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result"}]}}"#,
+        ]);
+        assert_eq!(
+            ClaudeCode
+                .last_assistant_message(file.path(), 80)
+                .as_deref(),
+            Some("Fixed the parser bug.")
+        );
+    }
+
+    #[test]
+    fn recent_messages_skips_tool_traffic() {
+        let file = write_jsonl(&[
+            r#"{"type":"user","message":{"role":"user","content":"fix the parser"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Looking."},{"type":"tool_use","name":"Read"}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","content":"..."}]}}"#,
+            r#"{"type":"user","isMeta":true,"message":{"role":"user","content":"Caveat: local command"}}"#,
+            r#"{"type":"user","message":{"role":"user","content":"<command-name>/clear</command-name>"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Fixed."}]}}"#,
+        ]);
+        let messages = ClaudeCode.recent_messages(file.path(), 2, 80);
+        assert_eq!(
+            messages,
+            [
+                Message {
+                    role: Role::Assistant,
+                    text: "Looking.".to_string()
+                },
+                Message {
+                    role: Role::Assistant,
+                    text: "Fixed.".to_string()
+                },
+            ]
+        );
+        assert_eq!(ClaudeCode.recent_messages(file.path(), 10, 80).len(), 3);
+    }
+
+    #[test]
+    fn last_assistant_message_truncates() {
+        let file =
+            write_jsonl(&[r#"{"type":"assistant","message":{"content":"one two three four"}}"#]);
+        assert_eq!(
+            ClaudeCode.last_assistant_message(file.path(), 8).as_deref(),
+            Some("one two…")
+        );
+    }
+
+    #[test]
+    fn discover_sessions_returns_vec_when_no_projects_dir() {
+        // discover() should never panic even when ~/.claude/projects/ does not
+        // exist — it returns an empty Vec instead.
+        // This test is inherently environment-dependent, but the important
+        // invariant is that the function returns Ok (not Err) when the
+        // directory is absent.
+        //
+        // We can only safely test this if projects/ is absent on this machine.
+        // If it IS present, the function should still return Ok.
+        let result = ClaudeCode.discover(None);
+        assert!(result.is_ok(), "discover must return Ok: {:?}", result);
+    }
+
+    #[test]
+    fn discover_sessions_filters_by_cwd() {
+        // Smoke test: passing a nonexistent path as the cwd filter must return
+        // Ok with an empty Vec, since no real session can have a project path
+        // that starts with a path that matches nothing on this machine.
+        let result = ClaudeCode.discover(Some(std::path::Path::new(
+            "/nonexistent/path/that/matches/nothing",
+        )));
+        assert!(
+            result.is_ok(),
+            "discover with cwd_filter must return Ok: {:?}",
+            result
+        );
+        let sessions = result.unwrap();
+        assert!(
+            sessions.is_empty(),
+            "expected empty Vec when filter matches nothing, got: {:?}",
+            sessions
+        );
+    }
+}
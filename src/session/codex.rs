@@ -0,0 +1,238 @@
+//! OpenAI Codex CLI sessions: rollout files under
+//! `$CODEX_HOME/sessions/YYYY/MM/DD/rollout-<timestamp>-<session_id>.jsonl`
+//! (`CODEX_HOME` defaults to `~/.codex`). The first line is a `session_meta` entry
+//! carrying the session ID and working directory.
+
+use std::path::{Path, PathBuf};
+
+use super::{Agent, Message, ProjectScope, Role, SessionInfo, SessionProvider};
+
+/// How much of the end of a rollout file [`Codex::last_assistant_message`] reads.
+const TAIL_BYTES: u64 = 256 * 1024;
+
+/// Directory levels below `sessions/`: year, month, day.
+const DATE_DEPTH: usize = 3;
+
+pub struct Codex;
+
+impl SessionProvider for Codex {
+    fn discover(&self, cwd_filter: Option<&Path>) -> anyhow::Result<Vec<SessionInfo>> {
+        let Some(dir) = sessions_dir().filter(|d| d.is_dir()) else {
+            return Ok(vec![]);
+        };
+        let cutoff = super::active_cutoff();
+        let scope = cwd_filter.map(ProjectScope::new);
+
+        let mut sessions = Vec::new();
+        for path in rollout_files(&dir) {
+            let Ok(mtime) = std::fs::metadata(&path).and_then(|m| m.modified()) else {
+                continue;
+            };
+            if mtime < cutoff {
+                continue;
+            }
+            // Rollouts from Codex versions that did not record a cwd cannot be
+            // attributed to a project, so they are skipped.
+            let Some((session_id, project)) = read_meta(&path) else {
+                continue;
+            };
+            if let Some(ref scope) = scope {
+                if !scope.matches(Path::new(&project)) {
+                    continue;
+                }
+            }
+            sessions.push(SessionInfo {
+                agent: Agent::Codex,
+                session_id,
+                project,
+                mtime,
+            });
+        }
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.mtime));
+        Ok(sessions)
+    }
+
+    /// The rollout whose file name ends in `-<session_id>.jsonl`.
+    fn find_session_file(&self, session_id: &str) -> Option<PathBuf> {
+        let suffix = format!("-{}.jsonl", session_id);
+        rollout_files(&sessions_dir()?).into_iter().find(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.ends_with(&suffix))
+        })
+    }
+
+    fn last_assistant_message(&self, path: &Path, max_chars: usize) -> Option<String> {
+        let text = super::tail_lines(path, TAIL_BYTES)
+            .ok()?
+            .iter()
+            .rev()
+            .find_map(|line| match message_text(line)? {
+                (Role::Assistant, text) => Some(text),
+                (Role::User, _) => None,
+            })?;
+        Some(super::summarize(&text, max_chars))
+    }
+
+    fn recent_messages(&self, path: &Path, count: usize, max_chars: usize) -> Vec<Message> {
+        let lines = super::tail_lines(path, TAIL_BYTES).unwrap_or_default();
+        super::last_messages(
+            lines.iter().filter_map(|line| message_text(line)),
+            count,
+            max_chars,
+        )
+    }
+
+    fn resume_command(&self, session_id: &str) -> std::process::Command {
+        let mut cmd = std::process::Command::new("codex");
+        cmd.arg("resume").arg(session_id);
+        cmd
+    }
+}
+
+fn sessions_dir() -> Option<PathBuf> {
+    let home = match std::env::var_os("CODEX_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::home_dir()?.join(".codex"),
+    };
+    Some(home.join("sessions"))
+}
+
+/// Every `rollout-*.jsonl` file in the date-partitioned tree under `dir`.
+fn rollout_files(dir: &Path) -> Vec<PathBuf> {
+    let mut level = vec![dir.to_path_buf()];
+    for _ in 0..DATE_DEPTH {
+        level = level
+            .iter()
+            .filter_map(|d| std::fs::read_dir(d).ok())
+            .flat_map(|entries| entries.flatten().map(|e| e.path()))
+            .filter(|p| p.is_dir())
+            .collect();
+    }
+    level
+        .iter()
+        .filter_map(|d| std::fs::read_dir(d).ok())
+        .flat_map(|entries| entries.flatten().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("rollout-") && n.ends_with(".jsonl"))
+        })
+        .collect()
+}
+
+/// Session ID and cwd from a rollout's `session_meta` line.
+fn read_meta(path: &Path) -> Option<(String, String)> {
+    use std::io::{BufRead, BufReader};
+    let file = std::fs::File::open(path).ok()?;
+    let first = BufReader::new(file).lines().next()?.ok()?;
+    let obj: serde_json::Value = serde_json::from_str(&first).ok()?;
+    let meta = match obj.get("type").and_then(|t| t.as_str()) {
+        Some("session_meta") => obj.get("payload")?,
+        _ => &obj,
+    };
+    let id = meta.get("id")?.as_str()?;
+    let cwd = meta.get("cwd")?.as_str()?;
+    (!id.is_empty() && !cwd.is_empty()).then(|| (id.to_string(), cwd.to_string()))
+}
+
+/// Role and joined text blocks of a user or assistant message entry, or `None` for
+/// any other line. The context Codex injects as user messages (`<environment_context>`
+/// and the like) is not a message.
+fn message_text(line: &str) -> Option<(Role, String)> {
+    let obj: serde_json::Value = serde_json::from_str(line).ok()?;
+    let item = match obj.get("type")?.as_str()? {
+        "response_item" => obj.get("payload")?,
+        _ => &obj,
+    };
+    if item.get("type")?.as_str()? != "message" {
+        return None;
+    }
+    let (role, block_type) = match item.get("role")?.as_str()? {
+        "user" => (Role::User, "input_text"),
+        "assistant" => (Role::Assistant, "output_text"),
+        _ => return None,
+    };
+    let text = item
+        .get("content")?
+        .as_array()?
+        .iter()
+        .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some(block_type))
+        .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if role == Role::User && text.trim_start().starts_with('<') {
+        return None;
+    }
+    (!text.trim().is_empty()).then_some((role, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::tests::write_jsonl;
+
+    #[test]
+    fn read_meta_from_session_meta_line() {
+        let file = write_jsonl(&[
+            r#"{"timestamp":"2025-09-01T10:00:00Z","type":"session_meta","payload":{"id":"0199a1b2-c3d4","cwd":"/work/app","originator":"codex_cli_rs"}}"#,
+        ]);
+        assert_eq!(
+            read_meta(file.path()),
+            Some(("0199a1b2-c3d4".to_string(), "/work/app".to_string()))
+        );
+
+        let old = write_jsonl(&[r#"{"id":"abc","timestamp":"2025-05-01T10:00:00Z"}"#]);
+        assert_eq!(read_meta(old.path()), None);
+    }
+
+    #[test]
+    fn last_assistant_message_reads_output_text() {
+        let file = write_jsonl(&[
+            r#"{"type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Tests   pass now."}]}}"#,
+            r#"{"type":"response_item","payload":{"type":"function_call","name":"shell"}}"#,
+            r#"{"type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"thanks"}]}}"#,
+        ]);
+        assert_eq!(
+            Codex.last_assistant_message(file.path(), 80).as_deref(),
+            Some("Tests pass now.")
+        );
+    }
+
+    #[test]
+    fn recent_messages_reads_both_roles() {
+        let file = write_jsonl(&[
+            r#"{"type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"<environment_context>cwd</environment_context>"}]}}"#,
+            r#"{"type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"run the tests"}]}}"#,
+            r#"{"type":"response_item","payload":{"type":"function_call","name":"shell"}}"#,
+            r#"{"type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"All green."}]}}"#,
+        ]);
+        let messages = Codex.recent_messages(file.path(), 5, 80);
+        assert_eq!(
+            messages,
+            [
+                Message {
+                    role: Role::User,
+                    text: "run the tests".to_string()
+                },
+                Message {
+                    role: Role::Assistant,
+                    text: "All green.".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rollout_files_walks_date_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let day = dir.path().join("2025/09/01");
+        std::fs::create_dir_all(&day).unwrap();
+        std::fs::write(day.join("rollout-2025-09-01T10-00-00-abc.jsonl"), "{}").unwrap();
+        std::fs::write(day.join("notes.txt"), "").unwrap();
+        std::fs::write(dir.path().join("rollout-stray.jsonl"), "{}").unwrap();
+
+        let files = rollout_files(dir.path());
+        assert_eq!(files, [day.join("rollout-2025-09-01T10-00-00-abc.jsonl")]);
+    }
+}
@@ -0,0 +1,102 @@
+//! Cursor CLI agent sessions: one directory per chat under
+//! `~/.cursor/chats/<workspace-hash>/<chat_id>/`, holding a SQLite `store.db`.
+//!
+//! The workspace is only recorded as a hash and the chat itself in SQLite, so cclink
+//! can neither scope these chats to a project precisely nor preview or carry them.
+//! Discovery attributes every recent chat to the directory it was asked about, so
+//! these chats are only offered with `--agent cursor`.
+
+use std::path::{Path, PathBuf};
+
+use super::{Agent, SessionInfo, SessionProvider};
+
+const STORE_FILE: &str = "store.db";
+
+pub struct Cursor;
+
+impl SessionProvider for Cursor {
+    fn discover(&self, cwd_filter: Option<&Path>) -> anyhow::Result<Vec<SessionInfo>> {
+        let Some(dir) = chats_dir().filter(|d| d.is_dir()) else {
+            return Ok(vec![]);
+        };
+        let project = match cwd_filter {
+            Some(dir) => dir.display().to_string(),
+            None => std::env::current_dir()?.display().to_string(),
+        };
+        let cutoff = super::active_cutoff();
+
+        let mut sessions = Vec::new();
+        for (chat_id, store) in chat_stores(&dir) {
+            let Ok(mtime) = std::fs::metadata(&store).and_then(|m| m.modified()) else {
+                continue;
+            };
+            if mtime < cutoff {
+                continue;
+            }
+            sessions.push(SessionInfo {
+                agent: Agent::Cursor,
+                session_id: chat_id,
+                project: project.clone(),
+                mtime,
+            });
+        }
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.mtime));
+        Ok(sessions)
+    }
+
+    fn scoped(&self) -> bool {
+        false
+    }
+
+    fn find_session_file(&self, session_id: &str) -> Option<PathBuf> {
+        chat_stores(&chats_dir()?)
+            .into_iter()
+            .find(|(chat_id, _)| chat_id == session_id)
+            .map(|(_, store)| store)
+    }
+
+    fn resume_command(&self, session_id: &str) -> std::process::Command {
+        let mut cmd = std::process::Command::new("cursor-agent");
+        cmd.arg("--resume").arg(session_id);
+        cmd
+    }
+}
+
+fn chats_dir() -> Option<PathBuf> {
+    Some(dirs::home_dir()?.join(".cursor/chats"))
+}
+
+/// `(chat_id, store.db path)` for every chat under `dir`.
+fn chat_stores(dir: &Path) -> Vec<(String, PathBuf)> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|workspace| std::fs::read_dir(workspace.path()).ok())
+        .flat_map(|chats| chats.flatten())
+        .filter_map(|chat| {
+            let store = chat.path().join(STORE_FILE);
+            let chat_id = chat.file_name().to_str()?.to_string();
+            store.is_file().then_some((chat_id, store))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chat_stores_lists_chats_with_a_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let chat = dir.path().join("0a1b2c/chat-1");
+        std::fs::create_dir_all(&chat).unwrap();
+        std::fs::write(chat.join(STORE_FILE), "").unwrap();
+        std::fs::create_dir_all(dir.path().join("0a1b2c/empty-chat")).unwrap();
+
+        assert_eq!(
+            chat_stores(dir.path()),
+            [("chat-1".to_string(), chat.join(STORE_FILE))]
+        );
+    }
+}
@@ -1,163 +1,562 @@
+//! Coding-agent sessions: discovery, lookup, and resume, behind [`SessionProvider`].
+//!
+//! Each supported agent keeps sessions in its own store and resumes them with its own
+//! command; one provider per agent hides the difference. Claude Code is the default,
+//! and the only agent payloads from before agents were recorded can mean.
+
+mod aider;
+mod claude;
+mod codex;
+mod cursor;
+
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
-/// Information about a discovered Claude Code session.
+/// Whether project paths should be compared case-insensitively.
+///
+/// The default filesystems on macOS (APFS) and Windows (NTFS) are case-insensitive,
+/// so `~/Code/app` and `~/code/app` refer to the same directory there.
+const CASE_INSENSITIVE_FS: bool = cfg!(any(target_os = "macos", windows));
+
+/// Sessions modified within this window count as active.
+const ACTIVE_WINDOW: Duration = Duration::from_secs(86400);
+
+/// A coding agent whose sessions cclink can hand off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Agent {
+    /// Claude Code (~/.claude/projects)
+    Claude,
+    /// OpenAI Codex CLI (~/.codex/sessions)
+    Codex,
+    /// Aider (.aider.chat.history.md in the project)
+    Aider,
+    /// Cursor's CLI agent (~/.cursor/chats)
+    Cursor,
+}
+
+impl Agent {
+    pub const ALL: [Agent; 4] = [Agent::Claude, Agent::Codex, Agent::Aider, Agent::Cursor];
+
+    /// Name used on the command line and in the payload.
+    pub fn name(self) -> &'static str {
+        match self {
+            Agent::Claude => "claude",
+            Agent::Codex => "codex",
+            Agent::Aider => "aider",
+            Agent::Cursor => "cursor",
+        }
+    }
+
+    /// The agent a payload's tag names. Payloads without one are Claude Code.
+    pub fn from_payload(tag: Option<&str>) -> anyhow::Result<Agent> {
+        let Some(name) = tag else {
+            return Ok(Agent::Claude);
+        };
+        Agent::ALL
+            .into_iter()
+            .find(|agent| agent.name() == name)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "this handoff is for agent '{}', which this cclink does not support; upgrade cclink",
+                    name
+                )
+            })
+    }
+
+    /// Tag to store in the payload. Claude Code stays implicit so its payloads are
+    /// unchanged, and older pickups keep reading them.
+    pub fn payload_tag(self) -> Option<String> {
+        (self != Agent::Claude).then(|| self.name().to_string())
+    }
+
+    pub fn provider(self) -> &'static dyn SessionProvider {
+        match self {
+            Agent::Claude => &claude::ClaudeCode,
+            Agent::Codex => &codex::Codex,
+            Agent::Aider => &aider::Aider,
+            Agent::Cursor => &cursor::Cursor,
+        }
+    }
+}
+
+/// Who wrote a transcript message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+impl Role {
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+        }
+    }
+}
+
+/// One user or assistant message from a session transcript, whitespace-collapsed
+/// and truncated.
+#[derive(Debug, PartialEq)]
+pub struct Message {
+    pub role: Role,
+    pub text: String,
+}
+
+/// Information about a discovered session.
 #[derive(Debug)]
 pub struct SessionInfo {
+    pub agent: Agent,
     pub session_id: String,
-    pub project: String, // cwd from JSONL progress record
+    pub project: String, // working directory the session ran in
     pub mtime: SystemTime,
 }
 
-/// Scan `~/.claude/projects/` for active Claude Code sessions.
-///
-/// Returns sessions modified within the last 24 hours, sorted by mtime
-/// descending (most recent first). Returns an empty Vec if the directory
-/// does not exist.
-///
-/// If `cwd_filter` is `Some(path)`, only sessions whose project cwd starts
-/// with (or equals) the canonical form of that path are returned. Sessions
-/// whose project path cannot be canonicalized (stale paths) are skipped.
-pub fn discover_sessions(cwd_filter: Option<&std::path::Path>) -> anyhow::Result<Vec<SessionInfo>> {
-    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
-    let projects_dir = home.join(".claude/projects");
+/// One agent's session store and resume command.
+pub trait SessionProvider {
+    /// Sessions modified within the last 24 hours, most recent first. Empty when the
+    /// agent has no store on this machine. `cwd_filter` limits the result to one
+    /// project, as for [`discover_sessions`].
+    fn discover(&self, cwd_filter: Option<&Path>) -> anyhow::Result<Vec<SessionInfo>>;
 
-    if !projects_dir.exists() {
-        return Ok(vec![]);
+    /// Whether `discover` can tell which project a session belongs to. Agents that
+    /// cannot are left out of auto-detection and only used with `--agent`.
+    fn scoped(&self) -> bool {
+        true
     }
 
-    let cutoff = SystemTime::now()
-        .checked_sub(Duration::from_secs(86400)) // 24-hour window for "active"
-        .unwrap_or(SystemTime::UNIX_EPOCH);
+    /// The file holding a session's transcript, if it is on this machine.
+    fn find_session_file(&self, session_id: &str) -> Option<PathBuf>;
 
-    // Canonicalize the filter path once before the loop
-    let canonical_filter =
-        cwd_filter.map(|p| std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf()));
+    /// Text of the last assistant reply in a session file, whitespace-collapsed and
+    /// truncated to `max_chars` (with a trailing `…`).
+    fn last_assistant_message(&self, _path: &Path, _max_chars: usize) -> Option<String> {
+        None
+    }
 
-    let mut sessions: Vec<SessionInfo> = Vec::new();
+    /// The last `count` user and assistant messages in a session file, oldest first,
+    /// each summarized to `max_chars`. Empty when the agent's transcripts cannot be
+    /// read. Tool calls and tool output are left out.
+    fn recent_messages(&self, _path: &Path, _count: usize, _max_chars: usize) -> Vec<Message> {
+        vec![]
+    }
 
-    for project_dir_entry in std::fs::read_dir(&projects_dir)? {
-        let project_dir = project_dir_entry?.path();
-        if !project_dir.is_dir() {
-            continue;
-        }
+    /// Whether session files can travel with `--with-transcript`.
+    fn transcripts(&self) -> bool {
+        false
+    }
 
-        for entry in std::fs::read_dir(&project_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            // Only process .jsonl files
-            if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
-                continue;
-            }
-
-            let mtime = entry.metadata()?.modified()?;
-
-            // Skip sessions older than 24 hours
-            if mtime < cutoff {
-                continue;
-            }
-
-            // Session ID is the filename stem (the UUID)
-            let session_id = path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("")
-                .to_string();
-
-            if session_id.is_empty() {
-                continue;
-            }
-
-            // Read cwd from JSONL progress record
-            if let Ok(project) = read_session_cwd(&path) {
-                // Filter by cwd if provided
-                if let Some(ref filter) = canonical_filter {
-                    let canonical_project = std::fs::canonicalize(&project)
-                        .unwrap_or_else(|_| std::path::PathBuf::from(&project));
-                    if !canonical_project.starts_with(filter) {
-                        continue;
-                    }
-                }
-
-                sessions.push(SessionInfo {
-                    session_id,
-                    project,
-                    mtime,
-                });
-            }
-        }
+    /// Write a handed-off transcript where this agent's resume will find it, for a
+    /// session that ran in `project`. Returns the path written; never overwrites.
+    fn install_transcript(
+        &self,
+        _session_id: &str,
+        _project: &str,
+        _transcript: &str,
+    ) -> anyhow::Result<PathBuf> {
+        anyhow::bail!("this agent's sessions cannot be installed from a transcript")
     }
 
-    // Sort by mtime descending (most recent first)
-    sessions.sort_by(|a, b| b.mtime.cmp(&a.mtime));
+    /// Command that resumes the session in the current directory.
+    fn resume_command(&self, session_id: &str) -> std::process::Command;
+}
 
+/// Discover active sessions of `agent`, or of every supported agent when `None`
+/// (auto-detection: agents without a store on this machine contribute nothing, and
+/// agents that cannot scope sessions to a project are skipped).
+///
+/// Sorted by mtime descending (most recent first). If `cwd_filter` is
+/// `Some(path)`, only sessions belonging to that project are returned — see
+/// [`ProjectScope`] for the matching rules (symlinks, case folding, and git
+/// worktrees of the same repository).
+pub fn discover_sessions(
+    agent: Option<Agent>,
+    cwd_filter: Option<&Path>,
+) -> anyhow::Result<Vec<SessionInfo>> {
+    let agents = match agent {
+        Some(agent) => vec![agent],
+        None => Agent::ALL
+            .into_iter()
+            .filter(|a| a.provider().scoped())
+            .collect(),
+    };
+    let mut sessions = Vec::new();
+    for agent in agents {
+        sessions.extend(agent.provider().discover(cwd_filter)?);
+    }
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.mtime));
+    tracing::debug!(
+        count = sessions.len(),
+        agent = ?agent,
+        filter = ?cwd_filter,
+        "discovered active sessions"
+    );
     Ok(sessions)
 }
 
-/// Read the `cwd` field from a JSONL session file.
+/// Oldest mtime a session may have and still count as active.
+fn active_cutoff() -> SystemTime {
+    SystemTime::now()
+        .checked_sub(ACTIVE_WINDOW)
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Collapse whitespace and truncate to `max_chars` (with a trailing `…`).
+fn summarize(text: &str, max_chars: usize) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= max_chars {
+        collapsed
+    } else {
+        let truncated: String = collapsed.chars().take(max_chars).collect();
+        format!("{}…", truncated.trim_end())
+    }
+}
+
+/// The last `count` of `messages`, oldest first, summarized to `max_chars`.
+fn last_messages(
+    messages: impl DoubleEndedIterator<Item = (Role, String)>,
+    count: usize,
+    max_chars: usize,
+) -> Vec<Message> {
+    let mut last: Vec<Message> = messages
+        .rev()
+        .take(count)
+        .map(|(role, text)| Message {
+            role,
+            text: summarize(&text, max_chars),
+        })
+        .collect();
+    last.reverse();
+    last
+}
+
+/// Write `contents` to `path` through a temporary file, refusing to replace an
+/// existing file.
+fn write_new(path: &Path, contents: &str) -> anyhow::Result<PathBuf> {
+    if path.exists() {
+        anyhow::bail!("{} already exists", path.display());
+    }
+    crate::util::write_atomic(path, contents)?;
+    Ok(path.to_path_buf())
+}
+
+/// The last complete lines within the final `max_bytes` of a file.
+///
+/// If the read starts mid-file, the first (partial) line is dropped.
+fn tail_lines(path: &Path, max_bytes: u64) -> std::io::Result<Vec<String>> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(max_bytes);
+    file.seek(SeekFrom::Start(start))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+
+    let text = String::from_utf8_lossy(&buf);
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    if start > 0 && !lines.is_empty() {
+        lines.remove(0);
+    }
+    Ok(lines)
+}
+
+/// The set of project paths considered to be "the current project".
 ///
-/// Reads up to 20 lines and looks for the first line with a non-empty `cwd`
-/// string. The second line is typically a `type=progress` record containing
-/// both `cwd` and `sessionId`. Caps at 20 lines to avoid reading large files.
-fn read_session_cwd(path: &std::path::Path) -> anyhow::Result<String> {
-    use std::io::{BufRead, BufReader};
-    let file = std::fs::File::open(path)?;
-    let reader = BufReader::new(file);
-
-    for line in reader.lines().take(20) {
-        let line = line?;
-        if let Ok(obj) = serde_json::from_str::<serde_json::Value>(&line) {
-            if let Some(cwd) = obj.get("cwd").and_then(|v| v.as_str()) {
-                if !cwd.is_empty() {
-                    return Ok(cwd.to_string());
-                }
-            }
+/// A session project matches when, after resolving symlinks on both sides, it is
+/// the scope root or a subdirectory of it (compared case-insensitively on
+/// macOS/Windows). When the root is in a git repository, the same place in every
+/// other worktree of that repository (`git worktree list`) matches too: a filter of
+/// `app/crates/core` also takes sessions under `app-feature/crates/core`, but not
+/// the rest of either checkout.
+struct ProjectScope {
+    /// The root, followed by its counterpart in each other worktree of its repo.
+    roots: Vec<PathBuf>,
+}
+
+impl ProjectScope {
+    fn new(path: &Path) -> Self {
+        let root = canonicalize_lossy(path);
+        let mut roots = vec![root.clone()];
+        if let Some(relative) = git_output(&root, &["rev-parse", "--show-toplevel"])
+            .and_then(|top| strip_prefix(&root, &canonicalize_lossy(Path::new(&top))))
+        {
+            roots.extend(
+                worktrees(&root)
+                    .into_iter()
+                    .map(|worktree| worktree.join(&relative))
+                    .filter(|r| !paths_equal(r, &root, CASE_INSENSITIVE_FS)),
+            );
         }
+        ProjectScope { roots }
+    }
+
+    fn matches(&self, project: &Path) -> bool {
+        let project = canonicalize_lossy(project);
+        self.roots
+            .iter()
+            .any(|root| path_starts_with(&project, root, CASE_INSENSITIVE_FS))
+    }
+}
+
+/// Resolve symlinks in `path`, falling back to the path as given when it does not
+/// exist on this machine (stale session paths are still compared literally).
+fn canonicalize_lossy(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Trimmed stdout of `git -C dir <args>`. Returns `None` when `dir` is not inside a
+/// git repository, does not exist, or git is not installed.
+fn git_output(dir: &Path, args: &[&str]) -> Option<String> {
+    if !dir.is_dir() {
+        return None;
+    }
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    Some(stdout.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+/// Canonical paths of every worktree of the repository containing `dir`, the main
+/// checkout included.
+fn worktrees(dir: &Path) -> Vec<PathBuf> {
+    git_output(dir, &["worktree", "list", "--porcelain"])
+        .map(|list| {
+            list.lines()
+                .filter_map(|line| line.strip_prefix("worktree "))
+                .map(|path| canonicalize_lossy(Path::new(path)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `path` relative to `prefix`, comparing components as [`path_starts_with`] does.
+fn strip_prefix(path: &Path, prefix: &Path) -> Option<PathBuf> {
+    path_starts_with(path, prefix, CASE_INSENSITIVE_FS).then(|| {
+        path.components()
+            .skip(prefix.components().count())
+            .collect()
+    })
+}
+
+/// `Path::starts_with`, optionally comparing each component case-insensitively.
+fn path_starts_with(path: &Path, prefix: &Path, case_insensitive: bool) -> bool {
+    if !case_insensitive {
+        return path.starts_with(prefix);
     }
+    let mut components = path.components();
+    prefix.components().all(|p| {
+        components
+            .next()
+            .is_some_and(|c| component_eq_ignore_case(c.as_os_str(), p.as_os_str()))
+    })
+}
+
+fn paths_equal(a: &Path, b: &Path, case_insensitive: bool) -> bool {
+    a.components().count() == b.components().count() && path_starts_with(a, b, case_insensitive)
+}
 
-    anyhow::bail!("no cwd found in session file: {}", path.display())
+fn component_eq_ignore_case(a: &std::ffi::OsStr, b: &std::ffi::OsStr) -> bool {
+    a.to_string_lossy().to_lowercase() == b.to_string_lossy().to_lowercase()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    pub(super) fn write_jsonl(lines: &[&str]) -> tempfile::NamedTempFile {
+        use std::io::Write;
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        for line in lines {
+            writeln!(file, "{}", line).unwrap();
+        }
+        file
+    }
+
     #[test]
-    fn discover_sessions_returns_vec_when_no_projects_dir() {
-        // discover_sessions() should never panic even when ~/.claude/projects/
-        // does not exist — it returns an empty Vec instead.
-        // This test is inherently environment-dependent, but the important
-        // invariant is that the function returns Ok (not Err) when the
-        // directory is absent.
-        //
-        // We can only safely test this if projects/ is absent on this machine.
-        // If it IS present, the function should still return Ok.
-        let result = discover_sessions(None);
-        assert!(
-            result.is_ok(),
-            "discover_sessions must return Ok: {:?}",
-            result
-        );
+    fn summarize_collapses_and_truncates() {
+        assert_eq!(summarize("a  b\nc", 10), "a b c");
+        assert_eq!(summarize("one two three", 7), "one two…");
+    }
+
+    #[test]
+    fn agent_payload_tags_round_trip() {
+        for agent in Agent::ALL {
+            let tag = agent.payload_tag();
+            assert_eq!(Agent::from_payload(tag.as_deref()).unwrap(), agent);
+        }
+        assert_eq!(Agent::Claude.payload_tag(), None);
+        assert!(Agent::from_payload(Some("emacs")).is_err());
+    }
+
+    #[test]
+    fn tail_lines_drops_partial_first_line() {
+        let file = write_jsonl(&["aaaaaaaaaa", "bbb", "ccc"]);
+        let lines = tail_lines(file.path(), 10).unwrap();
+        assert_eq!(lines, ["bbb", "ccc"]);
+    }
+
+    #[test]
+    fn path_starts_with_case_sensitive() {
+        let project = Path::new("/Users/me/Code/app/src");
+        assert!(path_starts_with(
+            project,
+            Path::new("/Users/me/Code/app"),
+            false
+        ));
+        assert!(!path_starts_with(
+            project,
+            Path::new("/users/me/code/app"),
+            false
+        ));
+    }
+
+    #[test]
+    fn path_starts_with_case_insensitive() {
+        let project = Path::new("/Users/me/Code/app/src");
+        assert!(path_starts_with(
+            project,
+            Path::new("/users/me/code/APP"),
+            true
+        ));
+        // Component-wise: "/Users/me/Code/ap" must not match "/Users/me/Code/app"
+        assert!(!path_starts_with(
+            project,
+            Path::new("/Users/me/Code/ap"),
+            true
+        ));
+        assert!(!path_starts_with(Path::new("/Users/me"), project, true));
+    }
+
+    #[test]
+    fn project_scope_matches_subdirectory() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let sub = dir.path().join("crates/core");
+        std::fs::create_dir_all(&sub).expect("Failed to create subdir");
+
+        let scope = ProjectScope::new(dir.path());
+        assert!(scope.matches(dir.path()));
+        assert!(scope.matches(&sub));
+        assert!(!scope.matches(Path::new("/nonexistent/path/that/matches/nothing")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn project_scope_resolves_symlinks_on_both_sides() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let real = dir.path().join("real-home/project");
+        std::fs::create_dir_all(&real).expect("Failed to create project dir");
+        let link = dir.path().join("linked-home");
+        std::os::unix::fs::symlink(dir.path().join("real-home"), &link)
+            .expect("Failed to create symlink");
+
+        // Session recorded through the symlink, filter given as the real path
+        let scope = ProjectScope::new(&real);
+        assert!(scope.matches(&link.join("project")));
+
+        // Session recorded with the real path, filter given through the symlink
+        let scope = ProjectScope::new(&link.join("project"));
+        assert!(scope.matches(&real));
     }
 
     #[test]
-    fn discover_sessions_filters_by_cwd() {
-        // Smoke test: passing a nonexistent path as the cwd filter must return
-        // Ok with an empty Vec, since no real session can have a project path
-        // that starts with a path that matches nothing on this machine.
-        let result = discover_sessions(Some(std::path::Path::new(
-            "/nonexistent/path/that/matches/nothing",
-        )));
+    fn project_scope_matches_other_worktree_of_same_repo() {
+        let git = |dir: &Path, args: &[&str]| {
+            std::process::Command::new("git")
+                .arg("-C")
+                .arg(dir)
+                .args(args)
+                .output()
+                .is_ok_and(|o| o.status.success())
+        };
+
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let main = dir.path().join("main");
+        let worktree = dir.path().join("feature");
+        let unrelated = dir.path().join("other");
+        std::fs::create_dir_all(&main).expect("Failed to create main dir");
+        std::fs::create_dir_all(&unrelated).expect("Failed to create unrelated dir");
+
+        let worktree_arg = worktree.to_str().expect("utf-8 temp path");
+        let ok = git(&main, &["init", "-q"])
+            && git(
+                &main,
+                &[
+                    "-c",
+                    "user.name=test",
+                    "-c",
+                    "user.email=test@example.com",
+                    "commit",
+                    "-q",
+                    "--allow-empty",
+                    "-m",
+                    "init",
+                ],
+            )
+            && git(
+                &main,
+                &["worktree", "add", "-q", "-b", "feature", worktree_arg],
+            )
+            && git(&unrelated, &["init", "-q"]);
+        if !ok {
+            eprintln!("git unavailable, skipping worktree test");
+            return;
+        }
+
+        let scope = ProjectScope::new(&main);
+        assert!(scope.matches(&worktree), "worktree of same repo must match");
+        assert!(!scope.matches(&unrelated), "unrelated repo must not match");
+
+        let scope = ProjectScope::new(&worktree);
         assert!(
-            result.is_ok(),
-            "discover_sessions with cwd_filter must return Ok: {:?}",
-            result
+            scope.matches(&main),
+            "main checkout must match from worktree"
         );
-        let sessions = result.unwrap();
+
+        // A subdirectory filter matches that subdirectory in each worktree only.
+        for sub in ["crates/core", "docs"] {
+            std::fs::create_dir_all(main.join(sub)).expect("Failed to create subdir");
+            std::fs::create_dir_all(worktree.join(sub)).expect("Failed to create subdir");
+        }
+        let scope = ProjectScope::new(&main.join("crates/core"));
+        assert!(scope.matches(&worktree.join("crates/core")));
+        assert!(!scope.matches(&worktree), "worktree root must not match");
+        assert!(!scope.matches(&main.join("docs")), "sibling must not match");
+    }
+
+    #[test]
+    fn project_scope_does_not_widen_to_an_enclosing_repo() {
+        // A dotfiles repo in $HOME contains every project without a repo of its own.
+        let home = tempfile::tempdir().expect("Failed to create temp dir");
+        let project = home.path().join("code/app");
+        let other = home.path().join("code/other");
+        std::fs::create_dir_all(&project).expect("Failed to create project dir");
+        std::fs::create_dir_all(&other).expect("Failed to create other dir");
+        let ok = std::process::Command::new("git")
+            .arg("-C")
+            .arg(home.path())
+            .args(["init", "-q"])
+            .output()
+            .is_ok_and(|o| o.status.success());
+        if !ok {
+            eprintln!("git unavailable, skipping enclosing repo test");
+            return;
+        }
+
+        let scope = ProjectScope::new(&project);
+        assert!(scope.matches(&project.join("src")));
         assert!(
-            sessions.is_empty(),
-            "expected empty Vec when filter matches nothing, got: {:?}",
-            sessions
+            !scope.matches(&other),
+            "other projects in $HOME must not match"
         );
+        assert!(!scope.matches(home.path()));
     }
 }
@@ -0,0 +1,615 @@
+//! Local state database: what this machine published and picked up, and the
+//! bookkeeping around it.
+//!
+//! One SQLite file, `state.db` in the platform data directory
+//! (`~/.local/share/cclink/` on Linux, `profiles/<name>/` below it for a profile). It
+//! never leaves the machine. Tables:
+//!
+//! - `history`: handoffs and files published from and picked up on this machine
+//!   (see [`crate::history`]).
+//! - `active`: the last handoff published from this machine (see [`crate::cache`]).
+//! - `seen`: the newest handoff picked up from each publisher, for rollback
+//!   protection.
+//! - `contacts`: how often, and when last, each publisher's handoffs were picked up.
+//! - `revoked`: verified revocation certificates seen at pickup, so a key stays
+//!   refused after its certificate is overwritten on the DHT.
+//! - `transitions`: verified key transitions followed at pickup, pinning each retired
+//!   key's successor.
+//!
+//! Contacts, trust pins and keys stay files: they are settings users edit and back up.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Context;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+
+use crate::cache::CachedHandoff;
+use crate::error::CclinkError;
+use crate::history::Entry;
+use crate::record::revocation::Revocation;
+use crate::record::transition::Transition;
+
+/// Schema migrations, oldest first. `PRAGMA user_version` counts those applied.
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE history (
+        id INTEGER PRIMARY KEY,
+        at INTEGER NOT NULL,
+        created_at INTEGER NOT NULL,
+        direction TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        name TEXT NOT NULL,
+        peer TEXT,
+        project TEXT,
+        tmux TEXT
+    );
+    CREATE TABLE active (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        created_at INTEGER NOT NULL,
+        pubkey TEXT NOT NULL,
+        record_hash TEXT,
+        ttl INTEGER NOT NULL
+    );
+    CREATE TABLE seen (
+        publisher TEXT PRIMARY KEY,
+        created_at INTEGER NOT NULL
+    );
+    CREATE TABLE contacts (
+        pubkey TEXT PRIMARY KEY,
+        pickups INTEGER NOT NULL,
+        last_pickup_at INTEGER NOT NULL
+    );
+",
+    "
+    CREATE TABLE revoked (
+        pubkey TEXT PRIMARY KEY,
+        created_at INTEGER NOT NULL,
+        reason TEXT,
+        signature TEXT NOT NULL
+    );
+",
+    "
+    CREATE TABLE transitions (
+        old_pubkey TEXT PRIMARY KEY,
+        created_at INTEGER NOT NULL,
+        grace_until INTEGER NOT NULL,
+        new_pubkey TEXT NOT NULL,
+        new_signature TEXT NOT NULL,
+        old_signature TEXT NOT NULL
+    );
+",
+];
+
+/// How long to wait for another cclink process holding the database lock.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Path of the state database.
+pub fn state_path() -> anyhow::Result<PathBuf> {
+    let base = dirs::data_dir()
+        .ok_or(CclinkError::HomeDirNotFound)?
+        .join("cclink");
+    Ok(crate::keys::store::profile_dir(&base, crate::keys::store::profile()).join("state.db"))
+}
+
+/// Create `dir` and its missing parents, owner-only (0700) on Unix.
+fn create_private_dir(dir: &Path) -> anyhow::Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        builder.mode(0o700);
+        builder
+            .create(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+            .with_context(|| format!("Failed to set 0700 permissions on {}", dir.display()))?;
+    }
+    #[cfg(not(unix))]
+    builder
+        .create(dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+    Ok(())
+}
+
+/// Create `path` if missing and make it owner-only (0600) on Unix. SQLite creates
+/// its journal files with the database's permissions, so this covers them too.
+fn restrict_permissions(path: &Path) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .mode(0o600)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to set 0600 permissions on {}", path.display()))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Pickups of one publisher's handoffs on this machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContactActivity {
+    pub pickups: u32,
+    /// Unix seconds of the last pickup.
+    pub last_pickup_at: u64,
+}
+
+/// An open state database.
+pub struct State {
+    conn: Connection,
+}
+
+impl State {
+    /// Open the state database, creating it on first use.
+    pub fn open() -> anyhow::Result<Self> {
+        let path = state_path()?;
+        if let Some(dir) = path.parent() {
+            create_private_dir(dir)?;
+        }
+        Self::open_at(&path)
+    }
+
+    /// Open the database at `path`, bringing its schema up to date.
+    ///
+    /// On Unix the database and its journal files are kept owner-only (0600): they
+    /// name contacts and projects.
+    pub fn open_at(path: &Path) -> anyhow::Result<Self> {
+        restrict_permissions(path)?;
+        let conn =
+            Connection::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        for suffix in ["-journal", "-wal", "-shm"] {
+            let mut sidecar = path.as_os_str().to_owned();
+            sidecar.push(suffix);
+            let sidecar = PathBuf::from(sidecar);
+            if sidecar.exists() {
+                restrict_permissions(&sidecar)?;
+            }
+        }
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        let mut state = State { conn };
+        state.migrate()?;
+        Ok(state)
+    }
+
+    /// Apply the migrations not applied yet.
+    fn migrate(&mut self) -> anyhow::Result<()> {
+        let applied: usize = self
+            .conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if applied > MIGRATIONS.len() {
+            anyhow::bail!(
+                "the state database was written by a newer cclink (schema {}); upgrade cclink",
+                applied
+            );
+        }
+        for (index, sql) in MIGRATIONS.iter().enumerate().skip(applied) {
+            let tx = self.conn.transaction()?;
+            tx.execute_batch(sql).with_context(|| {
+                format!("Failed to migrate the state database to {}", index + 1)
+            })?;
+            tx.pragma_update(None, "user_version", index + 1)?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    // ── History ──────────────────────────────────────────────────────────
+
+    /// Add `entry` to the history.
+    pub fn record_entry(&self, entry: &Entry) -> anyhow::Result<()> {
+        self.conn.execute(
+            "INSERT INTO history (at, created_at, direction, kind, name, peer, project, tmux)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entry.at,
+                entry.created_at,
+                crate::history::enum_name(&entry.direction)?,
+                crate::history::enum_name(&entry.kind)?,
+                entry.name,
+                entry.peer,
+                entry.project,
+                entry.tmux,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Every history entry, oldest first.
+    pub fn history(&self) -> anyhow::Result<Vec<Entry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT at, created_at, direction, kind, name, peer, project, tmux
+             FROM history ORDER BY at, id",
+        )?;
+        let entries = stmt
+            .query_map([], |row| {
+                Ok(Entry {
+                    at: row.get(0)?,
+                    created_at: row.get(1)?,
+                    direction: from_name(row.get(2)?)?,
+                    kind: from_name(row.get(3)?)?,
+                    name: row.get(4)?,
+                    peer: row.get(5)?,
+                    project: row.get(6)?,
+                    tmux: row.get(7)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(entries)
+    }
+
+    // ── Active handoff ───────────────────────────────────────────────────
+
+    /// The last handoff published from this machine, unless it was revoked since.
+    pub fn active(&self) -> anyhow::Result<Option<CachedHandoff>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT created_at, pubkey, record_hash, ttl FROM active WHERE id = 1",
+                [],
+                |row| {
+                    Ok(CachedHandoff {
+                        created_at: row.get(0)?,
+                        pubkey: row.get(1)?,
+                        record_hash: row.get(2)?,
+                        ttl: row.get(3)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// Remember `handoff` as the last one published, replacing the previous one.
+    pub fn save_active(&self, handoff: &CachedHandoff) -> anyhow::Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO active (id, created_at, pubkey, record_hash, ttl)
+             VALUES (1, ?1, ?2, ?3, ?4)",
+            params![
+                handoff.created_at,
+                handoff.pubkey,
+                handoff.record_hash,
+                handoff.ttl
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Forget the last published handoff.
+    pub fn clear_active(&self) -> anyhow::Result<()> {
+        self.conn.execute("DELETE FROM active", [])?;
+        Ok(())
+    }
+
+    // ── Rollback protection ──────────────────────────────────────────────
+
+    /// The newest `created_at` picked up from `publisher` when `created_at` is older
+    /// than it. The same handoff seen again is not a rollback.
+    pub fn rolled_back(&self, publisher: &str, created_at: u64) -> anyhow::Result<Option<u64>> {
+        let newest: Option<u64> = self
+            .conn
+            .query_row(
+                "SELECT created_at FROM seen WHERE publisher = ?1",
+                [publisher],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(newest.filter(|&newest| created_at < newest))
+    }
+
+    /// Whether a handoff from `publisher` was ever picked up on this machine.
+    pub fn has_seen(&self, publisher: &str) -> anyhow::Result<bool> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT 1 FROM seen WHERE publisher = ?1",
+                [publisher],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some())
+    }
+
+    /// Remember `created_at` for `publisher` unless a newer one is known.
+    pub fn record_seen(&self, publisher: &str, created_at: u64) -> anyhow::Result<()> {
+        self.conn.execute(
+            "INSERT INTO seen (publisher, created_at) VALUES (?1, ?2)
+             ON CONFLICT (publisher) DO UPDATE
+             SET created_at = max(created_at, excluded.created_at)",
+            params![publisher, created_at],
+        )?;
+        Ok(())
+    }
+
+    // ── Revoked keys ─────────────────────────────────────────────────────
+
+    /// The revocation certificate remembered for `pubkey`, if it was ever seen.
+    pub fn revocation(&self, pubkey: &str) -> anyhow::Result<Option<Revocation>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT created_at, pubkey, reason, signature FROM revoked WHERE pubkey = ?1",
+                [pubkey],
+                |row| {
+                    Ok(Revocation {
+                        created_at: row.get(0)?,
+                        pubkey: row.get(1)?,
+                        reason: row.get(2)?,
+                        signature: row.get(3)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// Remember a verified `revocation`. The first certificate seen for a key is kept.
+    pub fn record_revocation(&self, revocation: &Revocation) -> anyhow::Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO revoked (pubkey, created_at, reason, signature)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                revocation.pubkey,
+                revocation.created_at,
+                revocation.reason,
+                revocation.signature
+            ],
+        )?;
+        Ok(())
+    }
+
+    // ── Key transitions ──────────────────────────────────────────────────
+
+    /// The transition pinned for the retired key `old_pubkey`, if one was followed.
+    pub fn transition(&self, old_pubkey: &str) -> anyhow::Result<Option<Transition>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT created_at, grace_until, new_pubkey, new_signature, old_pubkey,
+                        old_signature
+                 FROM transitions WHERE old_pubkey = ?1",
+                [old_pubkey],
+                |row| {
+                    Ok(Transition {
+                        created_at: row.get(0)?,
+                        grace_until: row.get(1)?,
+                        new_pubkey: row.get(2)?,
+                        new_signature: row.get(3)?,
+                        old_pubkey: row.get(4)?,
+                        old_signature: row.get(5)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// Pin a verified `transition`. The first one followed for a retired key is kept.
+    pub fn record_transition(&self, transition: &Transition) -> anyhow::Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO transitions
+             (old_pubkey, created_at, grace_until, new_pubkey, new_signature, old_signature)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                transition.old_pubkey,
+                transition.created_at,
+                transition.grace_until,
+                transition.new_pubkey,
+                transition.new_signature,
+                transition.old_signature
+            ],
+        )?;
+        Ok(())
+    }
+
+    // ── Contact activity ─────────────────────────────────────────────────
+
+    /// Count a pickup of a handoff from `publisher` at `at` (Unix seconds).
+    pub fn note_pickup(&self, publisher: &str, at: u64) -> anyhow::Result<()> {
+        self.conn.execute(
+            "INSERT INTO contacts (pubkey, pickups, last_pickup_at) VALUES (?1, 1, ?2)
+             ON CONFLICT (pubkey) DO UPDATE
+             SET pickups = pickups + 1, last_pickup_at = max(last_pickup_at, excluded.last_pickup_at)",
+            params![publisher, at],
+        )?;
+        Ok(())
+    }
+
+    /// Pickups of `publisher`'s handoffs, if there were any.
+    pub fn contact_activity(&self, publisher: &str) -> anyhow::Result<Option<ContactActivity>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT pickups, last_pickup_at FROM contacts WHERE pubkey = ?1",
+                [publisher],
+                |row| {
+                    Ok(ContactActivity {
+                        pickups: row.get(0)?,
+                        last_pickup_at: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+}
+
+/// Parse a unit enum stored by its serde name.
+fn from_name<T: DeserializeOwned>(name: String) -> rusqlite::Result<T> {
+    serde_json::from_value(serde_json::Value::String(name)).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{Direction, Kind};
+
+    fn open(dir: &tempfile::TempDir) -> State {
+        State::open_at(&dir.path().join("state.db")).unwrap()
+    }
+
+    fn entry(at: u64, direction: Direction) -> Entry {
+        Entry {
+            at,
+            created_at: at - 5,
+            direction,
+            kind: Kind::Handoff,
+            name: "sess-1".to_string(),
+            peer: None,
+            project: Some("/work/app".to_string()),
+            tmux: None,
+        }
+    }
+
+    #[test]
+    fn test_reopened_with_its_data() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let first = entry(1_000, Direction::Out);
+        open(&dir).record_entry(&first).unwrap();
+        assert_eq!(open(&dir).history().unwrap(), [first]);
+    }
+
+    #[test]
+    fn test_history_round_trip_in_order() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let state = open(&dir);
+        let second = entry(2_000, Direction::In);
+        let first = entry(1_000, Direction::Out);
+        state.record_entry(&second).unwrap();
+        state.record_entry(&first).unwrap();
+        assert_eq!(state.history().unwrap(), [first, second]);
+    }
+
+    #[test]
+    fn test_active_handoff_saved_replaced_and_cleared() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let state = open(&dir);
+        assert_eq!(state.active().unwrap(), None);
+
+        let mut handoff = CachedHandoff {
+            created_at: 1_000,
+            pubkey: "abc".to_string(),
+            record_hash: None,
+            ttl: 60,
+        };
+        state.save_active(&handoff).unwrap();
+        handoff.created_at = 2_000;
+        handoff.record_hash = Some("hash".to_string());
+        state.save_active(&handoff).unwrap();
+        assert_eq!(state.active().unwrap(), Some(handoff));
+
+        state.clear_active().unwrap();
+        assert_eq!(state.active().unwrap(), None);
+    }
+
+    #[test]
+    fn test_older_handoff_is_a_rollback() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let state = open(&dir);
+        assert_eq!(state.rolled_back("pk", 100).unwrap(), None);
+        assert!(!state.has_seen("pk").unwrap());
+        state.record_seen("pk", 200).unwrap();
+        assert!(state.has_seen("pk").unwrap());
+        assert_eq!(state.rolled_back("pk", 200).unwrap(), None);
+        assert_eq!(state.rolled_back("pk", 300).unwrap(), None);
+        assert_eq!(state.rolled_back("pk", 100).unwrap(), Some(200));
+        assert_eq!(state.rolled_back("other", 100).unwrap(), None);
+
+        // Recording an older time never lowers the mark.
+        state.record_seen("pk", 150).unwrap();
+        assert_eq!(state.rolled_back("pk", 150).unwrap(), Some(200));
+    }
+
+    #[test]
+    fn test_revocation_is_remembered() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let state = open(&dir);
+        let keypair = pkarr::Keypair::from_secret_key(&[42u8; 32]);
+        let z32 = keypair.public_key().to_z32();
+        assert_eq!(state.revocation(&z32).unwrap(), None);
+
+        let revocation = crate::record::revocation::seal_revocation(
+            &keypair,
+            1_000,
+            Some("laptop stolen".to_string()),
+        )
+        .unwrap();
+        state.record_revocation(&revocation).unwrap();
+        let later = crate::record::revocation::seal_revocation(&keypair, 2_000, None).unwrap();
+        state.record_revocation(&later).unwrap();
+        assert_eq!(state.revocation(&z32).unwrap(), Some(revocation));
+        assert_eq!(state.revocation("other").unwrap(), None);
+    }
+
+    #[test]
+    fn test_transition_is_pinned() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let state = open(&dir);
+        let old = pkarr::Keypair::from_secret_key(&[42u8; 32]);
+        let z32 = old.public_key().to_z32();
+        assert_eq!(state.transition(&z32).unwrap(), None);
+
+        let first = crate::record::transition::seal_transition(
+            &old,
+            &pkarr::Keypair::from_secret_key(&[43u8; 32]),
+            1_000,
+            2_000,
+        )
+        .unwrap();
+        state.record_transition(&first).unwrap();
+        let second = crate::record::transition::seal_transition(
+            &old,
+            &pkarr::Keypair::from_secret_key(&[44u8; 32]),
+            3_000,
+            4_000,
+        )
+        .unwrap();
+        state.record_transition(&second).unwrap();
+        assert_eq!(state.transition(&z32).unwrap(), Some(first));
+    }
+
+    #[test]
+    fn test_contact_activity_counts_pickups() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let state = open(&dir);
+        assert_eq!(state.contact_activity("pk").unwrap(), None);
+        state.note_pickup("pk", 1_000).unwrap();
+        state.note_pickup("pk", 3_000).unwrap();
+        assert_eq!(
+            state.contact_activity("pk").unwrap(),
+            Some(ContactActivity {
+                pickups: 2,
+                last_pickup_at: 3_000
+            })
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_state_db_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::TempDir::new().unwrap();
+        let private = dir.path().join("cclink").join("profiles").join("work");
+        create_private_dir(&private).unwrap();
+        let mode = std::fs::metadata(&private).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o700, "Expected 0700 permissions, got {:04o}", mode);
+
+        let path = private.join("state.db");
+        let state = State::open_at(&path).unwrap();
+        state.record_seen("pk", 200).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600, "Expected 0600 permissions, got {:04o}", mode);
+
+        // A database left readable by an older build is tightened on open.
+        drop(state);
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        State::open_at(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600, "Expected 0600 permissions, got {:04o}", mode);
+    }
+}
@@ -0,0 +1,119 @@
+//! tmux integration for pickup: resume the agent in a tmux window instead of in
+//! place of cclink, so the session outlives the terminal it was picked up in.
+//!
+//! `cclink pickup --tmux` opens a window in the current tmux session (or in a
+//! detached `cclink` session when run outside tmux); `--tmux=<name>` uses that
+//! session, creating it if needed. The window's target is kept in the local history
+//! so the session can be found and re-attached later.
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Command;
+
+/// Session `pickup --tmux` uses outside tmux when no name is given.
+pub const DEFAULT_SESSION: &str = "cclink";
+
+/// Format of the target tmux prints for the window it created.
+const TARGET_FORMAT: &str = "#{session_name}:#{window_index}";
+
+/// Run `cmd` in a new tmux window started in `dir` and return the window's target
+/// (`session:window`).
+pub fn launch(cmd: &Command, session: Option<&str>, dir: &Path) -> anyhow::Result<String> {
+    let inside = std::env::var_os("TMUX").is_some();
+    let exists = session
+        .or((!inside).then_some(DEFAULT_SESSION))
+        .is_some_and(has_session);
+    let output = Command::new("tmux")
+        .args(window_args(cmd, session, inside, exists, dir))
+        .output()
+        .map_err(|e| anyhow::anyhow!("failed to run tmux (is it installed?): {}", e))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "tmux failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Whether a tmux session named exactly `name` exists.
+fn has_session(name: &str) -> bool {
+    Command::new("tmux")
+        .args(["has-session", "-t", &format!("={}", name)])
+        .output()
+        .is_ok_and(|out| out.status.success())
+}
+
+/// Arguments to tmux that open a window running `cmd` in `dir`.
+///
+/// The window goes into `session` if given, else into the current session when
+/// `inside` tmux, else into [`DEFAULT_SESSION`]. A named session that does not
+/// `exist` yet is created detached.
+fn window_args(
+    cmd: &Command,
+    session: Option<&str>,
+    inside: bool,
+    exists: bool,
+    dir: &Path,
+) -> Vec<OsString> {
+    let session = session.or((!inside).then_some(DEFAULT_SESSION));
+    let mut args: Vec<OsString> = match session {
+        Some(name) if !exists => vec!["new-session".into(), "-d".into(), "-s".into(), name.into()],
+        Some(name) => vec![
+            "new-window".into(),
+            "-t".into(),
+            format!("={}:", name).into(),
+        ],
+        None => vec!["new-window".into()],
+    };
+    args.extend(["-P", "-F", TARGET_FORMAT, "-c"].map(OsString::from));
+    args.push(dir.as_os_str().to_owned());
+    args.push("--".into());
+    args.push(cmd.get_program().to_owned());
+    args.extend(cmd.get_args().map(|arg| arg.to_owned()));
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(session: Option<&str>, inside: bool, exists: bool) -> Vec<String> {
+        let mut cmd = Command::new("claude");
+        cmd.arg("--resume").arg("abc");
+        window_args(&cmd, session, inside, exists, Path::new("/work/app"))
+            .into_iter()
+            .map(|a| a.into_string().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_window_args() {
+        let tail = [
+            "-P",
+            "-F",
+            TARGET_FORMAT,
+            "-c",
+            "/work/app",
+            "--",
+            "claude",
+            "--resume",
+            "abc",
+        ];
+        // Inside tmux without a name: a window in the current session.
+        assert_eq!(
+            args(None, true, false),
+            [&["new-window"][..], &tail[..]].concat()
+        );
+        // Outside tmux: the default session, created when missing.
+        assert_eq!(
+            args(None, false, false),
+            [&["new-session", "-d", "-s", "cclink"][..], &tail[..]].concat()
+        );
+        // A named session that exists gets a new window.
+        assert_eq!(
+            args(Some("work"), true, true),
+            [&["new-window", "-t", "=work:"][..], &tail[..]].concat()
+        );
+    }
+}
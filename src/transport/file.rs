@@ -0,0 +1,116 @@
+//! Directory transport: handoff records as files in a folder both machines share.
+//!
+//! With `--dir` (or `[transport] dir` in `cclink.toml`), publish writes the signed
+//! record to `<dir>/<pubkey>.json` instead of the DHT, and pickup reads it from
+//! there. Any folder kept in sync between the machines works — Syncthing, Dropbox,
+//! NFS — and no network path to the DHT is needed. The folder is trusted with
+//! nothing: the blob is encrypted and pickup checks the record's signature, as it
+//! does for a record from the DHT. A file holds the whole blob, so large handoffs
+//! are not chunked.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::record::HandoffRecord;
+
+/// Handoff records kept in a directory, one file per publisher.
+#[derive(Debug, Clone)]
+pub struct FileTransport {
+    dir: PathBuf,
+}
+
+impl FileTransport {
+    /// Transport over `dir`, which must already exist: a missing shared folder usually
+    /// means the sync tool is not set up, and creating it would hide that.
+    pub fn at(dir: &Path) -> anyhow::Result<Self> {
+        if !dir.is_dir() {
+            anyhow::bail!("transport directory {} does not exist", dir.display());
+        }
+        Ok(FileTransport {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// Write `record` as the current handoff of its publisher, replacing any earlier
+    /// one. The file is renamed into place so a sync tool never copies half of it.
+    pub fn publish(&self, record: &HandoffRecord) -> anyhow::Result<()> {
+        let path = self.path(&record.pubkey);
+        let json = serde_json::to_vec(record)
+            .map_err(|e| anyhow::anyhow!("failed to serialize record: {}", e))?;
+        crate::util::write_atomic(&path, json)
+    }
+
+    /// Read the handoff published by `pubkey_z32` and verify its signature.
+    pub fn resolve_record(&self, pubkey_z32: &str) -> anyhow::Result<HandoffRecord> {
+        let pubkey = pkarr::PublicKey::try_from(pubkey_z32)
+            .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+        let path = self.path(pubkey_z32);
+        let json = match std::fs::File::open(&path) {
+            Ok(file) => super::read_capped(file, super::max_response())
+                .with_context(|| format!("Failed to read {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(crate::error::CclinkError::RecordNotFound.into());
+            }
+            Err(e) => {
+                return Err(
+                    anyhow::Error::new(e).context(format!("Failed to read {}", path.display()))
+                );
+            }
+        };
+        let record = crate::record::migrate::parse(&json)?;
+        crate::record::verify_record(&record, &pubkey)?;
+        Ok(record)
+    }
+
+    fn path(&self, pubkey_z32: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", pubkey_z32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_then_resolve() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let transport = FileTransport::at(dir.path()).unwrap();
+        let keypair = pkarr::Keypair::from_secret_key(&[8u8; 32]);
+        let z32 = keypair.public_key().to_z32();
+
+        let missing = transport.resolve_record(&z32).unwrap_err();
+        assert!(matches!(
+            missing.downcast_ref::<crate::error::CclinkError>(),
+            Some(crate::error::CclinkError::RecordNotFound)
+        ));
+
+        // Larger than one DHT packet could carry: files are never chunked.
+        let record = HandoffRecord::builder(vec![1u8; 4000], 3600)
+            .created_at(1_000)
+            .sign(&keypair)
+            .unwrap();
+        transport.publish(&record).unwrap();
+        let resolved = transport.resolve_record(&z32).unwrap();
+        assert_eq!(resolved.blob, record.blob);
+        assert!(!dir.path().join(format!(".{}.json.tmp", z32)).exists());
+    }
+
+    #[test]
+    fn test_rejects_record_under_another_key() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let transport = FileTransport::at(dir.path()).unwrap();
+        let mallory = pkarr::Keypair::from_secret_key(&[9u8; 32]);
+        let alice = pkarr::Keypair::from_secret_key(&[10u8; 32])
+            .public_key()
+            .to_z32();
+
+        let forged = HandoffRecord::builder(b"blob".to_vec(), 3600)
+            .sign(&mallory)
+            .unwrap();
+        transport.publish(&forged).unwrap();
+        std::fs::rename(transport.path(&forged.pubkey), transport.path(&alice)).unwrap();
+        assert!(transport.resolve_record(&alice).is_err());
+        assert!(FileTransport::at(&dir.path().join("missing")).is_err());
+    }
+}
@@ -0,0 +1,293 @@
+//! Local-network transport: a handoff served over HTTP and found through mDNS.
+//!
+//! `cclink serve --lan` binds an HTTP endpoint on an ephemeral port that answers
+//! `GET /handoff` with the signed record, and advertises it as a `_cclink._tcp`
+//! service whose TXT property `pk` is the publisher's public key. `cclink pickup
+//! --lan` browses for that service, fetches the record from the first advertiser
+//! with the wanted key, and verifies its signature, so neither side needs the
+//! internet. Anyone on the network can fetch the record, as anyone can on the DHT;
+//! the blob stays encrypted.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+
+use crate::record::HandoffRecord;
+
+/// mDNS service type advertised by `cclink serve --lan`.
+pub const SERVICE_TYPE: &str = "_cclink._tcp.local.";
+
+/// TXT property carrying the publisher's z32 public key.
+const PUBKEY_PROPERTY: &str = "pk";
+
+/// Path the record is served under.
+const HANDOFF_PATH: &str = "/handoff";
+
+/// Room for the status line and headers on top of the record itself.
+const MAX_HEAD: u64 = 8192;
+
+/// How long one client may take to send its request or read the answer.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often [`LanServer::serve_one`] checks for a waiting client.
+const ACCEPT_POLL: Duration = Duration::from_millis(200);
+
+/// An HTTP endpoint serving one record, advertised over mDNS once started.
+pub struct LanServer {
+    listener: TcpListener,
+    body: String,
+    mdns: Option<mdns_sd::ServiceDaemon>,
+}
+
+impl LanServer {
+    /// Serve `record` on every interface and advertise it on the local network.
+    pub fn start(record: &HandoffRecord) -> anyhow::Result<Self> {
+        let mut server = Self::bind(record, "0.0.0.0:0")?;
+        let mdns = mdns_sd::ServiceDaemon::new()
+            .map_err(|e| anyhow::anyhow!("mDNS unavailable: {}", e))?;
+        let host = gethostname::gethostname().to_string_lossy().into_owned();
+        let host = host.split('.').next().unwrap_or("cclink");
+        let instance = format!("cclink-{}", &record.pubkey[..12.min(record.pubkey.len())]);
+        let properties = [(PUBKEY_PROPERTY, record.pubkey.as_str())];
+        let info = mdns_sd::ServiceInfo::new(
+            SERVICE_TYPE,
+            &instance,
+            &format!("{}.local.", host),
+            "",
+            server.port(),
+            &properties[..],
+        )
+        .map_err(|e| anyhow::anyhow!("invalid mDNS service: {}", e))?
+        .enable_addr_auto();
+        mdns.register(info)
+            .map_err(|e| anyhow::anyhow!("mDNS registration failed: {}", e))?;
+        server.mdns = Some(mdns);
+        Ok(server)
+    }
+
+    /// Serve `record` on `addr` without advertising it.
+    fn bind(record: &HandoffRecord, addr: &str) -> anyhow::Result<Self> {
+        let listener =
+            TcpListener::bind(addr).with_context(|| format!("Failed to listen on {}", addr))?;
+        listener.set_nonblocking(true)?;
+        let body = serde_json::to_string(record)
+            .map_err(|e| anyhow::anyhow!("failed to serialize record: {}", e))?;
+        Ok(LanServer {
+            listener,
+            body,
+            mdns: None,
+        })
+    }
+
+    /// The TCP port the record is served on.
+    pub fn port(&self) -> u16 {
+        self.listener.local_addr().map(|a| a.port()).unwrap_or(0)
+    }
+
+    /// Wait up to `wait` for a client and answer it. Returns the address of a client
+    /// that was sent the record; failures of a single client are logged, not returned.
+    pub fn serve_one(&self, wait: Duration) -> anyhow::Result<Option<SocketAddr>> {
+        let deadline = Instant::now() + wait;
+        loop {
+            match self.listener.accept() {
+                Ok((stream, peer)) => {
+                    return match self.answer(stream) {
+                        Ok(true) => Ok(Some(peer)),
+                        Ok(false) => Ok(None),
+                        Err(e) => {
+                            tracing::debug!(error = %e, %peer, "LAN client failed");
+                            Ok(None)
+                        }
+                    };
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    if Instant::now() >= deadline {
+                        return Ok(None);
+                    }
+                    std::thread::sleep(ACCEPT_POLL);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Answer one request. Returns whether it was for the record.
+    fn answer(&self, mut stream: TcpStream) -> anyhow::Result<bool> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_HEAD as usize {
+            let n = stream.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..n]);
+        }
+        let request = String::from_utf8_lossy(&request);
+        let wanted = request
+            .lines()
+            .next()
+            .is_some_and(|line| line.split(' ').take(2).eq(["GET", HANDOFF_PATH]));
+
+        let (status, body) = if wanted {
+            ("200 OK", self.body.as_str())
+        } else {
+            ("404 Not Found", "")
+        };
+        write!(
+            stream,
+            "HTTP/1.0 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )?;
+        stream.flush()?;
+        Ok(wanted)
+    }
+}
+
+impl Drop for LanServer {
+    fn drop(&mut self) {
+        if let Some(mdns) = self.mdns.take() {
+            if let Err(e) = mdns.shutdown() {
+                tracing::debug!(error = %e, "mDNS shutdown failed");
+            }
+        }
+    }
+}
+
+/// Find `pubkey_z32`'s handoff on the local network, waiting up to `wait` for an
+/// advertiser, and verify its signature.
+///
+/// Fails with [`crate::error::CclinkError::RecordNotFound`] if none answers in time.
+pub fn discover(pubkey_z32: &str, wait: Duration) -> anyhow::Result<HandoffRecord> {
+    let pubkey = pkarr::PublicKey::try_from(pubkey_z32)
+        .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+    let mdns =
+        mdns_sd::ServiceDaemon::new().map_err(|e| anyhow::anyhow!("mDNS unavailable: {}", e))?;
+    let events = mdns
+        .browse(SERVICE_TYPE)
+        .map_err(|e| anyhow::anyhow!("mDNS browse failed: {}", e))?;
+
+    let deadline = Instant::now() + wait;
+    let found = loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        let Ok(event) = events.recv_timeout(left) else {
+            break None;
+        };
+        let mdns_sd::ServiceEvent::ServiceResolved(info) = event else {
+            continue;
+        };
+        if info.get_property_val_str(PUBKEY_PROPERTY) != Some(pubkey_z32) {
+            continue;
+        }
+        let record = info.get_addresses().iter().find_map(|ip| {
+            let addr = SocketAddr::new(*ip, info.get_port());
+            match fetch(addr).and_then(|json| verified(&json, &pubkey)) {
+                Ok(record) => Some(record),
+                Err(e) => {
+                    tracing::debug!(error = %e, %addr, "LAN advertiser failed");
+                    None
+                }
+            }
+        });
+        if record.is_some() {
+            break record;
+        }
+    };
+    if let Err(e) = mdns.shutdown() {
+        tracing::debug!(error = %e, "mDNS shutdown failed");
+    }
+    found.ok_or_else(|| crate::error::CclinkError::RecordNotFound.into())
+}
+
+/// Fetch the record JSON served at `addr`.
+fn fetch(addr: SocketAddr) -> anyhow::Result<String> {
+    let started = Instant::now();
+    let mut stream = TcpStream::connect_timeout(&addr, IO_TIMEOUT)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    write!(
+        stream,
+        "GET {} HTTP/1.0\r\nHost: {}\r\n\r\n",
+        HANDOFF_PATH, addr
+    )?;
+    let limit = super::max_response();
+    let response = super::read_capped(stream, limit + MAX_HEAD)?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| anyhow::anyhow!("malformed response from {}", addr))?;
+    let status = head.lines().next().unwrap_or_default();
+    if status.split(' ').nth(1) != Some("200") {
+        anyhow::bail!("{} answered '{}'", addr, status);
+    }
+    if body.len() as u64 > limit {
+        return Err(crate::error::CclinkError::ResponseTooLarge {
+            size: body.len() as u64,
+            limit,
+        }
+        .into());
+    }
+    tracing::debug!(
+        %addr,
+        bytes = body.len(),
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "LAN fetch complete"
+    );
+    Ok(body.to_string())
+}
+
+/// Parse a served record and check it was signed by `pubkey`.
+fn verified(json: &str, pubkey: &pkarr::PublicKey) -> anyhow::Result<HandoffRecord> {
+    let record = crate::record::migrate::parse(json)?;
+    crate::record::verify_record(&record, pubkey)?;
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serve_and_fetch_over_loopback() {
+        let keypair = pkarr::Keypair::from_secret_key(&[11u8; 32]);
+        let record = HandoffRecord::builder(vec![2u8; 3000], 3600)
+            .created_at(1_000)
+            .sign(&keypair)
+            .unwrap();
+        let server = LanServer::bind(&record, "127.0.0.1:0").unwrap();
+        let addr: SocketAddr = ([127, 0, 0, 1], server.port()).into();
+
+        let client = std::thread::spawn(move || fetch(addr));
+        let served = server.serve_one(Duration::from_secs(10)).unwrap();
+        assert!(served.is_some());
+        let json = client.join().unwrap().unwrap();
+        let fetched = verified(&json, &keypair.public_key()).unwrap();
+        assert_eq!(fetched.blob, record.blob);
+
+        let other = pkarr::Keypair::from_secret_key(&[12u8; 32]).public_key();
+        assert!(verified(&json, &other).is_err());
+    }
+
+    #[test]
+    fn test_other_paths_are_not_found() {
+        let record = HandoffRecord::builder(b"blob".to_vec(), 3600)
+            .sign(&pkarr::Keypair::from_secret_key(&[13u8; 32]))
+            .unwrap();
+        let server = LanServer::bind(&record, "127.0.0.1:0").unwrap();
+        let port = server.port();
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+            stream.write_all(b"GET / HTTP/1.0\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+        assert_eq!(server.serve_one(Duration::from_secs(10)).unwrap(), None);
+        assert!(client.join().unwrap().starts_with("HTTP/1.0 404"));
+    }
+}
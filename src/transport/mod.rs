@@ -4,35 +4,284 @@
 //! PKARR SignedPacket on the Mainline DHT. No homeserver, no accounts, no signup
 //! tokens — the DHT publish is authenticated by the Ed25519 signature in the
 //! SignedPacket itself.
+//!
+//! Pickup receipts live in a separate per-handoff slot (a derived keypair) under
+//! the `_cclink-receipt` label, so they never overwrite the handoff itself.
+//!
+//! A handoff whose blob is too large for one packet is chunked: its packet carries
+//! the record (with an empty blob) plus a signed manifest under `_cclink-manifest`,
+//! and each frame lives in its own slot under `_cclink-chunk`
+//! (see [`crate::record::chunked`]).
+//!
+//! A multi-use burn handoff keeps its remaining uses under `_cclink-burn` in a slot
+//! only the publisher can derive, updated with compare-and-swap
+//! (see [`crate::record::burn`]).
+//!
+//! A rotated key's successor is announced by a cross-signed transition record under
+//! `_cclink-transition`, in a slot derived from the old public key
+//! (see [`crate::record::transition`]). A revocation certificate goes under
+//! `_cclink-revoked` in a slot derived the same way (see [`crate::record::revocation`]).
+//!
+//! The registry of devices sharing a key lives under `_cclink-devices` in a slot only
+//! that key can derive (see [`crate::record::devices`]). A key's display name goes
+//! under `_cclink-profile` in a slot derived from its public key
+//! (see [`crate::record::profile`]).
+//!
+//! `cclink verify` exchanges its offers under `_cclink-verify`, in per-direction
+//! slots only the two parties can derive (see [`crate::record::sas`]). One-time-code
+//! handoffs meet under `_cclink-wormhole` in slots derived from the code
+//! (see [`crate::record::wormhole`]).
+//!
+//! Each packet's DNS TTL is the caching header of the DHT: pkarr clients and relays
+//! keep a resolved packet for that long, and relays serve it as `Cache-Control:
+//! max-age`. Handoff packets carry their record's TTL (see [`dns_ttl`]) so cached
+//! copies do not outlive the handoff, and [`DhtClient::freshness`] reports it back.
+//!
+//! The Mainline DHT has no change notifications, so [`DhtClient::subscribe`] is
+//! built on polling. Callers consume it as a stream of new records and do not
+//! depend on how changes are detected.
+//!
+//! [`DhtClient::cached`] keeps resolved packets on disk between invocations and asks
+//! the DHT only for packets newer than the cached ones (see [`packet_cache`]).
+//!
+//! Machines that share a folder but not a path to the DHT can hand off through
+//! [`file::FileTransport`] instead, and machines on one network through
+//! [`lan::LanServer`] and [`lan::discover`].
 
+pub mod file;
+pub mod lan;
+pub mod packet_cache;
+
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+
+use crate::record::burn::BurnCounter;
+use crate::record::chunked::{BlobWriter, ChunkManifest, ChunkVerifier};
+use crate::record::devices::DeviceRegistry;
+use crate::record::profile::Profile;
+use crate::record::receipt::Receipt;
+use crate::record::revocation::Revocation;
+use crate::record::sas::VerifyOffer;
+use crate::record::transition::Transition;
+use crate::record::wormhole::WormholeMessage;
 use crate::record::HandoffRecord;
 
 /// DNS TXT record name for cclink handoff records inside a PKARR SignedPacket.
 const CCLINK_LABEL: &str = "_cclink";
 
-/// DNS TTL for the TXT record (seconds). This is the DNS-level TTL inside the
-/// SignedPacket, not the application-level HandoffRecord TTL.
-const DNS_TTL: u32 = 86400;
+/// DNS TXT record name for pickup receipts inside a receipt-slot SignedPacket.
+const RECEIPT_LABEL: &str = "_cclink-receipt";
+
+/// DNS TXT record name for a chunked handoff's manifest, next to `_cclink`.
+const MANIFEST_LABEL: &str = "_cclink-manifest";
+
+/// DNS TXT record name for one base64-encoded frame inside a chunk-slot SignedPacket.
+const CHUNK_LABEL: &str = "_cclink-chunk";
+
+/// DNS TXT record name for the use counter inside a burn-counter-slot SignedPacket.
+const BURN_LABEL: &str = "_cclink-burn";
+
+/// DNS TXT record name for a key transition inside a transition-slot SignedPacket.
+const TRANSITION_LABEL: &str = "_cclink-transition";
+
+/// DNS TXT record name for a revocation certificate inside a revocation-slot SignedPacket.
+const REVOCATION_LABEL: &str = "_cclink-revoked";
+
+/// DNS TXT record name for the device registry inside a devices-slot SignedPacket.
+const DEVICES_LABEL: &str = "_cclink-devices";
+
+/// DNS TXT record name for a signed display-name profile.
+const PROFILE_LABEL: &str = "_cclink-profile";
+
+/// DNS TXT record name for one side of a `cclink verify` ceremony.
+const VERIFY_LABEL: &str = "_cclink-verify";
+
+/// DNS TXT record name for one side of a one-time-code exchange.
+const WORMHOLE_LABEL: &str = "_cclink-wormhole";
+
+/// Longest DNS TTL (seconds) put in a SignedPacket, and the TTL of packets not tied
+/// to a record's lifetime (receipts). Matches the longest TTL pkarr caches honour.
+const DNS_TTL: u32 = pkarr::DEFAULT_MAXIMUM_TTL;
+
+/// DNS TTL of `cclink verify` offers and one-time-code messages, which are replaced
+/// within seconds and useless once the exchange is over.
+const EXCHANGE_DNS_TTL: u32 = pkarr::DEFAULT_MINIMUM_TTL;
+
+/// DNS TTL for the packets of `record`: its application-level TTL, capped at
+/// [`DNS_TTL`], so caches in front of the DHT drop the packet when the handoff expires.
+pub fn dns_ttl(record: &HandoffRecord) -> u32 {
+    u32::try_from(record.ttl).unwrap_or(u32::MAX).min(DNS_TTL)
+}
+
+/// How long a resolved packet may be served from cache, from its DNS TTL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Freshness {
+    /// Cache lifetime in seconds: the packet's DNS TTL within pkarr's bounds.
+    pub max_age: u32,
+    /// Seconds since this copy was fetched from the DHT.
+    pub age: u32,
+}
+
+impl Freshness {
+    fn of(packet: &pkarr::SignedPacket) -> Self {
+        Freshness {
+            max_age: packet.ttl(pkarr::DEFAULT_MINIMUM_TTL, DNS_TTL),
+            age: packet.elapsed(),
+        }
+    }
+
+    /// Seconds until this copy is stale.
+    pub fn fresh_for(&self) -> u32 {
+        self.max_age.saturating_sub(self.age)
+    }
+
+    /// Seconds a cache that fetched this copy could go on serving it after
+    /// `expires_at` (Unix seconds), or `None` if caches drop it in time.
+    pub fn outlives(&self, expires_at: u64, now: u64) -> Option<u64> {
+        let stale_at = now.saturating_add(u64::from(self.fresh_for()));
+        (stale_at > expires_at).then(|| stale_at - expires_at.max(now))
+    }
+}
 
 // ── DhtClient ────────────────────────────────────────────────────────────
 
+/// Default [`max_response`]: 1 MiB.
+pub const DEFAULT_MAX_RESPONSE: u64 = 1024 * 1024;
+
+/// Largest handoff accepted from any transport, set by [`set_max_response`].
+static MAX_RESPONSE: OnceLock<u64> = OnceLock::new();
+
+/// Accept handoffs of at most `limit` bytes for the rest of the process.
+pub fn set_max_response(limit: u64) -> anyhow::Result<()> {
+    MAX_RESPONSE
+        .set(limit)
+        .map_err(|_| anyhow::anyhow!("a response size limit is already set"))
+}
+
+/// Largest handoff, in bytes, read from the DHT, a shared folder or the local network.
+pub fn max_response() -> u64 {
+    MAX_RESPONSE.get().copied().unwrap_or(DEFAULT_MAX_RESPONSE)
+}
+
+/// Read `reader` to the end as UTF-8, stopping with
+/// [`crate::error::CclinkError::ResponseTooLarge`] once more than `limit` bytes arrive.
+pub fn read_capped(reader: impl std::io::Read, limit: u64) -> anyhow::Result<String> {
+    use std::io::Read;
+
+    let mut text = String::new();
+    reader
+        .take(limit.saturating_add(1))
+        .read_to_string(&mut text)?;
+    if text.len() as u64 > limit {
+        return Err(crate::error::CclinkError::ResponseTooLarge {
+            size: text.len() as u64,
+            limit,
+        }
+        .into());
+    }
+    Ok(text)
+}
+
+/// Request timeout of clients created after [`set_request_timeout`].
+static REQUEST_TIMEOUT: OnceLock<Duration> = OnceLock::new();
+
+/// Give every DHT request of clients created from now on at most `timeout` to be
+/// answered, instead of pkarr's default.
+pub fn set_request_timeout(timeout: Duration) -> anyhow::Result<()> {
+    REQUEST_TIMEOUT
+        .set(timeout)
+        .map_err(|_| anyhow::anyhow!("a request timeout is already set"))
+}
+
+/// A pkarr client builder for the DHT alone, with the configured request timeout.
+fn client_builder() -> pkarr::ClientBuilder {
+    let mut builder = pkarr::Client::builder();
+    builder.no_relays();
+    if let Some(&timeout) = REQUEST_TIMEOUT.get() {
+        builder.request_timeout(timeout);
+    }
+    builder
+}
+
+/// Callback told `(done, total)` bytes as a chunked handoff moves.
+pub type Progress = Box<dyn Fn(u64, u64) + Send + Sync>;
+
 /// Client for the PKARR Mainline DHT.
 ///
 /// Uses `pkarr::ClientBlocking` which handles its own async runtime internally.
 pub struct DhtClient {
     client: pkarr::ClientBlocking,
+    /// On-disk packet cache, for clients made with [`DhtClient::cached`].
+    cache: Option<packet_cache::PacketCache>,
+    /// Set with [`DhtClient::with_progress`].
+    progress: Option<Progress>,
 }
 
 impl DhtClient {
     /// Create a new DhtClient.
     pub fn new() -> anyhow::Result<Self> {
-        let client = pkarr::Client::builder()
-            .no_relays()
+        let client = client_builder()
             .build()
-            .map_err(|e| anyhow::anyhow!("failed to create pkarr client: {}", e))?
+            .map_err(|e| {
+                crate::error::CclinkError::NetworkError(format!(
+                    "failed to create pkarr client: {}",
+                    e
+                ))
+            })?
             .as_blocking();
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            cache: None,
+            progress: None,
+        })
+    }
+
+    /// Create a DhtClient backed by the on-disk packet cache.
+    ///
+    /// Every lookup still queries the DHT, but only for a packet newer than the
+    /// cached one, so an unchanged record is not downloaded again. For repeated
+    /// read-only lookups (`list`, `status`, `listen`); falls back to
+    /// [`new`](Self::new) when the cache directory cannot be created.
+    pub fn cached() -> anyhow::Result<Self> {
+        let cache = match packet_cache::PacketCache::open() {
+            Ok(cache) => cache,
+            Err(e) => {
+                tracing::debug!(error = %e, "packet cache unavailable");
+                return Self::new();
+            }
+        };
+        let client = client_builder()
+            .cache(std::sync::Arc::new(cache.clone()))
+            .build()
+            .map_err(|e| {
+                crate::error::CclinkError::NetworkError(format!(
+                    "failed to create pkarr client: {}",
+                    e
+                ))
+            })?
+            .as_blocking();
+
+        Ok(Self {
+            client,
+            cache: Some(cache),
+            progress: None,
+        })
+    }
+
+    /// Report the progress of chunked publishes and fetches to `report`, once per
+    /// chunk. Inline handoffs are a single packet and report nothing.
+    pub fn with_progress(mut self, report: impl Fn(u64, u64) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Box::new(report));
+        self
+    }
+
+    fn report_progress(&self, done: u64, total: u64) {
+        if let Some(ref report) = self.progress {
+            report(done, total);
+        }
     }
 
     /// Publish a HandoffRecord to the DHT.
@@ -42,28 +291,120 @@ impl DhtClient {
     pub fn publish(&self, keypair: &pkarr::Keypair, record: &HandoffRecord) -> anyhow::Result<()> {
         let json = serde_json::to_string(record)
             .map_err(|e| anyhow::anyhow!("failed to serialize record: {}", e))?;
+        self.publish_txt(keypair, CCLINK_LABEL, &json, dns_ttl(record))
+    }
 
-        let txt = pkarr::dns::rdata::TXT::try_from(json.as_str())
-            .map_err(|e| anyhow::anyhow!("failed to create TXT record: {}", e))?;
+    /// Publish a sealed handoff into `keypair`'s packet, chunked or inline.
+    pub fn publish_sealed(
+        &self,
+        keypair: &pkarr::Keypair,
+        sealed: &crate::record::SealedHandoff,
+    ) -> anyhow::Result<()> {
+        match sealed.chunks {
+            Some((ref frames, ref manifest)) => {
+                self.publish_chunked(keypair, &sealed.record, manifest, frames)
+            }
+            None => self.publish(keypair, &sealed.record),
+        }
+    }
 
-        let cas = self.current_timestamp(keypair);
+    /// Publish a chunked handoff: every frame into its chunk slot, then the record
+    /// and its manifest together in the publisher's packet.
+    ///
+    /// Frames go first so the handoff never points at chunks that are not there yet.
+    pub fn publish_chunked(
+        &self,
+        keypair: &pkarr::Keypair,
+        record: &HandoffRecord,
+        manifest: &ChunkManifest,
+        frames: &[Vec<u8>],
+    ) -> anyhow::Result<()> {
+        let ttl = dns_ttl(record);
+        let first_hash = crate::record::chunked::first_hash_bytes(manifest)?;
+        let total = frames.iter().map(|frame| frame.len() as u64).sum();
+        let mut done = 0;
+        for (index, frame) in frames.iter().enumerate() {
+            let slot = crate::crypto::chunk_keypair(&first_hash, index as u32);
+            let encoded = base64::engine::general_purpose::STANDARD.encode(frame);
+            self.publish_txt(&slot, CHUNK_LABEL, &encoded, ttl)?;
+            done += frame.len() as u64;
+            self.report_progress(done, total);
+        }
 
-        let signed_packet = pkarr::SignedPacket::builder()
-            .txt(
-                CCLINK_LABEL
-                    .try_into()
-                    .map_err(|e| anyhow::anyhow!("invalid label: {}", e))?,
-                txt,
-                DNS_TTL,
-            )
-            .sign(keypair)
-            .map_err(|e| anyhow::anyhow!("failed to sign packet: {}", e))?;
+        let record_json = serde_json::to_string(record)
+            .map_err(|e| anyhow::anyhow!("failed to serialize record: {}", e))?;
+        let manifest_json = serde_json::to_string(manifest)
+            .map_err(|e| anyhow::anyhow!("failed to serialize manifest: {}", e))?;
+        self.publish_txts(
+            keypair,
+            &[
+                (CCLINK_LABEL, record_json.as_str()),
+                (MANIFEST_LABEL, manifest_json.as_str()),
+            ],
+            ttl,
+        )
+    }
 
-        self.client
-            .publish(&signed_packet, cas)
-            .map_err(|e| anyhow::anyhow!("DHT publish failed: {}", e))?;
+    /// Fetch and reassemble the blob of chunked `record`, stored in `location`'s packet.
+    ///
+    /// The manifest signature is checked against `signer` (the publisher), and the
+    /// manifest against `record`, before any chunk is fetched; each frame is verified
+    /// against the hash chain as it arrives, and the result against the record's
+    /// `blob_hash`. `location` differs from `signer` for handoffs kept in derived slots.
+    ///
+    /// The blob is collected in memory, since its hash is checked before decryption:
+    /// each verified frame goes straight into a [`BlobWriter`] capped at
+    /// `chunked::MAX_BLOB_BYTES`, and [`ChunkVerifier`] refuses a manifest over it.
+    pub fn fetch_chunked_blob(
+        &self,
+        location: &pkarr::PublicKey,
+        signer: &pkarr::PublicKey,
+        record: &HandoffRecord,
+    ) -> anyhow::Result<Vec<u8>> {
+        let packet = self
+            .resolve_packet(location)
+            .ok_or_else(|| self.not_found())?;
+        let json = Self::extract_txt(&packet, MANIFEST_LABEL)
+            .map_err(|_| anyhow::anyhow!("handoff has an empty blob but no chunk manifest"))?;
+        let manifest: ChunkManifest = serde_json::from_str(&json)
+            .map_err(|e| anyhow::anyhow!("failed to deserialize manifest: {}", e))?;
 
-        Ok(())
+        if manifest.total_size > max_response() {
+            return Err(crate::error::CclinkError::ResponseTooLarge {
+                size: manifest.total_size,
+                limit: max_response(),
+            }
+            .into());
+        }
+
+        let first_hash = crate::record::chunked::first_hash_bytes(&manifest)?;
+        let mut verifier =
+            ChunkVerifier::new(&manifest, signer, record.created_at, BlobWriter::new())?;
+        let mut done = 0;
+        while verifier.next_index() < manifest.chunks {
+            let index = verifier.next_index();
+            let slot = crate::crypto::chunk_keypair(&first_hash, index).public_key();
+            let frame = self
+                .resolve_packet(&slot)
+                .and_then(|packet| Self::extract_txt(&packet, CHUNK_LABEL).ok())
+                .ok_or_else(|| {
+                    crate::error::CclinkError::NetworkError(format!(
+                        "chunk {} of {} not found on the DHT",
+                        index, manifest.chunks
+                    ))
+                })?;
+            let frame = base64::engine::general_purpose::STANDARD
+                .decode(frame)
+                .map_err(|e| anyhow::anyhow!("invalid chunk {} base64: {}", index, e))?;
+            verifier.push(&frame)?;
+            done += frame
+                .len()
+                .saturating_sub(crate::record::chunked::FRAME_HASH_LEN) as u64;
+            self.report_progress(done, manifest.total_size);
+        }
+        let ciphertext = verifier.finish()?.into_inner();
+        crate::record::verify_blob(record, &ciphertext)?;
+        Ok(ciphertext)
     }
 
     /// Resolve a HandoffRecord from the DHT by public key.
@@ -71,21 +412,370 @@ impl DhtClient {
     /// Looks up the SignedPacket for the given z32 public key, extracts the `_cclink`
     /// TXT record, deserializes the JSON, and verifies the inner Ed25519 signature.
     pub fn resolve_record(&self, pubkey_z32: &str) -> anyhow::Result<HandoffRecord> {
+        let record = self.resolve_record_unverified(pubkey_z32)?;
+        let pubkey = pkarr::PublicKey::try_from(pubkey_z32)
+            .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+
+        crate::record::verify_record(&record, &pubkey)?;
+
+        Ok(record)
+    }
+
+    /// Resolve a HandoffRecord without checking its inner Ed25519 signature.
+    ///
+    /// The PKARR packet signature is still enforced by pkarr. Only for diagnostics
+    /// (`cclink inspect`), which reports the signature status instead of failing.
+    pub fn resolve_record_unverified(&self, pubkey_z32: &str) -> anyhow::Result<HandoffRecord> {
         let pubkey = pkarr::PublicKey::try_from(pubkey_z32)
             .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
 
         let packet = self
+            .resolve_packet(&pubkey)
+            .ok_or_else(|| self.not_found())?;
+
+        let json = Self::extract_txt(&packet, CCLINK_LABEL)?;
+        crate::record::migrate::parse(&json)
+    }
+
+    /// Resolve the record kept in a derived slot and verify it was signed by `signer`.
+    ///
+    /// Slot packets (such as `cclink send` file slots) are signed by the slot key, but
+    /// the record inside must carry the publisher's own signature.
+    pub fn resolve_slot_record(
+        &self,
+        location: &pkarr::PublicKey,
+        signer: &pkarr::PublicKey,
+    ) -> anyhow::Result<HandoffRecord> {
+        let packet = self
+            .resolve_packet(location)
+            .ok_or_else(|| self.not_found())?;
+        let json = Self::extract_txt(&packet, CCLINK_LABEL)?;
+        let record = crate::record::migrate::parse(&json)?;
+        crate::record::verify_record(&record, signer)?;
+        Ok(record)
+    }
+
+    /// Watch the handoff published by `pubkey_z32`.
+    ///
+    /// The returned iterator blocks until the publisher's packet changes and yields
+    /// each newly published, signature-verified record. The record live when the
+    /// subscription starts is the baseline and is not yielded; revocations are skipped.
+    /// Changes are detected by querying the DHT every `interval`.
+    pub fn subscribe(
+        &self,
+        pubkey_z32: &str,
+        interval: Duration,
+    ) -> anyhow::Result<Subscription<'_>> {
+        let pubkey = pkarr::PublicKey::try_from(pubkey_z32)
+            .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+        let last_seen = self
             .client
-            .resolve(&pubkey)
-            .ok_or(crate::error::CclinkError::RecordNotFound)?;
+            .resolve_most_recent(&pubkey)
+            .map(|p| p.timestamp());
+        Ok(Subscription {
+            client: self,
+            interval,
+            last_seen,
+            pubkey,
+        })
+    }
 
-        let json = Self::extract_txt(&packet)?;
-        let record: HandoffRecord = serde_json::from_str(&json)
-            .map_err(|e| anyhow::anyhow!("failed to deserialize record: {}", e))?;
+    /// Publish a pickup receipt into a handoff's receipt slot.
+    ///
+    /// `slot_keypair` is the receipt-slot keypair carried in the handoff payload,
+    /// not the picker's identity key.
+    pub fn publish_receipt(
+        &self,
+        slot_keypair: &pkarr::Keypair,
+        receipt: &Receipt,
+    ) -> anyhow::Result<()> {
+        let json = serde_json::to_string(receipt)
+            .map_err(|e| anyhow::anyhow!("failed to serialize receipt: {}", e))?;
+        self.publish_txt(slot_keypair, RECEIPT_LABEL, &json, DNS_TTL)
+    }
 
-        crate::record::verify_record(&record, &pubkey)?;
+    /// Resolve the receipt in a handoff's receipt slot.
+    ///
+    /// Returns `Ok(None)` while the slot is empty. The receipt signature is not checked
+    /// here — callers verify it with `record::receipt::verify_receipt`.
+    pub fn resolve_receipt(
+        &self,
+        slot_pubkey: &pkarr::PublicKey,
+    ) -> anyhow::Result<Option<Receipt>> {
+        let Some(packet) = self.resolve_packet(slot_pubkey) else {
+            return Ok(None);
+        };
+        let Ok(json) = Self::extract_txt(&packet, RECEIPT_LABEL) else {
+            return Ok(None);
+        };
+        let receipt = serde_json::from_str(&json)
+            .map_err(|e| anyhow::anyhow!("failed to deserialize receipt: {}", e))?;
+        Ok(Some(receipt))
+    }
 
-        Ok(record)
+    /// Resolve the most recent use counter in a burn handoff's counter slot, with the
+    /// timestamp of the packet it came from for [`publish_burn_counter`](Self::publish_burn_counter).
+    ///
+    /// Returns `Ok(None)` while no use has been spent.
+    pub fn resolve_burn_counter(
+        &self,
+        slot_pubkey: &pkarr::PublicKey,
+    ) -> anyhow::Result<Option<(BurnCounter, pkarr::Timestamp)>> {
+        let Some(packet) = self.client.resolve_most_recent(slot_pubkey) else {
+            return Ok(None);
+        };
+        let Ok(json) = Self::extract_txt(&packet, BURN_LABEL) else {
+            return Ok(None);
+        };
+        let counter = serde_json::from_str(&json)
+            .map_err(|e| anyhow::anyhow!("failed to deserialize burn counter: {}", e))?;
+        Ok(Some((counter, packet.timestamp())))
+    }
+
+    /// Replace the counter in a burn handoff's counter slot, provided the slot still
+    /// holds the packet stamped `seen` (`None`: the slot was empty).
+    ///
+    /// Returns `Ok(false)` when another pickup updated the counter first; resolve it
+    /// again and retry.
+    pub fn publish_burn_counter(
+        &self,
+        slot_keypair: &pkarr::Keypair,
+        counter: &BurnCounter,
+        seen: Option<pkarr::Timestamp>,
+        ttl: u32,
+    ) -> anyhow::Result<bool> {
+        let json = serde_json::to_string(counter)
+            .map_err(|e| anyhow::anyhow!("failed to serialize burn counter: {}", e))?;
+        match self.publish_txts_cas(slot_keypair, &[(BURN_LABEL, &json)], ttl, seen) {
+            Ok(()) => Ok(true),
+            Err(e) if e.is::<pkarr::errors::ConcurrencyError>() => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Publish a key transition into the slot derived from its old key.
+    pub fn publish_transition(&self, transition: &Transition) -> anyhow::Result<()> {
+        let old = pkarr::PublicKey::try_from(transition.old_pubkey.as_str())
+            .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+        let json = serde_json::to_string(transition)
+            .map_err(|e| anyhow::anyhow!("failed to serialize transition: {}", e))?;
+        let slot = crate::crypto::transition_keypair(&old);
+        self.publish_txt(&slot, TRANSITION_LABEL, &json, DNS_TTL)
+    }
+
+    /// Resolve the transition announcing the successor of `old`, verified against it.
+    ///
+    /// Returns `Ok(None)` when the key was never rotated (or the record has expired).
+    /// As with revocations, anyone can write the slot, so content that does not parse
+    /// or fails verification is ignored rather than an error; pickup pins the first
+    /// transition it follows, so an erased or rewritten slot does not change it.
+    pub fn resolve_transition(&self, old: &pkarr::PublicKey) -> anyhow::Result<Option<Transition>> {
+        let slot = crate::crypto::transition_keypair(old).public_key();
+        let Some(packet) = self.resolve_packet(&slot) else {
+            return Ok(None);
+        };
+        let Ok(json) = Self::extract_txt(&packet, TRANSITION_LABEL) else {
+            return Ok(None);
+        };
+        let transition: Transition = match serde_json::from_str(&json) {
+            Ok(transition) => transition,
+            Err(e) => {
+                tracing::debug!(error = %e, "ignoring an unreadable transition slot");
+                return Ok(None);
+            }
+        };
+        match crate::record::transition::verify_transition(&transition, old) {
+            Ok(_) => Ok(Some(transition)),
+            Err(e) => {
+                tracing::debug!(error = %e, "ignoring an invalid transition");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Publish a revocation certificate into the revocation slot of the key it revokes.
+    ///
+    /// Needs only the certificate, not the revoked key's secret.
+    pub fn publish_revocation(&self, revocation: &Revocation) -> anyhow::Result<()> {
+        let pubkey = pkarr::PublicKey::try_from(revocation.pubkey.as_str())
+            .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+        let json = serde_json::to_string(revocation)
+            .map_err(|e| anyhow::anyhow!("failed to serialize revocation: {}", e))?;
+        let slot = crate::crypto::revocation_keypair(&pubkey);
+        self.publish_txt(&slot, REVOCATION_LABEL, &json, DNS_TTL)
+    }
+
+    /// Publish `revocation` into the packet of the key it revokes, signed by that key.
+    ///
+    /// Unlike the revocation slot, only the key itself can overwrite its packet, so
+    /// this copy cannot be hidden by anyone else. It replaces the key's handoff.
+    pub fn publish_revocation_in_place(
+        &self,
+        keypair: &pkarr::Keypair,
+        revocation: &Revocation,
+    ) -> anyhow::Result<()> {
+        let json = serde_json::to_string(revocation)
+            .map_err(|e| anyhow::anyhow!("failed to serialize revocation: {}", e))?;
+        self.publish_txt(keypair, REVOCATION_LABEL, &json, DNS_TTL)
+    }
+
+    /// Resolve the revocation certificate published for `pubkey`, verified against it:
+    /// from the key's own packet, else from its revocation slot.
+    ///
+    /// Returns `Ok(None)` when the key has not been revoked. Anyone can write the slot,
+    /// so content that does not parse or fails verification is ignored, not treated as
+    /// an error. The same goes for a certificate that was published and then
+    /// overwritten with a newer packet of junk: it is no longer seen here, and pickup
+    /// relies on the copy it remembered in the state database.
+    pub fn resolve_revocation(
+        &self,
+        pubkey: &pkarr::PublicKey,
+    ) -> anyhow::Result<Option<Revocation>> {
+        let slot = crate::crypto::revocation_keypair(pubkey).public_key();
+        Ok(self
+            .revocation_at(pubkey, pubkey)
+            .or_else(|| self.revocation_at(&slot, pubkey)))
+    }
+
+    /// The verified revocation of `pubkey` stored in `location`'s packet, if any.
+    fn revocation_at(
+        &self,
+        location: &pkarr::PublicKey,
+        pubkey: &pkarr::PublicKey,
+    ) -> Option<Revocation> {
+        let packet = self.resolve_packet(location)?;
+        let json = Self::extract_txt(&packet, REVOCATION_LABEL).ok()?;
+        let revocation: Revocation = match serde_json::from_str(&json) {
+            Ok(revocation) => revocation,
+            Err(e) => {
+                tracing::debug!(error = %e, "ignoring an unreadable revocation certificate");
+                return None;
+            }
+        };
+        match crate::record::revocation::verify_revocation(&revocation, pubkey) {
+            Ok(()) => Some(revocation),
+            Err(e) => {
+                tracing::debug!(error = %e, "ignoring an invalid revocation certificate");
+                None
+            }
+        }
+    }
+
+    /// Publish the device registry of `keypair` into its devices slot.
+    pub fn publish_devices(
+        &self,
+        keypair: &pkarr::Keypair,
+        registry: &DeviceRegistry,
+    ) -> anyhow::Result<()> {
+        let json = serde_json::to_string(registry)
+            .map_err(|e| anyhow::anyhow!("failed to serialize device registry: {}", e))?;
+        let slot = crate::crypto::devices_keypair(keypair);
+        self.publish_txt(&slot, DEVICES_LABEL, &json, DNS_TTL)
+    }
+
+    /// Resolve the device registry of `keypair`; empty while no device is registered.
+    ///
+    /// Always asks the DHT for the latest registry, so a device registered from another
+    /// machine a moment ago is found.
+    pub fn resolve_devices(&self, keypair: &pkarr::Keypair) -> anyhow::Result<DeviceRegistry> {
+        let slot = crate::crypto::devices_keypair(keypair).public_key();
+        let Some(packet) = self.client.resolve_most_recent(&slot) else {
+            return Ok(DeviceRegistry::default());
+        };
+        let Ok(json) = Self::extract_txt(&packet, DEVICES_LABEL) else {
+            return Ok(DeviceRegistry::default());
+        };
+        serde_json::from_str(&json)
+            .map_err(|e| anyhow::anyhow!("failed to deserialize device registry: {}", e))
+    }
+
+    /// Publish a display-name profile into the profile slot of the key it names.
+    pub fn publish_profile(&self, profile: &Profile) -> anyhow::Result<()> {
+        let pubkey = pkarr::PublicKey::try_from(profile.pubkey.as_str())
+            .map_err(|e| anyhow::anyhow!("invalid public key: {}", e))?;
+        let json = serde_json::to_string(profile)
+            .map_err(|e| anyhow::anyhow!("failed to serialize profile: {}", e))?;
+        let slot = crate::crypto::profile_keypair(&pubkey);
+        self.publish_txt(&slot, PROFILE_LABEL, &json, DNS_TTL)
+    }
+
+    /// Resolve the display-name profile of `pubkey`, verified against it.
+    ///
+    /// Returns `Ok(None)` when no name was set. As with revocations, anyone can write
+    /// the slot, so a profile that fails verification is ignored rather than an error.
+    pub fn resolve_profile(&self, pubkey: &pkarr::PublicKey) -> anyhow::Result<Option<Profile>> {
+        let slot = crate::crypto::profile_keypair(pubkey).public_key();
+        let Some(packet) = self.resolve_packet(&slot) else {
+            return Ok(None);
+        };
+        let Ok(json) = Self::extract_txt(&packet, PROFILE_LABEL) else {
+            return Ok(None);
+        };
+        let profile: Profile = serde_json::from_str(&json)
+            .map_err(|e| anyhow::anyhow!("failed to deserialize profile: {}", e))?;
+        match crate::record::profile::verify_profile(&profile, pubkey) {
+            Ok(()) => Ok(Some(profile)),
+            Err(e) => {
+                tracing::debug!(error = %e, "ignoring an invalid profile");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Display name published by `pubkey`, if any. Failures are logged, not returned.
+    pub fn display_name(&self, pubkey: &pkarr::PublicKey) -> Option<String> {
+        match self.resolve_profile(pubkey) {
+            Ok(profile) => profile.map(|p| p.name),
+            Err(e) => {
+                tracing::debug!(error = %e, "failed to resolve profile");
+                None
+            }
+        }
+    }
+
+    /// Publish one side of a `cclink verify` ceremony into `slot_keypair`.
+    pub fn publish_verify_offer(
+        &self,
+        slot_keypair: &pkarr::Keypair,
+        offer: &VerifyOffer,
+    ) -> anyhow::Result<()> {
+        let json = serde_json::to_string(offer)
+            .map_err(|e| anyhow::anyhow!("failed to serialize verification offer: {}", e))?;
+        self.publish_txt(slot_keypair, VERIFY_LABEL, &json, EXCHANGE_DNS_TTL)
+    }
+
+    /// Resolve the latest verification offer in the slot at `slot`, unverified; the
+    /// caller checks it with [`crate::record::sas::verify_offer`].
+    ///
+    /// Always asks the DHT, since the offer changes while the peer runs the ceremony.
+    pub fn resolve_verify_offer(&self, slot: &pkarr::PublicKey) -> Option<VerifyOffer> {
+        let packet = self.client.resolve_most_recent(slot)?;
+        let json = Self::extract_txt(&packet, VERIFY_LABEL).ok()?;
+        serde_json::from_str(&json)
+            .map_err(|e| tracing::debug!(error = %e, "ignoring a malformed verification offer"))
+            .ok()
+    }
+
+    /// Publish one side's message of a one-time-code exchange into `slot_keypair`.
+    pub fn publish_wormhole(
+        &self,
+        slot_keypair: &pkarr::Keypair,
+        message: &WormholeMessage,
+    ) -> anyhow::Result<()> {
+        let json = serde_json::to_string(message)
+            .map_err(|e| anyhow::anyhow!("failed to serialize code message: {}", e))?;
+        self.publish_txt(slot_keypair, WORMHOLE_LABEL, &json, EXCHANGE_DNS_TTL)
+    }
+
+    /// Resolve the latest one-time-code message in the slot at `slot`, if any.
+    ///
+    /// Always asks the DHT, since the publisher adds to its message mid-exchange.
+    pub fn resolve_wormhole(&self, slot: &pkarr::PublicKey) -> Option<WormholeMessage> {
+        let packet = self.client.resolve_most_recent(slot)?;
+        let json = Self::extract_txt(&packet, WORMHOLE_LABEL).ok()?;
+        serde_json::from_str(&json)
+            .map_err(|e| tracing::debug!(error = %e, "ignoring a malformed code message"))
+            .ok()
     }
 
     /// Revoke the active handoff by publishing an empty SignedPacket.
@@ -98,31 +788,221 @@ impl DhtClient {
             .sign(keypair)
             .map_err(|e| anyhow::anyhow!("failed to sign empty packet: {}", e))?;
 
+        let started = Instant::now();
         self.client
             .publish(&empty_packet, cas)
-            .map_err(|e| anyhow::anyhow!("DHT revoke failed: {}", e))?;
+            .map_err(|e| crate::error::CclinkError::DhtPublishFailed(e.to_string()))?;
+        tracing::debug!(
+            pubkey = %keypair.public_key().to_z32(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "DHT revoke complete"
+        );
 
         Ok(())
     }
 
+    /// The signed packets that make up what `slot` holds: its own packet and, when it
+    /// carries a chunk manifest, the packets of every chunk, chunks first. Empty when
+    /// nothing is published there. For `cclink backup`.
+    pub fn snapshot(&self, slot: &pkarr::PublicKey) -> anyhow::Result<Vec<pkarr::SignedPacket>> {
+        let Some(packet) = self.resolve_packet(slot) else {
+            return Ok(vec![]);
+        };
+        let mut packets = vec![];
+        if let Ok(json) = Self::extract_txt(&packet, MANIFEST_LABEL) {
+            let manifest: ChunkManifest = serde_json::from_str(&json)
+                .map_err(|e| anyhow::anyhow!("failed to deserialize manifest: {}", e))?;
+            let first_hash = crate::record::chunked::first_hash_bytes(&manifest)?;
+            let chunks = manifest
+                .chunks
+                .min(crate::record::chunked::MAX_CHUNKS as u32);
+            for index in 0..chunks {
+                let chunk = crate::crypto::chunk_keypair(&first_hash, index).public_key();
+                packets.push(self.resolve_packet(&chunk).ok_or_else(|| {
+                    crate::error::CclinkError::NetworkError(format!(
+                        "chunk {} of {} not found on the DHT",
+                        index, manifest.chunks
+                    ))
+                })?);
+            }
+        }
+        packets.push(packet);
+        Ok(packets)
+    }
+
+    /// The timestamp of the packet currently published for `pubkey`, if any.
+    pub fn packet_timestamp(&self, pubkey: &pkarr::PublicKey) -> Option<pkarr::Timestamp> {
+        self.resolve_packet(pubkey).map(|p| p.timestamp())
+    }
+
+    /// Put a packet signed earlier back on the DHT unchanged. No key is needed: the
+    /// packet carries its own signature. DHT nodes holding a newer packet keep theirs.
+    pub fn republish(&self, packet: &pkarr::SignedPacket) -> anyhow::Result<()> {
+        let started = Instant::now();
+        self.client
+            .publish(packet, None)
+            .map_err(|e| crate::error::CclinkError::DhtPublishFailed(e.to_string()))?;
+        tracing::debug!(
+            pubkey = %packet.public_key().to_z32(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "DHT republish complete"
+        );
+        Ok(())
+    }
+
+    /// Cache freshness of the packet published by `pubkey_z32`, as this client holds it.
+    ///
+    /// Answers from the client cache when the packet was just resolved, so calling this
+    /// after a `resolve_*` method costs no extra lookup.
+    pub fn freshness(&self, pubkey_z32: &str) -> Option<Freshness> {
+        let pubkey = pkarr::PublicKey::try_from(pubkey_z32).ok()?;
+        match self.cache {
+            Some(ref cache) => cache.packet(&pubkey),
+            None => self.resolve_packet(&pubkey),
+        }
+        .map(|p| Freshness::of(&p))
+    }
+
+    /// Block until the DHT bootstrap query finishes.
+    ///
+    /// Returns `true` if at least one DHT node answered — i.e. the network is reachable
+    /// over UDP. Used by `cclink doctor`.
+    pub fn bootstrapped(&self) -> bool {
+        self.client.dht().is_some_and(|dht| dht.bootstrapped())
+    }
+
     // ── Private helpers ──────────────────────────────────────────────────
 
+    /// The error for a lookup that found nothing: `RecordNotFound` when the DHT
+    /// answered, `NetworkError` when no node did.
+    fn not_found(&self) -> crate::error::CclinkError {
+        if self.bootstrapped() {
+            crate::error::CclinkError::RecordNotFound
+        } else {
+            crate::error::CclinkError::NetworkError(
+                "no DHT node answered; check that outgoing UDP is allowed".to_string(),
+            )
+        }
+    }
+
+    /// Store `json` as a TXT record named `label` with DNS TTL `ttl` in a SignedPacket
+    /// signed by `keypair`, and publish it to the Mainline DHT.
+    fn publish_txt(
+        &self,
+        keypair: &pkarr::Keypair,
+        label: &str,
+        json: &str,
+        ttl: u32,
+    ) -> anyhow::Result<()> {
+        self.publish_txts(keypair, &[(label, json)], ttl)
+    }
+
+    /// Store each `(label, json)` pair as a TXT record with DNS TTL `ttl` in one
+    /// SignedPacket signed by `keypair`, and publish it to the Mainline DHT.
+    fn publish_txts(
+        &self,
+        keypair: &pkarr::Keypair,
+        records: &[(&str, &str)],
+        ttl: u32,
+    ) -> anyhow::Result<()> {
+        let cas = self.current_timestamp(keypair);
+        self.publish_txts_cas(keypair, records, ttl, cas)
+    }
+
+    /// [`publish_txts`](Self::publish_txts) with an explicit compare-and-swap timestamp.
+    /// A lost race fails with the [`pkarr::errors::ConcurrencyError`] itself, so
+    /// callers can tell it from other publish failures.
+    fn publish_txts_cas(
+        &self,
+        keypair: &pkarr::Keypair,
+        records: &[(&str, &str)],
+        ttl: u32,
+        cas: Option<pkarr::Timestamp>,
+    ) -> anyhow::Result<()> {
+        let mut builder = pkarr::SignedPacket::builder();
+        for &(label, json) in records {
+            let txt = pkarr::dns::rdata::TXT::try_from(json)
+                .map_err(|e| anyhow::anyhow!("failed to create TXT record: {}", e))?;
+            tracing::debug!(
+                pubkey = %keypair.public_key().to_z32(),
+                label,
+                record_bytes = json.len(),
+                dns_ttl = ttl,
+                cas = ?cas,
+                "publishing record"
+            );
+            builder = builder.txt(
+                label
+                    .try_into()
+                    .map_err(|e| anyhow::anyhow!("invalid label: {}", e))?,
+                txt,
+                ttl,
+            );
+        }
+
+        let signed_packet = builder
+            .sign(keypair)
+            .map_err(|e| anyhow::anyhow!("failed to sign packet: {}", e))?;
+
+        let started = Instant::now();
+        self.client
+            .publish(&signed_packet, cas)
+            .map_err(|e| match e {
+                pkarr::errors::PublishError::Concurrency(e) => anyhow::Error::new(e),
+                e => crate::error::CclinkError::DhtPublishFailed(e.to_string()).into(),
+            })?;
+        tracing::debug!(
+            packet_bytes = signed_packet.encoded_packet().len(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "DHT publish complete"
+        );
+
+        Ok(())
+    }
+
+    /// Resolve the SignedPacket for a public key, logging the lookup.
+    ///
+    /// With the on-disk cache, a cached packet is revalidated against the DHT rather
+    /// than served as-is for its whole DNS TTL: it may be from an earlier run.
+    fn resolve_packet(&self, pubkey: &pkarr::PublicKey) -> Option<pkarr::SignedPacket> {
+        let started = Instant::now();
+        let packet = match self.cache {
+            Some(_) => self.client.resolve_most_recent(pubkey),
+            None => self.client.resolve(pubkey),
+        };
+        tracing::debug!(
+            pubkey = %pubkey.to_z32(),
+            cached = self.cache.is_some(),
+            found = packet.is_some(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "DHT resolve complete"
+        );
+        packet
+    }
+
     /// Get the current packet's timestamp for CAS (compare-and-swap).
     ///
     /// Returns `Some(timestamp)` if there's an existing packet, `None` otherwise.
     /// Used to prevent stale overwrites on the DHT.
     fn current_timestamp(&self, keypair: &pkarr::Keypair) -> Option<pkarr::Timestamp> {
-        self.client
+        let started = Instant::now();
+        let timestamp = self
+            .client
             .resolve_most_recent(&keypair.public_key())
-            .map(|p| p.timestamp())
+            .map(|p| p.timestamp());
+        tracing::trace!(
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "resolved CAS timestamp"
+        );
+        timestamp
     }
 
-    /// Extract the `_cclink` TXT record from a SignedPacket and reassemble its value.
-    fn extract_txt(packet: &pkarr::SignedPacket) -> anyhow::Result<String> {
+    /// Extract the TXT record named `label` from a SignedPacket and reassemble its value.
+    fn extract_txt(packet: &pkarr::SignedPacket, label: &str) -> anyhow::Result<String> {
         use pkarr::dns::rdata::RData;
 
         let rr = packet
-            .resource_records(CCLINK_LABEL)
+            .resource_records(label)
             .next()
             .ok_or(crate::error::CclinkError::RecordNotFound)?;
 
@@ -137,21 +1017,71 @@ impl DhtClient {
     }
 }
 
+// ── Subscription ─────────────────────────────────────────────────────────
+
+/// Stream of new handoff records from one publisher. See [`DhtClient::subscribe`].
+pub struct Subscription<'a> {
+    client: &'a DhtClient,
+    interval: Duration,
+    last_seen: Option<pkarr::Timestamp>,
+    pubkey: pkarr::PublicKey,
+}
+
+impl Iterator for Subscription<'_> {
+    type Item = anyhow::Result<HandoffRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            std::thread::sleep(self.interval);
+
+            // `resolve` would answer from the client cache for as long as the
+            // packet's DNS TTL; a subscription has to ask the DHT every time.
+            let Some(packet) = self.client.client.resolve_most_recent(&self.pubkey) else {
+                continue;
+            };
+            if self
+                .last_seen
+                .is_some_and(|seen| packet.timestamp() <= seen)
+            {
+                continue;
+            }
+            self.last_seen = Some(packet.timestamp());
+            tracing::debug!(pubkey = %self.pubkey.to_z32(), "publisher packet changed");
+
+            // An empty packet is a revocation, not a new handoff.
+            let Ok(json) = DhtClient::extract_txt(&packet, CCLINK_LABEL) else {
+                continue;
+            };
+            let record = crate::record::migrate::parse(&json).and_then(|record| {
+                crate::record::verify_record(&record, &self.pubkey)?;
+                Ok(record)
+            });
+            return Some(record);
+        }
+    }
+}
+
 // ── Tests ──────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[allow(deprecated)]
     use crate::record::{sign_record, HandoffRecordSignable};
 
     fn fixed_keypair() -> pkarr::Keypair {
         pkarr::Keypair::from_secret_key(&[42u8; 32])
     }
 
+    #[allow(deprecated)]
     fn sample_record(keypair: &pkarr::Keypair) -> HandoffRecord {
         let signable = HandoffRecordSignable {
             blob: "dGVzdA==".to_string(),
+            blob_hash: None,
             burn: false,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
             created_at: 1_700_000_000,
             hostname: "testhost".to_string(),
             pin_salt: None,
@@ -159,11 +1089,16 @@ mod tests {
             pubkey: keypair.public_key().to_z32(),
             recipient: None,
             ttl: 3600,
+            version: crate::record::migrate::CURRENT_VERSION,
         };
         let signature = sign_record(&signable, keypair).expect("sign_record failed");
         HandoffRecord {
             blob: signable.blob,
+            blob_hash: None,
             burn: false,
+            burn_grace: None,
+            burn_uses: None,
+            compressed: false,
             created_at: signable.created_at,
             hostname: signable.hostname,
             pin_salt: None,
@@ -172,9 +1107,62 @@ mod tests {
             recipient: None,
             signature,
             ttl: signable.ttl,
+            version: signable.version,
         }
     }
 
+    #[test]
+    fn test_read_capped_stops_at_limit() {
+        assert_eq!(read_capped(&b"0123456789"[..], 10).unwrap(), "0123456789");
+        let err = read_capped(std::io::repeat(b'x'), 10).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<crate::error::CclinkError>(),
+            Some(crate::error::CclinkError::ResponseTooLarge {
+                size: 11,
+                limit: 10
+            })
+        ));
+    }
+
+    #[test]
+    fn test_dns_ttl_follows_record_ttl() {
+        let keypair = fixed_keypair();
+        let record = sample_record(&keypair);
+        assert_eq!(dns_ttl(&record), 3600);
+
+        let long = HandoffRecord::builder(b"test".to_vec(), 7 * 86400)
+            .sign(&keypair)
+            .expect("sign failed");
+        assert_eq!(dns_ttl(&long), DNS_TTL);
+
+        let txt = pkarr::dns::rdata::TXT::try_from("{}").expect("TXT");
+        let packet = pkarr::SignedPacket::builder()
+            .txt(
+                CCLINK_LABEL.try_into().expect("label"),
+                txt,
+                dns_ttl(&record),
+            )
+            .sign(&keypair)
+            .expect("sign");
+        let freshness = Freshness::of(&packet);
+        assert_eq!(freshness.max_age, 3600);
+        assert!(freshness.fresh_for() <= 3600);
+    }
+
+    #[test]
+    fn test_freshness_outlives() {
+        let freshness = Freshness {
+            max_age: 3600,
+            age: 600,
+        };
+        assert_eq!(freshness.fresh_for(), 3000);
+        // Stale at 4000: a record expiring at 5000 is dropped in time.
+        assert_eq!(freshness.outlives(5_000, 1_000), None);
+        assert_eq!(freshness.outlives(3_000, 1_000), Some(1_000));
+        // Already expired: served for as long as the copy stays fresh.
+        assert_eq!(freshness.outlives(500, 1_000), Some(3_000));
+    }
+
     #[test]
     fn test_dht_client_new() {
         let _keypair = fixed_keypair();
@@ -208,6 +1196,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_full_chunk_fits_budget() {
+        let frame = vec![
+            0xabu8;
+            crate::record::chunked::FRAME_HASH_LEN
+                + crate::record::chunked::CHUNK_DATA_SIZE
+        ];
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&frame);
+        let txt = pkarr::dns::rdata::TXT::try_from(encoded.as_str()).expect("TXT::try_from");
+        let signed_packet = pkarr::SignedPacket::builder()
+            .txt(CHUNK_LABEL.try_into().expect("label"), txt, DNS_TTL)
+            .sign(&crate::crypto::chunk_keypair(&[1u8; 32], 0))
+            .expect("a full chunk must fit in one SignedPacket");
+        assert!(signed_packet.encoded_packet().len() <= 1000);
+    }
+
+    #[test]
+    fn test_record_and_manifest_share_a_packet() {
+        let keypair = fixed_keypair();
+        let mut record = sample_record(&keypair);
+        record.blob = String::new();
+        let (_frames, manifest) =
+            crate::record::chunked::split_blob(&[5u8; 5_000], &keypair, record.created_at)
+                .expect("split_blob");
+        let record_json = serde_json::to_string(&record).expect("serialize");
+        let manifest_json = serde_json::to_string(&manifest).expect("serialize");
+
+        let signed_packet = pkarr::SignedPacket::builder()
+            .txt(
+                CCLINK_LABEL.try_into().expect("label"),
+                pkarr::dns::rdata::TXT::try_from(record_json.as_str()).expect("TXT"),
+                DNS_TTL,
+            )
+            .txt(
+                MANIFEST_LABEL.try_into().expect("label"),
+                pkarr::dns::rdata::TXT::try_from(manifest_json.as_str()).expect("TXT"),
+                DNS_TTL,
+            )
+            .sign(&keypair)
+            .expect("sign");
+
+        let extracted = DhtClient::extract_txt(&signed_packet, MANIFEST_LABEL).expect("manifest");
+        let round_tripped: ChunkManifest = serde_json::from_str(&extracted).expect("deserialize");
+        assert_eq!(round_tripped, manifest);
+        assert_eq!(
+            DhtClient::extract_txt(&signed_packet, CCLINK_LABEL).expect("record"),
+            record_json
+        );
+    }
+
     #[test]
     fn test_extract_record_roundtrip() {
         let keypair = fixed_keypair();
@@ -221,7 +1259,7 @@ mod tests {
             .sign(&keypair)
             .expect("sign");
 
-        let extracted = DhtClient::extract_txt(&signed_packet).expect("extract_txt");
+        let extracted = DhtClient::extract_txt(&signed_packet, CCLINK_LABEL).expect("extract_txt");
         let round_tripped: HandoffRecord = serde_json::from_str(&extracted).expect("deserialize");
 
         assert_eq!(round_tripped.created_at, record.created_at);
@@ -258,7 +1296,7 @@ mod tests {
             .sign(&keypair)
             .expect("sign empty packet");
 
-        let result = DhtClient::extract_txt(&empty_packet);
+        let result = DhtClient::extract_txt(&empty_packet, CCLINK_LABEL);
         assert!(result.is_err(), "extract_txt should fail on empty packet");
     }
 
@@ -0,0 +1,139 @@
+//! On-disk cache of resolved SignedPackets, shared between cclink invocations.
+//!
+//! pkarr keeps the packets it resolves in a cache and, when it looks a key up again,
+//! asks DHT nodes only for a packet *newer* than the cached one (the BEP 44 `seq`
+//! of the cached packet's timestamp). Nodes holding the same packet answer without
+//! the value. That is the DHT's conditional GET: the cached timestamp plays the
+//! part of an ETag. The default cache lives in memory and dies with the process;
+//! this one keeps packets in `~/.pubky/packets/` so repeated `list`, `status`, and
+//! `listen` runs revalidate instead of downloading unchanged records again.
+//!
+//! A cached packet is dropped once its DNS TTL has passed, so a record that has
+//! vanished from the DHT is not served from disk forever.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+/// Directory of the packet cache inside the key directory.
+pub fn cache_dir() -> anyhow::Result<PathBuf> {
+    Ok(crate::keys::store::key_dir()?.join("packets"))
+}
+
+/// Bytes before the DNS packet in a serialized SignedPacket: last-seen time, public
+/// key, signature and timestamp.
+const SERIALIZED_HEADER_LEN: usize = 8 + 32 + 64 + 8;
+
+/// [`pkarr::Cache`] storing one file per public key.
+///
+/// Failures to read or write are logged and treated as misses: the cache must never
+/// make a lookup fail.
+#[derive(Debug, Clone)]
+pub struct PacketCache {
+    dir: PathBuf,
+}
+
+impl PacketCache {
+    /// Cache in the default directory, created if missing.
+    pub fn open() -> anyhow::Result<Self> {
+        Self::at(&cache_dir()?)
+    }
+
+    /// Cache in `dir`, created if missing.
+    pub fn at(dir: &Path) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        Ok(PacketCache {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// The cached packet for `pubkey`, read without going through pkarr.
+    pub fn packet(&self, pubkey: &pkarr::PublicKey) -> Option<pkarr::SignedPacket> {
+        pkarr::Cache::get(self, &pkarr::CacheKey::from(pubkey))
+    }
+
+    fn path(&self, key: &pkarr::CacheKey) -> PathBuf {
+        self.dir.join(crate::util::to_hex(&key[..]))
+    }
+}
+
+impl pkarr::Cache for PacketCache {
+    fn capacity(&self) -> usize {
+        usize::MAX
+    }
+
+    fn len(&self) -> usize {
+        std::fs::read_dir(&self.dir).map_or(0, |entries| entries.count())
+    }
+
+    fn put(&self, key: &pkarr::CacheKey, signed_packet: &pkarr::SignedPacket) {
+        let path = self.path(key);
+        if let Err(e) = crate::util::write_atomic(&path, signed_packet.serialize()) {
+            tracing::debug!(error = %e, path = %path.display(), "failed to cache packet");
+        }
+    }
+
+    fn get(&self, key: &pkarr::CacheKey) -> Option<pkarr::SignedPacket> {
+        let path = self.path(key);
+        let bytes = std::fs::read(&path).ok()?;
+        // pkarr slices the header without checking the length, so a truncated file
+        // would panic instead of failing to parse.
+        let parsed = if bytes.len() < SERIALIZED_HEADER_LEN {
+            Err(anyhow::anyhow!("truncated ({} bytes)", bytes.len()))
+        } else {
+            pkarr::SignedPacket::deserialize(&bytes).map_err(anyhow::Error::from)
+        };
+        let packet = match parsed {
+            Ok(packet) => packet,
+            Err(e) => {
+                tracing::debug!(error = %e, path = %path.display(), "dropping unreadable cached packet");
+                let _ = std::fs::remove_file(&path);
+                return None;
+            }
+        };
+        if packet.elapsed() >= packet.ttl(pkarr::DEFAULT_MINIMUM_TTL, super::DNS_TTL) {
+            let _ = std::fs::remove_file(&path);
+            return None;
+        }
+        Some(packet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pkarr::Cache;
+
+    #[test]
+    fn test_packet_cache_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = PacketCache::at(dir.path()).unwrap();
+        let keypair = pkarr::Keypair::from_secret_key(&[7u8; 32]);
+        let pubkey = keypair.public_key();
+        assert!(cache.packet(&pubkey).is_none());
+
+        let txt = pkarr::dns::rdata::TXT::try_from("{}").expect("TXT");
+        let packet = pkarr::SignedPacket::builder()
+            .txt("_cclink".try_into().expect("label"), txt, 3600)
+            .sign(&keypair)
+            .expect("sign");
+        cache.put(&pkarr::CacheKey::from(&pubkey), &packet);
+        assert_eq!(cache.len(), 1);
+
+        let cached = cache.packet(&pubkey).expect("cached packet");
+        assert_eq!(cached.timestamp(), packet.timestamp());
+        assert_eq!(cached.encoded_packet(), packet.encoded_packet());
+    }
+
+    #[test]
+    fn test_packet_cache_drops_unreadable_entry() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = PacketCache::at(dir.path()).unwrap();
+        let pubkey = pkarr::Keypair::from_secret_key(&[7u8; 32]).public_key();
+        let key = pkarr::CacheKey::from(&pubkey);
+        std::fs::write(cache.path(&key), b"not a packet").unwrap();
+        assert!(cache.packet(&pubkey).is_none());
+        assert_eq!(cache.len(), 0);
+    }
+}
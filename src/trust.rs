@@ -0,0 +1,179 @@
+//! Trust store: trust-on-first-use pinning of publisher keys.
+//!
+//! Stored as JSON in `~/.pubky/cclink_trust.json`. The first pickup under a contact
+//! name pins the key it resolved to, and later pickups under the same name are
+//! checked against the pin, so a contact whose key was swapped is caught. Pickups
+//! that name a z32 key directly are not pinned: the key is its own identity, so a pin
+//! under it could never see a change. `cclink verify` with a bare key records that
+//! key under itself, only to show it as verified.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+/// Path of the trust store inside the key directory.
+pub fn trust_path() -> anyhow::Result<PathBuf> {
+    Ok(crate::keys::store::key_dir()?.join("cclink_trust.json"))
+}
+
+/// A pinned key.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TrustEntry {
+    /// Unix timestamp (seconds) of the first pickup under this name.
+    pub first_seen: u64,
+    /// Pinned z32-encoded public key.
+    pub pubkey: String,
+    /// Unix timestamp (seconds) when `cclink verify` confirmed the key, if it has.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified_at: Option<u64>,
+}
+
+/// Result of checking a pickup against the store.
+#[derive(Debug, PartialEq)]
+pub enum TrustCheck {
+    /// Nothing pinned under this name yet.
+    FirstUse,
+    /// The pinned key matches.
+    Known,
+    /// A different key is pinned under this name.
+    Changed { pinned: String },
+}
+
+/// Name → pinned key, kept sorted by name.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct TrustStore {
+    keys: BTreeMap<String, TrustEntry>,
+}
+
+impl TrustStore {
+    /// Load the trust store from `path`. A missing file is an empty store.
+    pub fn load_from(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read trust store from {}", path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("Failed to parse trust store in {}", path.display()))
+    }
+
+    /// Write the trust store to `path` (temp file + rename).
+    pub fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        crate::util::write_atomic(path, json)
+    }
+
+    /// Compare `pubkey` with whatever is pinned under `name`.
+    pub fn check(&self, name: &str, pubkey: &str) -> TrustCheck {
+        match self.keys.get(name) {
+            None => TrustCheck::FirstUse,
+            Some(entry) if entry.pubkey == pubkey => TrustCheck::Known,
+            Some(entry) => TrustCheck::Changed {
+                pinned: entry.pubkey.clone(),
+            },
+        }
+    }
+
+    /// Pin `pubkey` under `name`, replacing any previous pin.
+    pub fn pin(&mut self, name: &str, pubkey: &str, now: u64) {
+        self.keys.insert(
+            name.to_string(),
+            TrustEntry {
+                first_seen: now,
+                pubkey: pubkey.to_string(),
+                verified_at: None,
+            },
+        );
+    }
+
+    /// Mark the key pinned under `name` as verified, pinning `pubkey` first if nothing
+    /// is pinned yet. Fails when a different key is pinned under `name`.
+    pub fn mark_verified(&mut self, name: &str, pubkey: &str, now: u64) -> anyhow::Result<()> {
+        if let TrustCheck::Changed { pinned } = self.check(name, pubkey) {
+            anyhow::bail!(
+                "{} is pinned to {}, not {}; run `cclink trust remove {}` first if the change is expected",
+                name,
+                pinned,
+                pubkey,
+                name
+            );
+        }
+        if self.check(name, pubkey) == TrustCheck::FirstUse {
+            self.pin(name, pubkey, now);
+        }
+        if let Some(entry) = self.keys.get_mut(name) {
+            entry.verified_at = Some(now);
+        }
+        Ok(())
+    }
+
+    /// Remove every pin whose name or key equals `name_or_key`. Returns how many.
+    pub fn remove(&mut self, name_or_key: &str) -> usize {
+        let before = self.keys.len();
+        self.keys
+            .retain(|name, entry| name != name_or_key && entry.pubkey != name_or_key);
+        before - self.keys.len()
+    }
+
+    /// Iterate over `(name, entry)` pairs in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &TrustEntry)> {
+        self.keys.iter().map(|(n, e)| (n.as_str(), e))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_use_then_known() {
+        let mut store = TrustStore::default();
+        assert_eq!(store.check("alice", "key-a"), TrustCheck::FirstUse);
+        store.pin("alice", "key-a", 100);
+        assert_eq!(store.check("alice", "key-a"), TrustCheck::Known);
+    }
+
+    #[test]
+    fn test_changed_key_detected() {
+        let mut store = TrustStore::default();
+        store.pin("alice", "key-a", 100);
+        assert_eq!(
+            store.check("alice", "key-b"),
+            TrustCheck::Changed {
+                pinned: "key-a".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_mark_verified_pins_and_refuses_changed_key() {
+        let mut store = TrustStore::default();
+        store.mark_verified("alice", "key-a", 300).unwrap();
+        assert_eq!(store.check("alice", "key-a"), TrustCheck::Known);
+        assert_eq!(store.iter().next().unwrap().1.verified_at, Some(300));
+        assert!(store.mark_verified("alice", "key-b", 400).is_err());
+    }
+
+    #[test]
+    fn test_remove_by_name_or_key_and_persist() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("cclink_trust.json");
+
+        let mut store = TrustStore::default();
+        store.pin("alice", "key-a", 100);
+        store.pin("key-a", "key-a", 100);
+        store.pin("bob", "key-b", 200);
+        store.save_to(&path).unwrap();
+
+        let mut store = TrustStore::load_from(&path).unwrap();
+        assert_eq!(store.remove("key-a"), 2, "name and key matches both go");
+        assert_eq!(store.remove("bob"), 1);
+        assert!(store.is_empty());
+    }
+}
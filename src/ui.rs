@@ -0,0 +1,230 @@
+//! Colors of human-readable output.
+//!
+//! Messages are colored by the role a piece of text plays, not by color: see
+//! [`Paint`]. The success, warning, error and identity colors come from the
+//! `[theme]` section of `~/.pubky/cclink.toml`:
+//!
+//! ```toml
+//! [theme]
+//! success = "green"
+//! warning = "bright yellow"
+//! error = "red"
+//! identity = "#5fafff"   # public keys, names, projects
+//! ```
+//!
+//! Everything is printed plain when the stream is not a terminal, with `NO_COLOR`
+//! set, or with `--no-color`.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use owo_colors::{AnsiColors, DynColors, OwoColorize, Stream};
+use serde::{Deserialize, Deserializer};
+
+/// Theme in effect, set once at startup from the config.
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+static NO_COLOR: AtomicBool = AtomicBool::new(false);
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Colors of the themed roles.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    #[serde(deserialize_with = "color")]
+    pub success: DynColors,
+    #[serde(deserialize_with = "color")]
+    pub warning: DynColors,
+    #[serde(deserialize_with = "color")]
+    pub error: DynColors,
+    /// Public keys, contact and device names, session IDs and projects.
+    #[serde(deserialize_with = "color")]
+    pub identity: DynColors,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            success: DynColors::Ansi(AnsiColors::Green),
+            warning: DynColors::Ansi(AnsiColors::Yellow),
+            error: DynColors::Ansi(AnsiColors::Red),
+            identity: DynColors::Ansi(AnsiColors::Cyan),
+        }
+    }
+}
+
+/// A color name such as `"bright blue"`, or `"#rrggbb"`.
+fn color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DynColors, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    name.parse().map_err(|_| {
+        serde::de::Error::custom(format!(
+            "unknown color {:?}: use a name like \"green\" or \"bright blue\", or \"#rrggbb\"",
+            name
+        ))
+    })
+}
+
+/// Use `theme` for the rest of the process.
+pub fn set_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
+
+/// Print no status lines or progress bars for the rest of the process (`--quiet`).
+pub fn set_quiet() {
+    QUIET.store(true, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// `println!` for status lines: what a command is doing or has done, as opposed to
+/// the output it was asked for. Silenced by `--quiet`, so scripts see only requested
+/// output, warnings and errors.
+#[macro_export]
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if !$crate::ui::is_quiet() {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Print without colors for the rest of the process, whatever the terminal supports.
+pub fn set_no_color() {
+    NO_COLOR.store(true, Ordering::Relaxed);
+    owo_colors::set_override(false);
+}
+
+/// Whether colors were turned off with [`set_no_color`]. For output that is not
+/// written through [`Paint`], such as log lines.
+pub fn no_color() -> bool {
+    NO_COLOR.load(Ordering::Relaxed)
+}
+
+/// Whether `NO_COLOR` is set to a non-empty value (<https://no-color.org>).
+pub fn no_color_env() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Role {
+    Success,
+    Warning,
+    Error,
+    Identity,
+    Emphasis,
+    Muted,
+}
+
+/// Styles any displayable value by the role it plays in a message.
+pub trait Paint: fmt::Display {
+    /// Something that worked: "Published.", "Revoked.", a passing check.
+    fn success(&self) -> Styled<'_, Self> {
+        Styled::new(self, Role::Success)
+    }
+
+    /// Something to look at before going on.
+    fn warning(&self) -> Styled<'_, Self> {
+        Styled::new(self, Role::Warning)
+    }
+
+    /// Something that failed.
+    fn error(&self) -> Styled<'_, Self> {
+        Styled::new(self, Role::Error)
+    }
+
+    /// Who or what a message is about: a publisher, contact, device or project.
+    fn identity(&self) -> Styled<'_, Self> {
+        Styled::new(self, Role::Identity)
+    }
+
+    /// A command or code to copy.
+    fn emphasis(&self) -> Styled<'_, Self> {
+        Styled::new(self, Role::Emphasis)
+    }
+
+    /// Background detail.
+    fn muted(&self) -> Styled<'_, Self> {
+        Styled::new(self, Role::Muted)
+    }
+}
+
+impl<T: fmt::Display + ?Sized> Paint for T {}
+
+/// Text styled by [`Paint`], colored only if its stream supports it.
+pub struct Styled<'a, T: ?Sized> {
+    text: &'a T,
+    role: Role,
+    stream: Stream,
+}
+
+impl<'a, T: ?Sized> Styled<'a, T> {
+    fn new(text: &'a T, role: Role) -> Self {
+        Self {
+            text,
+            role,
+            stream: Stream::Stdout,
+        }
+    }
+
+    /// Check for color support on stderr instead of stdout, for `eprintln!`.
+    pub fn stderr(self) -> Self {
+        Self {
+            stream: Stream::Stderr,
+            ..self
+        }
+    }
+}
+
+impl<T: fmt::Display + ?Sized> fmt::Display for Styled<'_, T> {
+    // Forwards `f`, so widths and alignment apply to the text as usual.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (theme, text, stream) = (theme(), self.text, self.stream);
+        match self.role {
+            Role::Success => fmt::Display::fmt(
+                &text.if_supports_color(stream, |t| t.color(theme.success)),
+                f,
+            ),
+            Role::Warning => fmt::Display::fmt(
+                &text.if_supports_color(stream, |t| t.color(theme.warning)),
+                f,
+            ),
+            Role::Error => {
+                fmt::Display::fmt(&text.if_supports_color(stream, |t| t.color(theme.error)), f)
+            }
+            Role::Identity => fmt::Display::fmt(
+                &text.if_supports_color(stream, |t| t.color(theme.identity)),
+                f,
+            ),
+            Role::Emphasis => fmt::Display::fmt(&text.if_supports_color(stream, |t| t.bold()), f),
+            Role::Muted => fmt::Display::fmt(&text.if_supports_color(stream, |t| t.dimmed()), f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_colors_parse_names_and_hex() {
+        let theme: Theme = toml::from_str(
+            r##"
+            warning = "bright yellow"
+            identity = "#5fafff"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(theme.warning, DynColors::Ansi(AnsiColors::BrightYellow));
+        assert_eq!(theme.identity, DynColors::Rgb(0x5f, 0xaf, 0xff));
+        assert_eq!(theme.success, Theme::default().success);
+        assert!(toml::from_str::<Theme>(r#"error = "reddish""#).is_err());
+    }
+}
@@ -13,10 +13,246 @@ pub fn human_duration(secs: u64) -> String {
     }
 }
 
+/// Parse a duration like `90`, `30s`, `30m`, `2h`, `7d`, or `1h30m` into seconds.
+///
+/// A bare number is seconds. Used as the clap value parser for durations such as
+/// `--burn-grace`; `--ttl` goes through [`parse_ttl`].
+pub fn parse_duration(input: &str) -> Result<u64, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("duration is empty".to_string());
+    }
+    if let Ok(secs) = input.parse::<u64>() {
+        return Ok(secs);
+    }
+
+    let mut total: u64 = 0;
+    let mut digits = String::new();
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            'w' => 7 * 86400,
+            _ => {
+                return Err(format!(
+                    "invalid duration '{}': unknown unit '{}'",
+                    input, c
+                ))
+            }
+        };
+        let value: u64 = digits.parse().map_err(|_| {
+            format!(
+                "invalid duration '{}': expected a number before '{}'",
+                input, c
+            )
+        })?;
+        total = value
+            .checked_mul(unit)
+            .and_then(|v| total.checked_add(v))
+            .ok_or_else(|| format!("duration '{}' is too large", input))?;
+        digits.clear();
+    }
+    if !digits.is_empty() {
+        return Err(format!(
+            "invalid duration '{}': trailing number needs a unit (s, m, h, d, w)",
+            input
+        ));
+    }
+    Ok(total)
+}
+
+/// Longest handoff TTL accepted: 30 days.
+pub const MAX_TTL: u64 = 30 * 86400;
+
+/// [`parse_duration`] for `--ttl`: at least one second and at most [`MAX_TTL`].
+pub fn parse_ttl(input: &str) -> Result<u64, String> {
+    match parse_duration(input)? {
+        0 => Err("TTL must be at least 1s".to_string()),
+        secs if secs > MAX_TTL => Err(format!(
+            "TTL '{}' is longer than the maximum of {}d",
+            input,
+            MAX_TTL / 86400
+        )),
+        secs => Ok(secs),
+    }
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD HH:MM UTC`.
+pub fn format_utc(unix_secs: u64) -> String {
+    let days = (unix_secs / 86400) as i64;
+    let rem = unix_secs % 86400;
+    // Civil-from-days (Howard Hinnant's algorithm), proleptic Gregorian calendar.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        rem / 3600,
+        (rem % 3600) / 60
+    )
+}
+
+/// Lowercase hex encoding of `bytes`.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decode hex text (either case) into bytes.
+pub fn from_hex(text: &str) -> anyhow::Result<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.chars().all(|c| c.is_ascii_hexdigit()) {
+        anyhow::bail!("invalid hex '{}'", text);
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&text[i..i + 2], 16)
+                .map_err(|e| anyhow::anyhow!("invalid hex: {}", e))
+        })
+        .collect()
+}
+
+/// Quote a CSV field when it contains a delimiter, quote, or line break (RFC 4180).
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Replace the file at `path` with `contents` without ever leaving it half written:
+/// the bytes go to a hidden temporary file next to it, which is renamed over it.
+pub fn write_atomic(path: &std::path::Path, contents: impl AsRef<[u8]>) -> anyhow::Result<()> {
+    use anyhow::Context;
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("{} is not a file path", path.display()))?;
+    let mut tmp = std::ffi::OsString::from(".");
+    tmp.push(name);
+    tmp.push(".tmp");
+    let tmp = path.with_file_name(tmp);
+    std::fs::write(&tmp, contents).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    if let Err(e) = std::fs::rename(&tmp, path) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e).with_context(|| format!("Failed to write {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Locate an executable on `PATH`, like `which`.
+///
+/// On Windows the common executable extensions are tried as well.
+pub fn find_executable(name: &str) -> Option<std::path::PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    let candidates: Vec<String> = if cfg!(windows) {
+        ["", ".exe", ".cmd", ".bat"]
+            .iter()
+            .map(|ext| format!("{}{}", name, ext))
+            .collect()
+    } else {
+        vec![name.to_string()]
+    };
+    std::env::split_paths(&path).find_map(|dir| {
+        candidates
+            .iter()
+            .map(|c| dir.join(c))
+            .find(|p| is_executable(p))
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &std::path::Path) -> bool {
+    path.is_file()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_write_atomic_replaces_and_leaves_no_temp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store.json");
+        write_atomic(&path, "old").unwrap();
+        write_atomic(&path, b"new").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        assert_eq!(to_hex(&[0x00, 0xab, 0x7f]), "00ab7f");
+        assert_eq!(from_hex("00AB7f").unwrap(), [0x00, 0xab, 0x7f]);
+        assert!(from_hex("abc").is_err());
+        assert!(from_hex("zz").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_executable() {
+        assert!(find_executable("sh").is_some(), "sh must be on PATH");
+        assert!(find_executable("definitely-not-a-real-binary-cclink").is_none());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("3600"), Ok(3600));
+        assert_eq!(parse_duration("30s"), Ok(30));
+        assert_eq!(parse_duration("30m"), Ok(1800));
+        assert_eq!(parse_duration("2h"), Ok(7200));
+        assert_eq!(parse_duration("7d"), Ok(604_800));
+        assert_eq!(parse_duration("1h30m"), Ok(5400));
+        assert_eq!(parse_duration("2H"), Ok(7200));
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_garbage() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("2x").is_err());
+        assert!(parse_duration("1h30").is_err());
+        assert!(parse_duration("-5m").is_err());
+    }
+
+    #[test]
+    fn test_parse_ttl_bounds() {
+        assert_eq!(parse_ttl("1s"), Ok(1));
+        assert_eq!(parse_ttl("30d"), Ok(MAX_TTL));
+        assert!(parse_ttl("0").is_err());
+        assert!(parse_ttl("0s").is_err());
+        assert!(parse_ttl("31d").is_err());
+        assert!(parse_ttl("300000000000000000s").is_err());
+    }
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_utc(1_700_000_000), "2023-11-14 22:13 UTC");
+        assert_eq!(format_utc(951_782_400), "2000-02-29 00:00 UTC");
+    }
+
     #[test]
     fn test_human_duration_seconds() {
         assert_eq!(human_duration(0), "0s");
@@ -8,6 +8,8 @@
 //!   4. Shared+burn    — combines shared encrypt + burn flag; recipient decrypts; sender cannot
 //!   5. Signed burn tamper detection — tamping burn after signing causes verify_record to fail
 //!   6. Signed recipient tamper detection — tampering recipient after signing causes verify_record to fail
+//!   7. Chunked file transfer — a `cclink send` payload too large for one packet is
+//!      chunked, reassembled, decrypted and decoded by the recipient
 //!
 //! All tests are `#[test]` (not `#[tokio::test]`) — no async, no network access.
 
@@ -15,6 +17,7 @@ use cclink::crypto::{
     age_decrypt, age_encrypt, age_identity, age_recipient, ed25519_to_x25519_public,
     ed25519_to_x25519_secret, pin_decrypt, pin_encrypt,
 };
+#[allow(deprecated)]
 use cclink::record::{sign_record, verify_record, HandoffRecord, HandoffRecordSignable};
 
 /// Fixed keypair with seed [42u8; 32] — used for the "self" / sender role.
@@ -175,13 +178,18 @@ fn test_shared_burn_encrypt_round_trip() {
 /// Then tamper burn=true. Verify the signature check fails.
 /// Proves that burn is part of the signed envelope in v1.1.
 #[test]
+#[allow(deprecated)]
 fn test_signed_burn_tamper_detection() {
     let keypair = keypair_a();
 
     // Build a signed record with burn=false
     let signable = HandoffRecordSignable {
         blob: "dGVzdGJsb2I=".to_string(),
+        blob_hash: None,
         burn: false,
+        burn_grace: None,
+        burn_uses: None,
+        compressed: false,
         created_at: 1_700_000_000,
         hostname: "testhost".to_string(),
         pin_salt: None,
@@ -189,12 +197,17 @@ fn test_signed_burn_tamper_detection() {
         pubkey: keypair.public_key().to_z32(),
         recipient: None,
         ttl: 3600,
+        version: cclink::record::migrate::CURRENT_VERSION,
     };
     let signature = sign_record(&signable, &keypair).expect("sign_record should succeed");
 
     let record = HandoffRecord {
         blob: signable.blob.clone(),
+        blob_hash: None,
         burn: false,
+        burn_grace: None,
+        burn_uses: None,
+        compressed: false,
         created_at: signable.created_at,
         hostname: signable.hostname.clone(),
         pin_salt: None,
@@ -203,6 +216,7 @@ fn test_signed_burn_tamper_detection() {
         recipient: None,
         signature: signature.clone(),
         ttl: signable.ttl,
+        version: signable.version,
     };
 
     // Valid record should verify
@@ -290,13 +304,18 @@ fn test_pin_record_owner_cannot_decrypt() {
 /// Then tamper recipient=Some("attacker"). Verify the signature check fails.
 /// Proves that recipient is part of the signed envelope in v1.1.
 #[test]
+#[allow(deprecated)]
 fn test_signed_recipient_tamper_detection() {
     let keypair = keypair_a();
 
     // Build a signed record with recipient=None (self-encrypted)
     let signable = HandoffRecordSignable {
         blob: "dGVzdGJsb2I=".to_string(),
+        blob_hash: None,
         burn: false,
+        burn_grace: None,
+        burn_uses: None,
+        compressed: false,
         created_at: 1_700_000_000,
         hostname: "testhost".to_string(),
         pin_salt: None,
@@ -304,12 +323,17 @@ fn test_signed_recipient_tamper_detection() {
         pubkey: keypair.public_key().to_z32(),
         recipient: None,
         ttl: 3600,
+        version: cclink::record::migrate::CURRENT_VERSION,
     };
     let signature = sign_record(&signable, &keypair).expect("sign_record should succeed");
 
     let record = HandoffRecord {
         blob: signable.blob.clone(),
+        blob_hash: None,
         burn: false,
+        burn_grace: None,
+        burn_uses: None,
+        compressed: false,
         created_at: signable.created_at,
         hostname: signable.hostname.clone(),
         pin_salt: None,
@@ -318,6 +342,7 @@ fn test_signed_recipient_tamper_detection() {
         recipient: None,
         signature: signature.clone(),
         ttl: signable.ttl,
+        version: signable.version,
     };
 
     // Valid record should verify
@@ -336,3 +361,106 @@ fn test_signed_recipient_tamper_detection() {
         "tampered recipient must cause signature verification failure"
     );
 }
+
+// ── Test 7: Builder-signed record tamper detection ────────────────────────
+
+/// Sign a record with `HandoffRecord::builder`, as publish does. It verifies, and
+/// flipping any signed field (burn, recipient, ttl) breaks the signature.
+#[test]
+fn test_builder_record_tamper_detection() {
+    let keypair = keypair_a();
+    let record = HandoffRecord::builder(b"testblob".to_vec(), 3600)
+        .created_at(1_700_000_000)
+        .sign(&keypair)
+        .expect("sign should succeed");
+    verify_record(&record, &keypair.public_key())
+        .expect("valid record should pass signature verification");
+
+    let tampered = [
+        HandoffRecord {
+            burn: true,
+            ..record.clone()
+        },
+        HandoffRecord {
+            recipient: Some("attacker-pubkey-z32encoded".to_string()),
+            ..record.clone()
+        },
+        HandoffRecord {
+            ttl: 999_999,
+            ..record.clone()
+        },
+    ];
+    for record in &tampered {
+        assert!(
+            verify_record(record, &keypair.public_key()).is_err(),
+            "tampered builder record must fail signature verification"
+        );
+    }
+}
+
+// ── Test 7: Chunked file transfer round-trip ──────────────────────────────
+
+/// Walk the `cclink send` → `cclink recv` data path without the DHT: stream a file
+/// through compression and encryption to the recipient, seal it (forcing chunking),
+/// then verify every frame against the signed manifest and stream-decrypt it back.
+#[test]
+#[allow(deprecated)]
+fn test_chunked_file_transfer_round_trip() {
+    use cclink::crypto::{age_decrypt_reader, age_encrypt_stream};
+    use cclink::record::chunked::ChunkVerifier;
+    use cclink::record::{compress_reader, decompress_reader, file, seal_with_chunking};
+    use std::io::Read;
+
+    let sender = keypair_a();
+    let recipient_kp = keypair_b();
+
+    // Pseudo-random bytes don't compress, so the ciphertext needs several chunks.
+    let contents: Vec<u8> = (0..5_000u32)
+        .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+        .collect();
+    let plaintext =
+        compress_reader(file::reader("notes.bin", &contents[..]).expect("frame")).expect("zstd");
+    let mut ciphertext = Vec::new();
+    age_encrypt_stream(plaintext, &mut ciphertext, &recipient_for(&recipient_kp)).expect("encrypt");
+
+    let signable = HandoffRecordSignable {
+        blob: String::new(),
+        blob_hash: None,
+        burn: false,
+        burn_grace: None,
+        burn_uses: None,
+        compressed: true,
+        created_at: 1_700_000_000,
+        hostname: String::new(),
+        pin_salt: None,
+        project: String::new(),
+        pubkey: sender.public_key().to_z32(),
+        recipient: Some(recipient_kp.public_key().to_z32()),
+        ttl: 3600,
+        version: cclink::record::migrate::CURRENT_VERSION,
+    };
+    let sealed = seal_with_chunking(signable, &ciphertext, &sender).expect("seal");
+    verify_record(&sealed.record, &sender.public_key()).expect("record signature");
+    let (frames, manifest) = sealed.chunks.expect("5 KB must be chunked");
+
+    let mut verifier = ChunkVerifier::new(
+        &manifest,
+        &sender.public_key(),
+        sealed.record.created_at,
+        Vec::new(),
+    )
+    .expect("manifest");
+    for frame in &frames {
+        verifier.push(frame).expect("frame");
+    }
+    let reassembled = verifier.finish().expect("complete");
+
+    let identity = identity_for(&recipient_kp);
+    let decrypted = age_decrypt_reader(&reassembled[..], &identity).expect("decrypt");
+    let mut plaintext = decompress_reader(decrypted).expect("decompress");
+    let name = file::read_header(&mut plaintext).expect("header");
+    let mut received = Vec::new();
+    plaintext.read_to_end(&mut received).expect("contents");
+    assert_eq!(name, "notes.bin");
+    assert_eq!(received, contents);
+}
@@ -133,6 +133,7 @@ fn test_hostname_not_in_record_json() {
         hostname: hostname.to_string(),
         project: "/home/user/project".to_string(),
         session_id: session_id.to_string(),
+        ..Default::default()
     };
     let payload_bytes = serde_json::to_vec(&payload).expect("serialize payload");
     let ciphertext = age_encrypt(&payload_bytes, &recipient).expect("encrypt");
@@ -141,7 +142,11 @@ fn test_hostname_not_in_record_json() {
     // Build record with empty outer fields (as publish.rs now does)
     let record = cclink::record::HandoffRecord {
         blob,
+        blob_hash: None,
         burn: false,
+        burn_grace: None,
+        burn_uses: None,
+        compressed: false,
         created_at: 1_700_000_000,
         hostname: String::new(),
         pin_salt: None,
@@ -150,6 +155,7 @@ fn test_hostname_not_in_record_json() {
         recipient: None,
         signature: "test-sig".to_string(),
         ttl: 3600,
+        version: cclink::record::migrate::CURRENT_VERSION,
     };
 
     let json = serde_json::to_string(&record).expect("serialize record");
@@ -180,6 +186,7 @@ fn test_project_path_not_in_record_json() {
         hostname: hostname.to_string(),
         project: project.to_string(),
         session_id: session_id.to_string(),
+        ..Default::default()
     };
     let payload_bytes = serde_json::to_vec(&payload).expect("serialize payload");
     let ciphertext = age_encrypt(&payload_bytes, &recipient).expect("encrypt");
@@ -187,7 +194,11 @@ fn test_project_path_not_in_record_json() {
 
     let record = cclink::record::HandoffRecord {
         blob,
+        blob_hash: None,
         burn: false,
+        burn_grace: None,
+        burn_uses: None,
+        compressed: false,
         created_at: 1_700_000_000,
         hostname: String::new(),
         pin_salt: None,
@@ -196,6 +207,7 @@ fn test_project_path_not_in_record_json() {
         recipient: None,
         signature: "test-sig".to_string(),
         ttl: 3600,
+        version: cclink::record::migrate::CURRENT_VERSION,
     };
 
     let json = serde_json::to_string(&record).expect("serialize record");
@@ -228,6 +240,7 @@ fn test_payload_encrypt_decrypt_round_trip() {
         hostname: "test-machine".to_string(),
         project: "/home/user/secret-project".to_string(),
         session_id: "sess-round-trip-12345".to_string(),
+        ..Default::default()
     };
     let payload_bytes = serde_json::to_vec(&payload).expect("serialize payload");
     let ciphertext = age_encrypt(&payload_bytes, &recipient).expect("encrypt");
@@ -0,0 +1,70 @@
+//! `--quiet` output tests: run the cclink binary against a throwaway home directory
+//! and check that status lines stay off stdout while requested output does not.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Run cclink with `home` as its home and data directory.
+fn cclink(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cclink"))
+        .args(args)
+        .env("HOME", home)
+        .env("XDG_DATA_HOME", home.join("data"))
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("NO_COLOR", "1")
+        .env_remove("CCLINK_PROFILE")
+        .output()
+        .expect("run cclink")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn test_quiet_init_prints_nothing() {
+    let home = tempfile::tempdir().expect("Failed to create temp dir");
+    let loud = cclink(home.path(), &["init", "--no-passphrase", "--yes"]);
+    assert!(loud.status.success());
+    assert!(stdout(&loud).contains("Public Key:"));
+
+    let quiet = cclink(
+        home.path(),
+        &["--quiet", "init", "--no-passphrase", "--yes"],
+    );
+    assert!(quiet.status.success());
+    assert_eq!(stdout(&quiet), "");
+
+    // Output that was asked for is still printed.
+    let whoami = cclink(home.path(), &["--quiet", "whoami"]);
+    assert!(whoami.status.success());
+    assert!(stdout(&whoami).contains("Fingerprint:"));
+}
+
+/// Needs the Mainline DHT: run with `cargo test -- --ignored`.
+#[test]
+#[ignore]
+fn test_quiet_send_and_recv_print_nothing() {
+    let home = tempfile::tempdir().expect("Failed to create temp dir");
+    assert!(cclink(home.path(), &["init", "--no-passphrase", "--yes"])
+        .status
+        .success());
+    let file = home.path().join("notes.txt");
+    std::fs::write(&file, "quiet notes\n".repeat(500)).expect("write file");
+
+    let sent = cclink(home.path(), &["--quiet", "send", file.to_str().unwrap()]);
+    assert!(sent.status.success(), "send failed: {:?}", sent);
+    assert_eq!(stdout(&sent), "");
+
+    let received = home.path().join("received.txt");
+    let recv = cclink(
+        home.path(),
+        &["--quiet", "recv", "--output", received.to_str().unwrap()],
+    );
+    assert!(recv.status.success(), "recv failed: {:?}", recv);
+    assert_eq!(stdout(&recv), "");
+    assert_eq!(
+        std::fs::read(&received).expect("read received file"),
+        std::fs::read(&file).expect("read sent file")
+    );
+}